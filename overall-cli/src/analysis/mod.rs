@@ -1,3 +1,1994 @@
 //! Repository analysis and priority calculation
 
-// Placeholder for Phase 3
+use crate::{
+    config::Config,
+    export::{ExportData, ExportRepo},
+    models::{
+        is_protected_branch, short_sha, Branch, BranchStatus, CiStatus, Commit, Group,
+        LocalOnlyRepo, LocalRepoStatus, PRState, PullRequest, Repository, UNGROUPED_GROUP_NAME,
+    },
+    storage::Database,
+    Result,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Write};
+
+/// How long ago a repository was last pushed, relative to a threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Staleness {
+    /// Pushed within the first half of the threshold
+    Fresh,
+    /// Pushed within the threshold but more than halfway through it
+    Aging,
+    /// Not pushed for at least the threshold
+    Stale,
+}
+
+impl fmt::Display for Staleness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Staleness::Fresh => write!(f, "Fresh"),
+            Staleness::Aging => write!(f, "Aging"),
+            Staleness::Stale => write!(f, "Stale"),
+        }
+    }
+}
+
+/// Classify a repository's last push against a days-since-push threshold
+pub fn staleness_flag(
+    pushed_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+    threshold_days: i64,
+) -> Staleness {
+    let age_days = (now - pushed_at).num_days();
+
+    if age_days >= threshold_days {
+        Staleness::Stale
+    } else if age_days * 2 >= threshold_days {
+        Staleness::Aging
+    } else {
+        Staleness::Fresh
+    }
+}
+
+/// Whether a branch has commits that have not reached the default branch
+///
+/// Scans compare every branch against the default branch and record the
+/// default branch itself as zero ahead, so checking `ahead_by` also excludes
+/// it; configured protected branches are excluded by name.
+pub fn is_unmerged(branch: &Branch, config: &Config) -> bool {
+    branch.ahead_by > 0 && !is_protected_branch(&branch.name, config)
+}
+
+/// Per-status branch counts for a repository
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BranchStatusSummary {
+    /// Branches with work not yet on the default branch (see [`is_unmerged`])
+    pub unmerged: usize,
+    pub ready_for_pr: usize,
+    pub in_review: usize,
+    pub needs_update: usize,
+}
+
+/// Count a repository's branches by status
+///
+/// This is the single definition of "unmerged" shared by the CLI report, the
+/// export consumed by the UI, and the server's bulk PR creation.
+pub fn branch_status_summary(branches: &[Branch], config: &Config) -> BranchStatusSummary {
+    let mut summary = BranchStatusSummary::default();
+
+    for branch in branches {
+        let unmerged = is_unmerged(branch, config);
+        if unmerged {
+            summary.unmerged += 1;
+        }
+        match branch.status {
+            BranchStatus::ReadyForPR if unmerged => summary.ready_for_pr += 1,
+            BranchStatus::InReview => summary.in_review += 1,
+            BranchStatus::NeedsUpdate => summary.needs_update += 1,
+            _ => {}
+        }
+    }
+
+    summary
+}
+
+/// The unmerged branch with the most commits ahead of the default branch
+///
+/// The biggest branch is usually the most valuable one to ship first, so it
+/// is the repo's focus branch; ties go to the most recently committed branch.
+pub fn largest_unmerged_branch<'a>(branches: &'a [Branch], config: &Config) -> Option<&'a Branch> {
+    branches
+        .iter()
+        .filter(|b| is_unmerged(b, config))
+        .max_by_key(|b| (b.ahead_by, b.last_commit_date))
+}
+
+/// Branches that look like abandoned work in progress: commits ahead of the
+/// default branch, no open PR, and no commit for at least `threshold_days`
+pub fn find_abandoned_branches<'a>(
+    branches: &'a [Branch],
+    pull_requests: &[PullRequest],
+    threshold_days: i64,
+    now: DateTime<Utc>,
+) -> Vec<&'a Branch> {
+    branches
+        .iter()
+        .filter(|b| b.ahead_by > 0)
+        .filter(|b| (now - b.last_commit_date).num_days() >= threshold_days)
+        .filter(|b| {
+            !pull_requests
+                .iter()
+                .any(|pr| pr.state == PRState::Open && pr.head_ref_name == b.name)
+        })
+        .collect()
+}
+
+/// Days after a push at which the recency factor has halved
+const RECENCY_HALF_LIFE_DAYS: f32 = 14.0;
+
+/// Branch or PR count at which those factors saturate
+const COUNT_SATURATION: f32 = 5.0;
+
+/// Score a repository from 0.0 (nothing to do) to 1.0 (most pressing)
+///
+/// Each factor is scaled to 0.0-1.0 and combined as a weighted average using
+/// the configured `analysis.priority` weights.
+pub fn compute_priority(
+    repo: &Repository,
+    branches: &[Branch],
+    pull_requests: &[PullRequest],
+    local_status: Option<&LocalRepoStatus>,
+    now: DateTime<Utc>,
+    config: &Config,
+) -> f32 {
+    let weights = &config.analysis.priority;
+    let age_days = (now - repo.pushed_at).num_days().max(0) as f32;
+    let recency = RECENCY_HALF_LIFE_DAYS / (RECENCY_HALF_LIFE_DAYS + age_days);
+
+    let unmerged = branch_status_summary(branches, config).unmerged as f32;
+    let open_prs = pull_requests
+        .iter()
+        .filter(|pr| pr.state == PRState::Open)
+        .count() as f32;
+    let local_dirty = if local_status.is_some_and(|s| s.is_dirty) {
+        1.0
+    } else {
+        0.0
+    };
+
+    let total_weight = weights.recency + weights.unmerged + weights.open_prs + weights.local_dirty;
+    if total_weight <= 0.0 {
+        return 0.0;
+    }
+
+    let score = weights.recency * recency
+        + weights.unmerged * (unmerged / COUNT_SATURATION).min(1.0)
+        + weights.open_prs * (open_prs / COUNT_SATURATION).min(1.0)
+        + weights.local_dirty * local_dirty;
+
+    score / total_weight
+}
+
+/// Overall traffic-light state of a repository, most urgent first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RepoStatus {
+    /// The local branch has diverged from its upstream (both ahead and
+    /// behind) and needs a rebase or merge before anything can be pushed
+    Diverged,
+    /// Conflicts, unpushed or unpulled local commits, or branches ahead of or
+    /// behind the default branch
+    NeedsSync,
+    /// Uncommitted files in the local clone
+    LocalChanges,
+    /// Unmerged branches waiting to be cleaned up
+    Stale,
+    /// Nothing left to do
+    Complete,
+}
+
+impl fmt::Display for RepoStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepoStatus::Diverged => write!(f, "diverged"),
+            RepoStatus::NeedsSync => write!(f, "needsSync"),
+            RepoStatus::LocalChanges => write!(f, "localChanges"),
+            RepoStatus::Stale => write!(f, "stale"),
+            RepoStatus::Complete => write!(f, "complete"),
+        }
+    }
+}
+
+impl RepoStatus {
+    /// Red in the traffic light: the repository needs syncing before anything else
+    pub fn needs_attention(self) -> bool {
+        matches!(self, RepoStatus::Diverged | RepoStatus::NeedsSync)
+    }
+}
+
+/// Classify a repository from its branches and local clone status
+pub fn repo_status(
+    branches: &[Branch],
+    local_status: Option<&LocalRepoStatus>,
+    config: &Config,
+) -> RepoStatus {
+    if local_status.is_some_and(|s| s.is_diverged) {
+        return RepoStatus::Diverged;
+    }
+
+    let local_needs_sync = local_status
+        .is_some_and(|s| s.has_conflicts || s.unpushed_commits > 0 || s.behind_commits > 0);
+    let branches_need_sync = branches.iter().any(|b| b.ahead_by > 0 || b.behind_by > 0);
+    if local_needs_sync || branches_need_sync {
+        return RepoStatus::NeedsSync;
+    }
+
+    if local_status.is_some_and(|s| s.uncommitted_files > 0) {
+        return RepoStatus::LocalChanges;
+    }
+
+    if branch_status_summary(branches, config).unmerged > 0 {
+        return RepoStatus::Stale;
+    }
+
+    RepoStatus::Complete
+}
+
+/// `repo_status`, except that a repository snoozed past `now` counts as
+/// Complete; once the snooze expires its real status shows again
+pub fn snoozable_repo_status(
+    snoozed_until: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    branches: &[Branch],
+    local_status: Option<&LocalRepoStatus>,
+    config: &Config,
+) -> RepoStatus {
+    if snoozed_until.is_some_and(|until| until > now) {
+        return RepoStatus::Complete;
+    }
+    repo_status(branches, local_status, config)
+}
+
+/// Remembers repository statuses between `overall watch` cycles to spot
+/// repositories that just turned red
+#[derive(Debug, Clone)]
+pub struct AttentionWatcher {
+    /// `None` until the first cycle, which only records a baseline
+    previous: Option<HashMap<String, RepoStatus>>,
+    last_alerted: HashMap<String, DateTime<Utc>>,
+    /// A repository is alerted at most once per cooldown, so one flapping
+    /// between states doesn't alert every cycle
+    cooldown: chrono::Duration,
+}
+
+impl AttentionWatcher {
+    pub fn new(cooldown: chrono::Duration) -> Self {
+        AttentionWatcher {
+            previous: None,
+            last_alerted: HashMap::new(),
+            cooldown,
+        }
+    }
+
+    /// Record this cycle's statuses and return the repositories (sorted) that
+    /// moved into a `needs_attention` status since the last cycle and were
+    /// not alerted within the cooldown
+    pub fn update(
+        &mut self,
+        statuses: HashMap<String, RepoStatus>,
+        now: DateTime<Utc>,
+    ) -> Vec<String> {
+        let mut alerts = Vec::new();
+
+        if let Some(previous) = &self.previous {
+            for (repo_id, status) in &statuses {
+                let was_red = previous.get(repo_id).is_some_and(|s| s.needs_attention());
+                if !status.needs_attention() || was_red {
+                    continue;
+                }
+                let cooling_down = self
+                    .last_alerted
+                    .get(repo_id)
+                    .is_some_and(|at| now - *at < self.cooldown);
+                if !cooling_down {
+                    self.last_alerted.insert(repo_id.clone(), now);
+                    alerts.push(repo_id.clone());
+                }
+            }
+        }
+
+        self.previous = Some(statuses);
+        alerts.sort();
+        alerts
+    }
+}
+
+/// Stored branch heads and PR states for one repository, taken around a scan
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanSnapshot {
+    pub repo_id: String,
+    /// Branch name to head SHA
+    pub branches: BTreeMap<String, String>,
+    /// PR number to state
+    pub pull_requests: BTreeMap<u32, PRState>,
+}
+
+impl ScanSnapshot {
+    pub fn new(repo_id: &str, branches: &[Branch], pull_requests: &[PullRequest]) -> Self {
+        Self {
+            repo_id: repo_id.to_string(),
+            branches: branches
+                .iter()
+                .map(|b| (b.name.clone(), b.sha.clone()))
+                .collect(),
+            pull_requests: pull_requests
+                .iter()
+                .map(|pr| (pr.number, pr.state.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// Snapshot the stored state of a repository
+pub fn snapshot_repo(db: &Database, repo_id: &str) -> Result<ScanSnapshot> {
+    Ok(ScanSnapshot::new(
+        repo_id,
+        &db.get_branches_for_repo(repo_id)?,
+        &db.get_pull_requests_for_repo(repo_id)?,
+    ))
+}
+
+/// Guess a repository's default branch from stored branches: the first
+/// exact (non-glob) protected branch name that exists
+///
+/// The default branch is not stored, so offline work such as
+/// `reclassify_repo` relies on this instead of asking GitHub.
+pub fn infer_default_branch(branches: &[Branch], config: &Config) -> Option<String> {
+    config
+        .github
+        .protected_branches
+        .iter()
+        .filter(|name| !name.ends_with('*'))
+        .find(|name| branches.iter().any(|b| &b.name == *name))
+        .cloned()
+}
+
+/// Re-run branch classification over a repository's stored branches and
+/// pull requests, without any network calls
+///
+/// Returns the number of branches whose stored status changed.
+pub fn reclassify_repo(db: &Database, repo_id: &str, config: &Config) -> Result<usize> {
+    let branches = db.get_branches_for_repo(repo_id)?;
+    let prs = db.get_pull_requests_for_repo(repo_id)?;
+    let default_branch = infer_default_branch(&branches, config).unwrap_or_default();
+
+    let mut changed = 0;
+    for branch in &branches {
+        let status = crate::github::classify_branch_status(branch, &prs, &default_branch);
+        if status != branch.status {
+            db.update_branch_status(branch.id, &status)?;
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
+/// What changed in a repository between two snapshots
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanDelta {
+    pub repo_id: String,
+    pub added_branches: Vec<String>,
+    /// Branches that disappeared, usually because they were merged and deleted
+    pub removed_branches: Vec<String>,
+    /// Branches whose head moved
+    pub updated_branches: Vec<String>,
+    pub opened_prs: Vec<u32>,
+    /// PRs that were open before and no longer are; `Closed` also covers PRs
+    /// that dropped out of the open list without a known final state
+    pub closed_prs: Vec<(u32, PRState)>,
+}
+
+impl ScanDelta {
+    pub fn is_empty(&self) -> bool {
+        self.added_branches.is_empty()
+            && self.removed_branches.is_empty()
+            && self.updated_branches.is_empty()
+            && self.opened_prs.is_empty()
+            && self.closed_prs.is_empty()
+    }
+}
+
+/// Pluralize a count for the changelog, e.g. "+2 branches"
+fn counted(sign: char, count: usize, noun: &str, plural: &str) -> String {
+    format!(
+        "{}{} {}",
+        sign,
+        count,
+        if count == 1 { noun } else { plural }
+    )
+}
+
+impl fmt::Display for ScanDelta {
+    /// One changelog line, e.g. "proact: +1 branch feature/x, -1 PR #42 merged"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.repo_id.rsplit('/').next().unwrap_or(&self.repo_id);
+        let mut parts = Vec::new();
+
+        let branch_part = |sign, names: &[String]| {
+            format!(
+                "{} {}",
+                counted(sign, names.len(), "branch", "branches"),
+                names.join(", ")
+            )
+        };
+        if !self.added_branches.is_empty() {
+            parts.push(branch_part('+', &self.added_branches));
+        }
+        if !self.removed_branches.is_empty() {
+            parts.push(branch_part('-', &self.removed_branches));
+        }
+        if !self.updated_branches.is_empty() {
+            parts.push(branch_part('~', &self.updated_branches));
+        }
+        if !self.opened_prs.is_empty() {
+            let numbers: Vec<String> = self.opened_prs.iter().map(|n| format!("#{}", n)).collect();
+            parts.push(format!(
+                "{} {}",
+                counted('+', numbers.len(), "PR", "PRs"),
+                numbers.join(", ")
+            ));
+        }
+        if !self.closed_prs.is_empty() {
+            let numbers: Vec<String> = self
+                .closed_prs
+                .iter()
+                .map(|(n, state)| format!("#{} {}", n, state.to_string().to_lowercase()))
+                .collect();
+            parts.push(format!(
+                "{} {}",
+                counted('-', numbers.len(), "PR", "PRs"),
+                numbers.join(", ")
+            ));
+        }
+
+        if parts.is_empty() {
+            write!(f, "{}: no changes", name)
+        } else {
+            write!(f, "{}: {}", name, parts.join(", "))
+        }
+    }
+}
+
+/// Diff two snapshots of the same repository
+pub fn scan_delta(before: &ScanSnapshot, after: &ScanSnapshot) -> ScanDelta {
+    let mut delta = ScanDelta {
+        repo_id: after.repo_id.clone(),
+        ..Default::default()
+    };
+
+    for (name, sha) in &after.branches {
+        match before.branches.get(name) {
+            None => delta.added_branches.push(name.clone()),
+            Some(previous) if previous != sha => delta.updated_branches.push(name.clone()),
+            Some(_) => {}
+        }
+    }
+    delta.removed_branches = before
+        .branches
+        .keys()
+        .filter(|name| !after.branches.contains_key(*name))
+        .cloned()
+        .collect();
+
+    for (number, state) in &after.pull_requests {
+        if *state == PRState::Open && before.pull_requests.get(number) != Some(&PRState::Open) {
+            delta.opened_prs.push(*number);
+        }
+    }
+    for (number, state) in &before.pull_requests {
+        if *state != PRState::Open {
+            continue;
+        }
+        match after.pull_requests.get(number) {
+            Some(PRState::Open) => {}
+            Some(final_state) => delta.closed_prs.push((*number, final_state.clone())),
+            None => delta.closed_prs.push((*number, PRState::Closed)),
+        }
+    }
+
+    delta
+}
+
+/// A repository present in both exports whose branch or open PR count changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoCountChange {
+    pub repo_id: String,
+    /// Branch count in the old and new export
+    pub branches: (usize, usize),
+    /// Open PR count in the old and new export
+    pub open_prs: (usize, usize),
+}
+
+/// What changed between two exports, e.g. two repos.json files taken days apart
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportDiff {
+    pub added_groups: Vec<String>,
+    pub removed_groups: Vec<String>,
+    pub added_repos: Vec<String>,
+    pub removed_repos: Vec<String>,
+    pub changed_repos: Vec<RepoCountChange>,
+}
+
+impl ExportDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_groups.is_empty()
+            && self.removed_groups.is_empty()
+            && self.added_repos.is_empty()
+            && self.removed_repos.is_empty()
+            && self.changed_repos.is_empty()
+    }
+}
+
+impl fmt::Display for ExportDiff {
+    /// One line per kind of change, then one per changed repository
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No differences");
+        }
+
+        let names = |sign, names: &[String], noun, plural| {
+            format!(
+                "{} {}",
+                counted(sign, names.len(), noun, plural),
+                names.join(", ")
+            )
+        };
+        if !self.added_groups.is_empty() {
+            writeln!(f, "{}", names('+', &self.added_groups, "group", "groups"))?;
+        }
+        if !self.removed_groups.is_empty() {
+            writeln!(f, "{}", names('-', &self.removed_groups, "group", "groups"))?;
+        }
+        if !self.added_repos.is_empty() {
+            writeln!(f, "{}", names('+', &self.added_repos, "repo", "repos"))?;
+        }
+        if !self.removed_repos.is_empty() {
+            writeln!(f, "{}", names('-', &self.removed_repos, "repo", "repos"))?;
+        }
+        for change in &self.changed_repos {
+            let mut parts = Vec::new();
+            if change.branches.0 != change.branches.1 {
+                parts.push(format!(
+                    "branches {} -> {}",
+                    change.branches.0, change.branches.1
+                ));
+            }
+            if change.open_prs.0 != change.open_prs.1 {
+                parts.push(format!(
+                    "open PRs {} -> {}",
+                    change.open_prs.0, change.open_prs.1
+                ));
+            }
+            writeln!(f, "~ {}: {}", change.repo_id, parts.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Every repository in an export, grouped or not, by id
+fn export_repos(data: &ExportData) -> BTreeMap<&str, &ExportRepo> {
+    data.groups
+        .iter()
+        .flat_map(|g| &g.repos)
+        .chain(&data.ungrouped)
+        .map(|r| (r.id.as_str(), r))
+        .collect()
+}
+
+/// Compare two exports: groups (by name) and repositories added or removed,
+/// and repositories whose branch or open PR count changed
+pub fn diff_exports(old: &ExportData, new: &ExportData) -> ExportDiff {
+    let old_groups: Vec<&str> = old.groups.iter().map(|g| g.name.as_str()).collect();
+    let new_groups: Vec<&str> = new.groups.iter().map(|g| g.name.as_str()).collect();
+    let old_repos = export_repos(old);
+    let new_repos = export_repos(new);
+
+    let mut diff = ExportDiff {
+        added_groups: new_groups
+            .iter()
+            .filter(|name| !old_groups.contains(name))
+            .map(|name| name.to_string())
+            .collect(),
+        removed_groups: old_groups
+            .iter()
+            .filter(|name| !new_groups.contains(name))
+            .map(|name| name.to_string())
+            .collect(),
+        ..Default::default()
+    };
+
+    for (id, new_repo) in &new_repos {
+        let Some(old_repo) = old_repos.get(id) else {
+            diff.added_repos.push(id.to_string());
+            continue;
+        };
+        let change = RepoCountChange {
+            repo_id: id.to_string(),
+            branches: (old_repo.branches.len(), new_repo.branches.len()),
+            open_prs: (old_repo.pr_count, new_repo.pr_count),
+        };
+        if change.branches.0 != change.branches.1 || change.open_prs.0 != change.open_prs.1 {
+            diff.changed_repos.push(change);
+        }
+    }
+    diff.removed_repos = old_repos
+        .keys()
+        .filter(|id| !new_repos.contains_key(*id))
+        .map(|id| id.to_string())
+        .collect();
+
+    diff
+}
+
+/// A named group of repositories with everything needed to report on them
+#[derive(Debug, Clone)]
+pub struct ReportGroup {
+    pub name: String,
+    pub repos: Vec<ReportRepo>,
+}
+
+/// A repository together with its stored branches and pull requests
+#[derive(Debug, Clone)]
+pub struct ReportRepo {
+    pub repo: Repository,
+    pub branches: Vec<Branch>,
+    pub pull_requests: Vec<PullRequest>,
+}
+
+/// Load report data for the given groups, optionally followed by an
+/// "Ungrouped" section for repositories outside every group
+pub fn build_report_groups(
+    db: &Database,
+    groups: &[Group],
+    include_ungrouped: bool,
+) -> Result<Vec<ReportGroup>> {
+    let load = |repos: Vec<Repository>| -> Result<Vec<ReportRepo>> {
+        repos
+            .into_iter()
+            .map(|repo| {
+                Ok(ReportRepo {
+                    branches: db.get_branches_for_repo(&repo.id)?,
+                    pull_requests: db.get_pull_requests_for_repo(&repo.id)?,
+                    repo,
+                })
+            })
+            .collect()
+    };
+
+    let mut report_groups = Vec::new();
+    for group in groups {
+        report_groups.push(ReportGroup {
+            name: group.name.clone(),
+            repos: load(db.get_repos_in_group(group.id)?)?,
+        });
+    }
+
+    if include_ungrouped {
+        let ungrouped = db.get_ungrouped_repositories()?;
+        if !ungrouped.is_empty() {
+            report_groups.push(ReportGroup {
+                name: UNGROUPED_GROUP_NAME.to_string(),
+                repos: load(ungrouped)?,
+            });
+        }
+    }
+
+    Ok(report_groups)
+}
+
+/// Render a standup-style markdown summary of pending work per repository
+///
+/// Only repositories with open PRs, branches ready for a PR, or local
+/// uncommitted/unpushed work are listed. `statuses` is keyed by repo id.
+pub fn render_markdown_report(
+    groups: &[ReportGroup],
+    statuses: &HashMap<String, LocalRepoStatus>,
+    config: &Config,
+) -> String {
+    let mut out = String::new();
+
+    for group in groups {
+        let _ = writeln!(out, "## {}\n", group.name);
+
+        let mut any = false;
+        for report_repo in &group.repos {
+            let items = report_items(report_repo, statuses.get(&report_repo.repo.id), config);
+            if items.is_empty() {
+                continue;
+            }
+            any = true;
+
+            let _ = writeln!(out, "- **{}**", report_repo.repo.id);
+            for item in items {
+                let _ = writeln!(out, "  - {}", item);
+            }
+        }
+
+        if !any {
+            out.push_str("- Nothing pending\n");
+        }
+        out.push('\n');
+    }
+
+    out.trim_end().to_string() + "\n"
+}
+
+/// Render the same summary as [`render_markdown_report`] as an HTML fragment
+pub fn render_html_report(
+    groups: &[ReportGroup],
+    statuses: &HashMap<String, LocalRepoStatus>,
+    config: &Config,
+) -> String {
+    let mut out = String::new();
+
+    for group in groups {
+        let _ = writeln!(out, "<h2>{}</h2>", escape_html(&group.name));
+        out.push_str("<ul>\n");
+
+        let mut any = false;
+        for report_repo in &group.repos {
+            let items = report_items(report_repo, statuses.get(&report_repo.repo.id), config);
+            if items.is_empty() {
+                continue;
+            }
+            any = true;
+
+            let _ = writeln!(
+                out,
+                "  <li><strong>{}</strong>\n    <ul>",
+                escape_html(&report_repo.repo.id)
+            );
+            for item in items {
+                let _ = writeln!(out, "      <li>{}</li>", escape_html(&item));
+            }
+            out.push_str("    </ul>\n  </li>\n");
+        }
+
+        if !any {
+            out.push_str("  <li>Nothing pending</li>\n");
+        }
+        out.push_str("</ul>\n");
+    }
+
+    out
+}
+
+/// Plain-text report lines for one repository, most actionable first
+fn report_items(
+    report_repo: &ReportRepo,
+    local_status: Option<&LocalRepoStatus>,
+    config: &Config,
+) -> Vec<String> {
+    let mut items = Vec::new();
+
+    if let Some(status) = local_status {
+        if status.has_conflicts {
+            items.push("Local: unresolved merge conflicts".to_string());
+        }
+        if status.is_diverged {
+            items.push("Local: diverged from remote - needs rebase/merge".to_string());
+        }
+
+        let mut local = Vec::new();
+        if status.uncommitted_files > 0 {
+            local.push(plural(status.uncommitted_files, "uncommitted file"));
+        }
+        if status.unpushed_commits > 0 {
+            local.push(plural(status.unpushed_commits, "unpushed commit"));
+        }
+        if status.behind_commits > 0 {
+            local.push(format!("{} behind remote", status.behind_commits));
+        }
+        if !local.is_empty() {
+            items.push(format!("Local: {}", local.join(", ")));
+        }
+    }
+
+    let open_prs: Vec<&PullRequest> = report_repo
+        .pull_requests
+        .iter()
+        .filter(|pr| pr.state == PRState::Open)
+        .collect();
+    for pr in &open_prs {
+        items.push(format!("PR #{}: {}", pr.number, pr.title));
+    }
+
+    // Biggest first, since those are usually worth shipping first
+    let mut ready: Vec<&Branch> = report_repo
+        .branches
+        .iter()
+        .filter(|branch| {
+            branch.status == BranchStatus::ReadyForPR
+                && is_unmerged(branch, config)
+                && !open_prs.iter().any(|pr| pr.head_ref_name == branch.name)
+        })
+        .collect();
+    ready.sort_by_key(|branch| std::cmp::Reverse(branch.ahead_by));
+    for branch in ready {
+        items.push(format!(
+            "Ready for PR: {} ({} ahead)",
+            branch.name, branch.ahead_by
+        ));
+    }
+
+    items
+}
+
+/// One concrete next step for a repository, e.g. "Push 3 commits in owner/repo"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusAction {
+    pub repo_id: String,
+    pub action: String,
+}
+
+/// The concrete next steps for one repository, most pressing first: local
+/// conflicts and divergence, then local sync, then open PRs awaiting review,
+/// then branches ready for a PR (biggest first)
+pub fn next_actions(
+    report_repo: &ReportRepo,
+    local_status: Option<&LocalRepoStatus>,
+    config: &Config,
+) -> Vec<String> {
+    let id = &report_repo.repo.id;
+    let mut actions = Vec::new();
+
+    if let Some(status) = local_status {
+        if status.has_conflicts {
+            actions.push(format!("Resolve conflicts in {}", id));
+        }
+        if status.is_diverged {
+            actions.push(format!("Rebase or merge {} onto its remote", id));
+        }
+        if status.is_unpublished {
+            let branch = status.current_branch.as_deref().unwrap_or("branch");
+            actions.push(format!("Publish {} in {} (git push -u)", branch, id));
+        } else if status.unpushed_commits > 0 {
+            actions.push(format!(
+                "Push {} in {}",
+                plural(status.unpushed_commits, "commit"),
+                id
+            ));
+        }
+        if status.behind_commits > 0 {
+            actions.push(format!(
+                "Pull {} in {}",
+                plural(status.behind_commits, "commit"),
+                id
+            ));
+        }
+        if status.uncommitted_files > 0 {
+            actions.push(format!(
+                "Commit {} in {}",
+                plural(status.uncommitted_files, "changed file"),
+                id
+            ));
+        }
+    }
+
+    let open_prs: Vec<&PullRequest> = report_repo
+        .pull_requests
+        .iter()
+        .filter(|pr| pr.state == PRState::Open)
+        .collect();
+    for pr in &open_prs {
+        actions.push(format!("Review PR #{} in {}: {}", pr.number, id, pr.title));
+    }
+
+    let mut ready: Vec<&Branch> = report_repo
+        .branches
+        .iter()
+        .filter(|branch| {
+            branch.status == BranchStatus::ReadyForPR
+                && is_unmerged(branch, config)
+                && !open_prs.iter().any(|pr| pr.head_ref_name == branch.name)
+        })
+        .collect();
+    ready.sort_by_key(|branch| std::cmp::Reverse(branch.ahead_by));
+    for branch in ready {
+        actions.push(format!("Create PR for {} {}", id, branch.name));
+    }
+
+    actions
+}
+
+/// Every repository's next steps as one flat list, most urgent repository
+/// (by `RepoStatus`, then priority) first
+pub fn focus_actions(db: &Database, config: &Config) -> Result<Vec<FocusAction>> {
+    let local: HashMap<String, LocalRepoStatus> = db
+        .get_all_local_repo_statuses()?
+        .into_iter()
+        .map(|s| (s.repo_id.clone(), s))
+        .collect();
+
+    // Already ordered by priority; the stable sort keeps that within a status.
+    // Snoozed repositories are deliberately deferred, so they get no actions.
+    let now = Utc::now();
+    let mut repos = Vec::new();
+    for repo in db.get_all_repositories()? {
+        if repo.is_snoozed(now) {
+            continue;
+        }
+        let report_repo = ReportRepo {
+            branches: db.get_branches_for_repo(&repo.id)?,
+            pull_requests: db.get_pull_requests_for_repo(&repo.id)?,
+            repo,
+        };
+        let local_status = local.get(&report_repo.repo.id);
+        let status = repo_status(&report_repo.branches, local_status, config);
+        repos.push((status, report_repo));
+    }
+    repos.sort_by_key(|(status, _)| *status);
+
+    Ok(repos
+        .iter()
+        .flat_map(|(_, report_repo)| {
+            next_actions(report_repo, local.get(&report_repo.repo.id), config)
+                .into_iter()
+                .map(|action| FocusAction {
+                    repo_id: report_repo.repo.id.clone(),
+                    action,
+                })
+        })
+        .collect())
+}
+
+/// Everything stored about one repository, as shown by `overall show`
+#[derive(Debug, Clone)]
+pub struct RepoDetail {
+    pub repo: Repository,
+    /// Each branch with its stored commits, newest first
+    pub branches: Vec<(Branch, Vec<Commit>)>,
+    pub pull_requests: Vec<PullRequest>,
+    pub local_status: Option<LocalRepoStatus>,
+    pub tags: Vec<String>,
+    pub ci_status: CiStatus,
+}
+
+/// Load a repository's detail, or `None` when it is not tracked
+pub fn load_repo_detail(db: &Database, repo_id: &str) -> Result<Option<RepoDetail>> {
+    let Some(repo) = db.get_repository(repo_id)? else {
+        return Ok(None);
+    };
+    let branches = db
+        .get_branches_for_repo(repo_id)?
+        .into_iter()
+        .map(|branch| {
+            let commits = db.get_commits_for_branch(branch.id)?;
+            Ok((branch, commits))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some(RepoDetail {
+        branches,
+        pull_requests: db.get_pull_requests_for_repo(repo_id)?,
+        local_status: db.get_local_repo_status(repo_id)?,
+        tags: db.get_tags_for_repo(repo_id)?,
+        ci_status: db.get_ci_status(repo_id)?,
+        repo,
+    }))
+}
+
+/// Git repositories under the enabled local roots whose id (from their path,
+/// see `local_git::extract_repo_id`) isn't tracked, i.e. clones never
+/// scanned from GitHub, ordered by id
+///
+/// Each comes with the GitHub repository its `origin` remote points at, if
+/// any; clones whose origin is tracked belong to it and are left out. Roots
+/// that can't be read are skipped; local scans report them.
+pub fn find_local_only_repos(db: &Database) -> Result<Vec<LocalOnlyRepo>> {
+    let mut seen_paths = std::collections::HashSet::new();
+    let mut local_only = Vec::new();
+
+    for root in db.get_all_local_repo_roots()?.iter().filter(|r| r.enabled) {
+        let Ok(paths) = crate::local_git::scan_for_git_repos(std::path::Path::new(&root.path))
+        else {
+            continue;
+        };
+        for path in paths {
+            if !seen_paths.insert(path.clone()) {
+                continue;
+            }
+            let Some(repo_id) = crate::local_git::extract_repo_id(&path) else {
+                continue;
+            };
+            if db.get_repository(&repo_id)?.is_some() {
+                continue;
+            }
+            let remote_repo_id = crate::local_git::origin_repo_id(&path).ok().flatten();
+            if let Some(remote) = &remote_repo_id {
+                if db.get_repository(remote)?.is_some() {
+                    continue;
+                }
+            }
+            local_only.push(LocalOnlyRepo {
+                repo_id,
+                remote_repo_id,
+                local_path: path.to_string_lossy().into_owned(),
+            });
+        }
+    }
+
+    local_only.sort_by(|a, b| a.repo_id.cmp(&b.repo_id));
+    Ok(local_only)
+}
+
+/// Commits listed per branch by [`render_repo_detail`]
+const DETAIL_COMMITS_PER_BRANCH: usize = 5;
+
+/// Render a repository's detail as a plain-text report: the detail modal of
+/// the web UI for the terminal
+pub fn render_repo_detail(detail: &RepoDetail, config: &Config, now: DateTime<Utc>) -> String {
+    let repo = &detail.repo;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "{}", repo.id);
+    if let Some(description) = repo.description.as_deref().filter(|d| !d.is_empty()) {
+        let _ = writeln!(out, "{}", description);
+    }
+    let mut traits = vec![repo.language_label().to_string()];
+    if repo.is_private {
+        traits.push("private".to_string());
+    }
+    if repo.is_fork {
+        traits.push("fork".to_string());
+    }
+    let _ = writeln!(out, "\n  Language:  {}", traits.join(", "));
+    let _ = writeln!(
+        out,
+        "  Last push: {} ({})",
+        repo.pushed_at.format("%Y-%m-%d %H:%M"),
+        staleness_flag(repo.pushed_at, now, config.analysis.stale_threshold_days)
+    );
+    let _ = writeln!(
+        out,
+        "  Priority:  {:.2}{}",
+        repo.priority,
+        if repo.priority_locked {
+            " (manual)"
+        } else {
+            ""
+        }
+    );
+    let branches: Vec<Branch> = detail.branches.iter().map(|(b, _)| b.clone()).collect();
+    let _ = writeln!(
+        out,
+        "  Status:    {}",
+        repo_status(&branches, detail.local_status.as_ref(), config)
+    );
+    if let Some(until) = repo.snoozed_until.filter(|_| repo.is_snoozed(now)) {
+        let _ = writeln!(
+            out,
+            "  Snoozed:   until {}",
+            until.format("%Y-%m-%d %H:%M UTC")
+        );
+    }
+    if detail.ci_status != CiStatus::None {
+        let _ = writeln!(out, "  CI:        {}", detail.ci_status);
+    }
+    if !detail.tags.is_empty() {
+        let _ = writeln!(out, "  Tags:      {}", detail.tags.join(", "));
+    }
+
+    let _ = writeln!(out, "\nBranches ({}):", detail.branches.len());
+    for (branch, commits) in &detail.branches {
+        let _ = writeln!(
+            out,
+            "  {}  +{} -{}  {}{}  last commit {}",
+            branch.name,
+            branch.ahead_by,
+            branch.behind_by,
+            branch.status,
+            if is_unmerged(branch, config) {
+                ", unmerged"
+            } else {
+                ""
+            },
+            branch.last_commit_date.format("%Y-%m-%d")
+        );
+        for commit in commits.iter().take(DETAIL_COMMITS_PER_BRANCH) {
+            let _ = writeln!(out, "    {} {}", short_sha(&commit.sha), commit.subject());
+        }
+        if commits.len() > DETAIL_COMMITS_PER_BRANCH {
+            let _ = writeln!(
+                out,
+                "    ... {} more",
+                commits.len() - DETAIL_COMMITS_PER_BRANCH
+            );
+        }
+    }
+
+    let open_prs: Vec<&PullRequest> = detail
+        .pull_requests
+        .iter()
+        .filter(|pr| pr.state == PRState::Open)
+        .collect();
+    let _ = writeln!(out, "\nOpen pull requests ({}):", open_prs.len());
+    for pr in open_prs {
+        let review = pr
+            .review_decision
+            .map(|d| format!(", {}", d))
+            .unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "  #{} {} ({}{})",
+            pr.number, pr.title, pr.head_ref_name, review
+        );
+        if let Some(url) = &pr.url {
+            let _ = writeln!(out, "    {}", url);
+        }
+    }
+
+    match &detail.local_status {
+        Some(status) => {
+            let _ = writeln!(out, "\nLocal clone: {}", status.local_path);
+            let _ = writeln!(
+                out,
+                "  Branch: {}",
+                status.current_branch.as_deref().unwrap_or("(detached)")
+            );
+            let _ = writeln!(out, "  State:  {}", local_state_summary(status));
+        }
+        None => out.push_str("\nLocal clone: none found\n"),
+    }
+
+    out
+}
+
+/// One-line summary of a clone's working tree and upstream state, e.g.
+/// "2 uncommitted, 1 unpushed" or "clean"
+pub fn local_state_summary(status: &LocalRepoStatus) -> String {
+    let mut state = Vec::new();
+    if status.is_diverged {
+        state.push("diverged - needs rebase/merge".to_string());
+    }
+    if status.has_conflicts {
+        state.push("conflicts".to_string());
+    }
+    if status.is_unpublished {
+        state.push("unpublished".to_string());
+    }
+    if status.uncommitted_files > 0 {
+        state.push(format!("{} uncommitted", status.uncommitted_files));
+    }
+    if status.unpushed_commits > 0 {
+        state.push(format!("{} unpushed", status.unpushed_commits));
+    }
+    if status.behind_commits > 0 {
+        state.push(format!("{} behind", status.behind_commits));
+    }
+    if state.is_empty() {
+        state.push("clean".to_string());
+    }
+    state.join(", ")
+}
+
+fn plural(count: u32, noun: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// Score of an exact match against a whole repo id or its name
+pub const EXACT_MATCH_SCORE: u32 = 1000;
+
+/// Match a partial repo id against candidates, best match first
+///
+/// Matching ignores ASCII case. An exact id or name scores
+/// `EXACT_MATCH_SCORE`; otherwise substrings of the name beat substrings of
+/// the whole id, which beat scattered subsequences (fewer gaps score higher).
+/// Candidates that do not contain the query as a subsequence are dropped.
+pub fn fuzzy_match(query: &str, candidates: &[String]) -> Vec<(String, u32)> {
+    let query = query.trim().to_ascii_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<(String, u32)> = candidates
+        .iter()
+        .filter_map(|candidate| fuzzy_score(&query, candidate).map(|s| (candidate.clone(), s)))
+        .collect();
+    matches.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    matches
+}
+
+fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let id = candidate.to_ascii_lowercase();
+    let name = id.rsplit('/').next().unwrap_or(&id);
+
+    if id == query || name == query {
+        return Some(EXACT_MATCH_SCORE);
+    }
+
+    // Earlier and tighter substring matches rank higher
+    let substring_score = |haystack: &str, base: u32| {
+        haystack.find(query).map(|pos| {
+            let extra = (haystack.len() - query.len()).min(100) as u32;
+            base - (pos.min(100) as u32) * 2 - extra
+        })
+    };
+    if let Some(score) = substring_score(name, 800) {
+        return Some(score);
+    }
+    if let Some(score) = substring_score(&id, 500) {
+        return Some(score);
+    }
+
+    // Subsequence: every query character in order, penalizing skipped ones
+    let mut gaps = 0u32;
+    let mut chars = id.chars();
+    for q in query.chars() {
+        loop {
+            match chars.next() {
+                Some(c) if c == q => break,
+                Some(_) => gaps += 1,
+                None => return None,
+            }
+        }
+    }
+    Some(200u32.saturating_sub(gaps).max(1))
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PriorityWeights;
+    use crate::models::ReviewDecision;
+    use crate::test_support::fixtures::CommitBuilder;
+    use crate::test_support::{BranchBuilder, PRBuilder, RepoBuilder};
+
+    #[test]
+    fn test_fuzzy_match_ranks_exact_substring_and_subsequence() {
+        let candidates: Vec<String> = [
+            "softwarewrighter/overall",
+            "softwarewrighter/overall-ui",
+            "softwarewrighter/proact",
+            "other/all-in-one",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let matches = fuzzy_match("Overall", &candidates);
+        assert_eq!(
+            matches[0],
+            ("softwarewrighter/overall".to_string(), EXACT_MATCH_SCORE)
+        );
+        assert_eq!(matches[1].0, "softwarewrighter/overall-ui");
+        assert!(matches[1].1 < EXACT_MATCH_SCORE);
+
+        // Name substrings beat subsequences scattered across the id
+        let matches = fuzzy_match("all", &candidates);
+        assert_eq!(matches[0].0, "other/all-in-one");
+        assert!(matches
+            .iter()
+            .all(|(id, _)| id != "softwarewrighter/proact"));
+
+        let matches = fuzzy_match("swproact", &candidates);
+        assert_eq!(
+            matches,
+            vec![("softwarewrighter/proact".to_string(), matches[0].1)]
+        );
+
+        assert!(fuzzy_match("zzz", &candidates).is_empty());
+        assert!(fuzzy_match("  ", &candidates).is_empty());
+    }
+
+    fn local_status(repo_id: &str, uncommitted: u32, unpushed: u32) -> LocalRepoStatus {
+        LocalRepoStatus {
+            id: 0,
+            repo_id: repo_id.to_string(),
+            local_path: format!("/tmp/{}", repo_id),
+            current_branch: Some("main".to_string()),
+            uncommitted_files: uncommitted,
+            unpushed_commits: unpushed,
+            behind_commits: 0,
+            is_dirty: uncommitted > 0 || unpushed > 0,
+            has_conflicts: false,
+            is_diverged: false,
+            is_unpublished: false,
+            last_checked: Utc::now(),
+        }
+    }
+
+    fn sample_groups() -> Vec<ReportGroup> {
+        let repo_id = "testowner/busy";
+        vec![ReportGroup {
+            name: "Active Projects".to_string(),
+            repos: vec![
+                ReportRepo {
+                    repo: RepoBuilder::new("busy").build(),
+                    branches: vec![
+                        BranchBuilder::new("main").build(repo_id),
+                        BranchBuilder::new("feature-ready").ahead(3).build(repo_id),
+                        BranchBuilder::new("feature-in-pr").ahead(1).build(repo_id),
+                    ],
+                    pull_requests: vec![PRBuilder::new(12, "Add <widgets>")
+                        .head_ref_name("feature-in-pr")
+                        .build(repo_id)],
+                },
+                ReportRepo {
+                    repo: RepoBuilder::new("quiet").build(),
+                    branches: vec![BranchBuilder::new("main").build("testowner/quiet")],
+                    pull_requests: vec![],
+                },
+            ],
+        }]
+    }
+
+    #[test]
+    fn test_render_markdown_report() {
+        let statuses = HashMap::from([(
+            "testowner/busy".to_string(),
+            local_status("testowner/busy", 2, 1),
+        )]);
+
+        let report = render_markdown_report(&sample_groups(), &statuses, &Config::default());
+
+        assert_eq!(
+            report,
+            "## Active Projects\n\n\
+             - **testowner/busy**\n  \
+             - Local: 2 uncommitted files, 1 unpushed commit\n  \
+             - PR #12: Add <widgets>\n  \
+             - Ready for PR: feature-ready (3 ahead)\n"
+        );
+    }
+
+    #[test]
+    fn test_next_actions() {
+        let groups = sample_groups();
+        let busy = &groups[0].repos[0];
+        let mut status = local_status("testowner/busy", 2, 3);
+        status.has_conflicts = true;
+
+        assert_eq!(
+            next_actions(busy, Some(&status), &Config::default()),
+            vec![
+                "Resolve conflicts in testowner/busy",
+                "Push 3 commits in testowner/busy",
+                "Commit 2 changed files in testowner/busy",
+                "Review PR #12 in testowner/busy: Add <widgets>",
+                "Create PR for testowner/busy feature-ready",
+            ]
+        );
+        assert!(next_actions(&groups[0].repos[1], None, &Config::default()).is_empty());
+
+        // A branch that was never pushed needs publishing, not just a push
+        let mut unpublished = local_status("testowner/busy", 0, 2);
+        unpublished.current_branch = Some("spike".to_string());
+        unpublished.is_unpublished = true;
+        assert_eq!(
+            next_actions(busy, Some(&unpublished), &Config::default())[0],
+            "Publish spike in testowner/busy (git push -u)"
+        );
+        assert_eq!(local_state_summary(&unpublished), "unpublished, 2 unpushed");
+    }
+
+    #[test]
+    fn test_render_repo_detail() {
+        let repo_id = "testowner/busy";
+        let now: DateTime<Utc> = "2026-03-01T12:00:00Z".parse().unwrap();
+        let day = |d: &str| format!("2026-{}T09:30:00Z", d).parse().unwrap();
+        let commits: Vec<Commit> = (1..=6)
+            .map(|i| {
+                CommitBuilder::new(&format!("abcdef{}000", i), &format!("Step {}", i)).build(2)
+            })
+            .collect();
+        let detail = RepoDetail {
+            repo: RepoBuilder::new("busy")
+                .with_language("Rust")
+                .with_description("Keeps busy")
+                .is_private(true)
+                .pushed_at(day("02-20"))
+                .priority(0.5)
+                .build(),
+            branches: vec![
+                (
+                    BranchBuilder::new("main")
+                        .last_commit_date(day("02-20"))
+                        .build(repo_id),
+                    vec![],
+                ),
+                (
+                    BranchBuilder::new("feature")
+                        .ahead(6)
+                        .behind(1)
+                        .last_commit_date(day("02-19"))
+                        .build(repo_id),
+                    commits,
+                ),
+            ],
+            pull_requests: vec![
+                PRBuilder::new(12, "Add feature")
+                    .head_ref_name("feature")
+                    .review_decision(ReviewDecision::Approved)
+                    .build(repo_id),
+                PRBuilder::new(3, "Old")
+                    .state(PRState::Merged)
+                    .build(repo_id),
+            ],
+            local_status: Some(local_status(repo_id, 2, 0)),
+            tags: vec!["oss".to_string()],
+            ci_status: CiStatus::Failing,
+        };
+
+        assert_eq!(
+            render_repo_detail(&detail, &Config::default(), now),
+            "testowner/busy\n\
+             Keeps busy\n\
+             \n  Language:  Rust, private\n\
+             \x20 Last push: 2026-02-20 09:30 (Fresh)\n\
+             \x20 Priority:  0.50\n\
+             \x20 Status:    needsSync\n\
+             \x20 CI:        failing\n\
+             \x20 Tags:      oss\n\
+             \nBranches (2):\n\
+             \x20 main  +0 -0  ReadyForPR  last commit 2026-02-20\n\
+             \x20 feature  +6 -1  ReadyForPR, unmerged  last commit 2026-02-19\n\
+             \x20   abcdef1 Step 1\n\
+             \x20   abcdef2 Step 2\n\
+             \x20   abcdef3 Step 3\n\
+             \x20   abcdef4 Step 4\n\
+             \x20   abcdef5 Step 5\n\
+             \x20   ... 1 more\n\
+             \nOpen pull requests (1):\n\
+             \x20 #12 Add feature (feature, Approved)\n\
+             \nLocal clone: /tmp/testowner/busy\n\
+             \x20 Branch: main\n\
+             \x20 State:  2 uncommitted\n"
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_report_empty_group() {
+        let groups = vec![ReportGroup {
+            name: "Idle".to_string(),
+            repos: vec![],
+        }];
+
+        let report = render_markdown_report(&groups, &HashMap::new(), &Config::default());
+
+        assert_eq!(report, "## Idle\n\n- Nothing pending\n");
+    }
+
+    #[test]
+    fn test_branch_status_summary() {
+        let repo_id = "testowner/repo";
+        let branches = vec![
+            BranchBuilder::new("main").build(repo_id),
+            // Protected names never count, even if somehow ahead
+            BranchBuilder::new("develop").ahead(4).build(repo_id),
+            BranchBuilder::new("feature-ready").ahead(2).build(repo_id),
+            // Behind the default branch but still carrying unmerged work
+            BranchBuilder::new("feature-diverged")
+                .ahead(1)
+                .behind(3)
+                .with_status(BranchStatus::NeedsUpdate)
+                .build(repo_id),
+            BranchBuilder::new("feature-review")
+                .ahead(1)
+                .with_status(BranchStatus::InReview)
+                .build(repo_id),
+        ];
+
+        let summary = branch_status_summary(&branches, &Config::default());
+
+        assert_eq!(
+            summary,
+            BranchStatusSummary {
+                unmerged: 3,
+                ready_for_pr: 1,
+                in_review: 1,
+                needs_update: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_largest_unmerged_branch() {
+        let repo_id = "testowner/repo";
+        let now = Utc::now();
+        let branches = vec![
+            BranchBuilder::new("main").build(repo_id),
+            // Protected, so never the focus however far ahead
+            BranchBuilder::new("develop").ahead(9).build(repo_id),
+            BranchBuilder::new("feature-small").ahead(2).build(repo_id),
+            BranchBuilder::new("feature-old")
+                .ahead(5)
+                .last_commit_date(now - chrono::Duration::days(3))
+                .build(repo_id),
+            BranchBuilder::new("feature-new")
+                .ahead(5)
+                .last_commit_date(now)
+                .build(repo_id),
+        ];
+
+        let focus = largest_unmerged_branch(&branches, &Config::default());
+
+        assert_eq!(focus.map(|b| b.name.as_str()), Some("feature-new"));
+        assert!(largest_unmerged_branch(&branches[..2], &Config::default()).is_none());
+    }
+
+    #[test]
+    fn test_find_abandoned_branches() {
+        let repo_id = "testowner/repo";
+        let now = Utc::now();
+        let old = now - chrono::Duration::days(90);
+        let branches = vec![
+            BranchBuilder::new("main")
+                .last_commit_date(old)
+                .build(repo_id),
+            BranchBuilder::new("wip-old")
+                .ahead(3)
+                .last_commit_date(old)
+                .build(repo_id),
+            BranchBuilder::new("wip-recent").ahead(3).build(repo_id),
+            BranchBuilder::new("old-in-review")
+                .ahead(1)
+                .last_commit_date(old)
+                .build(repo_id),
+            BranchBuilder::new("old-closed-pr")
+                .ahead(2)
+                .last_commit_date(old)
+                .build(repo_id),
+        ];
+        let prs = vec![
+            PRBuilder::new(1, "Review me")
+                .head_ref_name("old-in-review")
+                .build(repo_id),
+            PRBuilder::new(2, "Gave up")
+                .state(PRState::Closed)
+                .head_ref_name("old-closed-pr")
+                .build(repo_id),
+        ];
+
+        let names: Vec<_> = find_abandoned_branches(&branches, &prs, 60, now)
+            .iter()
+            .map(|b| b.name.as_str())
+            .collect();
+
+        assert_eq!(names, vec!["wip-old", "old-closed-pr"]);
+    }
+
+    #[test]
+    fn test_compute_priority_honors_weights() {
+        let now = Utc::now();
+        let repo_id = "testowner/repo";
+        let repo = RepoBuilder::new("repo")
+            .pushed_at(now - chrono::Duration::days(14))
+            .build();
+        let branches = vec![BranchBuilder::new("feature").ahead(1).build(repo_id)];
+        let dirty = local_status(repo_id, 3, 0);
+
+        let mut recency_only = Config::default();
+        recency_only.analysis.priority = PriorityWeights {
+            recency: 1.0,
+            unmerged: 0.0,
+            open_prs: 0.0,
+            local_dirty: 0.0,
+        };
+        let priority = compute_priority(&repo, &branches, &[], Some(&dirty), now, &recency_only);
+        assert!((priority - 0.5).abs() < 1e-6);
+
+        let mut dirty_only = Config::default();
+        dirty_only.analysis.priority = PriorityWeights {
+            recency: 0.0,
+            unmerged: 0.0,
+            open_prs: 0.0,
+            local_dirty: 2.0,
+        };
+        let priority = compute_priority(&repo, &branches, &[], Some(&dirty), now, &dirty_only);
+        assert!((priority - 1.0).abs() < 1e-6);
+        assert_eq!(
+            compute_priority(&repo, &branches, &[], None, now, &dirty_only),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_compute_priority_zero_weights() {
+        let repo = RepoBuilder::new("repo").build();
+        let mut config = Config::default();
+        config.analysis.priority = PriorityWeights {
+            recency: 0.0,
+            unmerged: 0.0,
+            open_prs: 0.0,
+            local_dirty: 0.0,
+        };
+
+        assert_eq!(
+            compute_priority(&repo, &[], &[], None, Utc::now(), &config),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_repo_status() {
+        let config = Config::default();
+        let repo_id = "testowner/repo";
+        let synced = vec![BranchBuilder::new("main").build(repo_id)];
+        let ahead = vec![
+            BranchBuilder::new("main").build(repo_id),
+            BranchBuilder::new("feature").ahead(2).build(repo_id),
+        ];
+
+        assert_eq!(repo_status(&synced, None, &config), RepoStatus::Complete);
+        assert_eq!(repo_status(&ahead, None, &config), RepoStatus::NeedsSync);
+        assert_eq!(
+            repo_status(&synced, Some(&local_status(repo_id, 3, 0)), &config),
+            RepoStatus::LocalChanges
+        );
+        assert_eq!(
+            repo_status(&synced, Some(&local_status(repo_id, 3, 1)), &config),
+            RepoStatus::NeedsSync
+        );
+
+        let mut conflicted = local_status(repo_id, 0, 0);
+        conflicted.has_conflicts = true;
+        assert_eq!(
+            repo_status(&synced, Some(&conflicted), &config),
+            RepoStatus::NeedsSync
+        );
+        assert_eq!(RepoStatus::NeedsSync.to_string(), "needsSync");
+
+        // Diverged outranks everything, conflicts included
+        let mut diverged = conflicted.clone();
+        diverged.unpushed_commits = 2;
+        diverged.behind_commits = 1;
+        diverged.is_diverged = true;
+        assert_eq!(
+            repo_status(&ahead, Some(&diverged), &config),
+            RepoStatus::Diverged
+        );
+        assert!(RepoStatus::Diverged < RepoStatus::NeedsSync);
+        assert_eq!(RepoStatus::Diverged.to_string(), "diverged");
+    }
+
+    #[test]
+    fn test_attention_watcher_alerts_on_transitions_with_cooldown() {
+        let start = Utc::now();
+        let minutes = |m| start + chrono::Duration::minutes(m);
+        let statuses = |pairs: &[(&str, RepoStatus)]| -> HashMap<String, RepoStatus> {
+            pairs.iter().map(|(id, s)| (id.to_string(), *s)).collect()
+        };
+        let mut watcher = AttentionWatcher::new(chrono::Duration::minutes(60));
+
+        // The first cycle is a baseline, even for repositories already red
+        let alerts = watcher.update(
+            statuses(&[
+                ("o/a", RepoStatus::Complete),
+                ("o/b", RepoStatus::NeedsSync),
+            ]),
+            start,
+        );
+        assert!(alerts.is_empty());
+
+        // Turning red alerts once; staying red does not
+        let alerts = watcher.update(
+            statuses(&[
+                ("o/a", RepoStatus::Diverged),
+                ("o/b", RepoStatus::NeedsSync),
+            ]),
+            minutes(5),
+        );
+        assert_eq!(alerts, vec!["o/a"]);
+        let red = statuses(&[
+            ("o/a", RepoStatus::NeedsSync),
+            ("o/b", RepoStatus::NeedsSync),
+        ]);
+        assert!(watcher.update(red.clone(), minutes(10)).is_empty());
+
+        // Flapping back to red within the cooldown is suppressed...
+        let green = statuses(&[
+            ("o/a", RepoStatus::Complete),
+            ("o/b", RepoStatus::NeedsSync),
+        ]);
+        watcher.update(green.clone(), minutes(15));
+        assert!(watcher.update(red.clone(), minutes(20)).is_empty());
+
+        // ...but alerts again once it has passed
+        watcher.update(green, minutes(70));
+        assert_eq!(watcher.update(red, minutes(75)), vec!["o/a"]);
+    }
+
+    #[test]
+    fn test_scan_delta() {
+        let repo_id = "testowner/proact";
+        let before = ScanSnapshot::new(
+            repo_id,
+            &[
+                BranchBuilder::new("main").sha("aaa").build(repo_id),
+                BranchBuilder::new("feature/old").sha("bbb").build(repo_id),
+                BranchBuilder::new("feature/moving")
+                    .sha("ccc")
+                    .build(repo_id),
+            ],
+            &[
+                PRBuilder::new(42, "Old").build(repo_id),
+                PRBuilder::new(43, "Gone").build(repo_id),
+            ],
+        );
+        let after = ScanSnapshot::new(
+            repo_id,
+            &[
+                BranchBuilder::new("main").sha("aaa").build(repo_id),
+                BranchBuilder::new("feature/moving")
+                    .sha("ddd")
+                    .build(repo_id),
+                BranchBuilder::new("feature/x").sha("eee").build(repo_id),
+            ],
+            &[
+                PRBuilder::new(42, "Old")
+                    .state(PRState::Merged)
+                    .build(repo_id),
+                PRBuilder::new(44, "New").build(repo_id),
+            ],
+        );
+
+        let delta = scan_delta(&before, &after);
+
+        assert_eq!(delta.added_branches, vec!["feature/x"]);
+        assert_eq!(delta.removed_branches, vec!["feature/old"]);
+        assert_eq!(delta.updated_branches, vec!["feature/moving"]);
+        assert_eq!(delta.opened_prs, vec![44]);
+        assert_eq!(
+            delta.closed_prs,
+            vec![(42, PRState::Merged), (43, PRState::Closed)]
+        );
+        assert_eq!(
+            delta.to_string(),
+            "proact: +1 branch feature/x, -1 branch feature/old, ~1 branch feature/moving, \
+             +1 PR #44, -2 PRs #42 merged, #43 closed"
+        );
+    }
+
+    /// A minimal export repo with `branches` branches and `open_prs` open PRs
+    fn export_repo_json(id: &str, branches: usize, open_prs: usize) -> serde_json::Value {
+        let (owner, name) = id.split_once('/').unwrap();
+        let branches: Vec<_> = (0..branches)
+            .map(|i| {
+                serde_json::json!({
+                    "name": format!("branch-{}", i),
+                    "sha": "abc1234",
+                    "aheadBy": 1,
+                    "behindBy": 0,
+                    "status": "ReadyForPR",
+                    "lastCommitDate": "2025-01-01T00:00:00Z",
+                    "commits": []
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "id": id,
+            "owner": owner,
+            "name": name,
+            "language": null,
+            "lastPush": "2025-01-01T00:00:00Z",
+            "branches": branches,
+            "pullRequests": [],
+            "unmergedCount": 0,
+            "prCount": open_prs
+        })
+    }
+
+    #[test]
+    fn test_diff_exports() {
+        let old: ExportData = serde_json::from_value(serde_json::json!({
+            "groups": [
+                { "id": 1, "name": "Work", "repos": [export_repo_json("o/kept", 2, 1)] },
+                { "id": 2, "name": "Old", "repos": [export_repo_json("o/gone", 1, 0)] }
+            ],
+            "ungrouped": [export_repo_json("o/same", 1, 0)]
+        }))
+        .unwrap();
+        // o/same moving from ungrouped into a group is not a change
+        let new: ExportData = serde_json::from_value(serde_json::json!({
+            "groups": [
+                { "id": 1, "name": "Work", "repos": [export_repo_json("o/kept", 3, 0)] },
+                {
+                    "id": 3,
+                    "name": "New",
+                    "repos": [export_repo_json("o/fresh", 1, 0), export_repo_json("o/same", 1, 0)]
+                }
+            ],
+            "ungrouped": []
+        }))
+        .unwrap();
+
+        let diff = diff_exports(&old, &new);
+
+        assert_eq!(diff.added_groups, vec!["New"]);
+        assert_eq!(diff.removed_groups, vec!["Old"]);
+        assert_eq!(diff.added_repos, vec!["o/fresh"]);
+        assert_eq!(diff.removed_repos, vec!["o/gone"]);
+        assert_eq!(
+            diff.changed_repos,
+            vec![RepoCountChange {
+                repo_id: "o/kept".to_string(),
+                branches: (2, 3),
+                open_prs: (1, 0),
+            }]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "+1 group New\n-1 group Old\n+1 repo o/fresh\n-1 repo o/gone\n~ o/kept: branches 2 -> 3, open PRs 1 -> 0\n"
+        );
+        assert!(diff_exports(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_scan_delta_unchanged_is_empty() {
+        let repo_id = "testowner/quiet";
+        let snapshot = ScanSnapshot::new(
+            repo_id,
+            &[BranchBuilder::new("main").sha("aaa").build(repo_id)],
+            &[PRBuilder::new(1, "Open").build(repo_id)],
+        );
+
+        let delta = scan_delta(&snapshot, &snapshot.clone());
+
+        assert!(delta.is_empty());
+        assert_eq!(delta.to_string(), "quiet: no changes");
+    }
+
+    #[test]
+    fn test_staleness_flag() {
+        let now = Utc::now();
+        let days_ago = |days| now - chrono::Duration::days(days);
+
+        assert_eq!(staleness_flag(days_ago(0), now, 30), Staleness::Fresh);
+        assert_eq!(staleness_flag(days_ago(14), now, 30), Staleness::Fresh);
+        assert_eq!(staleness_flag(days_ago(15), now, 30), Staleness::Aging);
+        assert_eq!(staleness_flag(days_ago(29), now, 30), Staleness::Aging);
+        assert_eq!(staleness_flag(days_ago(30), now, 30), Staleness::Stale);
+        assert_eq!(staleness_flag(days_ago(400), now, 30), Staleness::Stale);
+    }
+
+    #[test]
+    fn test_staleness_flag_future_push_is_fresh() {
+        let now = Utc::now();
+        let pushed_at = now + chrono::Duration::hours(1);
+
+        assert_eq!(staleness_flag(pushed_at, now, 30), Staleness::Fresh);
+    }
+
+    #[test]
+    fn test_build_report_groups_appends_ungrouped() -> Result<()> {
+        let test_db = crate::test_support::TestDatabase::new()?
+            .with_repo(RepoBuilder::new("grouped"))?
+            .with_repo(RepoBuilder::new("loose"))?
+            .with_branch("testowner/grouped", BranchBuilder::new("feature").ahead(1))?
+            .with_group("Active", 0, vec!["testowner/grouped"])?;
+        let groups = test_db.db.get_all_groups()?;
+
+        let report_groups = build_report_groups(&test_db.db, &groups, true)?;
+
+        let names: Vec<_> = report_groups.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, vec!["Active", "Ungrouped"]);
+        assert_eq!(report_groups[0].repos[0].branches.len(), 1);
+        assert_eq!(report_groups[1].repos[0].repo.id, "testowner/loose");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reclassify_repo_uses_stored_prs() -> Result<()> {
+        let repo_id = "testowner/proj";
+        let test_db = crate::test_support::TestDatabase::new()?
+            .with_repo(RepoBuilder::new("proj"))?
+            .with_branch(repo_id, BranchBuilder::new("main"))?
+            .with_branch(
+                repo_id,
+                BranchBuilder::new("feature/x")
+                    .ahead(1)
+                    .with_status(BranchStatus::ReadyForPR),
+            )?
+            .with_branch(
+                repo_id,
+                BranchBuilder::new("feature/y")
+                    .ahead(1)
+                    .with_status(BranchStatus::InReview),
+            )?
+            .with_pull_request(
+                repo_id,
+                PRBuilder::new(1, "X")
+                    .head_ref_name("feature/x")
+                    .build(repo_id),
+            )?;
+        let config = Config::default();
+
+        let branches = test_db.db.get_branches_for_repo(repo_id)?;
+        assert_eq!(
+            infer_default_branch(&branches, &config),
+            Some("main".to_string())
+        );
+
+        // feature/x has an open PR; feature/y's PR is gone
+        assert_eq!(reclassify_repo(&test_db.db, repo_id, &config)?, 2);
+        let statuses: BTreeMap<_, _> = test_db
+            .db
+            .get_branches_for_repo(repo_id)?
+            .into_iter()
+            .map(|b| (b.name, b.status))
+            .collect();
+        assert_eq!(statuses["main"], BranchStatus::ReadyForPR);
+        assert_eq!(statuses["feature/x"], BranchStatus::InReview);
+        assert_eq!(statuses["feature/y"], BranchStatus::ReadyForPR);
+
+        assert_eq!(reclassify_repo(&test_db.db, repo_id, &config)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_local_only_repos_reads_origin() -> Result<()> {
+        let test_db =
+            crate::test_support::TestDatabase::new()?.with_repo(RepoBuilder::new("tracked"))?;
+        let root = tempfile::tempdir()?;
+        let owner_dir = root.path().join("testowner");
+        for name in ["tracked", "scratch", "offline"] {
+            let clone = owner_dir.join(name);
+            std::fs::create_dir_all(&clone)?;
+            std::process::Command::new("git")
+                .args(["init", "-q"])
+                .current_dir(&clone)
+                .output()?;
+        }
+        std::process::Command::new("git")
+            .args([
+                "remote",
+                "add",
+                "origin",
+                "git@github.com:someone/renamed.git",
+            ])
+            .current_dir(owner_dir.join("scratch"))
+            .output()?;
+        test_db
+            .db
+            .add_local_repo_root(&owner_dir.to_string_lossy())?;
+
+        let local_only = find_local_only_repos(&test_db.db)?;
+
+        assert_eq!(
+            local_only
+                .iter()
+                .map(|r| (r.repo_id.as_str(), r.remote_repo_id.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("testowner/offline", None),
+                ("testowner/scratch", Some("someone/renamed"))
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_html_report_escapes_text() {
+        let report = render_html_report(&sample_groups(), &HashMap::new(), &Config::default());
+
+        assert!(report.contains("<h2>Active Projects</h2>"));
+        assert!(report.contains("<li>PR #12: Add &lt;widgets&gt;</li>"));
+        assert!(!report.contains("testowner/quiet"));
+    }
+}
@@ -1,18 +1,304 @@
 //! Configuration management
 
-use crate::Result;
+use crate::{models::BranchStatus, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
     pub version: String,
     pub github: GitHubConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub analysis: AnalysisConfig,
+    #[serde(default)]
+    pub ui: UiConfig,
+    #[serde(default)]
+    pub tools: ToolsConfig,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GitHubConfig {
-    pub owners: Vec<String>,
+    /// Users or organizations to track: plain names or `{ name, limit }` tables
+    pub owners: Vec<OwnerConfig>,
+    /// Repositories fetched per owner unless the owner sets its own `limit`
     pub repo_limit: usize,
+    /// Branches that never get PRs or count as unmerged work; a trailing `*`
+    /// matches any suffix (e.g. `release/*`)
+    #[serde(default = "default_protected_branches")]
+    pub protected_branches: Vec<String>,
+    /// Most open pull requests fetched per repository; scans warn when it is
+    /// reached. Recent merged and closed ones are fetched on top, separately
+    /// bounded
+    #[serde(default = "default_pr_limit")]
+    pub pr_limit: usize,
+    /// Repositories never tracked: exact ids or globs like `owner/test-*`.
+    /// Patterns added with `overall exclude add` apply as well.
+    #[serde(default)]
+    pub repo_exclude: Vec<String>,
+    /// Commits fetched per branch, keyed by branch status (e.g. `ReadyForPR`);
+    /// statuses not listed use `default_commit_limit`
+    #[serde(default = "default_commit_limits")]
+    pub commit_limits: BTreeMap<String, usize>,
+    #[serde(default = "default_commit_limit")]
+    pub default_commit_limit: usize,
+}
+
+impl GitHubConfig {
+    /// How many repositories to fetch for an owner: its own limit if it has
+    /// one, otherwise `repo_limit`
+    pub fn repo_limit_for(&self, owner: &str) -> usize {
+        self.owners
+            .iter()
+            .find(|o| o.name.eq_ignore_ascii_case(owner))
+            .and_then(|o| o.limit)
+            .unwrap_or(self.repo_limit)
+    }
+
+    /// The team an owner's scans are restricted to, if it sets one
+    pub fn team_for(&self, owner: &str) -> Option<&str> {
+        self.owners
+            .iter()
+            .find(|o| o.name.eq_ignore_ascii_case(owner))
+            .and_then(|o| o.team.as_deref())
+    }
+
+    /// How many commits a scan fetches for a branch with the given status
+    pub fn commit_limit_for(&self, status: &BranchStatus) -> usize {
+        self.commit_limits
+            .get(&status.to_string())
+            .copied()
+            .unwrap_or(self.default_commit_limit)
+    }
+}
+
+/// A tracked GitHub user or organization
+///
+/// Written either as a plain name (`"softwarewrighter"`, the original form)
+/// or as a table (`{ name = "noisy-org", limit = 10, team = "core" }`).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(from = "OwnerEntry")]
+pub struct OwnerConfig {
+    pub name: String,
+    /// Overrides `repo_limit` for this owner
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Organization team slug; scans of this owner only include the team's repos
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+}
+
+impl OwnerConfig {
+    pub fn new(name: &str) -> Self {
+        OwnerConfig {
+            name: name.to_string(),
+            limit: None,
+            team: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OwnerEntry {
+    Name(String),
+    Table {
+        name: String,
+        #[serde(default)]
+        limit: Option<usize>,
+        #[serde(default)]
+        team: Option<String>,
+    },
+}
+
+impl From<OwnerEntry> for OwnerConfig {
+    fn from(entry: OwnerEntry) -> Self {
+        match entry {
+            OwnerEntry::Name(name) => OwnerConfig::new(&name),
+            OwnerEntry::Table { name, limit, team } => OwnerConfig { name, limit, team },
+        }
+    }
+}
+
+/// Branches ready for a PR get enough history to write the PR body
+fn default_commit_limits() -> BTreeMap<String, usize> {
+    BTreeMap::from([(BranchStatus::ReadyForPR.to_string(), 50)])
+}
+
+fn default_commit_limit() -> usize {
+    10
+}
+
+fn default_pr_limit() -> usize {
+    100
+}
+
+fn default_protected_branches() -> Vec<String> {
+    vec![
+        "main".to_string(),
+        "master".to_string(),
+        "develop".to_string(),
+    ]
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StorageConfig {
+    /// Keep commit subjects plus at most this many characters of the body;
+    /// `None` stores messages in full
+    #[serde(default)]
+    pub max_commit_body_len: Option<usize>,
+    /// Automatic backups kept before older ones are deleted
+    #[serde(default = "default_backups_to_keep")]
+    pub backups_to_keep: usize,
+}
+
+fn default_backups_to_keep() -> usize {
+    10
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            max_commit_body_len: None,
+            backups_to_keep: default_backups_to_keep(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnalysisConfig {
+    /// Days without a push after which a repository is flagged as stale
+    #[serde(default = "default_stale_threshold_days")]
+    pub stale_threshold_days: i64,
+    /// Days without a commit after which an unmerged branch with no open PR
+    /// is reported as abandoned
+    #[serde(default = "default_abandoned_threshold_days")]
+    pub abandoned_threshold_days: i64,
+    #[serde(default)]
+    pub priority: PriorityWeights,
+}
+
+/// Relative weights of the factors combined into a repository's priority
+///
+/// Weights are normalized by their sum, so only their ratios matter.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct PriorityWeights {
+    /// How recently the repository was pushed
+    pub recency: f32,
+    /// Branches with work not yet on the default branch
+    pub unmerged: f32,
+    /// Open pull requests awaiting review or merge
+    pub open_prs: f32,
+    /// Uncommitted or unpushed work in a local clone
+    pub local_dirty: f32,
+}
+
+impl Default for PriorityWeights {
+    fn default() -> Self {
+        PriorityWeights {
+            recency: 0.4,
+            unmerged: 0.3,
+            open_prs: 0.2,
+            local_dirty: 0.1,
+        }
+    }
+}
+
+fn default_stale_threshold_days() -> i64 {
+    30
+}
+
+fn default_abandoned_threshold_days() -> i64 {
+    60
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        AnalysisConfig {
+            stale_threshold_days: default_stale_threshold_days(),
+            abandoned_threshold_days: default_abandoned_threshold_days(),
+            priority: PriorityWeights::default(),
+        }
+    }
+}
+
+/// Web UI preferences, served to the UI from `/api/ui-config`
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct UiConfig {
+    /// Repository list order before any column header is clicked
+    pub default_sort: SortConfig,
+    /// Regenerate static/repos.json after every CLI command that changes
+    /// the database, as if `--export` were passed
+    pub auto_export: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct SortConfig {
+    pub column: SortColumn,
+    pub ascending: bool,
+}
+
+impl Default for SortConfig {
+    fn default() -> Self {
+        SortConfig {
+            column: SortColumn::Status,
+            ascending: false,
+        }
+    }
+}
+
+/// Repository list columns the UI can sort by
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SortColumn {
+    Name,
+    Language,
+    LastUpdated,
+    Priority,
+    Status,
+}
+
+/// External programs overall runs
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct ToolsConfig {
+    /// `gh` binary: a name looked up on PATH or a full path;
+    /// `OVERALL_GH_PATH` overrides it
+    pub gh_path: String,
+    /// `git` binary, likewise; `OVERALL_GIT_PATH` overrides it
+    pub git_path: String,
+}
+
+impl Default for ToolsConfig {
+    fn default() -> Self {
+        ToolsConfig {
+            gh_path: "gh".to_string(),
+            git_path: "git".to_string(),
+        }
+    }
+}
+
+impl ToolsConfig {
+    /// The `gh` binary to run, honoring `OVERALL_GH_PATH`
+    pub fn gh(&self) -> String {
+        tool_path(std::env::var("OVERALL_GH_PATH").ok(), &self.gh_path)
+    }
+
+    /// The `git` binary to run, honoring `OVERALL_GIT_PATH`
+    pub fn git(&self) -> String {
+        tool_path(std::env::var("OVERALL_GIT_PATH").ok(), &self.git_path)
+    }
+}
+
+/// A non-blank environment override wins over the configured path
+fn tool_path(env_override: Option<String>, configured: &str) -> String {
+    env_override
+        .filter(|path| !path.trim().is_empty())
+        .unwrap_or_else(|| configured.to_string())
 }
 
 impl Default for Config {
@@ -20,9 +306,18 @@ impl Default for Config {
         Config {
             version: "1.0".to_string(),
             github: GitHubConfig {
-                owners: vec!["softwarewrighter".to_string()],
+                owners: vec![OwnerConfig::new("softwarewrighter")],
                 repo_limit: 50,
+                protected_branches: default_protected_branches(),
+                pr_limit: default_pr_limit(),
+                repo_exclude: Vec::new(),
+                commit_limits: default_commit_limits(),
+                default_commit_limit: default_commit_limit(),
             },
+            storage: StorageConfig::default(),
+            analysis: AnalysisConfig::default(),
+            ui: UiConfig::default(),
+            tools: ToolsConfig::default(),
         }
     }
 }
@@ -33,3 +328,106 @@ impl Config {
         Ok(Self::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_limit_for_status() {
+        let mut github = Config::default().github;
+        assert_eq!(github.commit_limit_for(&BranchStatus::ReadyForPR), 50);
+        assert_eq!(github.commit_limit_for(&BranchStatus::InReview), 10);
+
+        github.commit_limits.insert("InReview".to_string(), 3);
+        assert_eq!(github.commit_limit_for(&BranchStatus::InReview), 3);
+    }
+
+    #[test]
+    fn test_owners_accept_names_and_tables() {
+        let github: GitHubConfig = toml::from_str(
+            r#"
+            owners = ["softwarewrighter", { name = "noisy-org", limit = 10 }, { name = "big-org", limit = 200 }]
+            repo_limit = 50
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            github.owners[1],
+            OwnerConfig {
+                name: "noisy-org".to_string(),
+                limit: Some(10),
+                team: None,
+            }
+        );
+        assert_eq!(github.repo_limit_for("softwarewrighter"), 50);
+        assert_eq!(github.repo_limit_for("Noisy-Org"), 10);
+        assert_eq!(github.repo_limit_for("big-org"), 200);
+        // Owners not in the config (e.g. given on the command line) use the default
+        assert_eq!(github.repo_limit_for("someone-else"), 50);
+
+        // The original list-of-names form still loads
+        let github: GitHubConfig =
+            toml::from_str("owners = [\"a\", \"b\"]\nrepo_limit = 5").unwrap();
+        assert_eq!(
+            github.owners,
+            vec![OwnerConfig::new("a"), OwnerConfig::new("b")]
+        );
+    }
+
+    #[test]
+    fn test_team_is_scoped_to_its_owner() {
+        let github: GitHubConfig = toml::from_str(
+            r#"
+            owners = ["softwarewrighter", { name = "big-org", team = "core" }]
+            repo_limit = 50
+            "#,
+        )
+        .unwrap();
+        assert_eq!(github.team_for("Big-Org"), Some("core"));
+        assert_eq!(github.team_for("softwarewrighter"), None);
+        assert_eq!(github.team_for("someone-else"), None);
+    }
+
+    #[test]
+    fn test_tool_path_prefers_env_override() {
+        assert_eq!(tool_path(None, "gh"), "gh");
+        assert_eq!(tool_path(Some("  ".to_string()), "gh"), "gh");
+        assert_eq!(
+            tool_path(Some("/nix/store/abc-gh/bin/gh".to_string()), "gh"),
+            "/nix/store/abc-gh/bin/gh"
+        );
+
+        let tools: ToolsConfig = toml::from_str("git_path = \"/opt/git/bin/git\"").unwrap();
+        assert_eq!(tools.git_path, "/opt/git/bin/git");
+        assert_eq!(tools.gh_path, "gh");
+    }
+
+    #[test]
+    fn test_ui_default_sort_from_toml() {
+        let ui: UiConfig = toml::from_str(
+            r#"
+            [default_sort]
+            column = "lastUpdated"
+            ascending = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            ui.default_sort,
+            SortConfig {
+                column: SortColumn::LastUpdated,
+                ascending: true,
+            }
+        );
+
+        // Missing keys keep the built-in Status, most urgent first
+        let ui: UiConfig = toml::from_str("[default_sort]\ncolumn = \"name\"").unwrap();
+        assert_eq!(ui.default_sort.column, SortColumn::Name);
+        assert!(!ui.default_sort.ascending);
+        assert_eq!(
+            toml::from_str::<UiConfig>("").unwrap().default_sort,
+            SortConfig::default()
+        );
+    }
+}
@@ -31,6 +31,21 @@ pub enum Error {
 
     #[error("Invalid GitHub owner name: {0}")]
     InvalidOwner(String),
+
+    #[error("Invalid repository id: {0}")]
+    InvalidRepoId(String),
+
+    #[error("Invalid group name: {0}")]
+    InvalidGroupName(String),
+
+    #[error("Invalid tag: {0}")]
+    InvalidTag(String),
+
+    #[error("Invalid duration: {0}")]
+    InvalidDuration(String),
+
+    #[error("Export schema version {found} is newer than supported version {supported}; upgrade overall")]
+    UnsupportedExportVersion { found: u32, supported: u32 },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
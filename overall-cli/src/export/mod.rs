@@ -0,0 +1,1134 @@
+//! Export of tracked data to the repos.json format consumed by the web UI,
+//! and import of such a document back into the database
+
+use crate::{
+    analysis,
+    config::Config,
+    models::{
+        short_sha, validate_group_name, Branch, BranchStatus, CiStatus, Commit, Group, PRState,
+        PullRequest, Repository,
+    },
+    storage::Database,
+    Error, Result,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Version of the repos.json shape, written as `schemaVersion`
+///
+/// Bump it whenever the export shape changes in a way older readers can't
+/// handle, together with `EXPORT_SCHEMA_VERSION` in the wasm UI.
+///
+/// 2: `branches[].commits` holds only the newest `EXPORT_COMMITS_PER_BRANCH`
+/// commits, with `moreCommits` counting the rest.
+pub const EXPORT_SCHEMA_VERSION: u32 = 2;
+
+/// Most recent commits inlined per branch; the rest are counted in
+/// `moreCommits` and loaded on demand from the commits endpoint
+pub const EXPORT_COMMITS_PER_BRANCH: usize = 20;
+
+/// Top-level repos.json document
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportData {
+    /// `EXPORT_SCHEMA_VERSION` when written; 0 for exports that predate it
+    #[serde(default)]
+    pub schema_version: u32,
+    #[serde(default)]
+    pub generated_at: Option<DateTime<Utc>>,
+    pub groups: Vec<ExportGroup>,
+    pub ungrouped: Vec<ExportRepo>,
+    /// Configured protected branch patterns, so the UI applies the same list
+    #[serde(default)]
+    pub protected_branches: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportGroup {
+    pub id: i64,
+    pub name: String,
+    pub repos: Vec<ExportRepo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRepo {
+    pub id: String,
+    pub owner: String,
+    pub name: String,
+    /// Primary language, `null` when GitHub detected none
+    pub language: Option<String>,
+    /// Always written; older exports may lack it, and importing one keeps
+    /// the stored visibility
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_private: Option<bool>,
+    pub last_push: String,
+    /// Fresh, Aging or Stale relative to the configured days-since-push threshold
+    #[serde(default)]
+    pub staleness: String,
+    /// diverged, needsSync, localChanges, stale or complete (see `analysis::repo_status`)
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub priority: f32,
+    /// The priority is a manual override (see `Database::set_priority_override`)
+    #[serde(default)]
+    pub priority_locked: bool,
+    pub branches: Vec<ExportBranch>,
+    pub pull_requests: Vec<ExportPullRequest>,
+    pub unmerged_count: usize,
+    pub pr_count: usize,
+    /// Unmerged branch with the most commits ahead (see `analysis::largest_unmerged_branch`)
+    #[serde(default)]
+    pub focus_branch: Option<String>,
+    /// Local tags (see `Database::add_tag`), sorted
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Check runs on the default branch at the last scan
+    #[serde(default)]
+    pub ci_status: CiStatus,
+    /// Deferred until this time (see `Database::set_snooze`); `status` ignores
+    /// the snooze, so readers treat the repo as complete only while it lasts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snoozed_until: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportBranch {
+    pub name: String,
+    pub sha: String,
+    /// `sha` abbreviated for display (see `models::short_sha`)
+    #[serde(default)]
+    pub short_sha: String,
+    pub ahead_by: u32,
+    pub behind_by: u32,
+    pub status: String,
+    pub last_commit_date: String,
+    #[serde(default)]
+    pub has_open_pr: bool,
+    /// Whether the branch counts towards the repo's `unmergedCount`
+    #[serde(default)]
+    pub is_unmerged: bool,
+    /// Old work with no open PR (see `analysis::find_abandoned_branches`)
+    #[serde(default)]
+    pub is_abandoned: bool,
+    /// Newest first, at most `EXPORT_COMMITS_PER_BRANCH`
+    pub commits: Vec<ExportCommit>,
+    /// Stored commits left out of `commits`
+    #[serde(default)]
+    pub more_commits: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportCommit {
+    pub sha: String,
+    #[serde(default)]
+    pub short_sha: String,
+    pub message: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub authored_date: String,
+    pub committer_name: String,
+    pub committer_email: String,
+    pub committed_date: String,
+    #[serde(default)]
+    pub is_verified: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPullRequest {
+    pub number: u32,
+    pub title: String,
+    pub state: String,
+    #[serde(default)]
+    pub head_ref_name: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    /// `Approved`, `ChangesRequested` or `ReviewRequired`
+    #[serde(default)]
+    pub review_decision: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Counts of records written by an import
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub groups: usize,
+    pub repos: usize,
+    pub branches: usize,
+    pub commits: usize,
+    pub pull_requests: usize,
+}
+
+/// Build the repos.json document for the given groups
+///
+/// Only the groups passed in are exported, so callers can narrow the export
+/// by pre-filtering the list. Ungrouped repositories are only included when
+/// `include_ungrouped` is set; otherwise the `ungrouped` array is left empty.
+pub fn build_export(
+    db: &Database,
+    groups: &[Group],
+    include_ungrouped: bool,
+    config: &Config,
+) -> Result<Value> {
+    let mut export_data = ExportData {
+        schema_version: EXPORT_SCHEMA_VERSION,
+        generated_at: Some(Utc::now()),
+        groups: Vec::new(),
+        ungrouped: Vec::new(),
+        protected_branches: config.github.protected_branches.clone(),
+    };
+
+    for group in groups {
+        let repos = db.get_repos_in_group(group.id).unwrap_or_default();
+        export_data.groups.push(ExportGroup {
+            id: group.id,
+            name: group.name.clone(),
+            repos: repos
+                .into_iter()
+                .map(|r| export_repo(db, r, config))
+                .collect(),
+        });
+    }
+
+    if include_ungrouped {
+        let ungrouped = db.get_ungrouped_repositories().unwrap_or_default();
+        export_data.ungrouped = ungrouped
+            .into_iter()
+            .map(|r| export_repo(db, r, config))
+            .collect();
+    }
+
+    Ok(serde_json::to_value(export_data)?)
+}
+
+/// Keep only private (`private == true`) or only public repositories in an
+/// export built by [`build_export`]
+pub fn filter_by_visibility(export: Value, private: bool) -> Result<Value> {
+    let mut data: ExportData = serde_json::from_value(export)?;
+    for group in &mut data.groups {
+        group.repos.retain(|r| r.is_private == Some(private));
+    }
+    data.ungrouped.retain(|r| r.is_private == Some(private));
+    Ok(serde_json::to_value(data)?)
+}
+
+/// Build the export for every group plus the ungrouped repositories
+pub fn build_full_export(db: &Database, config: &Config) -> Result<Value> {
+    let groups = db.get_all_groups()?;
+    build_export(db, &groups, true, config)
+}
+
+/// What a repos.json regeneration wrote
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegenerateSummary {
+    pub path: PathBuf,
+    pub groups: usize,
+    pub repos: usize,
+}
+
+/// Rebuild the full export from the database and write it to `path`
+///
+/// Shared by the server, after its mutating endpoints, and the CLI, after
+/// mutating commands, so both keep the served repos.json in step.
+pub fn regenerate_repos_json(
+    db: &Database,
+    path: &Path,
+    config: &Config,
+) -> Result<RegenerateSummary> {
+    let export_data = build_full_export(db, config)?;
+    write_export(path, &export_data)?;
+
+    let groups = export_data["groups"].as_array().map_or(&[][..], |g| g);
+    let grouped_repos: usize = groups
+        .iter()
+        .map(|g| g["repos"].as_array().map_or(0, |r| r.len()))
+        .sum();
+    let ungrouped_repos = export_data["ungrouped"].as_array().map_or(0, |r| r.len());
+
+    Ok(RegenerateSummary {
+        path: path.to_path_buf(),
+        groups: groups.len(),
+        repos: grouped_repos + ungrouped_repos,
+    })
+}
+
+/// Write an export to `path` atomically, retrying once on failure
+///
+/// The document is written to a temporary file next to `path` and renamed
+/// over it, so readers never see a partially written repos.json.
+pub fn write_export(path: &Path, data: &Value) -> Result<()> {
+    let json = serde_json::to_string_pretty(data)?;
+    write_atomic(path, &json).or_else(|_| write_atomic(path, &json))
+}
+
+fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let tmp_path = temp_path_for(path);
+    if let Err(e) =
+        std::fs::write(&tmp_path, contents).and_then(|()| std::fs::rename(&tmp_path, path))
+    {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Per-process counter so concurrent writers never share a temp file
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Unique sibling temp file, so the final rename stays on one filesystem
+fn temp_path_for(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let n = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{}.{}.{}.tmp", file_name, std::process::id(), n))
+}
+
+/// Build the export entry for a single repository, including branches,
+/// commits and pull requests
+pub(crate) fn repo_to_json(db: &Database, repo: Repository, config: &Config) -> Value {
+    serde_json::to_value(export_repo(db, repo, config)).unwrap_or_default()
+}
+
+fn export_repo(db: &Database, repo: Repository, config: &Config) -> ExportRepo {
+    let branches = db.get_branches_for_repo(&repo.id).unwrap_or_default();
+    let prs = db.get_pull_requests_for_repo(&repo.id).unwrap_or_default();
+
+    let local_status = db.get_local_repo_status(&repo.id).unwrap_or_default();
+    let tags = db.get_tags_for_repo(&repo.id).unwrap_or_default();
+    let ci_status = db.get_ci_status(&repo.id).unwrap_or_default();
+
+    let unmerged_count = analysis::branch_status_summary(&branches, config).unmerged;
+    let focus_branch = analysis::largest_unmerged_branch(&branches, config).map(|b| b.name.clone());
+    let abandoned: Vec<String> = analysis::find_abandoned_branches(
+        &branches,
+        &prs,
+        config.analysis.abandoned_threshold_days,
+        Utc::now(),
+    )
+    .into_iter()
+    .map(|b| b.name.clone())
+    .collect();
+    let open_pr_count = prs
+        .iter()
+        .filter(|pr| matches!(pr.state, PRState::Open))
+        .count();
+
+    ExportRepo {
+        id: repo.id,
+        owner: repo.owner,
+        name: repo.name,
+        language: repo.language,
+        is_private: Some(repo.is_private),
+        last_push: repo.pushed_at.to_rfc3339(),
+        staleness: analysis::staleness_flag(
+            repo.pushed_at,
+            Utc::now(),
+            config.analysis.stale_threshold_days,
+        )
+        .to_string(),
+        status: analysis::repo_status(&branches, local_status.as_ref(), config).to_string(),
+        priority: repo.priority,
+        priority_locked: repo.priority_locked,
+        branches: branches
+            .iter()
+            .map(|b| {
+                let commits = db
+                    .get_commits_for_branch_limited(b.id, EXPORT_COMMITS_PER_BRANCH)
+                    .unwrap_or_default();
+                let total_commits = db.count_commits_for_branch(b.id).unwrap_or_default();
+                // Link PRs to branches by head ref so the UI knows a PR already exists
+                let has_open_pr = prs
+                    .iter()
+                    .any(|pr| pr.state == PRState::Open && pr.head_ref_name == b.name);
+                ExportBranch {
+                    name: b.name.clone(),
+                    sha: b.sha.clone(),
+                    short_sha: short_sha(&b.sha).to_string(),
+                    ahead_by: b.ahead_by,
+                    behind_by: b.behind_by,
+                    status: b.status.to_string(),
+                    last_commit_date: b.last_commit_date.to_rfc3339(),
+                    has_open_pr,
+                    is_unmerged: analysis::is_unmerged(b, config),
+                    is_abandoned: abandoned.contains(&b.name),
+                    commits: commits.iter().map(export_commit).collect(),
+                    more_commits: total_commits.saturating_sub(commits.len()),
+                }
+            })
+            .collect(),
+        pull_requests: prs
+            .iter()
+            .map(|pr| ExportPullRequest {
+                number: pr.number,
+                title: pr.title.clone(),
+                state: pr.state.to_string(),
+                head_ref_name: pr.head_ref_name.clone(),
+                url: pr.url.clone(),
+                review_decision: pr.review_decision.map(|d| d.to_string()),
+                created_at: pr.created_at.to_rfc3339(),
+                updated_at: pr.updated_at.to_rfc3339(),
+            })
+            .collect(),
+        unmerged_count,
+        pr_count: open_pr_count,
+        focus_branch,
+        tags,
+        ci_status,
+        snoozed_until: repo.snoozed_until.map(|until| until.to_rfc3339()),
+    }
+}
+
+/// A stored commit in the repos.json shape, also served by the commits endpoint
+pub fn export_commit(commit: &Commit) -> ExportCommit {
+    ExportCommit {
+        sha: commit.sha.clone(),
+        short_sha: short_sha(&commit.sha).to_string(),
+        // Bodies can be huge (squash merges); the UI only shows subjects
+        message: commit.subject().to_string(),
+        author_name: commit.author_name.clone(),
+        author_email: commit.author_email.clone(),
+        authored_date: commit.authored_date.to_rfc3339(),
+        committer_name: commit.committer_name.clone(),
+        committer_email: commit.committer_email.clone(),
+        committed_date: commit.committed_date.to_rfc3339(),
+        is_verified: commit.is_verified,
+    }
+}
+
+/// Parse a repos.json document, rejecting anything that is not an export
+/// or that was written by a newer overall with a shape this one can't read
+pub fn parse_export(json: &str) -> Result<ExportData> {
+    let data: ExportData = serde_json::from_str(json)?;
+    if data.schema_version > EXPORT_SCHEMA_VERSION {
+        return Err(Error::UnsupportedExportVersion {
+            found: data.schema_version,
+            supported: EXPORT_SCHEMA_VERSION,
+        });
+    }
+    Ok(data)
+}
+
+/// Populate the database from a parsed export
+///
+/// Repositories are upserted, keeping local-only fields (priority,
+/// description) of repositories that already exist, except that a locked
+/// (manually overridden) priority in the export is applied. Each repository's
+/// branches and pull requests are replaced by the exported ones. A branch's
+/// stored commits are kept alongside the exported ones when the export left
+/// some out (`moreCommits`), so importing never loses older history.
+/// Groups are matched by name (ignoring ASCII case) and created when missing.
+/// The import is all or nothing: if any entry fails, nothing is written.
+pub fn import_export(db: &Database, data: &ExportData) -> Result<ImportSummary> {
+    db.atomically(|db| import_all(db, data))
+}
+
+fn import_all(db: &Database, data: &ExportData) -> Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+    let mut existing_groups = db.get_all_groups()?;
+
+    for (index, group) in data.groups.iter().enumerate() {
+        // A group that collides with the synthetic ungrouped section (from
+        // before such names were rejected) is imported as ungrouped
+        if validate_group_name(&group.name).is_err() {
+            for repo in &group.repos {
+                import_repo(db, repo, &mut summary)?;
+            }
+            continue;
+        }
+
+        let group_id = match existing_groups
+            .iter()
+            .find(|g| g.name.eq_ignore_ascii_case(&group.name))
+        {
+            Some(existing) => existing.id,
+            None => {
+                let id = db.create_group(&group.name, index as i32)?;
+                existing_groups = db.get_all_groups()?;
+                id
+            }
+        };
+        summary.groups += 1;
+
+        for repo in &group.repos {
+            import_repo(db, repo, &mut summary)?;
+            db.add_repo_to_group(&repo.id, group_id)?;
+        }
+    }
+
+    for repo in &data.ungrouped {
+        import_repo(db, repo, &mut summary)?;
+    }
+
+    Ok(summary)
+}
+
+fn import_repo(db: &Database, repo: &ExportRepo, summary: &mut ImportSummary) -> Result<()> {
+    let pushed_at = parse_date(&repo.last_push, &repo.id)?;
+    let existing = db.get_repository(&repo.id)?;
+
+    db.save_repository(&Repository {
+        id: repo.id.clone(),
+        owner: repo.owner.clone(),
+        name: repo.name.clone(),
+        // Older exports wrote "Unknown" for repositories without a language
+        language: repo.language.clone().filter(|l| l != "Unknown"),
+        description: existing.as_ref().and_then(|r| r.description.clone()),
+        pushed_at,
+        created_at: existing.as_ref().map_or(pushed_at, |r| r.created_at),
+        updated_at: existing.as_ref().map_or(pushed_at, |r| r.updated_at),
+        is_fork: existing.as_ref().is_some_and(|r| r.is_fork),
+        priority: existing.as_ref().map_or(repo.priority, |r| r.priority),
+        is_private: repo
+            .is_private
+            .unwrap_or_else(|| existing.as_ref().is_some_and(|r| r.is_private)),
+        priority_locked: repo.priority_locked,
+        // save_repository keeps any stored GitHub id
+        github_id: None,
+        snoozed_until: None,
+    })?;
+    // The upsert keeps a stored repo's lock; a manual override in the export
+    // wins over the local priority
+    if repo.priority_locked {
+        db.set_priority_override(&repo.id, Some(repo.priority))?;
+    }
+    summary.repos += 1;
+
+    // Commits an export left out are only in the database; keep them
+    let mut previous_commits = HashMap::new();
+    for branch in db.get_branches_for_repo(&repo.id)? {
+        previous_commits.insert(branch.name, db.get_commits_for_branch(branch.id)?);
+    }
+
+    db.clear_branches_for_repo(&repo.id)?;
+    for branch in &repo.branches {
+        let status: BranchStatus = branch.status.parse().map_err(Error::Config)?;
+        let branch_id = db.save_branch(&Branch {
+            id: 0,
+            repo_id: repo.id.clone(),
+            name: branch.name.clone(),
+            sha: branch.sha.clone(),
+            ahead_by: branch.ahead_by,
+            behind_by: branch.behind_by,
+            status,
+            last_commit_date: parse_date(&branch.last_commit_date, &repo.id)?,
+        })?;
+        summary.branches += 1;
+
+        for commit in &branch.commits {
+            db.save_commit(&Commit {
+                id: 0,
+                branch_id,
+                sha: commit.sha.clone(),
+                message: commit.message.clone(),
+                author_name: commit.author_name.clone(),
+                author_email: commit.author_email.clone(),
+                authored_date: parse_date(&commit.authored_date, &repo.id)?,
+                committer_name: commit.committer_name.clone(),
+                committer_email: commit.committer_email.clone(),
+                committed_date: parse_date(&commit.committed_date, &repo.id)?,
+                is_verified: commit.is_verified,
+            })?;
+            summary.commits += 1;
+        }
+
+        if branch.more_commits > 0 {
+            let previous = previous_commits.remove(&branch.name).unwrap_or_default();
+            for commit in previous
+                .into_iter()
+                .filter(|c| !branch.commits.iter().any(|e| e.sha == c.sha))
+            {
+                db.save_commit(&Commit {
+                    id: 0,
+                    branch_id,
+                    ..commit
+                })?;
+            }
+        }
+    }
+
+    db.clear_pull_requests_for_repo(&repo.id)?;
+    let stored_branches = db.get_branches_for_repo(&repo.id)?;
+    for pr in &repo.pull_requests {
+        let state: PRState = pr.state.parse().map_err(Error::Config)?;
+        db.save_pull_request(&PullRequest {
+            id: 0,
+            repo_id: repo.id.clone(),
+            branch_id: stored_branches
+                .iter()
+                .find(|b| b.name == pr.head_ref_name)
+                .map(|b| b.id),
+            number: pr.number,
+            state,
+            title: pr.title.clone(),
+            head_ref_name: pr.head_ref_name.clone(),
+            url: pr.url.clone(),
+            review_decision: pr
+                .review_decision
+                .as_deref()
+                .map(str::parse)
+                .transpose()
+                .map_err(Error::Config)?,
+            created_at: parse_date(&pr.created_at, &repo.id)?,
+            updated_at: parse_date(&pr.updated_at, &repo.id)?,
+        })?;
+        summary.pull_requests += 1;
+    }
+
+    for tag in &repo.tags {
+        db.add_tag(&repo.id, tag)?;
+    }
+    if repo.ci_status != CiStatus::None {
+        db.save_ci_status(&repo.id, repo.ci_status)?;
+    }
+    if let Some(until) = &repo.snoozed_until {
+        db.set_snooze(&repo.id, Some(parse_date(until, &repo.id)?))?;
+    }
+
+    Ok(())
+}
+
+fn parse_date(value: &str, repo_id: &str) -> Result<DateTime<Utc>> {
+    value
+        .parse()
+        .map_err(|_| Error::Config(format!("Invalid date '{}' in {}", value, repo_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ReviewDecision;
+    use crate::test_support::fixtures::CommitBuilder;
+    use crate::test_support::{BranchBuilder, PRBuilder, RepoBuilder, TestDatabase};
+
+    #[test]
+    fn test_build_export_limits_to_given_groups() -> Result<()> {
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("active"))?
+            .with_repo(RepoBuilder::new("other"))?
+            .with_repo(RepoBuilder::new("loose"))?
+            .with_group("Active Projects", 0, vec!["testowner/active"])?
+            .with_group("Other", 1, vec!["testowner/other"])?;
+
+        let groups: Vec<Group> = test_db
+            .db
+            .get_all_groups()?
+            .into_iter()
+            .filter(|g| g.name == "Active Projects")
+            .collect();
+        let export = build_export(&test_db.db, &groups, false, &Config::default())?;
+
+        let exported_groups = export["groups"].as_array().unwrap();
+        assert_eq!(exported_groups.len(), 1);
+        assert_eq!(exported_groups[0]["name"], "Active Projects");
+        assert_eq!(exported_groups[0]["repos"][0]["id"], "testowner/active");
+        assert!(export["ungrouped"].as_array().unwrap().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_full_export_includes_ungrouped() -> Result<()> {
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("grouped"))?
+            .with_repo(RepoBuilder::new("loose"))?
+            .with_group("Group", 0, vec!["testowner/grouped"])?;
+
+        let export = build_full_export(&test_db.db, &Config::default())?;
+
+        assert_eq!(export["groups"].as_array().unwrap().len(), 1);
+        let ungrouped = export["ungrouped"].as_array().unwrap();
+        assert_eq!(ungrouped.len(), 1);
+        assert_eq!(ungrouped[0]["id"], "testowner/loose");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_regenerate_repos_json_counts_grouped_and_ungrouped() -> Result<()> {
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("grouped"))?
+            .with_repo(RepoBuilder::new("loose"))?
+            .with_group("Group", 0, vec!["testowner/grouped"])?;
+        let dir = tempfile::TempDir::new()?;
+        let path = dir.path().join("repos.json");
+
+        let summary = regenerate_repos_json(&test_db.db, &path, &Config::default())?;
+
+        assert_eq!(summary.path, path);
+        assert_eq!(summary.groups, 1);
+        assert_eq!(summary.repos, 2);
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        assert_eq!(written["ungrouped"][0]["id"], "testowner/loose");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_branches_linked_to_open_prs_by_head_ref() -> Result<()> {
+        let repo_id = "testowner/repo";
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("repo"))?
+            .with_branch(repo_id, BranchBuilder::new("feature-open").ahead(2))?
+            .with_branch(repo_id, BranchBuilder::new("feature-merged").ahead(1))?
+            .with_branch(repo_id, BranchBuilder::new("feature-none").ahead(1))?
+            .with_pull_request(
+                repo_id,
+                PRBuilder::new(1, "Open PR")
+                    .head_ref_name("feature-open")
+                    .build(repo_id),
+            )?
+            .with_pull_request(
+                repo_id,
+                PRBuilder::new(2, "Merged PR")
+                    .state(PRState::Merged)
+                    .head_ref_name("feature-merged")
+                    .build(repo_id),
+            )?;
+
+        let export = build_full_export(&test_db.db, &Config::default())?;
+
+        let branches = export["ungrouped"][0]["branches"].as_array().unwrap();
+        let has_open_pr = |name: &str| {
+            branches
+                .iter()
+                .find(|b| b["name"] == name)
+                .map(|b| b["hasOpenPr"].clone())
+                .unwrap()
+        };
+        assert_eq!(has_open_pr("feature-open"), true);
+        assert_eq!(has_open_pr("feature-merged"), false);
+        assert_eq!(has_open_pr("feature-none"), false);
+        assert_eq!(export["ungrouped"][0]["focusBranch"], "feature-open");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_round_trips_export() -> Result<()> {
+        let repo_id = "testowner/repo";
+        let source = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("repo").with_language("Rust"))?
+            .with_repo(RepoBuilder::new("loose"))?
+            .with_branch(repo_id, BranchBuilder::new("feature").ahead(2))?
+            .with_pull_request(
+                repo_id,
+                PRBuilder::new(7, "Feature")
+                    .head_ref_name("feature")
+                    .review_decision(ReviewDecision::Approved)
+                    .build(repo_id),
+            )?
+            .with_group("Active", 0, vec![repo_id])?;
+        let branch_id = source.db.get_branches_for_repo(repo_id)?[0].id;
+        source.db.save_commit(
+            &CommitBuilder::new("abc123", "Add feature")
+                .verified(true)
+                .build(branch_id),
+        )?;
+        source.db.add_tag(repo_id, "oss")?;
+        source.db.save_ci_status(repo_id, CiStatus::Failing)?;
+        source
+            .db
+            .set_snooze(repo_id, Some("2030-01-01T00:00:00Z".parse().unwrap()))?;
+        let exported = build_full_export(&source.db, &Config::default())?;
+
+        let target = TestDatabase::new()?;
+        let data = parse_export(&exported.to_string())?;
+        let summary = import_export(&target.db, &data)?;
+
+        assert_eq!(
+            summary,
+            ImportSummary {
+                groups: 1,
+                repos: 2,
+                branches: 1,
+                commits: 1,
+                pull_requests: 1,
+            }
+        );
+        let mut reexported = build_full_export(&target.db, &Config::default())?;
+        reexported["generatedAt"] = exported["generatedAt"].clone();
+        assert_eq!(reexported, exported);
+
+        let branch = &exported["groups"][0]["repos"][0]["branches"][0];
+        assert_eq!(branch["commits"][0]["isVerified"], true);
+
+        let pr = &exported["groups"][0]["repos"][0]["pullRequests"][0];
+        assert_eq!(pr["reviewDecision"], "Approved");
+        assert_eq!(
+            exported["groups"][0]["repos"][0]["tags"],
+            serde_json::json!(["oss"])
+        );
+        assert_eq!(exported["groups"][0]["repos"][0]["ciStatus"], "failing");
+        assert_eq!(exported["ungrouped"][0]["ciStatus"], "none");
+
+        let prs = target.db.get_pull_requests_for_repo(repo_id)?;
+        assert!(prs[0].branch_id.is_some(), "PR should link to its branch");
+        assert_eq!(prs[0].review_decision, Some(ReviewDecision::Approved));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_applies_locked_priority_to_existing_repo() -> Result<()> {
+        let repo_id = "testowner/repo";
+        let source = TestDatabase::new()?.with_repo(RepoBuilder::new("repo"))?;
+        source.db.set_priority_override(repo_id, Some(0.9))?;
+        let exported = build_full_export(&source.db, &Config::default())?;
+
+        let target = TestDatabase::new()?.with_repo(RepoBuilder::new("repo"))?;
+        target.db.update_repository_priority(repo_id, 0.2)?;
+        import_export(&target.db, &parse_export(&exported.to_string())?)?;
+
+        let repo = target.db.get_repository(repo_id)?.unwrap();
+        assert!(repo.priority_locked);
+        assert_eq!(repo.priority, 0.9);
+        // Reprioritizing leaves the imported override alone
+        assert!(!target.db.update_repository_priority(repo_id, 0.1)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_without_visibility_keeps_stored_value() -> Result<()> {
+        let test_db =
+            TestDatabase::new()?.with_repo(RepoBuilder::new("secret").is_private(true))?;
+        let mut export = build_full_export(&test_db.db, &Config::default())?;
+        export["ungrouped"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("isPrivate");
+
+        import_export(&test_db.db, &parse_export(&export.to_string())?)?;
+
+        assert!(
+            test_db
+                .db
+                .get_repository("testowner/secret")?
+                .unwrap()
+                .is_private
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_reuses_existing_group_by_name() -> Result<()> {
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("repo"))?
+            .with_group("Active", 0, vec![])?;
+        let exported = build_full_export(
+            &TestDatabase::new()?
+                .with_repo(RepoBuilder::new("repo"))?
+                .with_group("Active", 0, vec!["testowner/repo"])?
+                .db,
+            &Config::default(),
+        )?;
+
+        import_export(&test_db.db, &parse_export(&exported.to_string())?)?;
+
+        let groups = test_db.db.get_all_groups()?;
+        assert_eq!(groups.len(), 1);
+        assert_eq!(test_db.db.get_repos_in_group(groups[0].id)?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_honors_configured_protected_branches() -> Result<()> {
+        let repo_id = "testowner/repo";
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("repo"))?
+            .with_branch(repo_id, BranchBuilder::new("trunk").ahead(1))?
+            .with_branch(repo_id, BranchBuilder::new("release/1.0").ahead(2))?
+            .with_branch(repo_id, BranchBuilder::new("develop").ahead(3))?;
+        let mut config = Config::default();
+        config.github.protected_branches = vec!["trunk".to_string(), "release/*".to_string()];
+
+        let export = build_full_export(&test_db.db, &config)?;
+
+        assert_eq!(
+            export["protectedBranches"],
+            serde_json::json!(["trunk", "release/*"])
+        );
+        let repo = &export["ungrouped"][0];
+        assert_eq!(repo["unmergedCount"], 1, "Only develop is unprotected");
+        let unmerged: Vec<_> = repo["branches"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|b| b["isUnmerged"] == true)
+            .map(|b| b["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(unmerged, vec!["develop"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_by_visibility() -> Result<()> {
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("secret").is_private(true))?
+            .with_repo(RepoBuilder::new("open"))?;
+
+        let export = build_full_export(&test_db.db, &Config::default())?;
+        assert!(export["ungrouped"][0]["isPrivate"].is_boolean());
+
+        let private = filter_by_visibility(export.clone(), true)?;
+        let private_ids: Vec<&str> = private["ungrouped"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(private_ids, vec!["testowner/secret"]);
+
+        let public = filter_by_visibility(export, false)?;
+        assert_eq!(public["ungrouped"].as_array().unwrap().len(), 1);
+        assert_eq!(public["ungrouped"][0]["id"], "testowner/open");
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_includes_repo_status() -> Result<()> {
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("done"))?
+            .with_repo(RepoBuilder::new("busy"))?
+            .with_branch("testowner/busy", BranchBuilder::new("feature").ahead(1))?;
+
+        let export = build_full_export(&test_db.db, &Config::default())?;
+        let status_of = |id: &str| {
+            export["ungrouped"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|r| r["id"] == id)
+                .map(|r| r["status"].clone())
+                .unwrap()
+        };
+        assert_eq!(status_of("testowner/done"), "complete");
+        assert_eq!(status_of("testowner/busy"), "needsSync");
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_language_exports_as_null() -> Result<()> {
+        let test_db = TestDatabase::new()?.with_repo(RepoBuilder::new("repo"))?;
+
+        let export = build_full_export(&test_db.db, &Config::default())?;
+
+        assert!(export["ungrouped"][0]["language"].is_null());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_import_leaves_database_unchanged() -> Result<()> {
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("existing"))?
+            .with_branch("testowner/existing", BranchBuilder::new("feature"))?;
+        let mut export = build_full_export(
+            &TestDatabase::new()?
+                .with_repo(RepoBuilder::new("grouped"))?
+                .with_repo(RepoBuilder::new("existing"))?
+                .with_group("Active", 0, vec!["testowner/grouped"])?
+                .db,
+            &Config::default(),
+        )?;
+        // The grouped repository imports fine before the bad date is reached
+        export["ungrouped"][0]["lastPush"] = "not a date".into();
+
+        assert!(import_export(&test_db.db, &parse_export(&export.to_string())?).is_err());
+
+        let repos = test_db.db.get_all_repositories()?;
+        let ids: Vec<&str> = repos.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["testowner/existing"]);
+        assert!(test_db.db.get_all_groups()?.is_empty());
+        let branches = test_db.db.get_branches_for_repo("testowner/existing")?;
+        assert_eq!(branches.len(), 1);
+        assert_eq!(branches[0].name, "feature");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_treats_legacy_unknown_language_as_missing() -> Result<()> {
+        let test_db = TestDatabase::new()?;
+        let mut export = build_full_export(
+            &TestDatabase::new()?.with_repo(RepoBuilder::new("repo"))?.db,
+            &Config::default(),
+        )?;
+        export["ungrouped"][0]["language"] = "Unknown".into();
+
+        import_export(&test_db.db, &parse_export(&export.to_string())?)?;
+
+        let repo = test_db.db.get_repository("testowner/repo")?.unwrap();
+        assert_eq!(repo.language, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_export_replaces_file_atomically() -> Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let path = dir.path().join("repos.json");
+        std::fs::write(&path, "stale")?;
+
+        write_export(&path, &serde_json::json!({"groups": [], "ungrouped": []}))?;
+
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        assert_eq!(written["groups"], serde_json::json!([]));
+        // Only the final file remains; the temp file was renamed away
+        assert_eq!(std::fs::read_dir(dir.path())?.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrent_write_exports_use_separate_temp_files() -> Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let path = dir.path().join("repos.json");
+        assert_ne!(temp_path_for(&path), temp_path_for(&path));
+
+        std::thread::scope(|s| {
+            let handles: Vec<_> = (0..8)
+                .map(|i| {
+                    let path = &path;
+                    s.spawn(move || write_export(path, &serde_json::json!({"groups": [], "n": i})))
+                })
+                .collect();
+            for handle in handles {
+                assert!(handle.join().unwrap().is_ok());
+            }
+        });
+
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        assert!(written["n"].is_number());
+        assert_eq!(std::fs::read_dir(dir.path())?.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_export_fails_for_missing_directory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("missing").join("repos.json");
+
+        assert!(write_export(&path, &serde_json::json!({})).is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_parse_export_rejects_wrong_shape() {
+        assert!(parse_export("{\"repos\": []}").is_err());
+        assert!(parse_export("not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_export_checks_schema_version() -> Result<()> {
+        let test_db = TestDatabase::new()?.with_repo(RepoBuilder::new("repo"))?;
+        let exported = build_full_export(&test_db.db, &Config::default())?;
+        assert_eq!(exported["schemaVersion"], EXPORT_SCHEMA_VERSION);
+        assert!(exported["generatedAt"].is_string());
+
+        // Exports that predate the version field still load
+        let legacy = parse_export("{\"groups\": [], \"ungrouped\": []}")?;
+        assert_eq!(legacy.schema_version, 0);
+
+        let mut newer = exported;
+        newer["schemaVersion"] = (EXPORT_SCHEMA_VERSION + 1).into();
+        assert!(matches!(
+            parse_export(&newer.to_string()),
+            Err(Error::UnsupportedExportVersion { found, .. }) if found == EXPORT_SCHEMA_VERSION + 1
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_commit_messages_are_subject_only() -> Result<()> {
+        let repo_id = "testowner/repo";
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("repo"))?
+            .with_branch(repo_id, BranchBuilder::new("feature"))?;
+        let branch_id = test_db.db.get_branches_for_repo(repo_id)?[0].id;
+        test_db.db.save_commit(
+            &CommitBuilder::new("abc123", "Squash merge\n\n* lots\n* of\n* detail")
+                .build(branch_id),
+        )?;
+
+        let export = build_full_export(&test_db.db, &Config::default())?;
+
+        let commit = &export["ungrouped"][0]["branches"][0]["commits"][0];
+        assert_eq!(commit["message"], "Squash merge");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_caps_inlined_commits() -> Result<()> {
+        let repo_id = "testowner/repo";
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("repo"))?
+            .with_branch(repo_id, BranchBuilder::new("feature"))?;
+        let branch_id = test_db.db.get_branches_for_repo(repo_id)?[0].id;
+        let now = Utc::now();
+        for i in 0..EXPORT_COMMITS_PER_BRANCH + 3 {
+            test_db.db.save_commit(
+                &CommitBuilder::new(&format!("sha{}", i), "Work")
+                    .committed_date(now - chrono::Duration::minutes(i as i64))
+                    .build(branch_id),
+            )?;
+        }
+
+        let export = build_full_export(&test_db.db, &Config::default())?;
+
+        let branch = &export["ungrouped"][0]["branches"][0];
+        let commits = branch["commits"].as_array().unwrap();
+        assert_eq!(commits.len(), EXPORT_COMMITS_PER_BRANCH);
+        assert_eq!(commits[0]["sha"], "sha0");
+        assert_eq!(branch["moreCommits"], 3);
+
+        // Re-importing the capped export keeps the commits it left out
+        import_export(&test_db.db, &parse_export(&export.to_string())?)?;
+        let branch_id = test_db.db.get_branches_for_repo(repo_id)?[0].id;
+        assert_eq!(
+            test_db.db.count_commits_for_branch(branch_id)?,
+            EXPORT_COMMITS_PER_BRANCH + 3
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_includes_staleness() -> Result<()> {
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("recent").pushed_at(Utc::now()))?
+            .with_repo(
+                RepoBuilder::new("old").pushed_at(Utc::now() - chrono::Duration::days(90)),
+            )?;
+
+        let export = build_full_export(&test_db.db, &Config::default())?;
+
+        let staleness = |id: &str| {
+            export["ungrouped"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|r| r["id"] == id)
+                .map(|r| r["staleness"].clone())
+                .unwrap()
+        };
+        assert_eq!(staleness("testowner/recent"), "Fresh");
+        assert_eq!(staleness("testowner/old"), "Stale");
+
+        Ok(())
+    }
+}
@@ -1,9 +1,10 @@
 //! Trait for GitHub operations, enabling dependency injection for testing
 
 use crate::{
-    models::{Branch, BranchStatus, Commit, PullRequest, Repository},
+    models::{Branch, BranchStatus, CiStatus, Commit, MergeMethod, PullRequest, Repository},
     Result,
 };
+use std::path::Path;
 
 /// Trait for GitHub client operations
 ///
@@ -11,24 +12,57 @@ use crate::{
 /// - Inject real implementations (via `gh` CLI) in production
 /// - Inject mock implementations in tests (without network/auth dependencies)
 /// - Verify behavior through spy/mock patterns
-pub trait GitHubClient {
+///
+/// Implementations must be `Send + Sync` so they can be shared with the
+/// server's request handlers through `AppState`.
+pub trait GitHubClient: Send + Sync {
     /// List repositories for an owner (user or organization)
     fn list_repos(&self, owner: &str, limit: usize) -> Result<Vec<Repository>>;
 
+    /// Fetch a single repository's metadata by id (owner/name)
+    fn get_repository(&self, repo_id: &str) -> Result<Repository>;
+
+    /// List the repository ids (owner/name) a team has access to in an organization
+    fn list_team_repos(&self, org: &str, team: &str) -> Result<Vec<String>>;
+
+    /// Get the default branch name for a repository
+    fn get_default_branch(&self, repo_id: &str) -> Result<String>;
+
     /// Fetch all branches for a repository
     fn fetch_branches(&self, repo_id: &str) -> Result<Vec<Branch>>;
 
-    /// Fetch all pull requests for a repository
-    fn fetch_pull_requests(&self, repo_id: &str) -> Result<Vec<PullRequest>>;
+    /// Fetch all branches, reusing stored ahead/behind counts for branches
+    /// whose SHA matches one in `previous` and computing the rest in
+    /// `local_clone` when the repository is cloned locally
+    fn fetch_branches_since(
+        &self,
+        repo_id: &str,
+        default_branch: &str,
+        previous: &[Branch],
+        local_clone: Option<&Path>,
+    ) -> Result<Vec<Branch>>;
 
-    /// Fetch commits for a specific branch
+    /// Fetch up to `limit` pull requests for a repository
+    fn fetch_pull_requests(&self, repo_id: &str, limit: usize) -> Result<Vec<PullRequest>>;
+
+    /// Fetch the newest `limit` commits of a specific branch
     fn fetch_commits(
         &self,
         repo_id: &str,
         branch_name: &str,
         branch_id: i64,
+        limit: usize,
     ) -> Result<Vec<Commit>>;
 
+    /// Fetch the unified diff of `head` against `base`
+    fn fetch_branch_diff(&self, repo_id: &str, base: &str, head: &str) -> Result<String>;
+
+    /// Fetch the full message of a commit, which may be stored truncated
+    fn fetch_commit_message(&self, repo_id: &str, sha: &str) -> Result<String>;
+
+    /// Fetch the combined CI status of the check runs on `git_ref`
+    fn fetch_ci_status(&self, repo_id: &str, git_ref: &str) -> Result<CiStatus>;
+
     /// Classify branch status based on PR state
     fn classify_branch_status(
         &self,
@@ -37,7 +71,7 @@ pub trait GitHubClient {
         default_branch: &str,
     ) -> BranchStatus;
 
-    /// Create a pull request for a branch
+    /// Create a pull request for a branch, as a draft when `draft` is set
     /// Returns the PR URL on success
     fn create_pull_request(
         &self,
@@ -45,5 +79,12 @@ pub trait GitHubClient {
         branch_name: &str,
         title: Option<&str>,
         body: Option<&str>,
+        draft: bool,
     ) -> Result<String>;
+
+    /// Get the base branch a pull request targets
+    fn fetch_pull_request_base(&self, repo_id: &str, number: u32) -> Result<String>;
+
+    /// Merge a pull request with the given method
+    fn merge_pull_request(&self, repo_id: &str, number: u32, method: MergeMethod) -> Result<()>;
 }
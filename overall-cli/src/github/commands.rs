@@ -1,13 +1,102 @@
 use crate::{
-    models::{Branch, BranchStatus, Commit, PRState, PullRequest, Repository},
+    config::Config,
+    local_git,
+    models::{
+        validate_owner, Branch, BranchStatus, CiStatus, Commit, MergeMethod, PRState, PullRequest,
+        RepoId, Repository, ReviewDecision,
+    },
     Error, Result,
 };
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::OnceLock;
+
+/// Environment variables checked for a token, in order of preference
+const TOKEN_VARS: [&str; 2] = ["GH_TOKEN", "GITHUB_TOKEN"];
+
+/// How `gh` is authenticated for this process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GhAuth {
+    /// `gh auth status` succeeds
+    LoggedIn,
+    /// `gh auth status` fails, so the token in this variable is passed to
+    /// every `gh` invocation as `GH_TOKEN`
+    EnvToken(&'static str),
+    /// Neither a gh login nor a token in the environment
+    Missing,
+}
+
+/// Decide how to authenticate `gh`, returning the token to pass along if any
+fn resolve_gh_auth(
+    logged_in: bool,
+    var: impl Fn(&str) -> Option<String>,
+) -> (GhAuth, Option<String>) {
+    if logged_in {
+        return (GhAuth::LoggedIn, None);
+    }
+    TOKEN_VARS
+        .iter()
+        .find_map(|name| {
+            var(name)
+                .filter(|token| !token.trim().is_empty())
+                .map(|token| (GhAuth::EnvToken(name), Some(token)))
+        })
+        .unwrap_or((GhAuth::Missing, None))
+}
+
+/// The `gh` binary, resolved once per process (see `ToolsConfig::gh`)
+fn gh_program() -> &'static str {
+    static PROGRAM: OnceLock<String> = OnceLock::new();
+    PROGRAM.get_or_init(|| Config::load().unwrap_or_default().tools.gh())
+}
+
+/// Auth state, checked once per process with `gh auth status`
+fn gh_auth_state() -> &'static (GhAuth, Option<String>) {
+    static STATE: OnceLock<(GhAuth, Option<String>)> = OnceLock::new();
+    STATE.get_or_init(|| {
+        let logged_in = Command::new(gh_program())
+            .args(["auth", "status"])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false);
+        resolve_gh_auth(logged_in, |name| std::env::var(name).ok())
+    })
+}
+
+/// How `gh` is authenticated (see `GhAuth`)
+pub fn gh_auth() -> GhAuth {
+    gh_auth_state().0.clone()
+}
+
+/// A `gh` command, carrying `GH_TOKEN` from the environment when gh has no login
+fn gh_command() -> Command {
+    let mut command = Command::new(gh_program());
+    if let Some(token) = &gh_auth_state().1 {
+        command.env("GH_TOKEN", token);
+    }
+    command
+}
+
+/// The first line of `gh --version`
+pub fn gh_version() -> Result<String> {
+    let output = Command::new(gh_program())
+        .arg("--version")
+        .output()
+        .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh: {}", e)))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().next().unwrap_or_default().to_string())
+}
+
+/// `--json` fields deserialized into `GhRepository`
+const GH_REPOSITORY_FIELDS: &str =
+    "id,name,owner,pushedAt,createdAt,updatedAt,primaryLanguage,description,isFork,isPrivate";
 
 #[derive(Debug, Deserialize)]
 struct GhRepository {
+    /// GraphQL node id, stable across renames and transfers
+    id: String,
     name: String,
     owner: GhOwner,
     #[serde(rename = "pushedAt")]
@@ -21,6 +110,8 @@ struct GhRepository {
     description: Option<String>,
     #[serde(rename = "isFork")]
     is_fork: bool,
+    #[serde(rename = "isPrivate", default)]
+    is_private: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,7 +129,7 @@ pub fn list_repos(owner: &str, limit: usize) -> Result<Vec<Repository>> {
     validate_owner(owner)?;
 
     // Execute gh CLI command
-    let output = Command::new("gh")
+    let output = gh_command()
         .args([
             "repo",
             "list",
@@ -46,7 +137,7 @@ pub fn list_repos(owner: &str, limit: usize) -> Result<Vec<Repository>> {
             "--limit",
             &limit.to_string(),
             "--json",
-            "name,owner,pushedAt,createdAt,updatedAt,primaryLanguage,description,isFork",
+            GH_REPOSITORY_FIELDS,
         ])
         .output()
         .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
@@ -68,49 +159,116 @@ pub fn list_repos(owner: &str, limit: usize) -> Result<Vec<Repository>> {
     // Convert to our Repository model
     let mut repos: Vec<Repository> = gh_repos
         .into_iter()
-        .map(|gh_repo| {
-            Ok(Repository {
-                id: format!("{}/{}", gh_repo.owner.login, gh_repo.name),
-                owner: gh_repo.owner.login,
-                name: gh_repo.name,
-                language: gh_repo.primary_language.map(|l| l.name),
-                description: gh_repo.description,
-                pushed_at: parse_github_timestamp(&gh_repo.pushed_at)?,
-                created_at: parse_github_timestamp(&gh_repo.created_at)?,
-                updated_at: parse_github_timestamp(&gh_repo.updated_at)?,
-                is_fork: gh_repo.is_fork,
-                priority: 0.0, // Will be calculated later
-            })
-        })
+        .map(repository_from_gh)
         .collect::<Result<Vec<Repository>>>()?;
 
     // Sort by pushed_at descending (most recent first)
-    repos.sort_by(|a, b| b.pushed_at.cmp(&a.pushed_at));
+    repos.sort_by_key(|r| std::cmp::Reverse(r.pushed_at));
 
     Ok(repos)
 }
 
-fn validate_owner(owner: &str) -> Result<()> {
-    if owner.is_empty() {
-        return Err(Error::InvalidOwner("Owner cannot be empty".to_string()));
-    }
+/// Fetch a single repository's metadata, e.g. for a local clone that was
+/// never part of an owner scan
+pub fn get_repository(repo_id: &str) -> Result<Repository> {
+    let repo_id: RepoId = repo_id.parse()?;
 
-    // GitHub usernames: alphanumeric, hyphens, max 39 chars
-    if !owner.chars().all(|c| c.is_alphanumeric() || c == '-') {
-        return Err(Error::InvalidOwner(format!(
-            "Invalid owner name '{}': must be alphanumeric or hyphens",
-            owner
+    let output = gh_command()
+        .args([
+            "repo",
+            "view",
+            repo_id.as_str(),
+            "--json",
+            GH_REPOSITORY_FIELDS,
+        ])
+        .output()
+        .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitHubCLI(format!(
+            "Failed to view {}: {}",
+            repo_id, stderr
         )));
     }
 
-    if owner.len() > 39 {
-        return Err(Error::InvalidOwner(format!(
-            "Owner name too long: {} characters (max 39)",
-            owner.len()
+    let gh_repo: GhRepository = serde_json::from_slice(&output.stdout)?;
+    repository_from_gh(gh_repo)
+}
+
+/// Convert `gh repo list`/`gh repo view` JSON into our Repository model
+fn repository_from_gh(gh_repo: GhRepository) -> Result<Repository> {
+    Ok(Repository {
+        id: format!("{}/{}", gh_repo.owner.login, gh_repo.name),
+        owner: gh_repo.owner.login,
+        name: gh_repo.name,
+        language: gh_repo.primary_language.map(|l| l.name),
+        description: gh_repo.description,
+        pushed_at: parse_github_timestamp(&gh_repo.pushed_at)?,
+        created_at: parse_github_timestamp(&gh_repo.created_at)?,
+        updated_at: parse_github_timestamp(&gh_repo.updated_at)?,
+        is_fork: gh_repo.is_fork,
+        priority: 0.0, // Will be calculated later
+        is_private: gh_repo.is_private,
+        priority_locked: false,
+        github_id: Some(gh_repo.id),
+        snoozed_until: None,
+    })
+}
+
+/// List the repository ids (owner/name) a team has access to in an organization
+pub fn list_team_repos(org: &str, team: &str) -> Result<Vec<String>> {
+    validate_owner(org)?;
+
+    // Team slugs are lowercase alphanumerics, hyphens and underscores
+    if team.is_empty()
+        || !team
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(Error::Config(format!("Invalid team slug '{}'", team)));
+    }
+
+    // Use --jq so paginated output is one id per line rather than concatenated arrays
+    let output = gh_command()
+        .args([
+            "api",
+            &format!("orgs/{}/teams/{}/repos", org, team),
+            "--paginate",
+            "--jq",
+            ".[].full_name",
+        ])
+        .output()
+        .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitHubCLI(format!(
+            "Failed to list repos for team {}/{}: {}",
+            org, team, stderr
         )));
     }
 
-    Ok(())
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|e| Error::GitHubCLI(format!("Invalid UTF-8 in response: {}", e)))?;
+
+    Ok(stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Keep only repositories whose id appears in `team_repo_ids`
+///
+/// GitHub ids are case-insensitive, so the comparison is too.
+pub fn filter_repos_to_team(repos: &mut Vec<Repository>, team_repo_ids: &[String]) {
+    repos.retain(|repo| {
+        team_repo_ids
+            .iter()
+            .any(|id| id.eq_ignore_ascii_case(&repo.id))
+    });
 }
 
 fn parse_github_timestamp(timestamp: &str) -> Result<DateTime<Utc>> {
@@ -157,6 +315,26 @@ struct GhComparison {
 }
 
 pub fn fetch_branches(repo_id: &str) -> Result<Vec<Branch>> {
+    let default_branch = get_default_branch(repo_id)?;
+    fetch_branches_since(repo_id, &default_branch, &[], None)
+}
+
+/// Fetch branches, reusing stored results for branches that have not moved
+///
+/// `previous` holds the branches stored by the last scan. A branch whose SHA
+/// is unchanged keeps its stored commit date and ahead/behind counts, skipping
+/// the per-branch commit and compare API calls, as long as the default branch
+/// itself has not moved either.
+///
+/// With a `local_clone` of the repository, ahead/behind counts come from
+/// local git instead of one compare API call per branch, falling back to the
+/// API for branches whose commits the clone has not fetched.
+pub fn fetch_branches_since(
+    repo_id: &str,
+    default_branch: &str,
+    previous: &[Branch],
+    local_clone: Option<&Path>,
+) -> Result<Vec<Branch>> {
     // Parse repo_id (owner/name format)
     let parts: Vec<&str> = repo_id.split('/').collect();
     if parts.len() != 2 {
@@ -167,7 +345,7 @@ pub fn fetch_branches(repo_id: &str) -> Result<Vec<Branch>> {
     }
 
     // Fetch branches using gh API
-    let output = Command::new("gh")
+    let output = gh_command()
         .args(["api", &format!("repos/{}/branches", repo_id), "--paginate"])
         .output()
         .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
@@ -185,14 +363,32 @@ pub fn fetch_branches(repo_id: &str) -> Result<Vec<Branch>> {
 
     let gh_branches: Vec<GhBranch> = serde_json::from_str(&stdout)?;
 
-    // Get default branch to compare against
-    let default_branch = get_default_branch(repo_id)?;
+    // Stored comparisons are only valid while the default branch is where it was
+    let default_sha = gh_branches
+        .iter()
+        .find(|b| b.name == default_branch)
+        .map(|b| b.commit.sha.clone());
+    let default_unchanged = default_sha
+        .as_deref()
+        .is_some_and(|sha| unchanged_branch(previous, default_branch, sha).is_some());
 
     // Convert to our Branch model
     let branches: Vec<Branch> = gh_branches
         .into_iter()
         .enumerate()
         .map(|(idx, gh_branch)| {
+            if default_unchanged {
+                if let Some(stored) =
+                    unchanged_branch(previous, &gh_branch.name, &gh_branch.commit.sha)
+                {
+                    return Ok(Branch {
+                        id: idx as i64,
+                        status: BranchStatus::ReadyForPR,
+                        ..stored.clone()
+                    });
+                }
+            }
+
             // Fetch commit details to get the date
             let commit_details = fetch_commit_details(repo_id, &gh_branch.commit.sha)?;
             let last_commit_date = commit_details;
@@ -201,9 +397,20 @@ pub fn fetch_branches(repo_id: &str) -> Result<Vec<Branch>> {
             let (ahead_by, behind_by) = if gh_branch.name == default_branch {
                 (0, 0)
             } else {
-                match compare_branches(repo_id, &default_branch, &gh_branch.name) {
-                    Ok((ahead, behind)) => (ahead, behind),
-                    Err(_) => (0, 0), // If comparison fails, assume no difference
+                let local = local_clone
+                    .zip(default_sha.as_deref())
+                    .and_then(|(path, base)| {
+                        local_git::count_ahead_behind(path, base, &gh_branch.commit.sha)
+                            .map_err(|e| {
+                                tracing::debug!("{}: comparing locally failed: {}", repo_id, e)
+                            })
+                            .ok()
+                    });
+                match local {
+                    Some(counts) => counts,
+                    None => compare_branches(repo_id, default_branch, &gh_branch.name)
+                        // If comparison fails, assume no difference
+                        .unwrap_or((0, 0)),
                 }
             };
 
@@ -223,8 +430,13 @@ pub fn fetch_branches(repo_id: &str) -> Result<Vec<Branch>> {
     Ok(branches)
 }
 
+/// Find a previously stored branch with the same name still at the same SHA
+fn unchanged_branch<'a>(previous: &'a [Branch], name: &str, sha: &str) -> Option<&'a Branch> {
+    previous.iter().find(|b| b.name == name && b.sha == sha)
+}
+
 fn fetch_commit_details(repo_id: &str, sha: &str) -> Result<DateTime<Utc>> {
-    let output = Command::new("gh")
+    let output = gh_command()
         .args(["api", &format!("repos/{}/commits/{}", repo_id, sha)])
         .output()
         .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
@@ -244,8 +456,8 @@ fn fetch_commit_details(repo_id: &str, sha: &str) -> Result<DateTime<Utc>> {
     parse_github_timestamp(&commit.commit.author.date)
 }
 
-fn get_default_branch(repo_id: &str) -> Result<String> {
-    let output = Command::new("gh")
+pub fn get_default_branch(repo_id: &str) -> Result<String> {
+    let output = gh_command()
         .args([
             "api",
             &format!("repos/{}", repo_id),
@@ -271,11 +483,33 @@ fn get_default_branch(repo_id: &str) -> Result<String> {
     Ok(branch)
 }
 
+/// Percent-encode a branch or tag name for use in an API path
+///
+/// Slashes are kept, as GitHub reads them as part of the ref; everything
+/// outside the unreserved set (e.g. `#`, `%`, `?`) is escaped.
+fn encode_ref(git_ref: &str) -> String {
+    let mut encoded = String::with_capacity(git_ref.len());
+    for byte in git_ref.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
 fn compare_branches(repo_id: &str, base: &str, head: &str) -> Result<(u32, u32)> {
-    let output = Command::new("gh")
+    let output = gh_command()
         .args([
             "api",
-            &format!("repos/{}/compare/{}...{}", repo_id, base, head),
+            &format!(
+                "repos/{}/compare/{}...{}",
+                repo_id,
+                encode_ref(base),
+                encode_ref(head)
+            ),
         ])
         .output()
         .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
@@ -298,6 +532,97 @@ fn compare_branches(repo_id: &str, base: &str, head: &str) -> Result<(u32, u32)>
     Ok((comparison.ahead_by, comparison.behind_by))
 }
 
+/// Fetch the unified diff of `head` against `base`
+pub fn fetch_branch_diff(repo_id: &str, base: &str, head: &str) -> Result<String> {
+    let output = gh_command()
+        .args([
+            "api",
+            &format!(
+                "repos/{}/compare/{}...{}",
+                repo_id,
+                encode_ref(base),
+                encode_ref(head)
+            ),
+            "-H",
+            "Accept: application/vnd.github.diff",
+        ])
+        .output()
+        .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitHubCLI(format!(
+            "Failed to fetch diff for {}...{}: {}",
+            base, head, stderr
+        )));
+    }
+
+    // Diffs may contain binary or non-UTF-8 content; show it lossily rather than failing
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The HTTP status `gh api` reports on failure, e.g. 404 from
+/// `gh: Not Found (HTTP 404)`
+fn http_status(stderr: &str) -> Option<u16> {
+    let start = stderr.rfind("(HTTP ")? + "(HTTP ".len();
+    let end = start + stderr[start..].find(')')?;
+    stderr[start..end].parse().ok()
+}
+
+#[derive(Debug, Deserialize)]
+struct GhCheckRuns {
+    check_runs: Vec<GhCheckRun>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhCheckRun {
+    status: String,
+    conclusion: Option<String>,
+}
+
+/// Combined CI status of the check runs on `git_ref` (a branch or SHA)
+pub fn fetch_ci_status(repo_id: &str, git_ref: &str) -> Result<CiStatus> {
+    let output = gh_command()
+        .args([
+            "api",
+            &format!(
+                "repos/{}/commits/{}/check-runs",
+                repo_id,
+                encode_ref(git_ref)
+            ),
+            "--paginate",
+            "--jq",
+            "{check_runs: .check_runs}",
+        ])
+        .output()
+        .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = format!(
+            "Failed to fetch check runs for {}: {}",
+            git_ref,
+            stderr.trim()
+        );
+        return Err(match http_status(&stderr) {
+            Some(status) => Error::GitHubAPI { status, message },
+            None => Error::GitHubCLI(message),
+        });
+    }
+
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|e| Error::GitHubCLI(format!("Invalid UTF-8 in response: {}", e)))?;
+
+    // --paginate prints one object per page
+    let mut runs = Vec::new();
+    for page in serde_json::Deserializer::from_str(&stdout).into_iter::<GhCheckRuns>() {
+        runs.extend(page?.check_runs);
+    }
+    Ok(CiStatus::from_check_runs(runs.iter().map(|run| {
+        (run.status.as_str(), run.conclusion.as_deref())
+    })))
+}
+
 // Commit-related structures
 #[derive(Debug, Deserialize)]
 struct GhCommitFull {
@@ -310,6 +635,13 @@ struct GhCommitFullInfo {
     message: String,
     author: GhCommitAuthor,
     committer: GhCommitCommitter,
+    #[serde(default)]
+    verification: GhCommitVerification,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GhCommitVerification {
+    verified: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -326,14 +658,29 @@ struct GhCommitCommitter {
     date: String,
 }
 
-pub fn fetch_commits(repo_id: &str, branch_name: &str, branch_id: i64) -> Result<Vec<Commit>> {
-    // Fetch commits for the branch
-    let output = Command::new("gh")
-        .args([
-            "api",
-            &format!("repos/{}/commits?sha={}", repo_id, branch_name),
-            "--paginate",
-        ])
+/// GitHub serves at most this many commits per page
+const MAX_COMMITS_PER_PAGE: usize = 100;
+
+pub fn fetch_commits(
+    repo_id: &str,
+    branch_name: &str,
+    branch_id: i64,
+    limit: usize,
+) -> Result<Vec<Commit>> {
+    // Small limits fit in one page, so only paginate when more are wanted
+    let endpoint = format!(
+        "repos/{}/commits?sha={}&per_page={}",
+        repo_id,
+        branch_name,
+        limit.clamp(1, MAX_COMMITS_PER_PAGE)
+    );
+    let mut args = vec!["api", endpoint.as_str()];
+    if limit > MAX_COMMITS_PER_PAGE {
+        args.push("--paginate");
+    }
+
+    let output = gh_command()
+        .args(&args)
         .output()
         .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
 
@@ -350,10 +697,10 @@ pub fn fetch_commits(repo_id: &str, branch_name: &str, branch_id: i64) -> Result
 
     let gh_commits: Vec<GhCommitFull> = serde_json::from_str(&stdout)?;
 
-    // Convert to our Commit model (limit to first 50 commits to avoid overwhelming the UI)
+    // Convert to our Commit model, keeping only the requested number
     let commits: Vec<Commit> = gh_commits
         .into_iter()
-        .take(50)
+        .take(limit)
         .enumerate()
         .map(|(idx, gh_commit)| {
             Ok(Commit {
@@ -367,6 +714,7 @@ pub fn fetch_commits(repo_id: &str, branch_name: &str, branch_id: i64) -> Result
                 committer_name: gh_commit.commit.committer.name,
                 committer_email: gh_commit.commit.committer.email,
                 committed_date: parse_github_timestamp(&gh_commit.commit.committer.date)?,
+                is_verified: gh_commit.commit.verification.verified,
             })
         })
         .collect::<Result<Vec<Commit>>>()?;
@@ -374,23 +722,173 @@ pub fn fetch_commits(repo_id: &str, branch_name: &str, branch_id: i64) -> Result
     Ok(commits)
 }
 
+/// Fetch the full, untruncated message of a single commit
+pub fn fetch_commit_message(repo_id: &str, sha: &str) -> Result<String> {
+    if sha.is_empty() || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::GitHubCLI(format!("Invalid commit SHA '{}'", sha)));
+    }
+
+    let output = gh_command()
+        .args([
+            "api",
+            &format!("repos/{}/commits/{}", repo_id, sha),
+            "--jq",
+            ".commit.message",
+        ])
+        .output()
+        .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitHubCLI(format!(
+            "Failed to fetch commit {}: {}",
+            sha, stderr
+        )));
+    }
+
+    let message = String::from_utf8(output.stdout)
+        .map_err(|e| Error::GitHubCLI(format!("Invalid UTF-8 in response: {}", e)))?;
+
+    Ok(message.trim_end().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_validate_owner_valid() {
-        assert!(validate_owner("softwarewrighter").is_ok());
-        assert!(validate_owner("rust-lang").is_ok());
-        assert!(validate_owner("test123").is_ok());
+    fn test_resolve_gh_auth() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+
+        // A gh login wins even when a token is present
+        assert_eq!(
+            resolve_gh_auth(true, env(&[("GITHUB_TOKEN", "t")])),
+            (GhAuth::LoggedIn, None)
+        );
+        assert_eq!(
+            resolve_gh_auth(false, env(&[("GITHUB_TOKEN", "t1")])),
+            (GhAuth::EnvToken("GITHUB_TOKEN"), Some("t1".to_string()))
+        );
+        assert_eq!(
+            resolve_gh_auth(false, env(&[("GITHUB_TOKEN", "t1"), ("GH_TOKEN", "t2")])),
+            (GhAuth::EnvToken("GH_TOKEN"), Some("t2".to_string()))
+        );
+        assert_eq!(
+            resolve_gh_auth(false, env(&[("GH_TOKEN", " "), ("GITHUB_TOKEN", "t1")])),
+            (GhAuth::EnvToken("GITHUB_TOKEN"), Some("t1".to_string()))
+        );
+        assert_eq!(resolve_gh_auth(false, env(&[])), (GhAuth::Missing, None));
     }
 
     #[test]
-    fn test_validate_owner_invalid() {
-        assert!(validate_owner("").is_err());
-        assert!(validate_owner("invalid@name").is_err());
-        assert!(validate_owner("invalid.name").is_err());
-        assert!(validate_owner(&"a".repeat(40)).is_err());
+    fn test_encode_ref_escapes_reserved_characters() {
+        assert_eq!(encode_ref("main"), "main");
+        assert_eq!(encode_ref("feature/login-v2"), "feature/login-v2");
+        assert_eq!(encode_ref("fix#12"), "fix%2312");
+        assert_eq!(encode_ref("a b?c%d"), "a%20b%3Fc%25d");
+        assert_eq!(encode_ref("café"), "caf%C3%A9");
+    }
+
+    #[test]
+    fn test_http_status_from_gh_stderr() {
+        assert_eq!(http_status("gh: Not Found (HTTP 404)\n"), Some(404));
+        assert_eq!(
+            http_status("gh: Resource not accessible by integration (HTTP 403)"),
+            Some(403)
+        );
+        assert_eq!(http_status("error connecting to api.github.com"), None);
+    }
+
+    #[test]
+    fn test_pr_number_from_url() {
+        assert_eq!(
+            pr_number_from_url("https://github.com/owner/repo/pull/42"),
+            Some(42)
+        );
+        assert_eq!(
+            pr_number_from_url("https://github.com/owner/repo/pull/7/"),
+            Some(7)
+        );
+        assert_eq!(pr_number_from_url("https://github.com/owner/repo"), None);
+    }
+
+    #[test]
+    fn test_unchanged_branch_matches_name_and_sha() {
+        use crate::test_support::BranchBuilder;
+
+        let previous = vec![
+            BranchBuilder::new("feature")
+                .sha("abc123")
+                .ahead(2)
+                .build("o/r"),
+            BranchBuilder::new("other").sha("def456").build("o/r"),
+        ];
+
+        let stored = unchanged_branch(&previous, "feature", "abc123").unwrap();
+        assert_eq!(stored.ahead_by, 2);
+        assert!(unchanged_branch(&previous, "feature", "moved").is_none());
+        assert!(unchanged_branch(&previous, "new-branch", "abc123").is_none());
+    }
+
+    #[test]
+    fn test_filter_repos_to_team() {
+        use crate::test_support::RepoBuilder;
+
+        let mut repos = vec![
+            RepoBuilder::new("team-repo").owner("org").build(),
+            RepoBuilder::new("Mixed-Case").owner("org").build(),
+            RepoBuilder::new("unrelated").owner("org").build(),
+        ];
+        let team_ids = vec!["org/team-repo".to_string(), "org/mixed-case".to_string()];
+
+        filter_repos_to_team(&mut repos, &team_ids);
+
+        let ids: Vec<_> = repos.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["org/team-repo", "org/Mixed-Case"]);
+    }
+
+    #[test]
+    fn test_list_team_repos_rejects_invalid_slug() {
+        assert!(list_team_repos("org", "").is_err());
+        assert!(list_team_repos("org", "bad/slug").is_err());
+        assert!(list_team_repos("bad@org", "team").is_err());
+    }
+
+    #[test]
+    fn test_commit_verification_is_parsed() {
+        let json = r#"[
+            {"sha": "a1", "commit": {
+                "message": "Signed",
+                "author": {"name": "A", "email": "a@x.com", "date": "2024-01-01T00:00:00Z"},
+                "committer": {"name": "A", "email": "a@x.com", "date": "2024-01-01T00:00:00Z"},
+                "verification": {"verified": true, "reason": "valid"}
+            }},
+            {"sha": "b2", "commit": {
+                "message": "Unsigned",
+                "author": {"name": "B", "email": "b@x.com", "date": "2024-01-01T00:00:00Z"},
+                "committer": {"name": "B", "email": "b@x.com", "date": "2024-01-01T00:00:00Z"}
+            }}
+        ]"#;
+
+        let commits: Vec<GhCommitFull> = serde_json::from_str(json).unwrap();
+
+        assert!(commits[0].commit.verification.verified);
+        assert!(!commits[1].commit.verification.verified);
+    }
+
+    #[test]
+    fn test_pr_limit_warning_fires_at_limit() {
+        assert!(pr_limit_warning("owner/repo", 99, 100).is_none());
+
+        let warning = pr_limit_warning("owner/repo", 100, 100).unwrap();
+        assert!(warning.contains("owner/repo"));
+        assert!(warning.contains("github.pr_limit"));
     }
 
     #[test]
@@ -398,6 +896,113 @@ mod tests {
         let result = parse_github_timestamp("2023-11-15T12:00:00Z");
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_classify_branch_status_matches_pr_by_head_ref() {
+        use crate::test_support::{BranchBuilder, PRBuilder};
+
+        let branch = BranchBuilder::new("feature-a").ahead(2).build("owner/repo");
+        let other_pr = PRBuilder::new(1, "Other")
+            .head_ref_name("feature-b")
+            .build("owner/repo");
+        let own_pr = PRBuilder::new(2, "Feature A")
+            .head_ref_name("feature-a")
+            .build("owner/repo");
+
+        // A PR for a different branch must not affect this branch
+        assert_eq!(
+            classify_branch_status(&branch, std::slice::from_ref(&other_pr), "main"),
+            BranchStatus::ReadyForPR
+        );
+        assert_eq!(
+            classify_branch_status(&branch, &[other_pr, own_pr], "main"),
+            BranchStatus::InReview
+        );
+    }
+
+    #[test]
+    fn test_classify_branch_status_open_pr_behind_needs_update() {
+        use crate::test_support::{BranchBuilder, PRBuilder};
+
+        let branch = BranchBuilder::new("feature-a")
+            .ahead(2)
+            .behind(3)
+            .build("owner/repo");
+        let merged_pr = PRBuilder::new(1, "Old attempt")
+            .state(PRState::Merged)
+            .head_ref_name("feature-a")
+            .build("owner/repo");
+        let open_pr = PRBuilder::new(2, "Feature A")
+            .head_ref_name("feature-a")
+            .build("owner/repo");
+
+        assert_eq!(
+            classify_branch_status(&branch, &[merged_pr, open_pr], "main"),
+            BranchStatus::NeedsUpdate
+        );
+    }
+
+    #[test]
+    fn test_link_pull_requests_to_branches() {
+        use crate::test_support::{BranchBuilder, PRBuilder};
+
+        let mut branch = BranchBuilder::new("feature-a").build("owner/repo");
+        branch.id = 42;
+        let mut prs = vec![
+            PRBuilder::new(1, "Feature A")
+                .head_ref_name("feature-a")
+                .build("owner/repo"),
+            PRBuilder::new(2, "Deleted branch")
+                .head_ref_name("gone")
+                .build("owner/repo"),
+        ];
+
+        link_pull_requests_to_branches(&mut prs, &[branch]);
+
+        assert_eq!(prs[0].branch_id, Some(42));
+        assert_eq!(prs[1].branch_id, None);
+    }
+
+    #[test]
+    fn test_clone_destination() {
+        let dest = tempfile::tempdir().unwrap();
+
+        assert_eq!(
+            clone_destination("owner/repo", dest.path()).unwrap(),
+            dest.path().join("owner").join("repo")
+        );
+
+        // Never clone over an existing directory or outside the destination
+        std::fs::create_dir_all(dest.path().join("owner").join("repo")).unwrap();
+        assert!(clone_destination("owner/repo", dest.path()).is_err());
+        assert!(clone_destination("owner/..", dest.path()).is_err());
+    }
+
+    #[test]
+    fn test_merge_pr_lists_keeps_every_open_pr() {
+        let pr = |number: u32, state: &str| GhPR {
+            number,
+            state: state.to_string(),
+            title: format!("PR {}", number),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+            head_ref_name: format!("branch-{}", number),
+            url: None,
+            review_decision: String::new(),
+        };
+
+        // An old open PR survives however much history there is
+        let open = vec![pr(3, "OPEN")];
+        let history = vec![pr(9, "MERGED"), pr(8, "CLOSED"), pr(3, "MERGED")];
+
+        let merged = merge_pr_lists(open, history);
+
+        let numbers: Vec<(u32, &str)> = merged
+            .iter()
+            .map(|pr| (pr.number, pr.state.as_str()))
+            .collect();
+        assert_eq!(numbers, vec![(3, "OPEN"), (9, "MERGED"), (8, "CLOSED")]);
+    }
 }
 
 // PR-related structures
@@ -411,39 +1016,39 @@ struct GhPR {
     #[serde(rename = "updatedAt")]
     updated_at: String,
     #[serde(rename = "headRefName")]
-    #[allow(dead_code)]
     head_ref_name: String,
+    #[serde(default)]
+    url: Option<String>,
+    /// Empty when no review is required
+    #[serde(default, rename = "reviewDecision")]
+    review_decision: String,
 }
 
-pub fn fetch_pull_requests(repo_id: &str) -> Result<Vec<PullRequest>> {
-    let output = Command::new("gh")
-        .args([
-            "pr",
-            "list",
-            "-R",
-            repo_id,
-            "--json",
-            "number,state,title,createdAt,updatedAt,headRefName",
-            "--limit",
-            "100",
-        ])
-        .output()
-        .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(Error::GitHubCLI(format!(
-            "gh CLI command failed: {}",
-            stderr
-        )));
-    }
+/// Warning for a PR list that reached the fetch limit and may be truncated
+pub fn pr_limit_warning(repo_id: &str, fetched: usize, limit: usize) -> Option<String> {
+    (fetched >= limit).then(|| {
+        format!(
+            "{} returned {} pull requests, the configured limit; some may be missing (raise github.pr_limit)",
+            repo_id, fetched
+        )
+    })
+}
 
-    let stdout = String::from_utf8(output.stdout)
-        .map_err(|e| Error::GitHubCLI(format!("Invalid UTF-8 in response: {}", e)))?;
+/// Merged and closed PRs kept for history, bounded separately from open ones
+/// so a busy history never crowds out a long-open PR
+const PR_HISTORY_LIMIT: usize = 50;
 
-    let gh_prs: Vec<GhPR> = serde_json::from_str(&stdout)?;
+/// Fetch up to `limit` open pull requests plus the most recent merged and
+/// closed ones (up to `PR_HISTORY_LIMIT`) for history
+pub fn fetch_pull_requests(repo_id: &str, limit: usize) -> Result<Vec<PullRequest>> {
+    let open = fetch_pull_requests_in_state(repo_id, "open", limit)?;
+    if let Some(warning) = pr_limit_warning(repo_id, open.len(), limit) {
+        eprintln!("Warning: {}", warning);
+    }
+    // `closed` includes merged pull requests
+    let history = fetch_pull_requests_in_state(repo_id, "closed", PR_HISTORY_LIMIT)?;
 
-    let prs: Vec<PullRequest> = gh_prs
+    merge_pr_lists(open, history)
         .into_iter()
         .enumerate()
         .map(|(idx, gh_pr)| {
@@ -461,13 +1066,55 @@ pub fn fetch_pull_requests(repo_id: &str) -> Result<Vec<PullRequest>> {
                 number: gh_pr.number,
                 state,
                 title: gh_pr.title,
+                head_ref_name: gh_pr.head_ref_name,
+                url: gh_pr.url,
+                review_decision: ReviewDecision::from_github(&gh_pr.review_decision),
                 created_at: parse_github_timestamp(&gh_pr.created_at)?,
                 updated_at: parse_github_timestamp(&gh_pr.updated_at)?,
             })
         })
-        .collect::<Result<Vec<PullRequest>>>()?;
+        .collect()
+}
+
+/// `gh pr list --state <state>`, newest first
+fn fetch_pull_requests_in_state(repo_id: &str, state: &str, limit: usize) -> Result<Vec<GhPR>> {
+    let output = gh_command()
+        .args([
+            "pr",
+            "list",
+            "-R",
+            repo_id,
+            "--state",
+            state,
+            "--json",
+            "number,state,title,createdAt,updatedAt,headRefName,url,reviewDecision",
+            "--limit",
+            &limit.to_string(),
+        ])
+        .output()
+        .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitHubCLI(format!(
+            "gh CLI command failed: {}",
+            stderr
+        )));
+    }
+
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|e| Error::GitHubCLI(format!("Invalid UTF-8 in response: {}", e)))?;
 
-    Ok(prs)
+    Ok(serde_json::from_str(&stdout)?)
+}
+
+/// Open PRs followed by history PRs, keeping the open entry when a PR shows
+/// up in both (it changed state between the two calls)
+fn merge_pr_lists(open: Vec<GhPR>, history: Vec<GhPR>) -> Vec<GhPR> {
+    let mut seen: std::collections::HashSet<u32> = open.iter().map(|pr| pr.number).collect();
+    let mut prs = open;
+    prs.extend(history.into_iter().filter(|pr| seen.insert(pr.number)));
+    prs
 }
 
 pub fn classify_branch_status(
@@ -480,11 +1127,13 @@ pub fn classify_branch_status(
         return BranchStatus::ReadyForPR; // Default branch doesn't need PR
     }
 
-    // Find PR for this branch
-    let pr = prs.iter().find(|pr| {
-        // Match by branch name (headRefName in PR)
-        pr.branch_id.is_none() // Simple match for now
-    });
+    // Find PR for this branch by its head ref, preferring an open PR over
+    // closed/merged ones left behind by earlier work on the same branch
+    let branch_prs = prs.iter().filter(|pr| pr.head_ref_name == branch.name);
+    let pr = branch_prs
+        .clone()
+        .find(|pr| pr.state == PRState::Open)
+        .or_else(|| branch_prs.clone().next());
 
     match pr {
         Some(pr) if pr.state == PRState::Open => {
@@ -499,6 +1148,29 @@ pub fn classify_branch_status(
     }
 }
 
+/// Link pull requests to their branches by matching head ref names
+///
+/// Sets `branch_id` on each PR whose `head_ref_name` matches a stored branch.
+pub fn link_pull_requests_to_branches(prs: &mut [PullRequest], branches: &[Branch]) {
+    for pr in prs.iter_mut() {
+        pr.branch_id = branches
+            .iter()
+            .find(|b| b.name == pr.head_ref_name)
+            .map(|b| b.id);
+    }
+}
+
+/// Title used for a PR created without one: the branch name with `-`/`_`
+/// replaced by spaces
+pub fn default_pr_title(branch_name: &str) -> String {
+    branch_name.replace(['-', '_'], " ")
+}
+
+/// Parse the PR number from a PR URL such as `https://github.com/o/r/pull/42`
+pub fn pr_number_from_url(url: &str) -> Option<u32> {
+    url.trim_end_matches('/').rsplit('/').next()?.parse().ok()
+}
+
 /// Create a pull request for a branch
 /// Returns the PR URL on success
 pub fn create_pull_request(
@@ -506,6 +1178,7 @@ pub fn create_pull_request(
     branch_name: &str,
     title: Option<&str>,
     body: Option<&str>,
+    draft: bool,
 ) -> Result<String> {
     // Validate repo_id format
     if !repo_id.contains('/') {
@@ -519,7 +1192,7 @@ pub fn create_pull_request(
     let mut args = vec!["pr", "create", "--repo", repo_id, "--head", branch_name];
 
     // Use provided title or generate from branch name
-    let default_title = branch_name.replace(['-', '_'], " ");
+    let default_title = default_pr_title(branch_name);
     let pr_title = title.unwrap_or(&default_title);
     args.push("--title");
     args.push(pr_title);
@@ -530,8 +1203,13 @@ pub fn create_pull_request(
     args.push("--body");
     args.push(pr_body);
 
+    // Draft PRs don't request review until marked ready
+    if draft {
+        args.push("--draft");
+    }
+
     // Execute command
-    let output = Command::new("gh")
+    let output = gh_command()
         .args(&args)
         .output()
         .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
@@ -566,3 +1244,99 @@ pub fn create_pull_request(
 
     Ok(pr_url)
 }
+
+/// Get the base branch a pull request targets
+pub fn fetch_pull_request_base(repo_id: &str, number: u32) -> Result<String> {
+    let output = gh_command()
+        .args([
+            "pr",
+            "view",
+            &number.to_string(),
+            "--repo",
+            repo_id,
+            "--json",
+            "baseRefName",
+            "--jq",
+            ".baseRefName",
+        ])
+        .output()
+        .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitHubCLI(format!(
+            "Failed to get base branch of PR #{}: {}",
+            number, stderr
+        )));
+    }
+
+    let base = String::from_utf8(output.stdout)
+        .map_err(|e| Error::GitHubCLI(format!("Invalid UTF-8 in response: {}", e)))?
+        .trim()
+        .to_string();
+
+    Ok(base)
+}
+
+/// Merge a pull request with the given method
+pub fn merge_pull_request(repo_id: &str, number: u32, method: MergeMethod) -> Result<()> {
+    let output = gh_command()
+        .args([
+            "pr",
+            "merge",
+            &number.to_string(),
+            "--repo",
+            repo_id,
+            method.flag(),
+        ])
+        .output()
+        .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitHubCLI(format!(
+            "Failed to merge PR #{}: {}",
+            number, stderr
+        )));
+    }
+
+    Ok(())
+}
+
+/// Where `clone_repo` puts a repository: `<dest>/<owner>/<name>`, the layout
+/// `local_git::extract_repo_id` reads ids back from
+fn clone_destination(repo_id: &str, dest: &Path) -> Result<PathBuf> {
+    let id: RepoId = repo_id.parse()?;
+    let path = dest.join(id.owner()).join(id.name());
+    if path.exists() {
+        return Err(Error::GitHubCLI(format!(
+            "Cannot clone {}: {} already exists",
+            repo_id,
+            path.display()
+        )));
+    }
+    Ok(path)
+}
+
+pub fn clone_repo(repo_id: &str, dest: &Path) -> Result<PathBuf> {
+    let path = clone_destination(repo_id, dest)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let output = gh_command()
+        .args(["repo", "clone", repo_id])
+        .arg(&path)
+        .output()
+        .map_err(|e| Error::GitHubCLI(format!("Failed to execute gh CLI: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitHubCLI(format!(
+            "Failed to clone {}: {}",
+            repo_id, stderr
+        )));
+    }
+
+    Ok(path)
+}
@@ -3,15 +3,19 @@
 pub mod client_trait;
 pub mod commands;
 pub mod real_client;
+pub mod scan;
 
 // Re-export the trait and real client for convenience
 pub use client_trait::GitHubClient;
+pub use commands::GhAuth;
 pub use real_client::RealGitHubClient;
+pub use scan::ScanOptions;
 
 use crate::{
-    models::{Branch, BranchStatus, Commit, PullRequest, Repository},
+    models::{Branch, BranchStatus, CiStatus, Commit, MergeMethod, PullRequest, Repository},
     Result,
 };
+use std::path::{Path, PathBuf};
 
 /// List repositories for a given owner (user or organization)
 ///
@@ -21,6 +25,16 @@ pub fn list_repos(owner: &str, limit: usize) -> Result<Vec<Repository>> {
     commands::list_repos(owner, limit)
 }
 
+/// List the repository ids a team in an organization has access to
+pub fn list_team_repos(org: &str, team: &str) -> Result<Vec<String>> {
+    commands::list_team_repos(org, team)
+}
+
+/// Keep only repositories belonging to the given team repo ids
+pub fn filter_repos_to_team(repos: &mut Vec<Repository>, team_repo_ids: &[String]) {
+    commands::filter_repos_to_team(repos, team_repo_ids)
+}
+
 /// Fetch all branches for a repository
 ///
 /// This is a convenience function that uses RealGitHubClient.
@@ -29,20 +43,85 @@ pub fn fetch_branches(repo_id: &str) -> Result<Vec<Branch>> {
     commands::fetch_branches(repo_id)
 }
 
-/// Fetch all pull requests for a repository
+/// Fetch all branches, reusing stored ahead/behind counts for unchanged ones
+///
+/// Pass the branches stored by the previous scan and an already-resolved
+/// default branch to avoid repeating per-branch compare calls, and the local
+/// clone, if any, to compare the remaining branches without the API.
+pub fn fetch_branches_since(
+    repo_id: &str,
+    default_branch: &str,
+    previous: &[Branch],
+    local_clone: Option<&Path>,
+) -> Result<Vec<Branch>> {
+    commands::fetch_branches_since(repo_id, default_branch, previous, local_clone)
+}
+
+/// Fetch up to `limit` pull requests for a repository, warning when the
+/// limit is reached
+///
+/// This is a convenience function that uses RealGitHubClient.
+/// For testable code, prefer injecting a GitHubClient trait object.
+pub fn fetch_pull_requests(repo_id: &str, limit: usize) -> Result<Vec<PullRequest>> {
+    commands::fetch_pull_requests(repo_id, limit)
+}
+
+/// Fetch the newest `limit` commits of a specific branch
+///
+/// This is a convenience function that uses RealGitHubClient.
+/// For testable code, prefer injecting a GitHubClient trait object.
+pub fn fetch_commits(
+    repo_id: &str,
+    branch_name: &str,
+    branch_id: i64,
+    limit: usize,
+) -> Result<Vec<Commit>> {
+    commands::fetch_commits(repo_id, branch_name, branch_id, limit)
+}
+
+/// Fetch the unified diff of a branch against a base branch
 ///
 /// This is a convenience function that uses RealGitHubClient.
 /// For testable code, prefer injecting a GitHubClient trait object.
-pub fn fetch_pull_requests(repo_id: &str) -> Result<Vec<PullRequest>> {
-    commands::fetch_pull_requests(repo_id)
+pub fn fetch_branch_diff(repo_id: &str, base: &str, head: &str) -> Result<String> {
+    commands::fetch_branch_diff(repo_id, base, head)
 }
 
-/// Fetch commits for a specific branch
+/// Fetch the combined CI status of the check runs on a branch or commit
 ///
 /// This is a convenience function that uses RealGitHubClient.
 /// For testable code, prefer injecting a GitHubClient trait object.
-pub fn fetch_commits(repo_id: &str, branch_name: &str, branch_id: i64) -> Result<Vec<Commit>> {
-    commands::fetch_commits(repo_id, branch_name, branch_id)
+pub fn fetch_ci_status(repo_id: &str, git_ref: &str) -> Result<CiStatus> {
+    commands::fetch_ci_status(repo_id, git_ref)
+}
+
+/// Fetch the full message of a single commit
+///
+/// Stored commit messages may be truncated; use this to show the whole body
+/// on demand.
+pub fn fetch_commit_message(repo_id: &str, sha: &str) -> Result<String> {
+    commands::fetch_commit_message(repo_id, sha)
+}
+
+/// How `gh` is authenticated; without a gh login, `GH_TOKEN` or
+/// `GITHUB_TOKEN` from the environment is passed to every `gh` call
+pub fn gh_auth() -> GhAuth {
+    commands::gh_auth()
+}
+
+/// The installed gh CLI version
+pub fn gh_version() -> Result<String> {
+    commands::gh_version()
+}
+
+/// Get the default branch name for a repository
+pub fn get_default_branch(repo_id: &str) -> Result<String> {
+    commands::get_default_branch(repo_id)
+}
+
+/// Link pull requests to their branches by head ref name
+pub fn link_pull_requests_to_branches(prs: &mut [PullRequest], branches: &[Branch]) {
+    commands::link_pull_requests_to_branches(prs, branches)
 }
 
 /// Classify branch status based on PR state
@@ -64,8 +143,25 @@ pub fn create_pull_request(
     branch_name: &str,
     title: Option<&str>,
     body: Option<&str>,
+    draft: bool,
 ) -> Result<String> {
-    commands::create_pull_request(repo_id, branch_name, title, body)
+    commands::create_pull_request(repo_id, branch_name, title, body, draft)
+}
+
+/// Merge a pull request with the given method
+///
+/// This is a convenience function that uses RealGitHubClient.
+/// For testable code, prefer injecting a GitHubClient trait object.
+pub fn merge_pull_request(repo_id: &str, number: u32, method: MergeMethod) -> Result<()> {
+    commands::merge_pull_request(repo_id, number, method)
+}
+
+/// Clone a repository with `gh repo clone` into `<dest>/<owner>/<name>`,
+/// returning the clone's path
+///
+/// Fails without cloning when that path already exists.
+pub fn clone_repo(repo_id: &str, dest: &Path) -> Result<PathBuf> {
+    commands::clone_repo(repo_id, dest)
 }
 
 #[cfg(test)]
@@ -2,9 +2,10 @@
 
 use super::{client_trait::GitHubClient, commands};
 use crate::{
-    models::{Branch, BranchStatus, Commit, PullRequest, Repository},
+    models::{Branch, BranchStatus, CiStatus, Commit, MergeMethod, PullRequest, Repository},
     Result,
 };
+use std::path::Path;
 
 /// Real GitHub client that delegates to gh CLI commands
 pub struct RealGitHubClient;
@@ -26,12 +27,34 @@ impl GitHubClient for RealGitHubClient {
         commands::list_repos(owner, limit)
     }
 
+    fn get_repository(&self, repo_id: &str) -> Result<Repository> {
+        commands::get_repository(repo_id)
+    }
+
+    fn list_team_repos(&self, org: &str, team: &str) -> Result<Vec<String>> {
+        commands::list_team_repos(org, team)
+    }
+
+    fn get_default_branch(&self, repo_id: &str) -> Result<String> {
+        commands::get_default_branch(repo_id)
+    }
+
     fn fetch_branches(&self, repo_id: &str) -> Result<Vec<Branch>> {
         commands::fetch_branches(repo_id)
     }
 
-    fn fetch_pull_requests(&self, repo_id: &str) -> Result<Vec<PullRequest>> {
-        commands::fetch_pull_requests(repo_id)
+    fn fetch_branches_since(
+        &self,
+        repo_id: &str,
+        default_branch: &str,
+        previous: &[Branch],
+        local_clone: Option<&Path>,
+    ) -> Result<Vec<Branch>> {
+        commands::fetch_branches_since(repo_id, default_branch, previous, local_clone)
+    }
+
+    fn fetch_pull_requests(&self, repo_id: &str, limit: usize) -> Result<Vec<PullRequest>> {
+        commands::fetch_pull_requests(repo_id, limit)
     }
 
     fn fetch_commits(
@@ -39,8 +62,21 @@ impl GitHubClient for RealGitHubClient {
         repo_id: &str,
         branch_name: &str,
         branch_id: i64,
+        limit: usize,
     ) -> Result<Vec<Commit>> {
-        commands::fetch_commits(repo_id, branch_name, branch_id)
+        commands::fetch_commits(repo_id, branch_name, branch_id, limit)
+    }
+
+    fn fetch_branch_diff(&self, repo_id: &str, base: &str, head: &str) -> Result<String> {
+        commands::fetch_branch_diff(repo_id, base, head)
+    }
+
+    fn fetch_commit_message(&self, repo_id: &str, sha: &str) -> Result<String> {
+        commands::fetch_commit_message(repo_id, sha)
+    }
+
+    fn fetch_ci_status(&self, repo_id: &str, git_ref: &str) -> Result<CiStatus> {
+        commands::fetch_ci_status(repo_id, git_ref)
     }
 
     fn classify_branch_status(
@@ -58,8 +94,17 @@ impl GitHubClient for RealGitHubClient {
         branch_name: &str,
         title: Option<&str>,
         body: Option<&str>,
+        draft: bool,
     ) -> Result<String> {
-        commands::create_pull_request(repo_id, branch_name, title, body)
+        commands::create_pull_request(repo_id, branch_name, title, body, draft)
+    }
+
+    fn fetch_pull_request_base(&self, repo_id: &str, number: u32) -> Result<String> {
+        commands::fetch_pull_request_base(repo_id, number)
+    }
+
+    fn merge_pull_request(&self, repo_id: &str, number: u32, method: MergeMethod) -> Result<()> {
+        commands::merge_pull_request(repo_id, number, method)
     }
 }
 
@@ -0,0 +1,983 @@
+//! Repository scanning, independent of how progress is reported
+//!
+//! `run_scan` fetches repositories, branches, pull requests and commits
+//! through a `GitHubClient` and stores them, emitting a `ScanEvent` for each
+//! step. The CLI prints the events; other callers can forward them elsewhere.
+
+use super::{commands, GitHubClient};
+use crate::{
+    analysis,
+    config::{Config, GitHubConfig},
+    local_git,
+    models::{short_sha, CiStatus, Repository},
+    storage::Database,
+    Result,
+};
+use serde::Serialize;
+use std::fmt;
+use std::path::PathBuf;
+
+/// What to scan and how much of it to fetch
+///
+/// Start from `for_owner` or `from_config` (or `default()` for the built-in
+/// configuration) and adjust with the builder methods, e.g.
+/// `ScanOptions::for_owner(&config.github, owner).all_branch_commits(true)`.
+/// New scan settings belong here rather than in `run_scan`'s signature.
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Maximum number of repositories to scan
+    pub limit: usize,
+    /// Only scan repositories owned by this organization team (slug); the
+    /// limit applies to the team's repositories
+    pub team: Option<String>,
+    /// Fetch commits for every branch, not only those ahead of the default branch
+    pub all_branch_commits: bool,
+    /// `git fetch` local clones before comparing branches in them, so fewer
+    /// comparisons fall back to the GitHub API
+    pub fetch_local_clones: bool,
+    /// PR limit, per-status commit limits and exclude patterns
+    pub github: GitHubConfig,
+}
+
+impl ScanOptions {
+    /// Options taking the default repo limit from the configuration, with no team
+    pub fn from_config(github: &GitHubConfig) -> Self {
+        Self {
+            limit: github.repo_limit,
+            team: None,
+            all_branch_commits: false,
+            fetch_local_clones: false,
+            github: github.clone(),
+        }
+    }
+
+    /// Options for scanning `owner`, with its configured limit and team
+    pub fn for_owner(github: &GitHubConfig, owner: &str) -> Self {
+        Self::from_config(github)
+            .limit(github.repo_limit_for(owner))
+            .team(github.team_for(owner).map(String::from))
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn team(mut self, team: Option<String>) -> Self {
+        self.team = team;
+        self
+    }
+
+    pub fn all_branch_commits(mut self, all_branch_commits: bool) -> Self {
+        self.all_branch_commits = all_branch_commits;
+        self
+    }
+
+    pub fn fetch_local_clones(mut self, fetch_local_clones: bool) -> Self {
+        self.fetch_local_clones = fetch_local_clones;
+        self
+    }
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self::from_config(&Config::default().github)
+    }
+}
+
+/// Progress reported while a scan runs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ScanEvent {
+    /// The repository list was restricted to a team
+    #[serde(rename_all = "camelCase")]
+    TeamFiltered { team: String, repos: usize },
+    /// Repositories matching an exclude pattern were dropped
+    #[serde(rename_all = "camelCase")]
+    ReposExcluded { count: usize },
+    /// The repositories that will be processed
+    #[serde(rename_all = "camelCase")]
+    ReposFound { count: usize },
+    /// Processing of a repository started (`index` is 1-based)
+    #[serde(rename_all = "camelCase")]
+    RepoStarted {
+        index: usize,
+        total: usize,
+        repo_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    BranchesFetched { repo_id: String, count: usize },
+    #[serde(rename_all = "camelCase")]
+    PullRequestsFetched { repo_id: String, count: usize },
+    #[serde(rename_all = "camelCase")]
+    CommitsFetched {
+        repo_id: String,
+        branch: String,
+        count: usize,
+    },
+    /// A non-fatal error; the scan carries on with the next step
+    #[serde(rename_all = "camelCase")]
+    Error {
+        repo_id: String,
+        stage: ScanStage,
+        message: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    RepoFinished { repo_id: String },
+}
+
+/// The step of a repository's scan that failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScanStage {
+    Exclusions,
+    Repository,
+    DefaultBranch,
+    Branches,
+    PullRequests,
+    Commits,
+    Ci,
+}
+
+impl fmt::Display for ScanStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanStage::Exclusions => write!(f, "exclusions"),
+            ScanStage::Repository => write!(f, "repository"),
+            ScanStage::DefaultBranch => write!(f, "default branch"),
+            ScanStage::Branches => write!(f, "branches"),
+            ScanStage::PullRequests => write!(f, "pull requests"),
+            ScanStage::Commits => write!(f, "commits"),
+            ScanStage::Ci => write!(f, "CI status"),
+        }
+    }
+}
+
+/// A per-repo failure collected in `ScanSummary::errors`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanError {
+    pub repo_id: String,
+    pub stage: ScanStage,
+    pub message: String,
+}
+
+/// Machine-readable result of a scan
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanSummary {
+    /// Owner scanned; empty for `scan_repo_ids`
+    pub owner: String,
+    pub repos_processed: usize,
+    pub branches: usize,
+    pub pull_requests: usize,
+    pub commits: usize,
+    pub repos: Vec<RepoScanResult>,
+    /// Every per-repo error, in the order they occurred; a non-empty list
+    /// means the scan was only partly successful
+    pub errors: Vec<ScanError>,
+    /// Changelog lines for repositories that changed since the last scan
+    pub changes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoScanResult {
+    pub id: String,
+    pub branches: usize,
+    pub pull_requests: usize,
+    pub commits: usize,
+    pub errors: Vec<String>,
+}
+
+impl RepoScanResult {
+    fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            branches: 0,
+            pull_requests: 0,
+            commits: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Record a non-fatal error and report it as progress
+    fn error(&mut self, progress: &mut impl FnMut(ScanEvent), stage: ScanStage, message: String) {
+        progress(ScanEvent::Error {
+            repo_id: self.id.clone(),
+            stage,
+            message: message.clone(),
+        });
+        self.errors.push(message);
+    }
+}
+
+/// Repositories listed for an owner before filtering them to a team
+const TEAM_REPO_LIST_LIMIT: usize = 10_000;
+
+/// Scan an owner's repositories into the database
+///
+/// Only failing to list the repositories (or the team's repositories) aborts
+/// the scan; per-repo failures are reported as `ScanEvent::Error` and
+/// collected in `ScanSummary::errors`.
+pub fn run_scan(
+    db: &Database,
+    client: &dyn GitHubClient,
+    owner: &str,
+    opts: &ScanOptions,
+    mut report: impl FnMut(ScanEvent),
+) -> Result<ScanSummary> {
+    let mut errors = Vec::new();
+    let mut progress = |event: ScanEvent| {
+        if let ScanEvent::Error {
+            repo_id,
+            stage,
+            message,
+        } = &event
+        {
+            errors.push(ScanError {
+                repo_id: repo_id.clone(),
+                stage: *stage,
+                message: message.clone(),
+            });
+        }
+        report(event);
+    };
+
+    let repos = match opts.team {
+        // Restrict to the team's repositories. The whole owner is listed so
+        // team repos pushed less recently than the newest `limit` still count;
+        // the limit then applies to the team's repos.
+        Some(ref team) => {
+            let team_repo_ids = client.list_team_repos(owner, team)?;
+            let mut repos = client.list_repos(owner, TEAM_REPO_LIST_LIMIT)?;
+            commands::filter_repos_to_team(&mut repos, &team_repo_ids);
+            progress(ScanEvent::TeamFiltered {
+                team: team.clone(),
+                repos: repos.len(),
+            });
+            repos.truncate(opts.limit);
+            repos
+        }
+        None => client.list_repos(owner, opts.limit)?,
+    };
+
+    let mut summary = scan_repos(db, client, owner, repos, opts, &mut progress);
+    summary.errors = errors;
+    Ok(summary)
+}
+
+/// Scan individual repositories by id (owner/name), e.g. local clones that
+/// were never part of an owner scan
+///
+/// A repository GitHub can't find is reported as a `ScanStage::Repository`
+/// error and skipped; `ScanSummary::owner` is left empty.
+pub fn scan_repo_ids(
+    db: &Database,
+    client: &dyn GitHubClient,
+    repo_ids: &[String],
+    opts: &ScanOptions,
+    mut report: impl FnMut(ScanEvent),
+) -> ScanSummary {
+    let mut errors = Vec::new();
+    let mut progress = |event: ScanEvent| {
+        if let ScanEvent::Error {
+            repo_id,
+            stage,
+            message,
+        } = &event
+        {
+            errors.push(ScanError {
+                repo_id: repo_id.clone(),
+                stage: *stage,
+                message: message.clone(),
+            });
+        }
+        report(event);
+    };
+
+    let repos = repo_ids
+        .iter()
+        .filter_map(|repo_id| match client.get_repository(repo_id) {
+            Ok(repo) => Some(repo),
+            Err(e) => {
+                progress(ScanEvent::Error {
+                    repo_id: repo_id.clone(),
+                    stage: ScanStage::Repository,
+                    message: format!("Error fetching repository: {}", e),
+                });
+                None
+            }
+        })
+        .collect();
+
+    let mut summary = scan_repos(db, client, "", repos, opts, &mut progress);
+    summary.errors = errors;
+    summary
+}
+
+/// Drop excluded repositories, then scan the rest in order
+fn scan_repos(
+    db: &Database,
+    client: &dyn GitHubClient,
+    owner: &str,
+    mut repos: Vec<Repository>,
+    opts: &ScanOptions,
+    progress: &mut impl FnMut(ScanEvent),
+) -> ScanSummary {
+    // Drop excluded repositories before doing any per-repo work
+    let before_exclude = repos.len();
+    repos.retain(
+        |repo| match db.is_excluded(&repo.id, &opts.github.repo_exclude) {
+            Ok(excluded) => !excluded,
+            Err(e) => {
+                progress(ScanEvent::Error {
+                    repo_id: repo.id.clone(),
+                    stage: ScanStage::Exclusions,
+                    message: format!("Error checking exclusions: {}", e),
+                });
+                true
+            }
+        },
+    );
+    if repos.len() < before_exclude {
+        progress(ScanEvent::ReposExcluded {
+            count: before_exclude - repos.len(),
+        });
+    }
+
+    progress(ScanEvent::ReposFound { count: repos.len() });
+
+    let mut summary = ScanSummary {
+        owner: owner.to_string(),
+        repos_processed: 0,
+        branches: 0,
+        pull_requests: 0,
+        commits: 0,
+        repos: Vec::new(),
+        errors: Vec::new(),
+        changes: Vec::new(),
+    };
+
+    for (i, repo) in repos.iter().enumerate() {
+        progress(ScanEvent::RepoStarted {
+            index: i + 1,
+            total: repos.len(),
+            repo_id: repo.id.clone(),
+        });
+
+        if let Some(result) = scan_repo(db, client, repo, opts, &mut summary, progress) {
+            summary.repos_processed += 1;
+            summary.branches += result.branches;
+            summary.pull_requests += result.pull_requests;
+            summary.commits += result.commits;
+            summary.repos.push(result);
+        }
+
+        progress(ScanEvent::RepoFinished {
+            repo_id: repo.id.clone(),
+        });
+    }
+
+    summary
+}
+
+/// Scan a single repository, returning `None` when it could not be saved
+///
+/// Unsaved repositories are still added to `summary.repos` so their error is
+/// reported, but do not count as processed.
+fn scan_repo(
+    db: &Database,
+    client: &dyn GitHubClient,
+    repo: &Repository,
+    opts: &ScanOptions,
+    summary: &mut ScanSummary,
+    progress: &mut impl FnMut(ScanEvent),
+) -> Option<RepoScanResult> {
+    let mut result = RepoScanResult::new(&repo.id);
+
+    // Snapshot what was stored before so the scan can report a delta;
+    // repositories seen for the first time have nothing to diff against
+    let before = match db.get_repository(&repo.id) {
+        Ok(Some(_)) => analysis::snapshot_repo(db, &repo.id).ok(),
+        _ => None,
+    };
+
+    if let Err(e) = db.save_repository(repo) {
+        result.error(
+            progress,
+            ScanStage::Repository,
+            format!("Error saving repository: {}", e),
+        );
+        summary.repos.push(result);
+        return None;
+    }
+
+    // Resolve the default branch once per repo; it is needed for
+    // both branch comparison and classification
+    let default_branch = match client.get_default_branch(&repo.id) {
+        Ok(default_branch) => Some(default_branch),
+        Err(e) => {
+            result.error(
+                progress,
+                ScanStage::DefaultBranch,
+                format!("Error getting default branch: {}", e),
+            );
+            None
+        }
+    };
+
+    // CI on the default branch; failing checks there are the most urgent
+    if let Some(ref default_branch) = default_branch {
+        match client.fetch_ci_status(&repo.id, default_branch) {
+            Ok(status) => {
+                if let Err(e) = db.save_ci_status(&repo.id, status) {
+                    result.error(
+                        progress,
+                        ScanStage::Ci,
+                        format!("Error saving CI status: {}", e),
+                    );
+                }
+            }
+            // Checks the token can't see, or a ref without any, mean no CI
+            Err(crate::Error::GitHubAPI {
+                status: 403 | 404, ..
+            }) => {
+                if let Err(e) = db.save_ci_status(&repo.id, CiStatus::None) {
+                    result.error(
+                        progress,
+                        ScanStage::Ci,
+                        format!("Error saving CI status: {}", e),
+                    );
+                }
+            }
+            Err(e) => result.error(
+                progress,
+                ScanStage::Ci,
+                format!("Error fetching CI status: {}", e),
+            ),
+        }
+    }
+
+    // Branches from the last scan let unchanged ones skip comparison
+    let previous_branches = db.get_branches_for_repo(&repo.id).unwrap_or_default();
+
+    // A local clone lets the remaining branches be compared with git
+    let local_clone = db
+        .get_local_repo_status(&repo.id)
+        .ok()
+        .flatten()
+        .map(|status| PathBuf::from(status.local_path))
+        .filter(|path| path.is_dir());
+    if let (Some(path), true) = (&local_clone, opts.fetch_local_clones) {
+        if let Err(e) = local_git::fetch_remote(path) {
+            tracing::debug!("{}: fetching {} failed: {}", repo.id, path.display(), e);
+        }
+    }
+
+    let fetched = match default_branch {
+        Some(ref default_branch) => client.fetch_branches_since(
+            &repo.id,
+            default_branch,
+            &previous_branches,
+            local_clone.as_deref(),
+        ),
+        None => client.fetch_branches(&repo.id),
+    };
+    match fetched {
+        Ok(branches) => {
+            progress(ScanEvent::BranchesFetched {
+                repo_id: repo.id.clone(),
+                count: branches.len(),
+            });
+            result.branches = branches.len();
+
+            if let Err(e) = db.clear_branches_for_repo(&repo.id) {
+                result.error(
+                    progress,
+                    ScanStage::Branches,
+                    format!("Error clearing old branches: {}", e),
+                );
+            }
+
+            // Save branches; commits are fetched once they are classified
+            for branch in &branches {
+                if let Err(e) = db.save_branch(branch) {
+                    result.error(
+                        progress,
+                        ScanStage::Branches,
+                        format!("Error saving branch {}: {}", branch.name, e),
+                    );
+                }
+            }
+        }
+        Err(e) => result.error(
+            progress,
+            ScanStage::Branches,
+            format!("Error fetching branches: {}", e),
+        ),
+    }
+
+    match client.fetch_pull_requests(&repo.id, opts.github.pr_limit) {
+        Ok(mut prs) => {
+            progress(ScanEvent::PullRequestsFetched {
+                repo_id: repo.id.clone(),
+                count: prs.len(),
+            });
+            result.pull_requests = prs.len();
+
+            if let Err(e) = db.clear_pull_requests_for_repo(&repo.id) {
+                result.error(
+                    progress,
+                    ScanStage::PullRequests,
+                    format!("Error clearing old PRs: {}", e),
+                );
+            }
+
+            // Link PRs to their stored branches by head ref
+            let stored_branches = db.get_branches_for_repo(&repo.id).unwrap_or_default();
+            commands::link_pull_requests_to_branches(&mut prs, &stored_branches);
+
+            for pr in &prs {
+                if let Err(e) = db.save_pull_request(pr) {
+                    result.error(
+                        progress,
+                        ScanStage::PullRequests,
+                        format!("Error saving PR #{}: {}", pr.number, e),
+                    );
+                }
+            }
+
+            // Classify branches now that their PRs are known
+            if let Some(ref default_branch) = default_branch {
+                for branch in &stored_branches {
+                    let status = client.classify_branch_status(branch, &prs, default_branch);
+                    if status != branch.status {
+                        if let Err(e) = db.update_branch_status(branch.id, &status) {
+                            result.error(
+                                progress,
+                                ScanStage::PullRequests,
+                                format!("Error updating status for {}: {}", branch.name, e),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        Err(e) => result.error(
+            progress,
+            ScanStage::PullRequests,
+            format!("Error fetching pull requests: {}", e),
+        ),
+    }
+
+    // Fetch commits for branches with unmerged changes, or for every
+    // branch when asked to, sized by each branch's classified status
+    let stored_branches = db.get_branches_for_repo(&repo.id).unwrap_or_default();
+    for branch in &stored_branches {
+        if !opts.all_branch_commits && branch.ahead_by == 0 {
+            continue;
+        }
+
+        let limit = opts.github.commit_limit_for(&branch.status);
+        match client.fetch_commits(&repo.id, &branch.name, branch.id, limit) {
+            Ok(commits) => {
+                progress(ScanEvent::CommitsFetched {
+                    repo_id: repo.id.clone(),
+                    branch: branch.name.clone(),
+                    count: commits.len(),
+                });
+                result.commits += commits.len();
+
+                if let Err(e) = db.clear_commits_for_branch(branch.id) {
+                    result.error(
+                        progress,
+                        ScanStage::Commits,
+                        format!("Error clearing old commits: {}", e),
+                    );
+                }
+
+                for commit in &commits {
+                    if let Err(e) = db.save_commit(commit) {
+                        result.error(
+                            progress,
+                            ScanStage::Commits,
+                            format!("Error saving commit {}: {}", short_sha(&commit.sha), e),
+                        );
+                    }
+                }
+            }
+            Err(e) => result.error(
+                progress,
+                ScanStage::Commits,
+                format!("Error fetching commits for {}: {}", branch.name, e),
+            ),
+        }
+    }
+
+    match before {
+        Some(before) => {
+            if let Ok(after) = analysis::snapshot_repo(db, &repo.id) {
+                let delta = analysis::scan_delta(&before, &after);
+                if !delta.is_empty() {
+                    summary.changes.push(delta.to_string());
+                }
+            }
+        }
+        None => summary
+            .changes
+            .push(format!("{}: new repository", repo.name)),
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OwnerConfig;
+    use crate::models::BranchStatus;
+    use crate::test_support::fixtures::CommitBuilder;
+    use crate::test_support::{
+        BranchBuilder, MockGitHubClient, PRBuilder, RepoBuilder, TestDatabase,
+    };
+
+    fn scan(
+        db: &Database,
+        client: &MockGitHubClient,
+        opts: &ScanOptions,
+    ) -> (ScanSummary, Vec<ScanEvent>) {
+        let mut events = Vec::new();
+        let summary = run_scan(db, client, "testowner", opts, |e| events.push(e)).unwrap();
+        (summary, events)
+    }
+
+    #[test]
+    fn test_scan_options_builder() {
+        let mut config = Config::default();
+        let defaults = ScanOptions::default();
+        assert_eq!(defaults.limit, config.github.repo_limit);
+        assert_eq!(defaults.team, None);
+        assert!(!defaults.all_branch_commits);
+        assert!(!defaults.fetch_local_clones);
+
+        let opts = ScanOptions::from_config(&config.github)
+            .limit(5)
+            .team(Some("core".to_string()))
+            .all_branch_commits(true)
+            .fetch_local_clones(true);
+        assert_eq!(opts.limit, 5);
+        assert_eq!(opts.team.as_deref(), Some("core"));
+        assert!(opts.all_branch_commits);
+        assert!(opts.fetch_local_clones);
+
+        config.github.owners.push(OwnerConfig {
+            name: "big-org".to_string(),
+            limit: Some(200),
+            team: Some("core".to_string()),
+        });
+        let owner_opts = ScanOptions::for_owner(&config.github, "big-org");
+        assert_eq!(owner_opts.limit, 200);
+        assert_eq!(owner_opts.team.as_deref(), Some("core"));
+        // Another owner's team does not leak into this one's scans
+        let other = ScanOptions::for_owner(&config.github, "softwarewrighter");
+        assert_eq!(other.team, None);
+    }
+
+    #[test]
+    fn test_run_scan_stores_repos_and_reports_progress() {
+        let test_db = TestDatabase::new().unwrap();
+        let repo_id = "testowner/proj";
+        let client = MockGitHubClient::new()
+            .with_repo("testowner", RepoBuilder::new("proj").build())
+            .with_branches(
+                repo_id,
+                vec![
+                    BranchBuilder::new("main").build(repo_id),
+                    BranchBuilder::new("feature/x").ahead(2).build(repo_id),
+                ],
+            )
+            .with_pull_requests(
+                repo_id,
+                vec![PRBuilder::new(7, "Add x")
+                    .head_ref_name("feature/x")
+                    .build(repo_id)],
+            )
+            .with_commits(
+                repo_id,
+                "feature/x",
+                vec![CommitBuilder::new("abcdef1234", "Add x").build(0)],
+            )
+            .with_ci_status(repo_id, CiStatus::Failing);
+        let opts = ScanOptions::default();
+
+        let (summary, events) = scan(&test_db.db, &client, &opts);
+
+        assert_eq!(summary.repos_processed, 1);
+        assert_eq!(summary.branches, 2);
+        assert_eq!(summary.pull_requests, 1);
+        assert_eq!(summary.commits, 1);
+        assert!(summary.errors.is_empty());
+        assert_eq!(summary.changes, vec!["proj: new repository"]);
+
+        assert_eq!(
+            events,
+            vec![
+                ScanEvent::ReposFound { count: 1 },
+                ScanEvent::RepoStarted {
+                    index: 1,
+                    total: 1,
+                    repo_id: repo_id.to_string()
+                },
+                ScanEvent::BranchesFetched {
+                    repo_id: repo_id.to_string(),
+                    count: 2
+                },
+                ScanEvent::PullRequestsFetched {
+                    repo_id: repo_id.to_string(),
+                    count: 1
+                },
+                ScanEvent::CommitsFetched {
+                    repo_id: repo_id.to_string(),
+                    branch: "feature/x".to_string(),
+                    count: 1
+                },
+                ScanEvent::RepoFinished {
+                    repo_id: repo_id.to_string()
+                },
+            ]
+        );
+
+        // The open PR classifies the branch and is linked to it
+        let branches = test_db.db.get_branches_for_repo(repo_id).unwrap();
+        let feature = branches.iter().find(|b| b.name == "feature/x").unwrap();
+        assert_eq!(feature.status, BranchStatus::InReview);
+        let prs = test_db.db.get_pull_requests_for_repo(repo_id).unwrap();
+        assert_eq!(prs[0].branch_id, Some(feature.id));
+        assert_eq!(
+            test_db.db.get_ci_status(repo_id).unwrap(),
+            CiStatus::Failing
+        );
+    }
+
+    #[test]
+    fn test_repeated_commit_in_a_scan_keeps_its_first_row() {
+        let test_db = TestDatabase::new().unwrap();
+        let repo_id = "testowner/proj";
+        // The same SHA twice, as overlapping API pages can return it
+        let client = MockGitHubClient::new()
+            .with_repo("testowner", RepoBuilder::new("proj").build())
+            .with_branches(
+                repo_id,
+                vec![BranchBuilder::new("feature").ahead(2).build(repo_id)],
+            )
+            .with_commits(
+                repo_id,
+                "feature",
+                vec![
+                    CommitBuilder::new("abcdef1234", "Second").build(0),
+                    CommitBuilder::new("1234abcdef", "First").build(1),
+                    CommitBuilder::new("abcdef1234", "Second").build(2),
+                ],
+            );
+        let opts = ScanOptions::default();
+        let stored_shas = || {
+            let branches = test_db.db.get_branches_for_repo(repo_id).unwrap();
+            let mut commits = test_db.db.get_commits_for_branch(branches[0].id).unwrap();
+            commits.sort_by_key(|c| c.id);
+            commits.into_iter().map(|c| c.sha).collect::<Vec<_>>()
+        };
+
+        scan(&test_db.db, &client, &opts);
+        // The repeat updates the row saved first instead of replacing it
+        assert_eq!(stored_shas(), vec!["abcdef1234", "1234abcdef"]);
+
+        // Rescanning the branch adds no duplicate rows
+        scan(&test_db.db, &client, &opts);
+        assert_eq!(stored_shas(), vec!["abcdef1234", "1234abcdef"]);
+    }
+
+    #[test]
+    fn test_run_scan_filters_team_and_excluded_repos() {
+        let test_db = TestDatabase::new().unwrap();
+        test_db.db.add_exclude_pattern("testowner/old-*").unwrap();
+        let client = MockGitHubClient::new()
+            .with_repo("testowner", RepoBuilder::new("kept").build())
+            .with_repo("testowner", RepoBuilder::new("old-thing").build())
+            .with_repo("testowner", RepoBuilder::new("other-team").build())
+            .with_team_repos(
+                "testowner",
+                "core",
+                vec!["testowner/kept", "testowner/old-thing"],
+            );
+        let opts = ScanOptions::default().team(Some("core".to_string()));
+
+        let (summary, events) = scan(&test_db.db, &client, &opts);
+
+        let ids: Vec<&str> = summary.repos.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["testowner/kept"]);
+        assert!(events.contains(&ScanEvent::TeamFiltered {
+            team: "core".to_string(),
+            repos: 2
+        }));
+        assert!(events.contains(&ScanEvent::ReposExcluded { count: 1 }));
+    }
+
+    #[test]
+    fn test_run_scan_collects_per_repo_errors() {
+        let test_db = TestDatabase::new().unwrap();
+        let client = MockGitHubClient::new()
+            .with_repo("testowner", RepoBuilder::new("ok").build())
+            .with_repo("testowner", RepoBuilder::new("flaky").build())
+            .with_failing_pull_requests("testowner/flaky");
+        let opts = ScanOptions::default();
+
+        let (summary, _) = scan(&test_db.db, &client, &opts);
+
+        // The failing repo is still stored and counted; only the error is extra
+        assert_eq!(summary.repos_processed, 2);
+        assert_eq!(summary.errors.len(), 1);
+        let error = &summary.errors[0];
+        assert_eq!(error.repo_id, "testowner/flaky");
+        assert_eq!(error.stage, ScanStage::PullRequests);
+        assert!(error.message.contains("Bad gateway"));
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["errors"][0]["repoId"], "testowner/flaky");
+        assert_eq!(json["errors"][0]["stage"], "pullRequests");
+    }
+
+    #[test]
+    fn test_scan_treats_inaccessible_checks_as_no_ci() {
+        let test_db = TestDatabase::new().unwrap();
+        let client = MockGitHubClient::new()
+            .with_repo("testowner", RepoBuilder::new("private").build())
+            .with_repo("testowner", RepoBuilder::new("gone").build())
+            .with_repo("testowner", RepoBuilder::new("broken").build())
+            .with_ci_error("testowner/private", 403)
+            .with_ci_error("testowner/gone", 404)
+            .with_ci_error("testowner/broken", 500);
+        let opts = ScanOptions::default();
+
+        let (summary, _) = scan(&test_db.db, &client, &opts);
+
+        // Only the unexpected failure is an error
+        assert_eq!(summary.errors.len(), 1);
+        assert_eq!(summary.errors[0].repo_id, "testowner/broken");
+        assert_eq!(summary.errors[0].stage, ScanStage::Ci);
+        for repo_id in ["testowner/private", "testowner/gone"] {
+            assert_eq!(test_db.db.get_ci_status(repo_id).unwrap(), CiStatus::None);
+        }
+    }
+
+    #[test]
+    fn test_scan_repo_ids_fetches_each_repository() {
+        let test_db = TestDatabase::new().unwrap();
+        let client = MockGitHubClient::new().with_repo(
+            "someone",
+            RepoBuilder::new("side-project").owner("someone").build(),
+        );
+        let opts = ScanOptions::default();
+
+        let summary = scan_repo_ids(
+            &test_db.db,
+            &client,
+            &[
+                "someone/side-project".to_string(),
+                "someone/gone".to_string(),
+            ],
+            &opts,
+            |_| {},
+        );
+
+        assert_eq!(summary.repos_processed, 1);
+        assert!(test_db
+            .db
+            .get_repository("someone/side-project")
+            .unwrap()
+            .is_some());
+        assert_eq!(summary.errors.len(), 1);
+        assert_eq!(summary.errors[0].repo_id, "someone/gone");
+        assert_eq!(summary.errors[0].stage, ScanStage::Repository);
+    }
+
+    #[test]
+    fn test_run_scan_passes_local_clone_to_branch_fetch() {
+        let test_db = TestDatabase::new()
+            .unwrap()
+            .with_repo(RepoBuilder::new("cloned"))
+            .unwrap();
+        let clone = tempfile::tempdir().unwrap();
+        test_db
+            .db
+            .save_local_repo_status(&crate::models::LocalRepoStatus {
+                id: 0,
+                repo_id: "testowner/cloned".to_string(),
+                local_path: clone.path().to_string_lossy().into_owned(),
+                current_branch: Some("main".to_string()),
+                uncommitted_files: 0,
+                unpushed_commits: 0,
+                behind_commits: 0,
+                is_dirty: false,
+                has_conflicts: false,
+                is_diverged: false,
+                is_unpublished: false,
+                last_checked: chrono::Utc::now(),
+            })
+            .unwrap();
+        let client = MockGitHubClient::new()
+            .with_repo("testowner", RepoBuilder::new("cloned").build())
+            .with_repo("testowner", RepoBuilder::new("remote-only").build());
+        let opts = ScanOptions::default();
+
+        scan(&test_db.db, &client, &opts);
+
+        assert_eq!(
+            client.get_branch_fetch_clones(),
+            vec![
+                (
+                    "testowner/cloned".to_string(),
+                    Some(clone.path().to_path_buf())
+                ),
+                ("testowner/remote-only".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_scan_limits_after_team_filter() {
+        let test_db = TestDatabase::new().unwrap();
+        // Newest first, as gh lists them; the team's repos are the oldest
+        let client = MockGitHubClient::new()
+            .with_repo("testowner", RepoBuilder::new("newest").build())
+            .with_repo("testowner", RepoBuilder::new("newer").build())
+            .with_repo("testowner", RepoBuilder::new("team-a").build())
+            .with_repo("testowner", RepoBuilder::new("team-b").build())
+            .with_team_repos(
+                "testowner",
+                "core",
+                vec!["testowner/team-a", "testowner/team-b"],
+            );
+        let opts = ScanOptions::default()
+            .limit(1)
+            .team(Some("core".to_string()));
+
+        let (summary, _) = scan(&test_db.db, &client, &opts);
+
+        let ids: Vec<&str> = summary.repos.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["testowner/team-a"]);
+    }
+
+    #[test]
+    fn test_run_scan_aborts_when_team_is_unknown() {
+        let test_db = TestDatabase::new().unwrap();
+        let client =
+            MockGitHubClient::new().with_repo("testowner", RepoBuilder::new("proj").build());
+        let opts = ScanOptions::default().team(Some("missing".to_string()));
+
+        let result = run_scan(&test_db.db, &client, "testowner", &opts, |_| {});
+
+        assert!(result.is_err());
+        assert!(test_db.db.get_all_repositories().unwrap().is_empty());
+    }
+}
@@ -10,6 +10,7 @@ pub mod ai;
 pub mod analysis;
 pub mod config;
 pub mod error;
+pub mod export;
 pub mod github;
 pub mod local_git;
 pub mod models;
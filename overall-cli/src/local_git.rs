@@ -1,12 +1,29 @@
 //! Local git repository scanning and status detection
 
-use crate::{models::LocalRepoStatus, Result};
+use crate::{
+    config::Config,
+    models::{LocalRepoStatus, RepoId},
+    Result,
+};
 use chrono::Utc;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::OnceLock;
+
+/// The `git` binary, resolved once per process (see `ToolsConfig::git`)
+fn git_program() -> &'static str {
+    static PROGRAM: OnceLock<String> = OnceLock::new();
+    PROGRAM.get_or_init(|| Config::load().unwrap_or_default().tools.git())
+}
 
 /// Scan a directory for git repositories
-/// Returns a list of paths to git repositories found
+/// Returns a list of canonicalized paths to git repositories found, so the
+/// same repository reached through different roots yields the same path
+///
+/// A symlinked root is resolved once up front, so ids come from the real
+/// directory names. Entries that are symlinks pointing outside the root are
+/// skipped, and a repository reached both directly and through a symlink
+/// inside the root is reported once.
 pub fn scan_for_git_repos(root_path: &Path) -> Result<Vec<PathBuf>> {
     let mut repos = Vec::new();
 
@@ -17,8 +34,12 @@ pub fn scan_for_git_repos(root_path: &Path) -> Result<Vec<PathBuf>> {
         )));
     }
 
+    let root = std::fs::canonicalize(root_path).map_err(|e| {
+        crate::Error::GitCommand(format!("Failed to resolve {}: {}", root_path.display(), e))
+    })?;
+
     // Read directory entries
-    let entries = std::fs::read_dir(root_path)
+    let entries = std::fs::read_dir(&root)
         .map_err(|e| crate::Error::GitCommand(format!("Failed to read directory: {}", e)))?;
 
     for entry in entries {
@@ -29,37 +50,97 @@ pub fn scan_for_git_repos(root_path: &Path) -> Result<Vec<PathBuf>> {
         let path = entry.path();
 
         // Check if this directory is a git repository
-        if path.is_dir() && path.join(".git").exists() {
-            repos.push(path);
+        if !(path.is_dir() && path.join(".git").exists()) {
+            continue;
         }
+        let Ok(canonical) = std::fs::canonicalize(&path) else {
+            continue;
+        };
+        if entry.file_type().is_ok_and(|t| t.is_symlink()) && !canonical.starts_with(&root) {
+            tracing::debug!(
+                "Skipping {}: symlink to {} outside the root",
+                path.display(),
+                canonical.display()
+            );
+            continue;
+        }
+        repos.push(canonical);
     }
 
+    repos.sort();
+    repos.dedup();
     Ok(repos)
 }
 
 /// Extract repo_id (owner/name) from a local git repository path
 /// Expects paths like: ~/github/softwarewrighter/overall
 /// Returns: softwarewrighter/overall
+///
+/// Non-UTF-8 components are converted lossily (see [`is_lossy_path`]) rather
+/// than dropping the repository.
 pub fn extract_repo_id(local_path: &Path) -> Option<String> {
     let components: Vec<_> = local_path.components().collect();
     if components.len() >= 2 {
         let name = components[components.len() - 1]
             .as_os_str()
-            .to_str()?
-            .to_string();
+            .to_string_lossy();
         let owner = components[components.len() - 2]
             .as_os_str()
-            .to_str()?
-            .to_string();
+            .to_string_lossy();
         Some(format!("{}/{}", owner, name))
     } else {
         None
     }
 }
 
+/// Parse `owner/name` from a GitHub remote URL
+///
+/// Accepts the HTTPS (`https://github.com/owner/name.git`), scp-like SSH
+/// (`git@github.com:owner/name.git`) and `ssh://` forms, with or without
+/// `.git`. Remotes on other hosts give `None`.
+pub fn github_repo_id_from_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let path = [
+        "https://github.com/",
+        "http://github.com/",
+        "ssh://git@github.com/",
+        "git://github.com/",
+        "git@github.com:",
+    ]
+    .iter()
+    .find_map(|prefix| url.strip_prefix(prefix))?;
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    path.parse::<RepoId>().ok().map(String::from)
+}
+
+/// `owner/name` of the clone's `origin` remote, or `None` when there is no
+/// `origin` or it doesn't point at GitHub
+pub fn origin_repo_id(repo_path: &Path) -> Result<Option<String>> {
+    let output = Command::new(git_program())
+        .args(["remote", "get-url", "origin"])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| crate::Error::GitCommand(format!("Failed to read origin remote: {}", e)))?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    Ok(github_repo_id_from_url(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Whether a path is not valid UTF-8, so ids and paths derived from it are
+/// lossy and may not match the GitHub repository
+pub fn is_lossy_path(path: &Path) -> bool {
+    path.to_str().is_none()
+}
+
 /// Get the current branch name for a repository
 pub fn get_current_branch(repo_path: &Path) -> Result<Option<String>> {
-    let output = Command::new("git")
+    let output = Command::new(git_program())
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
         .current_dir(repo_path)
         .output()
@@ -79,7 +160,7 @@ pub fn get_current_branch(repo_path: &Path) -> Result<Option<String>> {
 
 /// Count uncommitted files (modified, added, deleted)
 pub fn count_uncommitted_files(repo_path: &Path) -> Result<u32> {
-    let output = Command::new("git")
+    let output = Command::new(git_program())
         .args(["status", "--porcelain"])
         .current_dir(repo_path)
         .output()
@@ -95,11 +176,56 @@ pub fn count_uncommitted_files(repo_path: &Path) -> Result<u32> {
     Ok(status_lines.lines().filter(|line| !line.is_empty()).count() as u32)
 }
 
+/// Check whether a repository is stuck in a conflicted merge or rebase
+///
+/// Looks for in-progress MERGE_HEAD/REBASE_HEAD markers in the git directory,
+/// then for unmerged entries (UU, AA, DD, ...) in `git status --porcelain`.
+pub fn has_conflicts(repo_path: &Path) -> Result<bool> {
+    let output = Command::new(git_program())
+        .args(["rev-parse", "--git-dir"])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| crate::Error::GitCommand(format!("Failed to get git dir: {}", e)))?;
+
+    if output.status.success() {
+        let git_dir = String::from_utf8(output.stdout)
+            .map_err(|e| crate::Error::GitCommand(format!("Invalid UTF-8 in git dir: {}", e)))?;
+        // --git-dir may be relative to the repository
+        let git_dir = repo_path.join(git_dir.trim());
+        if git_dir.join("MERGE_HEAD").exists() || git_dir.join("REBASE_HEAD").exists() {
+            return Ok(true);
+        }
+    }
+
+    let output = Command::new(git_program())
+        .args(["status", "--porcelain"])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| crate::Error::GitCommand(format!("Failed to get git status: {}", e)))?;
+
+    if !output.status.success() {
+        return Ok(false);
+    }
+
+    let status_lines = String::from_utf8(output.stdout)
+        .map_err(|e| crate::Error::GitCommand(format!("Invalid UTF-8 in status: {}", e)))?;
+
+    Ok(status_lines.lines().any(is_unmerged_status_line))
+}
+
+/// Porcelain status codes for paths with unresolved conflicts
+fn is_unmerged_status_line(line: &str) -> bool {
+    matches!(
+        line.get(..2),
+        Some("UU" | "AA" | "DD" | "AU" | "UA" | "DU" | "UD")
+    )
+}
+
 /// Get the number of commits ahead and behind the remote
 /// Returns (ahead, behind)
 pub fn get_ahead_behind(repo_path: &Path, branch: &str) -> Result<(u32, u32)> {
     // First, try to get the upstream branch
-    let upstream_output = Command::new("git")
+    let upstream_output = Command::new(git_program())
         .args([
             "rev-parse",
             "--abbrev-ref",
@@ -120,7 +246,7 @@ pub fn get_ahead_behind(repo_path: &Path, branch: &str) -> Result<(u32, u32)> {
         .to_string();
 
     // Get ahead/behind counts
-    let output = Command::new("git")
+    let output = Command::new(git_program())
         .args([
             "rev-list",
             "--left-right",
@@ -138,35 +264,301 @@ pub fn get_ahead_behind(repo_path: &Path, branch: &str) -> Result<(u32, u32)> {
     }
 
     let counts = String::from_utf8(output.stdout)
-        .map_err(|e| crate::Error::GitCommand(format!("Invalid UTF-8 in counts: {}", e)))?
-        .trim()
-        .to_string();
+        .map_err(|e| crate::Error::GitCommand(format!("Invalid UTF-8 in counts: {}", e)))?;
 
-    let parts: Vec<&str> = counts.split_whitespace().collect();
-    if parts.len() == 2 {
-        let ahead = parts[0].parse::<u32>().unwrap_or(0);
-        let behind = parts[1].parse::<u32>().unwrap_or(0);
-        Ok((ahead, behind))
-    } else {
-        Ok((0, 0))
+    Ok(parse_left_right_counts(&counts).unwrap_or((0, 0)))
+}
+
+/// Count commits `head` is ahead of and behind `base` in a local clone
+/// Returns (ahead, behind), matching GitHub's compare API
+///
+/// Both may be SHAs or ref names. Fails when git cannot resolve either, e.g.
+/// a SHA the clone has not fetched yet, so callers can fall back to the API.
+pub fn count_ahead_behind(repo_path: &Path, base: &str, head: &str) -> Result<(u32, u32)> {
+    let output = Command::new(git_program())
+        .args([
+            "rev-list",
+            "--left-right",
+            "--count",
+            &format!("{}...{}", head, base),
+        ])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| crate::Error::GitCommand(format!("Failed to compare {}: {}", head, e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(crate::Error::GitCommand(format!(
+            "Failed to compare {} with {}: {}",
+            head,
+            base,
+            stderr.trim()
+        )));
     }
+
+    let counts = String::from_utf8(output.stdout)
+        .map_err(|e| crate::Error::GitCommand(format!("Invalid UTF-8 in counts: {}", e)))?;
+    parse_left_right_counts(&counts).ok_or_else(|| {
+        crate::Error::GitCommand(format!("Unexpected rev-list output: {}", counts.trim()))
+    })
 }
 
-/// Get the full status of a local git repository
-pub fn get_repo_status(repo_path: &Path) -> Result<LocalRepoStatus> {
-    let repo_id = extract_repo_id(repo_path)
-        .ok_or_else(|| crate::Error::GitCommand("Failed to extract repo ID".to_string()))?;
+/// Count commits on a branch with no upstream that aren't on the remote's
+/// default branch, e.g. `origin/main`
+///
+/// Returns `None` when the branch has an upstream configured (even one
+/// deleted on the remote after merging), already exists on `origin`, or there
+/// is no remote default to compare with: only a branch that was never pushed
+/// is unpublished.
+pub fn count_unpublished_commits(repo_path: &Path, branch: &str) -> Result<Option<u32>> {
+    if branch == "HEAD" || has_upstream_config(repo_path, branch)? {
+        return Ok(None);
+    }
+    if ref_exists(repo_path, &format!("refs/remotes/origin/{}", branch))? {
+        return Ok(None);
+    }
+    let Some(default) = remote_default_branch(repo_path)? else {
+        return Ok(None);
+    };
+    Ok(count_ahead_behind(repo_path, &default, branch)
+        .ok()
+        .map(|(ahead, _)| ahead))
+}
+
+/// The branch `origin/HEAD` points at, or `origin/main` / `origin/master` for
+/// clones whose remote HEAD was never recorded (e.g. `git remote add`)
+fn remote_default_branch(repo_path: &Path) -> Result<Option<String>> {
+    let output = Command::new(git_program())
+        .args([
+            "symbolic-ref",
+            "--quiet",
+            "--short",
+            "refs/remotes/origin/HEAD",
+        ])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| crate::Error::GitCommand(format!("Failed to read origin/HEAD: {}", e)))?;
+    if output.status.success() {
+        let default = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !default.is_empty() {
+            return Ok(Some(default));
+        }
+    }
+
+    for candidate in ["origin/main", "origin/master"] {
+        if ref_exists(repo_path, &format!("refs/remotes/{}", candidate))? {
+            return Ok(Some(candidate.to_string()));
+        }
+    }
+    Ok(None)
+}
 
+/// Whether `branch.<name>.merge` is set, i.e. the branch tracks an upstream
+fn has_upstream_config(repo_path: &Path, branch: &str) -> Result<bool> {
+    let output = Command::new(git_program())
+        .args(["config", "--get", &format!("branch.{}.merge", branch)])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| crate::Error::GitCommand(format!("Failed to read git config: {}", e)))?;
+    Ok(output.status.success())
+}
+
+/// Whether `git rev-parse --verify` resolves a ref in the clone
+fn ref_exists(repo_path: &Path, name: &str) -> Result<bool> {
+    let output = Command::new(git_program())
+        .args(["rev-parse", "--verify", "--quiet", name])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| crate::Error::GitCommand(format!("Failed to resolve {}: {}", name, e)))?;
+    Ok(output.status.success())
+}
+
+/// Parse `git rev-list --left-right --count <branch>...<upstream>` output
+///
+/// The left count is commits only on the branch (ahead), the right count is
+/// commits only on the upstream (behind). Returns `None` unless the output is
+/// exactly two whitespace-separated numbers.
+fn parse_left_right_counts(output: &str) -> Option<(u32, u32)> {
+    let mut parts = output.split_whitespace();
+    let ahead = parts.next()?.parse().ok()?;
+    let behind = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((ahead, behind))
+}
+
+/// Checked-out branch, working tree and upstream state of a clone
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct WorkingState {
+    current_branch: Option<String>,
+    uncommitted_files: u32,
+    unpushed_commits: u32,
+    behind_commits: u32,
+    has_conflicts: bool,
+    /// No upstream and not on `origin`: `unpushed_commits` counts commits
+    /// ahead of the remote's default branch instead
+    is_unpublished: bool,
+}
+
+/// Read the working state by running `git` (four or more processes)
+fn working_state_from_git(repo_path: &Path) -> Result<WorkingState> {
     let current_branch = get_current_branch(repo_path)?;
     let uncommitted_files = count_uncommitted_files(repo_path)?;
 
-    let (unpushed_commits, behind_commits) = if let Some(ref branch) = current_branch {
-        get_ahead_behind(repo_path, branch)?
-    } else {
-        (0, 0)
+    let (unpushed_commits, behind_commits, is_unpublished) = match current_branch.as_deref() {
+        Some(branch) => match count_unpublished_commits(repo_path, branch)? {
+            Some(ahead) => (ahead, 0, true),
+            None => {
+                let (ahead, behind) = get_ahead_behind(repo_path, branch)?;
+                (ahead, behind, false)
+            }
+        },
+        None => (0, 0, false),
     };
 
-    let is_dirty = uncommitted_files > 0 || unpushed_commits > 0;
+    Ok(WorkingState {
+        current_branch,
+        uncommitted_files,
+        unpushed_commits,
+        behind_commits,
+        has_conflicts: has_conflicts(repo_path)?,
+        is_unpublished,
+    })
+}
+
+/// In-process equivalents of the `git` invocations above, matching their
+/// output: `HEAD` for a detached head, porcelain-style file counts (untracked
+/// directories count once, staged renames once)
+#[cfg(feature = "libgit2")]
+mod libgit2 {
+    use super::WorkingState;
+    use crate::Result;
+    use git2::{BranchType, Oid, Repository, Status, StatusOptions};
+    use std::path::Path;
+
+    fn git_error(e: git2::Error) -> crate::Error {
+        crate::Error::GitCommand(format!("libgit2: {}", e.message()))
+    }
+
+    /// Tip of the remote's default branch, as `remote_default_branch` finds it
+    fn remote_default_target(repo: &Repository) -> Option<Oid> {
+        repo.find_reference("refs/remotes/origin/HEAD")
+            .and_then(|head| head.resolve())
+            .ok()
+            .or_else(|| {
+                ["main", "master"].iter().find_map(|name| {
+                    repo.find_reference(&format!("refs/remotes/origin/{}", name))
+                        .ok()
+                })
+            })
+            .and_then(|default| default.target())
+    }
+
+    pub(super) fn working_state(repo_path: &Path) -> Result<WorkingState> {
+        let repo = Repository::open(repo_path).map_err(git_error)?;
+
+        // An unborn branch has no HEAD yet, like `git rev-parse` failing
+        let head = repo.head().ok();
+        let current_branch = head
+            .as_ref()
+            .and_then(|head| head.shorthand().ok().map(str::to_string));
+
+        let statuses = repo
+            .statuses(Some(
+                StatusOptions::new()
+                    .include_untracked(true)
+                    .recurse_untracked_dirs(false)
+                    .renames_head_to_index(true),
+            ))
+            .map_err(git_error)?;
+        let uncommitted_files = statuses
+            .iter()
+            .filter(|entry| entry.status() != Status::CURRENT)
+            .count() as u32;
+
+        let merge_in_progress =
+            repo.path().join("MERGE_HEAD").exists() || repo.path().join("REBASE_HEAD").exists();
+        let has_conflicts = merge_in_progress
+            || statuses
+                .iter()
+                .any(|entry| entry.status().contains(Status::CONFLICTED));
+
+        let (unpushed_commits, behind_commits, is_unpublished) = match (&head, &current_branch) {
+            (Some(head), Some(branch)) if head.is_branch() => {
+                let upstream = repo
+                    .find_branch(branch, BranchType::Local)
+                    .and_then(|b| b.upstream())
+                    .ok()
+                    .and_then(|upstream| upstream.get().target());
+                let has_upstream = repo
+                    .config()
+                    .and_then(|config| config.get_string(&format!("branch.{}.merge", branch)))
+                    .is_ok();
+                let on_origin = repo
+                    .find_reference(&format!("refs/remotes/origin/{}", branch))
+                    .is_ok();
+                match (head.target(), upstream) {
+                    (Some(local), Some(upstream)) => {
+                        let (ahead, behind) = repo
+                            .graph_ahead_behind(local, upstream)
+                            .map_err(git_error)?;
+                        (ahead as u32, behind as u32, false)
+                    }
+                    // Never pushed: count what the remote's default lacks
+                    (Some(local), None) if !has_upstream && !on_origin => {
+                        match remote_default_target(&repo)
+                            .and_then(|default| repo.graph_ahead_behind(local, default).ok())
+                        {
+                            Some((ahead, _)) => (ahead as u32, 0, true),
+                            None => (0, 0, false),
+                        }
+                    }
+                    // No upstream configured
+                    _ => (0, 0, false),
+                }
+            }
+            _ => (0, 0, false),
+        };
+
+        Ok(WorkingState {
+            current_branch,
+            uncommitted_files,
+            unpushed_commits,
+            behind_commits,
+            has_conflicts,
+            is_unpublished,
+        })
+    }
+}
+
+/// Read the working state in-process when built with the `libgit2` feature,
+/// falling back to running `git` for repositories libgit2 cannot open (e.g.
+/// ones using extensions it doesn't support)
+fn working_state(repo_path: &Path) -> Result<WorkingState> {
+    #[cfg(feature = "libgit2")]
+    match libgit2::working_state(repo_path) {
+        Ok(state) => return Ok(state),
+        Err(e) => tracing::debug!("{}; using git for {}", e, repo_path.display()),
+    }
+
+    working_state_from_git(repo_path)
+}
+
+/// Get the full status of a local git repository
+pub fn get_repo_status(repo_path: &Path) -> Result<LocalRepoStatus> {
+    let repo_id = extract_repo_id(repo_path)
+        .ok_or_else(|| crate::Error::GitCommand("Failed to extract repo ID".to_string()))?;
+
+    let WorkingState {
+        current_branch,
+        uncommitted_files,
+        unpushed_commits,
+        behind_commits,
+        has_conflicts,
+        is_unpublished,
+    } = working_state(repo_path)?;
+    let is_dirty = uncommitted_files > 0 || unpushed_commits > 0 || has_conflicts;
 
     Ok(LocalRepoStatus {
         id: 0, // Will be set by database
@@ -177,13 +569,16 @@ pub fn get_repo_status(repo_path: &Path) -> Result<LocalRepoStatus> {
         unpushed_commits,
         behind_commits,
         is_dirty,
+        has_conflicts,
+        is_diverged: unpushed_commits > 0 && behind_commits > 0,
+        is_unpublished,
         last_checked: Utc::now(),
     })
 }
 
 /// Fetch remote updates for a repository
 pub fn fetch_remote(repo_path: &Path) -> Result<()> {
-    let output = Command::new("git")
+    let output = Command::new(git_program())
         .args(["fetch", "--all"])
         .current_dir(repo_path)
         .output()
@@ -220,10 +615,297 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_github_repo_id_from_url() {
+        for url in [
+            "https://github.com/softwarewrighter/overall.git",
+            "https://github.com/softwarewrighter/overall",
+            "git@github.com:softwarewrighter/overall.git",
+            "ssh://git@github.com/softwarewrighter/overall.git\n",
+        ] {
+            assert_eq!(
+                github_repo_id_from_url(url),
+                Some("softwarewrighter/overall".to_string()),
+                "{}",
+                url
+            );
+        }
+
+        assert_eq!(
+            github_repo_id_from_url("https://gitlab.com/owner/repo.git"),
+            None
+        );
+        assert_eq!(github_repo_id_from_url("git@github.com:owner.git"), None);
+        assert_eq!(github_repo_id_from_url("/srv/git/owner/repo.git"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract_repo_id_non_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = PathBuf::from("/home/github/owner").join(OsStr::from_bytes(b"caf\xe9"));
+        assert!(is_lossy_path(&path));
+        assert_eq!(
+            extract_repo_id(&path),
+            Some("owner/caf\u{FFFD}".to_string())
+        );
+        assert!(!is_lossy_path(Path::new("/home/github/owner/cafe")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_resolves_symlinked_root_and_repos() {
+        use std::os::unix::fs::symlink;
+
+        let temp = tempfile::tempdir().unwrap();
+        let real_root = temp.path().join("real").join("owner");
+        std::fs::create_dir_all(real_root.join("repo").join(".git")).unwrap();
+        // An alias for a repository inside the root
+        symlink(real_root.join("repo"), real_root.join("repo-alias")).unwrap();
+        // A repository outside the root, linked in
+        let outside = temp.path().join("elsewhere").join("other");
+        std::fs::create_dir_all(outside.join(".git")).unwrap();
+        symlink(&outside, real_root.join("other")).unwrap();
+        // The configured root is itself a symlink
+        let linked_root = temp.path().join("linked");
+        symlink(&real_root, &linked_root).unwrap();
+
+        let repos = scan_for_git_repos(&linked_root).unwrap();
+
+        assert_eq!(repos.len(), 1, "{:?}", repos);
+        assert_eq!(extract_repo_id(&repos[0]), Some("owner/repo".to_string()));
+    }
+
     #[test]
     fn test_scan_nonexistent_path() {
         let path = PathBuf::from("/nonexistent/path");
         let result = scan_for_git_repos(&path);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parse_left_right_counts() {
+        assert_eq!(parse_left_right_counts("3\t1\n"), Some((3, 1)));
+        assert_eq!(parse_left_right_counts("0   7"), Some((0, 7)));
+        assert_eq!(parse_left_right_counts("  12 \t 0  \n"), Some((12, 0)));
+        assert_eq!(parse_left_right_counts(""), None);
+        assert_eq!(parse_left_right_counts("5"), None);
+        assert_eq!(parse_left_right_counts("1\t2\t3"), None);
+        assert_eq!(parse_left_right_counts("x\t2"), None);
+        assert_eq!(parse_left_right_counts("-1\t2"), None);
+    }
+
+    #[test]
+    fn test_get_ahead_behind_orientation() {
+        let temp = tempfile::tempdir().unwrap();
+        let repo = temp.path();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(repo)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+
+        git(&["init", "-q", "-b", "main"]);
+        std::fs::write(repo.join("file.txt"), "base\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-qm", "base"]);
+
+        // feature tracks main, then gains two commits while main gains one
+        git(&["checkout", "-qb", "feature", "--track", "main"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "feature 1"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "feature 2"]);
+        git(&["checkout", "-q", "main"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "main 1"]);
+
+        assert_eq!(get_ahead_behind(repo, "feature").unwrap(), (2, 1));
+        // main has no upstream configured
+        assert_eq!(get_ahead_behind(repo, "main").unwrap(), (0, 0));
+
+        // The same counts without an upstream, as the compare API reports them
+        assert_eq!(count_ahead_behind(repo, "main", "feature").unwrap(), (2, 1));
+        assert_eq!(count_ahead_behind(repo, "feature", "main").unwrap(), (1, 2));
+        let unknown = "0123456789abcdef0123456789abcdef01234567";
+        assert!(count_ahead_behind(repo, "main", unknown).is_err());
+    }
+
+    #[test]
+    fn test_unpublished_branch_counts_against_remote_default() {
+        let temp = tempfile::tempdir().unwrap();
+        let git = |dir: &Path, args: &[&str]| {
+            let output = Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+
+        let clone = temp.path().join("clone");
+        git(
+            temp.path(),
+            &["init", "-q", "--bare", "-b", "main", "origin.git"],
+        );
+        git(temp.path(), &["clone", "-q", "origin.git", "clone"]);
+        git(&clone, &["commit", "-q", "--allow-empty", "-m", "base"]);
+        git(&clone, &["push", "-q", "-u", "origin", "main"]);
+
+        // A new local branch with two commits, never pushed
+        git(&clone, &["checkout", "-qb", "spike"]);
+        git(&clone, &["commit", "-q", "--allow-empty", "-m", "spike 1"]);
+        git(&clone, &["commit", "-q", "--allow-empty", "-m", "spike 2"]);
+
+        assert_eq!(get_ahead_behind(&clone, "spike").unwrap(), (0, 0));
+        assert_eq!(count_unpublished_commits(&clone, "spike").unwrap(), Some(2));
+        // main tracks origin/main, so it's published
+        assert_eq!(count_unpublished_commits(&clone, "main").unwrap(), None);
+
+        let state = working_state_from_git(&clone).unwrap();
+        assert!(state.is_unpublished);
+        assert_eq!((state.unpushed_commits, state.behind_commits), (2, 0));
+        #[cfg(feature = "libgit2")]
+        assert_eq!(libgit2::working_state(&clone).unwrap(), state);
+
+        // Once pushed without tracking, the branch exists on origin
+        git(&clone, &["push", "-q", "origin", "spike"]);
+        assert_eq!(count_unpublished_commits(&clone, "spike").unwrap(), None);
+        assert!(!working_state_from_git(&clone).unwrap().is_unpublished);
+    }
+
+    /// A clone of a bare "origin" that is one commit ahead and one behind its
+    /// upstream, with a modified file, a staged rename and an untracked
+    /// directory (four porcelain lines)
+    #[cfg(feature = "libgit2")]
+    fn diverged_clone_with_changes(temp: &Path) -> PathBuf {
+        let git = |dir: &Path, args: &[&str]| {
+            let output = Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+
+        let origin = temp.join("origin.git");
+        let other = temp.join("other");
+        let clone = temp.join("clone");
+        git(temp, &["init", "-q", "--bare", "-b", "main", "origin.git"]);
+        git(temp, &["clone", "-q", "origin.git", "other"]);
+        std::fs::write(other.join("a.txt"), "a\n").unwrap();
+        std::fs::write(other.join("b.txt"), "b\n").unwrap();
+        git(&other, &["add", "."]);
+        git(&other, &["commit", "-qm", "base"]);
+        git(&other, &["push", "-q", "origin", "main"]);
+        git(temp, &["clone", "-q", origin.to_str().unwrap(), "clone"]);
+
+        git(&other, &["commit", "-q", "--allow-empty", "-m", "upstream"]);
+        git(&other, &["push", "-q", "origin", "main"]);
+        git(&clone, &["commit", "-q", "--allow-empty", "-m", "local"]);
+        git(&clone, &["fetch", "-q"]);
+
+        std::fs::write(clone.join("a.txt"), "changed\n").unwrap();
+        git(&clone, &["mv", "b.txt", "c.txt"]);
+        std::fs::create_dir(clone.join("notes")).unwrap();
+        std::fs::write(clone.join("notes/1.md"), "").unwrap();
+        std::fs::write(clone.join("notes/2.md"), "").unwrap();
+        std::fs::write(clone.join("new.txt"), "").unwrap();
+        clone
+    }
+
+    #[cfg(feature = "libgit2")]
+    #[test]
+    fn test_libgit2_working_state_matches_git() {
+        let temp = tempfile::tempdir().unwrap();
+        let clone = diverged_clone_with_changes(temp.path());
+
+        let expected = WorkingState {
+            current_branch: Some("main".to_string()),
+            uncommitted_files: 4,
+            unpushed_commits: 1,
+            behind_commits: 1,
+            has_conflicts: false,
+            is_unpublished: false,
+        };
+        assert_eq!(working_state_from_git(&clone).unwrap(), expected);
+        assert_eq!(libgit2::working_state(&clone).unwrap(), expected);
+
+        // Detached: no branch to compare with an upstream
+        Command::new("git")
+            .args(["checkout", "-q", "--detach"])
+            .current_dir(&clone)
+            .output()
+            .unwrap();
+        assert_eq!(
+            libgit2::working_state(&clone).unwrap(),
+            working_state_from_git(&clone).unwrap()
+        );
+    }
+
+    /// `cargo test --features libgit2 bench_working_state -- --ignored --nocapture`
+    #[cfg(feature = "libgit2")]
+    #[test]
+    #[ignore]
+    fn bench_working_state() {
+        let temp = tempfile::tempdir().unwrap();
+        let clone = diverged_clone_with_changes(temp.path());
+        let runs = 200;
+
+        let time = |read: &dyn Fn(&Path) -> Result<WorkingState>| {
+            let start = std::time::Instant::now();
+            for _ in 0..runs {
+                read(&clone).unwrap();
+            }
+            start.elapsed() / runs
+        };
+        println!("git:     {:?} per repo", time(&working_state_from_git));
+        println!("libgit2: {:?} per repo", time(&libgit2::working_state));
+    }
+
+    #[test]
+    fn test_is_unmerged_status_line() {
+        assert!(is_unmerged_status_line("UU src/lib.rs"));
+        assert!(is_unmerged_status_line("AA new.rs"));
+        assert!(is_unmerged_status_line("DU removed.rs"));
+        assert!(!is_unmerged_status_line(" M src/lib.rs"));
+        assert!(!is_unmerged_status_line("?? untracked.rs"));
+        assert!(!is_unmerged_status_line(""));
+    }
+
+    #[test]
+    fn test_has_conflicts_detects_conflicted_merge() {
+        let temp = tempfile::tempdir().unwrap();
+        let repo = temp.path();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(repo)
+                .output()
+                .unwrap()
+        };
+
+        git(&["init", "-q", "-b", "main"]);
+        std::fs::write(repo.join("file.txt"), "base\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-qm", "base"]);
+        assert!(!has_conflicts(repo).unwrap());
+
+        git(&["checkout", "-qb", "feature"]);
+        std::fs::write(repo.join("file.txt"), "feature\n").unwrap();
+        git(&["commit", "-qam", "feature"]);
+        git(&["checkout", "-q", "main"]);
+        std::fs::write(repo.join("file.txt"), "main\n").unwrap();
+        git(&["commit", "-qam", "main"]);
+
+        let merge = git(&["merge", "feature"]);
+        assert!(!merge.status.success(), "Merge should conflict");
+        assert!(has_conflicts(repo).unwrap());
+    }
 }
@@ -1,9 +1,24 @@
 // Copyright (c) 2025 Michael A Wright
 // SPDX-License-Identifier: MIT
 
-use clap::{Parser, Subcommand};
-use overall_cli::{github, storage::Database};
-use std::path::PathBuf;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use overall_cli::{
+    analysis,
+    config::Config,
+    export,
+    github::{
+        self,
+        scan::{run_scan, scan_repo_ids, ScanEvent, ScanOptions},
+    },
+    storage::Database,
+};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Upper bound for `watch --cooldown`, well inside what `chrono::Duration` holds
+const MAX_WATCH_COOLDOWN_MINUTES: i64 = 365 * 24 * 60;
 
 #[derive(Parser)]
 #[command(name = "overall")]
@@ -12,6 +27,10 @@ use std::path::PathBuf;
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Regenerate static/repos.json after commands that change the database
+    #[arg(long, global = true)]
+    export: bool,
 }
 
 #[derive(Subcommand)]
@@ -21,18 +40,240 @@ enum Commands {
         /// GitHub user or organization name
         owner: String,
 
-        /// Maximum number of repositories to fetch
-        #[arg(short, long, default_value = "50")]
-        limit: usize,
+        /// Maximum number of repositories to fetch [default: the owner's
+        /// configured limit, else `repo_limit`]
+        #[arg(short, long)]
+        limit: Option<usize>,
+
+        /// Only scan repositories owned by this organization team (slug)
+        /// [default: the owner's configured team]
+        #[arg(long)]
+        team: Option<String>,
+
+        /// Output format (json prints a summary object and sends progress to stderr)
+        #[arg(short, long, value_enum, default_value_t = ScanFormat::Text)]
+        format: ScanFormat,
+
+        /// Fetch commits for every branch, not only those ahead of the default branch
+        #[arg(long)]
+        all_branch_commits: bool,
+
+        /// `git fetch` locally cloned repos first; their branches are compared
+        /// with local git instead of the GitHub API either way
+        #[arg(long)]
+        fetch_local: bool,
     },
     /// List all tracked repositories
-    List,
+    List {
+        /// Only list private repositories
+        #[arg(long, conflicts_with = "public_only")]
+        private_only: bool,
+
+        /// Only list public repositories
+        #[arg(long)]
+        public_only: bool,
+
+        /// Stream one JSON object per repository per line
+        #[arg(long)]
+        ndjson: bool,
+
+        /// Only list repositories with work left: open PRs, unmerged branches
+        /// or local changes (any status but complete)
+        #[arg(long)]
+        pending: bool,
+    },
     /// Export data to JSON for UI consumption
     Export {
         /// Output file path
         #[arg(short, long, default_value = "static/repos.json")]
         output: PathBuf,
+
+        /// Only export the named group (omits ungrouped repositories)
+        #[arg(short, long)]
+        group: Option<String>,
+
+        /// Only export private repositories
+        #[arg(long, conflicts_with = "public_only")]
+        private_only: bool,
+
+        /// Only export public repositories
+        #[arg(long)]
+        public_only: bool,
+    },
+    /// Import data from a repos.json export (e.g. from another machine)
+    Import {
+        /// Export file to read
+        file: PathBuf,
+    },
+    /// Compare two exports: added/removed repos and groups, changed branch/PR counts
+    DiffExport {
+        /// Older export file
+        old: PathBuf,
+
+        /// Newer export file
+        new: PathBuf,
+    },
+    /// Print a standup summary of pending PRs, ready branches and local changes
+    Report {
+        /// Only report on the named group (omits ungrouped repositories)
+        #[arg(short, long)]
+        group: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = ReportFormat::Markdown)]
+        format: ReportFormat,
+    },
+    /// List every local clone and GitHub branch that is ahead or behind, most urgent first
+    NeedsSync,
+    /// Recompute repository priorities using the configured weights
+    Reprioritize,
+    /// Re-run branch classification on stored branches and PRs (no network)
+    Reclassify {
+        /// Only reclassify this repository (any part of its id)
+        repo: Option<String>,
+    },
+    /// List repositories pushed to recently, most recent first
+    Recent {
+        /// Look-back window in days
+        #[arg(long, default_value_t = 7)]
+        days: i64,
+    },
+    /// List branches that look abandoned: commits ahead, no open PR, and no
+    /// recent commits
+    Abandoned {
+        /// Days without a commit (default: analysis.abandoned_threshold_days)
+        #[arg(long)]
+        days: Option<i64>,
+    },
+    /// Show everything stored about one repository: metadata, branches with
+    /// their commits, open PRs and the local clone's state
+    Show {
+        /// Repository id or any part of it, e.g. "overall" or "sw/ovr"
+        repo_id: String,
+    },
+    /// Show where a repository is cloned locally and its working tree state
+    Where {
+        /// Repository id (owner/name)
+        repo_id: String,
+
+        /// Print only the clone path, e.g. for cd "$(overall where owner/repo --path-only)"
+        #[arg(long)]
+        path_only: bool,
+    },
+    /// Clone tracked repositories that have no local clone yet (with gh repo
+    /// clone, into <dir>/<owner>/<name>), then register and scan them
+    CloneMissing {
+        /// Directory holding one subdirectory per owner (default: ~/github)
+        #[arg(long, value_name = "DIR")]
+        into: Option<PathBuf>,
+
+        /// List what would be cloned without cloning
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List clones under the local roots that aren't tracked from GitHub
+    LocalOnly {
+        /// Scan the GitHub repositories their origin remotes point at, so
+        /// they become tracked
+        #[arg(long)]
+        scan: bool,
+    },
+    /// Rescan local clones periodically and report repositories that turn red
+    /// (diverged or needing sync)
+    Watch {
+        /// Seconds between cycles
+        #[arg(long, default_value_t = 300, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+
+        /// Also show a desktop notification (notify-send on Linux, osascript on macOS)
+        #[arg(long)]
+        notify: bool,
+
+        /// Minutes before the same repository can alert again (at most a year)
+        #[arg(
+            long,
+            default_value_t = 60,
+            value_parser = clap::value_parser!(i64).range(0..=MAX_WATCH_COOLDOWN_MINUTES)
+        )]
+        cooldown: i64,
+    },
+    /// Stop tracking a repository and delete everything stored for it
+    Untrack {
+        /// Repository to untrack (any part of its id)
+        repo_id: String,
+
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Move a repository into a group (out of any other) and regenerate the export
+    Assign {
+        /// Repository id or any part of it
+        repo_id: String,
+
+        /// Group name (matched ignoring case)
+        group: String,
+
+        /// Create the group if it doesn't exist
+        #[arg(long)]
+        create: bool,
+
+        /// Export file to regenerate; skipped when its directory doesn't exist
+        #[arg(short, long, default_value = "static/repos.json")]
+        output: PathBuf,
+    },
+    /// Tag a repository (tags are independent of groups) and regenerate the export
+    Tag {
+        /// Repository id or any part of it
+        repo_id: String,
+
+        /// Tag name, e.g. client-work (matched ignoring case)
+        tag: String,
+
+        /// Remove the tag instead of adding it
+        #[arg(long)]
+        remove: bool,
+
+        /// Export file to regenerate; skipped when its directory doesn't exist
+        #[arg(short, long, default_value = "static/repos.json")]
+        output: PathBuf,
+    },
+    /// Dismiss a repository from attention for a while (it counts as
+    /// complete until the snooze expires) and regenerate the export
+    Snooze {
+        /// Repository id or any part of it
+        repo_id: String,
+
+        /// How long, e.g. 30m, 12h, 2d or 1w
+        #[arg(required_unless_present = "clear")]
+        duration: Option<String>,
+
+        /// Wake the repository now instead
+        #[arg(long, conflicts_with = "duration")]
+        clear: bool,
+
+        /// Export file to regenerate; skipped when its directory doesn't exist
+        #[arg(short, long, default_value = "static/repos.json")]
+        output: PathBuf,
+    },
+    /// Manage repositories that are never tracked
+    Exclude {
+        #[command(subcommand)]
+        action: ExcludeAction,
     },
+    /// Manage repository groups
+    Group {
+        #[command(subcommand)]
+        action: GroupAction,
+    },
+    /// Back up the database (to ~/.overall/backups unless an output path is given)
+    Backup {
+        /// Write the backup to this file instead of the rotated backups directory
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Check that gh is installed and authenticated
+    Doctor,
     /// Start web UI server
     Serve {
         /// Port to listen on
@@ -42,9 +283,218 @@ enum Commands {
         /// Enable debug logging
         #[arg(short, long)]
         debug: bool,
+
+        /// PEM certificate chain; serves HTTPS together with --tls-key
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+
+        /// PEM private key for --tls-cert
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+
+        /// Origin allowed to call the API, e.g. http://localhost:3000
+        /// (repeatable; replaces the default allow-any policy)
+        #[arg(long = "cors-origin", value_name = "ORIGIN")]
+        cors_origins: Vec<String>,
+    },
+    /// Print a shell completion script, e.g. `overall completions zsh > _overall`
+    #[command(hide = true)]
+    Completions { shell: Shell },
+    /// Print tracked repo ids starting with a prefix, one per line (used by
+    /// the completion scripts)
+    #[command(name = "__complete-repos", hide = true)]
+    CompleteRepos {
+        #[arg(default_value = "", allow_hyphen_values = true)]
+        prefix: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExcludeAction {
+    /// Exclude an exact repo id or a glob like owner/test-*
+    Add {
+        pattern: String,
+
+        /// Also delete already-tracked repositories matching the pattern
+        #[arg(long)]
+        prune: bool,
+    },
+    /// Stop excluding a pattern added with `exclude add`
+    Remove { pattern: String },
+    /// List stored and configured exclude patterns
+    List,
+}
+
+#[derive(Subcommand)]
+enum GroupAction {
+    /// Add a repository to a group, creating the group if needed
+    Add {
+        /// Group name (matched ignoring case)
+        group: String,
+
+        /// Repository id or any part of it, e.g. "overall" or "sw/ovr"
+        repo: String,
     },
 }
 
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ScanFormat {
+    Text,
+    Json,
+}
+
+/// Whether a command writes to the database, so `--export` (or
+/// `ui.auto_export`) should regenerate repos.json once it finishes;
+/// `assign`, `tag` and `snooze` regenerate their own output and are left out
+fn changes_database(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::Scan { .. }
+            | Commands::Import { .. }
+            | Commands::Reprioritize
+            | Commands::Reclassify { .. }
+            | Commands::Untrack { .. }
+            | Commands::CloneMissing { dry_run: false, .. }
+            | Commands::LocalOnly { scan: true }
+            | Commands::Group { .. }
+            | Commands::Exclude {
+                action: ExcludeAction::Add { .. } | ExcludeAction::Remove { .. }
+            }
+    )
+}
+
+/// `Some(true)` for `--private-only`, `Some(false)` for `--public-only`
+fn visibility_filter(private_only: bool, public_only: bool) -> Option<bool> {
+    if private_only {
+        Some(true)
+    } else if public_only {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Resolve a partial repo id to a tracked repository, prompting on stdin
+/// when several match equally well; exits on no match or no choice
+fn resolve_repo_id(db: &Database, query: &str) -> String {
+    let repo_ids: Vec<String> = match db.get_all_repositories() {
+        Ok(repos) => repos.into_iter().map(|r| r.id).collect(),
+        Err(e) => {
+            eprintln!("Error loading repositories: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let matches = analysis::fuzzy_match(query, &repo_ids);
+    let exact: Vec<_> = matches
+        .iter()
+        .filter(|(_, score)| *score == analysis::EXACT_MATCH_SCORE)
+        .collect();
+    match (matches.len(), exact.len()) {
+        (0, _) => {
+            eprintln!("No tracked repository matches '{}'", query);
+            std::process::exit(1);
+        }
+        (1, _) => return matches[0].0.clone(),
+        (_, 1) => return exact[0].0.clone(),
+        _ => {}
+    }
+
+    const MAX_CHOICES: usize = 10;
+    let choices = &matches[..matches.len().min(MAX_CHOICES)];
+    println!("Several repositories match '{}':", query);
+    for (i, (id, _)) in choices.iter().enumerate() {
+        println!("  {}. {}", i + 1, id);
+    }
+    if matches.len() > MAX_CHOICES {
+        println!("  ... and {} more", matches.len() - MAX_CHOICES);
+    }
+    print!("Choose a repository [1-{}]: ", choices.len());
+    let _ = std::io::stdout().flush();
+
+    let mut answer = String::new();
+    let _ = std::io::stdin().read_line(&mut answer);
+    match answer.trim().parse::<usize>() {
+        Ok(n) if (1..=choices.len()).contains(&n) => choices[n - 1].0.clone(),
+        _ => {
+            eprintln!("No repository chosen");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Every repository's traffic-light status, exiting with an error on failure
+fn load_repo_statuses(db: &Database) -> HashMap<String, analysis::RepoStatus> {
+    let config = Config::load().unwrap_or_default();
+    match db.get_repo_statuses(&config) {
+        Ok(statuses) => statuses,
+        Err(e) => {
+            eprintln!("Error computing repository statuses: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Whether a repository has work left (any status but complete)
+fn is_pending(statuses: &HashMap<String, analysis::RepoStatus>, repo_id: &str) -> bool {
+    statuses
+        .get(repo_id)
+        .is_some_and(|status| *status != analysis::RepoStatus::Complete)
+}
+
+/// Read and parse an export file, exiting with an error if either fails
+fn read_export_file(path: &Path) -> export::ExportData {
+    let json_str = match std::fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error reading {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    };
+
+    match export::parse_export(&json_str) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Error: {} is not a valid export: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Rewrite the export after a CLI change, exiting on failure; skipped with
+/// a note when the output directory doesn't exist (e.g. outside the repo)
+fn regenerate_export(db: &Database, output: &Path) {
+    if !output
+        .parent()
+        .is_some_and(|dir| dir.as_os_str().is_empty() || dir.is_dir())
+    {
+        println!(
+            "Skipped regenerating {} (directory not found); run `overall export`",
+            output.display()
+        );
+        return;
+    }
+    let config = Config::load().unwrap_or_default();
+    match export::regenerate_repos_json(db, output, &config) {
+        Ok(summary) => println!(
+            "✓ Regenerated {} ({} groups, {} repos)",
+            summary.path.display(),
+            summary.groups,
+            summary.repos
+        ),
+        Err(e) => {
+            eprintln!("Error regenerating {}: {}", output.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
 fn get_db_path() -> PathBuf {
     let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
     PathBuf::from(home).join(".overall").join("overall.db")
@@ -70,134 +520,177 @@ fn main() {
         std::process::exit(1);
     }
 
+    let export_after = (cli.export || Config::load().unwrap_or_default().ui.auto_export)
+        && cli.command.as_ref().is_some_and(changes_database);
+    // Commands that saved some of their changes before failing set this
+    // instead of exiting, so the export below still picks those changes up
+    let mut exit_code = 0;
+
     match cli.command {
-        Some(Commands::Scan { owner, limit }) => {
-            println!("Scanning repositories for: {} (limit: {})", owner, limit);
+        Some(Commands::Scan {
+            owner,
+            limit,
+            team,
+            format,
+            all_branch_commits,
+            fetch_local,
+        }) => {
+            // In JSON mode progress goes to stderr so stdout only carries the summary
+            let json = matches!(format, ScanFormat::Json);
+            let mut out: Box<dyn Write> = if json {
+                Box::new(std::io::stderr())
+            } else {
+                Box::new(std::io::stdout())
+            };
+
+            let config = Config::load().unwrap_or_default();
+            let limit = limit.unwrap_or_else(|| config.github.repo_limit_for(&owner));
+
+            let _ = writeln!(
+                out,
+                "Scanning repositories for: {} (limit: {})",
+                owner, limit
+            );
+
+            let mut opts = ScanOptions::for_owner(&config.github, &owner)
+                .limit(limit)
+                .all_branch_commits(all_branch_commits)
+                .fetch_local_clones(fetch_local);
+            // --team overrides the owner's configured team
+            if team.is_some() {
+                opts = opts.team(team);
+            }
 
             // Open database
             let db_path = get_db_path();
-            let db = match Database::open_or_create(&db_path) {
+            let mut db = match Database::open_or_create(&db_path) {
                 Ok(db) => db,
                 Err(e) => {
                     eprintln!("Error opening database: {}", e);
                     std::process::exit(1);
                 }
             };
+            db.set_max_commit_body_len(config.storage.max_commit_body_len);
 
-            // Fetch repositories
-            let repos = match github::list_repos(&owner, limit) {
-                Ok(repos) => repos,
+            let client = github::RealGitHubClient::new();
+            let result = run_scan(&db, &client, &owner, &opts, |event| match event {
+                ScanEvent::TeamFiltered { team, .. } => {
+                    let _ = writeln!(out, "Filtered to team '{}'", team);
+                }
+                ScanEvent::ReposExcluded { count } => {
+                    let _ = writeln!(out, "Skipping {} excluded repositories", count);
+                }
+                ScanEvent::ReposFound { count } => {
+                    let _ = writeln!(out, "\nFound {} repositories\n", count);
+                }
+                ScanEvent::RepoStarted {
+                    index,
+                    total,
+                    repo_id,
+                } => {
+                    let _ = writeln!(out, "[{}/{}] Processing {}...", index, total, repo_id);
+                }
+                ScanEvent::BranchesFetched { count, .. } => {
+                    let _ = writeln!(out, "  Fetching branches... found {}", count);
+                }
+                ScanEvent::PullRequestsFetched { count, .. } => {
+                    let _ = writeln!(out, "  Fetching pull requests... found {}", count);
+                }
+                ScanEvent::CommitsFetched { branch, count, .. } => {
+                    let _ = writeln!(out, "  Fetching commits for {}... found {}", branch, count);
+                }
+                ScanEvent::Error { message, .. } => eprintln!("  {}", message),
+                ScanEvent::RepoFinished { .. } => {
+                    let _ = writeln!(out);
+                }
+            });
+            let summary = match result {
+                Ok(summary) => summary,
                 Err(e) => {
-                    eprintln!("Error fetching repositories: {}", e);
+                    eprintln!("Error scanning repositories: {}", e);
                     std::process::exit(1);
                 }
             };
 
-            println!("\nFound {} repositories\n", repos.len());
-
-            // Process each repository
-            for (i, repo) in repos.iter().enumerate() {
-                println!("[{}/{}] Processing {}...", i + 1, repos.len(), repo.id);
-
-                // Save repository
-                if let Err(e) = db.save_repository(repo) {
-                    eprintln!("  Error saving repository: {}", e);
-                    continue;
+            if summary.changes.is_empty() {
+                let _ = writeln!(out, "No changes since the last scan");
+            } else {
+                let _ = writeln!(out, "Changes since the last scan:");
+                for change in &summary.changes {
+                    let _ = writeln!(out, "  {}", change);
                 }
+            }
+            let _ = writeln!(out);
 
-                // Fetch and save branches
-                print!("  Fetching branches...");
-                match github::fetch_branches(&repo.id) {
-                    Ok(branches) => {
-                        println!(" found {}", branches.len());
-
-                        // Clear old branches
-                        if let Err(e) = db.clear_branches_for_repo(&repo.id) {
-                            eprintln!("  Error clearing old branches: {}", e);
-                        }
+            if json {
+                println!("{}", serde_json::to_string_pretty(&summary).unwrap());
+            }
 
-                        // Save branches and fetch commits for unmerged branches
-                        for branch in &branches {
-                            match db.save_branch(branch) {
-                                Ok(branch_id) => {
-                                    // Fetch commits for branches with unmerged changes
-                                    if branch.ahead_by > 0 {
-                                        print!("  Fetching commits for {}...", branch.name);
-                                        match github::fetch_commits(
-                                            &repo.id,
-                                            &branch.name,
-                                            branch_id,
-                                        ) {
-                                            Ok(commits) => {
-                                                println!(" found {}", commits.len());
-
-                                                // Clear old commits for this branch
-                                                if let Err(e) =
-                                                    db.clear_commits_for_branch(branch_id)
-                                                {
-                                                    eprintln!(
-                                                        "    Error clearing old commits: {}",
-                                                        e
-                                                    );
-                                                }
-
-                                                // Save commits
-                                                for commit in &commits {
-                                                    if let Err(e) = db.save_commit(commit) {
-                                                        eprintln!(
-                                                            "    Error saving commit {}: {}",
-                                                            &commit.sha[..7],
-                                                            e
-                                                        );
-                                                    }
-                                                }
-                                            }
-                                            Err(e) => {
-                                                eprintln!("\n    Error fetching commits: {}", e);
-                                            }
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    eprintln!("  Error saving branch {}: {}", branch.name, e);
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("\n  Error fetching branches: {}", e);
-                    }
+            if summary.errors.is_empty() {
+                let _ = writeln!(out, "✓ Scan complete! Data saved to {}", db_path.display());
+            } else {
+                let failed_repos: std::collections::HashSet<_> =
+                    summary.errors.iter().map(|e| &e.repo_id).collect();
+                eprintln!(
+                    "✗ Scan finished with {} errors in {} of {} repositories:",
+                    summary.errors.len(),
+                    failed_repos.len(),
+                    summary.repos.len()
+                );
+                for error in &summary.errors {
+                    eprintln!("  {} [{}]: {}", error.repo_id, error.stage, error.message);
                 }
-
-                // Fetch and save pull requests
-                print!("  Fetching pull requests...");
-                match github::fetch_pull_requests(&repo.id) {
-                    Ok(prs) => {
-                        println!(" found {}", prs.len());
-
-                        // Clear old PRs
-                        if let Err(e) = db.clear_pull_requests_for_repo(&repo.id) {
-                            eprintln!("  Error clearing old PRs: {}", e);
-                        }
-
-                        // Save PRs
-                        for pr in &prs {
-                            if let Err(e) = db.save_pull_request(pr) {
-                                eprintln!("  Error saving PR #{}: {}", pr.number, e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("\n  Error fetching pull requests: {}", e);
-                    }
+                eprintln!("Everything else was saved to {}", db_path.display());
+                exit_code = 1;
+            }
+        }
+        Some(Commands::List {
+            private_only,
+            public_only,
+            ndjson: true,
+            pending,
+        }) => {
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
                 }
+            };
 
-                println!();
+            let visibility = visibility_filter(private_only, public_only);
+            let statuses = pending.then(|| load_repo_statuses(&db));
+            let mut stdout = std::io::stdout().lock();
+            let result = db.for_each_repository(|repo| {
+                if visibility.is_some_and(|private| repo.is_private != private) {
+                    return Ok(());
+                }
+                if statuses
+                    .as_ref()
+                    .is_some_and(|statuses| !is_pending(statuses, &repo.id))
+                {
+                    return Ok(());
+                }
+                writeln!(stdout, "{}", serde_json::to_string(&repo)?)?;
+                Ok(())
+            });
+            match result {
+                Ok(()) => {}
+                // The reader went away (e.g. `| head`); nothing left to do
+                Err(overall_cli::Error::IO(e)) if e.kind() == std::io::ErrorKind::BrokenPipe => {}
+                Err(e) => {
+                    eprintln!("Error listing repositories: {}", e);
+                    std::process::exit(1);
+                }
             }
-
-            println!("✓ Scan complete! Data saved to {}", db_path.display());
         }
-        Some(Commands::List) => {
+        Some(Commands::List {
+            private_only,
+            public_only,
+            ndjson: false,
+            pending,
+        }) => {
             println!("Listing repositories...");
 
             let db_path = get_db_path();
@@ -210,23 +703,38 @@ fn main() {
             };
 
             match db.get_all_repositories() {
-                Ok(repos) => {
+                Ok(mut repos) => {
+                    if let Some(private) = visibility_filter(private_only, public_only) {
+                        repos.retain(|r| r.is_private == private);
+                    }
+                    let statuses = pending.then(|| load_repo_statuses(&db));
+                    if let Some(statuses) = &statuses {
+                        repos.retain(|r| is_pending(statuses, &r.id));
+                    }
+
                     if repos.is_empty() {
-                        println!("No repositories found. Run 'overall scan <owner>' to fetch repositories.");
+                        if pending {
+                            println!("No repositories with pending work.");
+                        } else {
+                            println!("No repositories found. Run 'overall scan <owner>' to fetch repositories.");
+                        }
                         return;
                     }
 
                     println!("\nFound {} repositories:\n", repos.len());
                     for (i, repo) in repos.iter().enumerate() {
                         println!("{}. {}", i + 1, repo.id);
-                        println!(
-                            "   Language: {}",
-                            repo.language.as_ref().unwrap_or(&"Unknown".to_string())
-                        );
+                        println!("   Language: {}", repo.language_label());
+                        if repo.is_private {
+                            println!("   Visibility: private");
+                        }
                         println!(
                             "   Last push: {}",
                             repo.pushed_at.format("%Y-%m-%d %H:%M:%S")
                         );
+                        if let Some(status) = statuses.as_ref().and_then(|s| s.get(&repo.id)) {
+                            println!("   Status: {}", status);
+                        }
 
                         // Get branch count
                         if let Ok(branches) = db.get_branches_for_repo(&repo.id) {
@@ -253,7 +761,12 @@ fn main() {
                 }
             }
         }
-        Some(Commands::Export { output }) => {
+        Some(Commands::Export {
+            output,
+            group,
+            private_only,
+            public_only,
+        }) => {
             println!("Exporting data to {}...", output.display());
 
             let db_path = get_db_path();
@@ -265,142 +778,33 @@ fn main() {
                 }
             };
 
-            // Build JSON structure with groups
-            use serde_json::json;
-            let mut export_data = json!({
-                "groups": [],
-                "ungrouped": []
-            });
-
-            // Export all groups
-            let groups = db.get_all_groups().unwrap_or_default();
-            for group in &groups {
-                let repos = db.get_repos_in_group(group.id).unwrap_or_default();
-                let mut group_repos = Vec::new();
-
-                for repo in repos {
-                    let branches = db.get_branches_for_repo(&repo.id).unwrap_or_default();
-                    let prs = db.get_pull_requests_for_repo(&repo.id).unwrap_or_default();
-
-                    // Exclude main/master/develop branches - they should never have PRs created
-                    let unmerged_count = branches
-                        .iter()
-                        .filter(|b| {
-                            b.ahead_by > 0
-                                && b.behind_by == 0
-                                && b.name != "main"
-                                && b.name != "master"
-                                && b.name != "develop"
-                        })
-                        .count();
-                    let open_pr_count = prs
-                        .iter()
-                        .filter(|pr| matches!(pr.state, overall_cli::models::PRState::Open))
-                        .count();
-
-                    group_repos.push(json!({
-                        "id": repo.id,
-                        "owner": repo.owner,
-                        "name": repo.name,
-                        "language": repo.language.unwrap_or_else(|| "Unknown".to_string()),
-                        "lastPush": repo.pushed_at.to_rfc3339(),
-                        "branches": branches.iter().map(|b| {
-                            let commits = db.get_commits_for_branch(b.id).unwrap_or_default();
-                            json!({
-                                "name": b.name,
-                                "sha": b.sha,
-                                "aheadBy": b.ahead_by,
-                                "behindBy": b.behind_by,
-                                "status": b.status.to_string(),
-                                "lastCommitDate": b.last_commit_date.to_rfc3339(),
-                                "commits": commits.iter().map(|c| json!({
-                                    "sha": c.sha,
-                                    "message": c.message,
-                                    "authorName": c.author_name,
-                                    "authorEmail": c.author_email,
-                                    "authoredDate": c.authored_date.to_rfc3339(),
-                                    "committerName": c.committer_name,
-                                    "committerEmail": c.committer_email,
-                                    "committedDate": c.committed_date.to_rfc3339(),
-                                })).collect::<Vec<_>>(),
-                            })
-                        }).collect::<Vec<_>>(),
-                        "pullRequests": prs.iter().map(|pr| json!({
-                            "number": pr.number,
-                            "title": pr.title,
-                            "state": pr.state.to_string(),
-                            "createdAt": pr.created_at.to_rfc3339(),
-                            "updatedAt": pr.updated_at.to_rfc3339(),
-                        })).collect::<Vec<_>>(),
-                        "unmergedCount": unmerged_count,
-                        "prCount": open_pr_count,
-                    }));
-                }
-
-                export_data["groups"].as_array_mut().unwrap().push(json!({
-                    "id": group.id,
-                    "name": group.name,
-                    "repos": group_repos
-                }));
-            }
-
-            // Export ungrouped repositories
-            let ungrouped = db.get_ungrouped_repositories().unwrap_or_default();
-            for repo in ungrouped {
-                let branches = db.get_branches_for_repo(&repo.id).unwrap_or_default();
-                let prs = db.get_pull_requests_for_repo(&repo.id).unwrap_or_default();
-
-                let unmerged_count = branches
-                    .iter()
-                    .filter(|b| b.ahead_by > 0 && b.behind_by == 0)
-                    .count();
-                let open_pr_count = prs
-                    .iter()
-                    .filter(|pr| matches!(pr.state, overall_cli::models::PRState::Open))
-                    .count();
-
-                export_data["ungrouped"]
-                    .as_array_mut()
-                    .unwrap()
-                    .push(json!({
-                        "id": repo.id,
-                        "owner": repo.owner,
-                        "name": repo.name,
-                        "language": repo.language.unwrap_or_else(|| "Unknown".to_string()),
-                        "lastPush": repo.pushed_at.to_rfc3339(),
-                        "branches": branches.iter().map(|b| {
-                            let commits = db.get_commits_for_branch(b.id).unwrap_or_default();
-                            json!({
-                                "name": b.name,
-                                "sha": b.sha,
-                                "aheadBy": b.ahead_by,
-                                "behindBy": b.behind_by,
-                                "status": b.status.to_string(),
-                                "lastCommitDate": b.last_commit_date.to_rfc3339(),
-                                "commits": commits.iter().map(|c| json!({
-                                    "sha": c.sha,
-                                    "message": c.message,
-                                    "authorName": c.author_name,
-                                    "authorEmail": c.author_email,
-                                    "authoredDate": c.authored_date.to_rfc3339(),
-                                    "committerName": c.committer_name,
-                                    "committerEmail": c.committer_email,
-                                    "committedDate": c.committed_date.to_rfc3339(),
-                                })).collect::<Vec<_>>(),
-                            })
-                        }).collect::<Vec<_>>(),
-                        "pullRequests": prs.iter().map(|pr| json!({
-                            "number": pr.number,
-                            "title": pr.title,
-                            "state": pr.state.to_string(),
-                            "createdAt": pr.created_at.to_rfc3339(),
-                            "updatedAt": pr.updated_at.to_rfc3339(),
-                        })).collect::<Vec<_>>(),
-                        "unmergedCount": unmerged_count,
-                        "prCount": open_pr_count,
-                    }));
+            let mut groups = db.get_all_groups().unwrap_or_default();
+            if let Some(ref group_name) = group {
+                groups.retain(|g| &g.name == group_name);
+                if groups.is_empty() {
+                    eprintln!("Error: group '{}' not found", group_name);
+                    std::process::exit(1);
+                }
             }
 
+            // Build JSON structure with groups, skipping ungrouped repos when filtering
+            let export_data = match export::build_export(
+                &db,
+                &groups,
+                group.is_none(),
+                &Config::load().unwrap_or_default(),
+            )
+            .and_then(|data| match visibility_filter(private_only, public_only) {
+                Some(private) => export::filter_by_visibility(data, private),
+                None => Ok(data),
+            }) {
+                Ok(data) => data,
+                Err(e) => {
+                    eprintln!("Error building export: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
             // Create output directory if needed
             if let Some(parent) = output.parent() {
                 if let Err(e) = std::fs::create_dir_all(parent) {
@@ -410,8 +814,7 @@ fn main() {
             }
 
             // Write JSON file
-            let json_str = serde_json::to_string_pretty(&export_data).unwrap();
-            if let Err(e) = std::fs::write(&output, json_str) {
+            if let Err(e) = export::write_export(&output, &export_data) {
                 eprintln!("Error writing output file: {}", e);
                 std::process::exit(1);
             }
@@ -425,37 +828,1162 @@ fn main() {
                 output.display()
             );
         }
-        Some(Commands::Serve { port, debug }) => {
-            if debug {
-                println!(
-                    "[DEBUG] Starting web server on port {} (debug mode enabled)...",
-                    port
-                );
-            } else {
-                println!("Starting web server on port {}...", port);
-            }
+        Some(Commands::Import { file }) => {
+            println!("Importing data from {}...", file.display());
+
+            let data = read_export_file(&file);
 
             let db_path = get_db_path();
-            let static_dir = std::path::PathBuf::from("static");
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
 
-            // Check if static directory exists
-            if !static_dir.exists() {
-                eprintln!("Error: static directory not found");
-                eprintln!("Run './target/release/overall export' first to generate static files");
-                std::process::exit(1);
+            // Import replaces branches, commits and PRs, so keep a way back
+            let config = Config::load().unwrap_or_default();
+            match db.backup_snapshot(config.storage.backups_to_keep) {
+                Ok(path) => println!("Backed up database to {}", path.display()),
+                Err(e) => {
+                    eprintln!("Error backing up database, import aborted: {}", e);
+                    std::process::exit(1);
+                }
             }
 
-            // Run the server using tokio runtime
-            let runtime = tokio::runtime::Runtime::new().unwrap();
-            if let Err(e) =
-                runtime.block_on(overall_cli::server::serve(port, db_path, static_dir, debug))
-            {
-                eprintln!("Server error: {}", e);
-                std::process::exit(1);
+            match export::import_export(&db, &data) {
+                Ok(summary) => println!(
+                    "✓ Imported {} groups, {} repositories, {} branches, {} commits and {} pull requests",
+                    summary.groups,
+                    summary.repos,
+                    summary.branches,
+                    summary.commits,
+                    summary.pull_requests
+                ),
+                Err(e) => {
+                    eprintln!("Error importing data: {}", e);
+                    std::process::exit(1);
+                }
             }
         }
-        None => {
-            println!("Use --help for usage information");
+        Some(Commands::DiffExport { old, new }) => {
+            let old = read_export_file(&old);
+            let new = read_export_file(&new);
+            print!("{}", analysis::diff_exports(&old, &new));
         }
+        Some(Commands::Report { group, format }) => {
+            let config = Config::load().unwrap_or_default();
+
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let mut groups = db.get_all_groups().unwrap_or_default();
+            if let Some(ref group_name) = group {
+                groups.retain(|g| &g.name == group_name);
+                if groups.is_empty() {
+                    eprintln!("Error: group '{}' not found", group_name);
+                    std::process::exit(1);
+                }
+            }
+
+            let report_groups = match analysis::build_report_groups(&db, &groups, group.is_none()) {
+                Ok(g) => g,
+                Err(e) => {
+                    eprintln!("Error building report: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let statuses = db
+                .get_all_local_repo_statuses()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|s| (s.repo_id.clone(), s))
+                .collect();
+
+            let report = match format {
+                ReportFormat::Markdown => {
+                    analysis::render_markdown_report(&report_groups, &statuses, &config)
+                }
+                ReportFormat::Html => {
+                    analysis::render_html_report(&report_groups, &statuses, &config)
+                }
+            };
+            print!("{}", report);
+        }
+        Some(Commands::NeedsSync) => {
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let needs = match db.get_sync_needs() {
+                Ok(needs) => needs,
+                Err(e) => {
+                    eprintln!("Error loading sync status: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            if needs.is_empty() {
+                println!("Everything is in sync");
+                return;
+            }
+
+            for need in &needs {
+                let location = if need.is_local { "local" } else { "github" };
+                let branch = need.branch.as_deref().unwrap_or("(detached)");
+
+                let mut details = Vec::new();
+                if need.has_conflicts {
+                    details.push("conflicts".to_string());
+                }
+                if need.is_local && need.ahead > 0 && need.behind > 0 {
+                    details.push("diverged".to_string());
+                }
+                if need.ahead > 0 {
+                    details.push(format!("{} ahead", need.ahead));
+                }
+                if need.behind > 0 {
+                    details.push(format!("{} behind", need.behind));
+                }
+
+                println!(
+                    "{:<40} {:<6} {:<30} {}",
+                    need.repo_id,
+                    location,
+                    branch,
+                    details.join(", ")
+                );
+            }
+        }
+        Some(Commands::Reprioritize) => {
+            let config = Config::load().unwrap_or_default();
+
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let repos = match db.get_all_repositories() {
+                Ok(repos) => repos,
+                Err(e) => {
+                    eprintln!("Error loading repositories: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let statuses: std::collections::HashMap<_, _> = db
+                .get_all_local_repo_statuses()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|s| (s.repo_id.clone(), s))
+                .collect();
+
+            let now = chrono::Utc::now();
+            let mut updated = 0;
+            let mut locked = 0;
+            for repo in &repos {
+                // Manual overrides stay until unlocked
+                if repo.priority_locked {
+                    locked += 1;
+                    continue;
+                }
+
+                let branches = db.get_branches_for_repo(&repo.id).unwrap_or_default();
+                let prs = db.get_pull_requests_for_repo(&repo.id).unwrap_or_default();
+                let priority = analysis::compute_priority(
+                    repo,
+                    &branches,
+                    &prs,
+                    statuses.get(&repo.id),
+                    now,
+                    &config,
+                );
+
+                match db.update_repository_priority(&repo.id, priority) {
+                    Ok(true) => updated += 1,
+                    Ok(false) => locked += 1,
+                    Err(e) => eprintln!("Error updating priority for {}: {}", repo.id, e),
+                }
+            }
+
+            if locked > 0 {
+                println!(
+                    "✓ Updated priority for {} repositories ({} locked by a manual override)",
+                    updated, locked
+                );
+            } else {
+                println!("✓ Updated priority for {} repositories", updated);
+            }
+        }
+        Some(Commands::Assign {
+            repo_id,
+            group,
+            create,
+            output,
+        }) => {
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let repo_id = resolve_repo_id(&db, &repo_id);
+            let existing = match db.get_all_groups() {
+                Ok(groups) => groups
+                    .into_iter()
+                    .find(|g| g.name.eq_ignore_ascii_case(&group)),
+                Err(e) => {
+                    eprintln!("Error loading groups: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let (group_id, group) = match existing {
+                Some(existing) => (existing.id, existing.name),
+                None if create => match db.get_or_create_group(&group) {
+                    Ok(id) => (id, group),
+                    Err(e) => {
+                        eprintln!("Error creating group '{}': {}", group, e);
+                        std::process::exit(1);
+                    }
+                },
+                None => {
+                    eprintln!(
+                        "Error: no group named '{}' (pass --create to create it)",
+                        group
+                    );
+                    std::process::exit(1);
+                }
+            };
+
+            if let Err(e) = db.move_repo_to_group(&repo_id, group_id) {
+                eprintln!("Error moving {} to group: {}", repo_id, e);
+                std::process::exit(1);
+            }
+            println!("✓ Moved {} to group '{}'", repo_id, group);
+            regenerate_export(&db, &output);
+        }
+        Some(Commands::Tag {
+            repo_id,
+            tag,
+            remove,
+            output,
+        }) => {
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let repo_id = resolve_repo_id(&db, &repo_id);
+            let result = if remove {
+                db.remove_tag(&repo_id, &tag)
+            } else {
+                db.add_tag(&repo_id, &tag)
+            };
+            match result {
+                Ok(true) if remove => println!("✓ Removed tag '{}' from {}", tag, repo_id),
+                Ok(true) => println!("✓ Tagged {} with '{}'", repo_id, tag),
+                Ok(false) if remove => {
+                    println!("{} has no tag '{}'", repo_id, tag);
+                    return;
+                }
+                Ok(false) => {
+                    println!("{} is already tagged '{}'", repo_id, tag);
+                    return;
+                }
+                Err(e) => {
+                    eprintln!("Error updating tags for {}: {}", repo_id, e);
+                    std::process::exit(1);
+                }
+            }
+            match db.get_tags_for_repo(&repo_id) {
+                Ok(tags) if tags.is_empty() => println!("  Tags: (none)"),
+                Ok(tags) => println!("  Tags: {}", tags.join(", ")),
+                Err(_) => {}
+            }
+            regenerate_export(&db, &output);
+        }
+        Some(Commands::Snooze {
+            repo_id,
+            duration,
+            clear,
+            output,
+        }) => {
+            let until = match duration.filter(|_| !clear) {
+                Some(duration) => match overall_cli::models::snooze_until(&duration) {
+                    Ok(until) => Some(until),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                },
+                None => None,
+            };
+
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let repo_id = resolve_repo_id(&db, &repo_id);
+            match db.set_snooze(&repo_id, until) {
+                Ok(true) => match until {
+                    Some(until) => println!(
+                        "✓ Snoozed {} until {}",
+                        repo_id,
+                        until.format("%Y-%m-%d %H:%M UTC")
+                    ),
+                    None => println!("✓ {} is no longer snoozed", repo_id),
+                },
+                Ok(false) => {
+                    eprintln!("Repository {} is not tracked", repo_id);
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("Error snoozing {}: {}", repo_id, e);
+                    std::process::exit(1);
+                }
+            }
+            regenerate_export(&db, &output);
+        }
+        Some(Commands::Group { action }) => {
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            match action {
+                GroupAction::Add { group, repo } => {
+                    let repo_id = resolve_repo_id(&db, &repo);
+                    let result = db
+                        .get_or_create_group(&group)
+                        .and_then(|group_id| db.add_repo_to_group(&repo_id, group_id));
+                    match result {
+                        Ok(()) => println!("✓ Added {} to group '{}'", repo_id, group),
+                        Err(e) => {
+                            eprintln!("Error adding {} to group: {}", repo_id, e);
+                            std::process::exit(1);
+                        }
+                    }
+                }
+            }
+        }
+        Some(Commands::Reclassify { repo }) => {
+            let config = Config::load().unwrap_or_default();
+
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let repo_ids = match repo {
+                Some(query) => vec![resolve_repo_id(&db, &query)],
+                None => match db.get_all_repositories() {
+                    Ok(repos) => repos.into_iter().map(|r| r.id).collect(),
+                    Err(e) => {
+                        eprintln!("Error loading repositories: {}", e);
+                        std::process::exit(1);
+                    }
+                },
+            };
+
+            let mut changed = 0;
+            for repo_id in &repo_ids {
+                match analysis::reclassify_repo(&db, repo_id, &config) {
+                    Ok(0) => {}
+                    Ok(n) => {
+                        println!("{}: {} branch statuses changed", repo_id, n);
+                        changed += n;
+                    }
+                    Err(e) => eprintln!("Error reclassifying {}: {}", repo_id, e),
+                }
+            }
+
+            println!(
+                "✓ Reclassified {} repositories ({} branch statuses changed)",
+                repo_ids.len(),
+                changed
+            );
+        }
+        Some(Commands::Recent { days }) => {
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            match db.get_recently_pushed(days) {
+                Ok(repos) if repos.is_empty() => {
+                    println!("No repositories pushed to in the last {} days.", days);
+                }
+                Ok(repos) => {
+                    for repo in &repos {
+                        println!("{}  {}", repo.pushed_at.format("%Y-%m-%d %H:%M"), repo.id);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error loading repositories: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Commands::Abandoned { days }) => {
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let config = Config::load().unwrap_or_default();
+            let days = days.unwrap_or(config.analysis.abandoned_threshold_days);
+            let repos = match db.get_all_repositories() {
+                Ok(repos) => repos,
+                Err(e) => {
+                    eprintln!("Error loading repositories: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let now = chrono::Utc::now();
+            let mut found = 0;
+            for repo in &repos {
+                let branches = db.get_branches_for_repo(&repo.id).unwrap_or_default();
+                let prs = db.get_pull_requests_for_repo(&repo.id).unwrap_or_default();
+                let abandoned = analysis::find_abandoned_branches(&branches, &prs, days, now);
+                if abandoned.is_empty() {
+                    continue;
+                }
+
+                println!("{}", repo.id);
+                for branch in abandoned {
+                    println!(
+                        "   {} (+{} ahead, last commit {} days ago)",
+                        branch.name,
+                        branch.ahead_by,
+                        (now - branch.last_commit_date).num_days()
+                    );
+                    found += 1;
+                }
+            }
+
+            if found == 0 {
+                println!("No abandoned branches (no commits for {} days).", days);
+            } else {
+                println!(
+                    "\n{} abandoned branches with no commits for {} days or more",
+                    found, days
+                );
+            }
+        }
+        Some(Commands::Show { repo_id }) => {
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let repo_id = resolve_repo_id(&db, &repo_id);
+            match analysis::load_repo_detail(&db, &repo_id) {
+                Ok(Some(detail)) => {
+                    let config = Config::load().unwrap_or_default();
+                    print!(
+                        "{}",
+                        analysis::render_repo_detail(&detail, &config, chrono::Utc::now())
+                    );
+                }
+                Ok(None) => {
+                    eprintln!("{} is not tracked", repo_id);
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("Error loading {}: {}", repo_id, e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Commands::Where { repo_id, path_only }) => {
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let status = match db.get_local_repo_status(&repo_id) {
+                Ok(Some(status)) => status,
+                Ok(None) => {
+                    eprintln!("{} has no local clone (run a local scan first)", repo_id);
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("Error loading local status: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            println!("{}", status.local_path);
+            if !path_only {
+                println!(
+                    "Branch: {}",
+                    status.current_branch.as_deref().unwrap_or("(detached)")
+                );
+
+                println!("Status: {}", analysis::local_state_summary(&status));
+            }
+        }
+        Some(Commands::CloneMissing { into, dry_run }) => {
+            let db = match Database::open_or_create(&get_db_path()) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let missing = match db.get_repositories_without_local_clone() {
+                Ok(missing) => missing,
+                Err(e) => {
+                    eprintln!("Error loading repositories: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let into = into.unwrap_or_else(|| {
+                let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+                PathBuf::from(home).join("github")
+            });
+
+            if missing.is_empty() {
+                println!("Every tracked repository has a local clone");
+            } else if dry_run {
+                for repo in &missing {
+                    println!(
+                        "{} -> {}",
+                        repo.id,
+                        into.join(&repo.owner).join(&repo.name).display()
+                    );
+                }
+            } else {
+                let mut failed = 0;
+                let mut owner_dirs = std::collections::BTreeSet::new();
+                for repo in &missing {
+                    match github::clone_repo(&repo.id, &into) {
+                        Ok(path) => {
+                            println!("✓ Cloned {} into {}", repo.id, path.display());
+                            owner_dirs.extend(path.parent().map(|p| p.to_path_buf()));
+                        }
+                        Err(e) => {
+                            failed += 1;
+                            eprintln!("Error cloning {}: {}", repo.id, e);
+                        }
+                    }
+                }
+
+                // Scans only look at registered roots, one per owner directory
+                let roots = db.get_all_local_repo_roots().unwrap_or_default();
+                for dir in &owner_dirs {
+                    let path = dir.to_string_lossy();
+                    if !roots
+                        .iter()
+                        .any(|root| Path::new(&root.path) == dir.as_path())
+                    {
+                        match db.add_local_repo_root(&path) {
+                            Ok(_) => println!("Added local root {}", path),
+                            Err(e) => eprintln!("Error adding local root {}: {}", path, e),
+                        }
+                    }
+                }
+
+                let roots = db.get_all_local_repo_roots().unwrap_or_default();
+                let db = std::sync::Mutex::new(db);
+                let (scanned, _) = overall_cli::server::scan_local_roots(&db, &roots);
+                println!(
+                    "Cloned {} of {} missing repositories; scanned {} local repositories",
+                    missing.len() - failed,
+                    missing.len(),
+                    scanned
+                );
+            }
+        }
+        Some(Commands::LocalOnly { scan }) => {
+            let config = Config::load().unwrap_or_default();
+            let mut db = match Database::open_or_create(&get_db_path()) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            db.set_max_commit_body_len(config.storage.max_commit_body_len);
+
+            let local_only = match analysis::find_local_only_repos(&db) {
+                Ok(local_only) => local_only,
+                Err(e) => {
+                    eprintln!("Error finding local-only repositories: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            if local_only.is_empty() {
+                println!("Every clone under the local roots is tracked");
+            }
+            for repo in &local_only {
+                match &repo.remote_repo_id {
+                    Some(remote) if *remote != repo.repo_id => println!(
+                        "{}  {}  (origin: {})",
+                        repo.repo_id, repo.local_path, remote
+                    ),
+                    Some(_) => println!("{}  {}", repo.repo_id, repo.local_path),
+                    None => println!("{}  {}  (no GitHub origin)", repo.repo_id, repo.local_path),
+                }
+            }
+
+            let mut remote_ids: Vec<String> = local_only
+                .iter()
+                .filter_map(|repo| repo.remote_repo_id.clone())
+                .collect();
+            remote_ids.sort();
+            remote_ids.dedup();
+            if scan && !remote_ids.is_empty() {
+                println!("\nScanning {} repositories from GitHub\n", remote_ids.len());
+                let client = github::RealGitHubClient::new();
+                let opts = ScanOptions::from_config(&config.github);
+                let summary =
+                    scan_repo_ids(&db, &client, &remote_ids, &opts, |event| match event {
+                        ScanEvent::RepoStarted {
+                            index,
+                            total,
+                            repo_id,
+                        } => println!("[{}/{}] Processing {}...", index, total, repo_id),
+                        ScanEvent::Error {
+                            repo_id, message, ..
+                        } => {
+                            eprintln!("  {}: {}", repo_id, message)
+                        }
+                        _ => {}
+                    });
+
+                // Now that they are tracked, record the clones' local state
+                let roots = db.get_all_local_repo_roots().unwrap_or_default();
+                let db = std::sync::Mutex::new(db);
+                overall_cli::server::scan_local_roots(&db, &roots);
+
+                println!(
+                    "✓ Tracked {} of {} repositories",
+                    summary.repos_processed,
+                    remote_ids.len()
+                );
+            }
+        }
+        Some(Commands::Watch {
+            interval,
+            notify,
+            cooldown,
+        }) => {
+            let config = Config::load().unwrap_or_default();
+            let db = match Database::open_or_create(&get_db_path()) {
+                Ok(db) => std::sync::Mutex::new(db),
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            println!(
+                "Watching local repositories every {}s (Ctrl-C to stop)",
+                interval
+            );
+            let mut watcher = analysis::AttentionWatcher::new(chrono::Duration::minutes(cooldown));
+            loop {
+                let roots = db
+                    .lock()
+                    .unwrap()
+                    .get_all_local_repo_roots()
+                    .unwrap_or_default();
+                overall_cli::server::scan_local_roots(&db, &roots);
+
+                let statuses = match db.lock().unwrap().get_repo_statuses(&config) {
+                    Ok(statuses) => statuses,
+                    Err(e) => {
+                        eprintln!("Error computing repository statuses: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+                for repo_id in watcher.update(statuses, chrono::Utc::now()) {
+                    println!("! {} needs attention", repo_id);
+                    if notify {
+                        if let Err(e) =
+                            send_notification("overall", &format!("{} needs attention", repo_id))
+                        {
+                            eprintln!("Warning: desktop notification failed: {}", e);
+                        }
+                    }
+                }
+
+                std::thread::sleep(std::time::Duration::from_secs(interval));
+            }
+        }
+        Some(Commands::Untrack { repo_id, yes }) => {
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let repo_id = resolve_repo_id(&db, &repo_id);
+            if !yes {
+                print!(
+                    "Stop tracking {} and delete its branches, commits and PRs? [y/N]: ",
+                    repo_id
+                );
+                let _ = std::io::stdout().flush();
+
+                let mut answer = String::new();
+                let _ = std::io::stdin().read_line(&mut answer);
+                if !matches!(answer.trim(), "y" | "Y" | "yes") {
+                    eprintln!("Aborted");
+                    std::process::exit(1);
+                }
+            }
+
+            let config = Config::load().unwrap_or_default();
+            match db.backup_snapshot(config.storage.backups_to_keep) {
+                Ok(path) => println!("Backed up database to {}", path.display()),
+                Err(e) => {
+                    eprintln!("Error backing up database, untrack aborted: {}", e);
+                    std::process::exit(1);
+                }
+            }
+
+            match db.delete_repository(&repo_id) {
+                Ok(_) => println!(
+                    "✓ Stopped tracking {} (it returns on the next scan unless excluded)",
+                    repo_id
+                ),
+                Err(e) => {
+                    eprintln!("Error deleting repository: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Commands::Exclude { action }) => {
+            let config = Config::load().unwrap_or_default();
+
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            match action {
+                ExcludeAction::Add { pattern, prune } => {
+                    if prune {
+                        match db.backup_snapshot(config.storage.backups_to_keep) {
+                            Ok(path) => println!("Backed up database to {}", path.display()),
+                            Err(e) => {
+                                eprintln!("Error backing up database, exclude aborted: {}", e);
+                                std::process::exit(1);
+                            }
+                        }
+                    }
+                    if let Err(e) = db.add_exclude_pattern(&pattern) {
+                        eprintln!("Error adding exclude pattern: {}", e);
+                        std::process::exit(1);
+                    }
+                    println!("✓ Excluding {}", pattern);
+
+                    if prune {
+                        match db.prune_excluded_repositories(&config.github.repo_exclude) {
+                            Ok(pruned) => {
+                                for repo_id in &pruned {
+                                    println!("  Removed {}", repo_id);
+                                }
+                                println!("✓ Pruned {} excluded repositories", pruned.len());
+                            }
+                            Err(e) => {
+                                eprintln!("Error pruning excluded repositories: {}", e);
+                                exit_code = 1;
+                            }
+                        }
+                    }
+                }
+                ExcludeAction::Remove { pattern } => match db.remove_exclude_pattern(&pattern) {
+                    Ok(true) => println!("✓ No longer excluding {}", pattern),
+                    Ok(false) => {
+                        eprintln!("Error: {} is not an excluded pattern", pattern);
+                        std::process::exit(1);
+                    }
+                    Err(e) => {
+                        eprintln!("Error removing exclude pattern: {}", e);
+                        std::process::exit(1);
+                    }
+                },
+                ExcludeAction::List => {
+                    let stored = match db.get_exclude_patterns() {
+                        Ok(patterns) => patterns,
+                        Err(e) => {
+                            eprintln!("Error loading exclude patterns: {}", e);
+                            std::process::exit(1);
+                        }
+                    };
+                    if stored.is_empty() && config.github.repo_exclude.is_empty() {
+                        println!("No repositories are excluded.");
+                    }
+                    for pattern in &stored {
+                        println!("{}", pattern);
+                    }
+                    for pattern in &config.github.repo_exclude {
+                        println!("{} (config)", pattern);
+                    }
+                }
+            }
+        }
+        Some(Commands::Backup { output }) => {
+            let db_path = get_db_path();
+            let db = match Database::open_or_create(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("Error opening database: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let result = match output {
+                Some(path) => db.backup_to(&path).map(|()| path),
+                None => {
+                    db.backup_snapshot(Config::load().unwrap_or_default().storage.backups_to_keep)
+                }
+            };
+            match result {
+                Ok(path) => println!("✓ Backed up database to {}", path.display()),
+                Err(e) => {
+                    eprintln!("Error backing up database: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Commands::Doctor) => {
+            let mut ok = true;
+
+            match github::gh_version() {
+                Ok(version) => println!("✓ gh CLI: {}", version),
+                Err(e) => {
+                    ok = false;
+                    println!("✗ gh CLI: {} (install from https://cli.github.com)", e);
+                }
+            }
+
+            match github::gh_auth() {
+                github::GhAuth::LoggedIn => println!("✓ GitHub auth: logged in (gh auth status)"),
+                github::GhAuth::EnvToken(var) => println!(
+                    "✓ GitHub auth: gh is not logged in; using {} from the environment (passed to gh as GH_TOKEN)",
+                    var
+                ),
+                github::GhAuth::Missing => {
+                    ok = false;
+                    println!("✗ GitHub auth: not logged in; run `gh auth login` or set GH_TOKEN or GITHUB_TOKEN");
+                }
+            }
+
+            println!("  Database: {}", get_db_path().display());
+
+            if !ok {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Serve {
+            port,
+            debug,
+            tls_cert,
+            tls_key,
+            cors_origins,
+        }) => {
+            if debug {
+                println!(
+                    "[DEBUG] Starting web server on port {} (debug mode enabled)...",
+                    port
+                );
+            } else {
+                println!("Starting web server on port {}...", port);
+            }
+
+            let db_path = get_db_path();
+            let static_dir = std::path::PathBuf::from("static");
+
+            // Check if static directory exists
+            if !static_dir.exists() {
+                eprintln!("Error: static directory not found");
+                eprintln!("Run './target/release/overall export' first to generate static files");
+                std::process::exit(1);
+            }
+
+            // Run the server using tokio runtime
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let tls = tls_cert
+                .zip(tls_key)
+                .map(|(cert, key)| overall_cli::server::TlsFiles { cert, key });
+            if let Err(e) = runtime.block_on(overall_cli::server::serve(
+                port,
+                db_path,
+                static_dir,
+                debug,
+                tls,
+                cors_origins,
+            )) {
+                eprintln!("Server error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Completions { shell }) => {
+            write_completions(shell, &mut std::io::stdout());
+        }
+        Some(Commands::CompleteRepos { prefix }) => {
+            // Completion must never print errors into the user's command line
+            let ids = Database::open_or_create(&get_db_path())
+                .and_then(|db| db.get_repo_ids_with_prefix(&prefix))
+                .unwrap_or_default();
+            for id in ids {
+                println!("{}", id);
+            }
+        }
+        None => {
+            println!("Use --help for usage information");
+        }
+    }
+
+    if export_after {
+        match Database::open_or_create(&get_db_path()) {
+            Ok(db) => regenerate_export(&db, Path::new("static/repos.json")),
+            Err(e) => {
+                eprintln!("Error opening database: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+}
+
+/// Show a desktop notification with the platform's own tool
+fn send_notification(title: &str, body: &str) -> std::io::Result<()> {
+    let status = if cfg!(target_os = "macos") {
+        let quote = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        std::process::Command::new("osascript")
+            .arg("-e")
+            .arg(format!(
+                "display notification \"{}\" with title \"{}\"",
+                quote(body),
+                quote(title)
+            ))
+            .status()?
+    } else {
+        std::process::Command::new("notify-send")
+            .args([title, body])
+            .status()?
+    };
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!("exited with {}", status)))
+    }
+}
+
+/// Bash: complete repo ids for the first positional of `reclassify`, `show`,
+/// `where`, `untrack`, `assign`, `tag` and `snooze` and the second of `group add`, otherwise defer to
+/// the generated `_overall`
+const BASH_REPO_COMPLETION: &str = r#"
+_overall_repos() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    if [[ ${cur} != -* ]]; then
+        case "${COMP_WORDS[1]},${COMP_CWORD}" in
+            reclassify,2|show,2|where,2|untrack,2|assign,2|tag,2|snooze,2)
+                COMPREPLY=( $(overall __complete-repos "${cur}") )
+                return 0
+                ;;
+            group,4)
+                if [[ "${COMP_WORDS[2]}" == add ]]; then
+                    COMPREPLY=( $(overall __complete-repos "${cur}") )
+                    return 0
+                fi
+                ;;
+        esac
+    fi
+    _overall "$@"
+}
+
+complete -F _overall_repos -o bashdefault -o default overall
+"#;
+
+/// Zsh: the completion function the repo arguments are pointed at
+const ZSH_REPO_COMPLETION: &str = r#"
+(( $+functions[_overall_repos] )) ||
+_overall_repos() {
+    local -a repos
+    repos=(${(f)"$(overall __complete-repos "$PREFIX")"})
+    compadd -a repos
+}
+"#;
+
+const FISH_REPO_COMPLETION: &str = r#"
+complete -c overall -n "__fish_seen_subcommand_from reclassify show where untrack" -f -a "(overall __complete-repos (commandline -ct))"
+complete -c overall -n "__fish_seen_subcommand_from assign tag snooze; and test (count (commandline -opc)) -eq 2" -f -a "(overall __complete-repos (commandline -ct))"
+complete -c overall -n "__fish_seen_subcommand_from group; and __fish_seen_subcommand_from add; and test (count (commandline -opc)) -ge 4" -f -a "(overall __complete-repos (commandline -ct))"
+"#;
+
+/// The clap-generated script, with repo id arguments completed from the
+/// database through `overall __complete-repos`
+fn write_completions(shell: Shell, out: &mut dyn Write) {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), "overall", &mut script);
+    let mut script = String::from_utf8_lossy(&script).into_owned();
+
+    match shell {
+        Shell::Bash => script.push_str(BASH_REPO_COMPLETION),
+        Shell::Zsh => {
+            // Repo arguments are the positionals named `repo` or `repo_id`,
+            // required (`':repo`) or optional (`'::repo`)
+            script = script
+                .lines()
+                .map(|line| {
+                    let is_repo = line.starts_with("':repo") || line.starts_with("'::repo");
+                    if is_repo && line.ends_with(":_default' \\") {
+                        line.replace(":_default' \\", ":_overall_repos' \\")
+                    } else {
+                        line.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            // Define the helper before the trailing compdef/dispatch block
+            match script.rfind("\nif [ \"$funcstack[1]\" = \"_overall\" ]") {
+                Some(at) => script.insert_str(at, ZSH_REPO_COMPLETION),
+                None => script.push_str(ZSH_REPO_COMPLETION),
+            }
+            script.push('\n');
+        }
+        Shell::Fish => script.push_str(FISH_REPO_COMPLETION),
+        _ => {}
+    }
+
+    let _ = out.write_all(script.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completions_cover_subcommands() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let mut script = Vec::new();
+            write_completions(shell, &mut script);
+            let script = String::from_utf8(script).unwrap();
+            assert!(script.contains("diff-export"), "{} script", shell);
+            assert!(
+                script.contains("overall __complete-repos"),
+                "{} script",
+                shell
+            );
+        }
+    }
+
+    #[test]
+    fn test_zsh_completion_points_repo_arguments_at_database() {
+        let mut script = Vec::new();
+        write_completions(Shell::Zsh, &mut script);
+        let script = String::from_utf8(script).unwrap();
+
+        assert!(script
+            .contains(":repo_id -- Repository to untrack (any part of its id):_overall_repos'"));
+        assert!(
+            !script.contains(":repo_id -- Repository to untrack (any part of its id):_default'")
+        );
+        // Optional repo positionals are completed too
+        assert!(script.contains(
+            "'::repo -- Only reclassify this repository (any part of its id):_overall_repos'"
+        ));
+        // The helper is defined before the script dispatches to _overall
+        assert!(script.find("_overall_repos() {") < script.find("compdef _overall overall"));
+    }
+
+    #[test]
+    fn test_watch_rejects_out_of_range_timing() {
+        for args in [
+            vec!["overall", "watch", "--interval", "0"],
+            vec!["overall", "watch", "--cooldown", "-1"],
+            vec!["overall", "watch", "--cooldown", "9223372036854775807"],
+        ] {
+            assert!(Cli::try_parse_from(&args).is_err(), "{:?}", args);
+        }
+
+        let cli = Cli::try_parse_from([
+            "overall",
+            "watch",
+            "--interval",
+            "1",
+            "--cooldown",
+            "525600",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Watch {
+                interval: 1,
+                cooldown: MAX_WATCH_COOLDOWN_MINUTES,
+                ..
+            })
+        ));
     }
 }
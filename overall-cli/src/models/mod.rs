@@ -1,6 +1,9 @@
+use crate::config::Config;
+use crate::Error;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::ops::Deref;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -15,6 +18,155 @@ pub struct Repository {
     pub updated_at: DateTime<Utc>,
     pub is_fork: bool,
     pub priority: f32,
+    #[serde(default)]
+    pub is_private: bool,
+    /// Priority was set manually and is kept by `reprioritize` and scans
+    #[serde(default)]
+    pub priority_locked: bool,
+    /// GitHub's node id, which survives renames and transfers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_id: Option<String>,
+    /// Deliberately deferred: the repository counts as complete until then
+    /// (set with `Database::set_snooze`, not by saving the repository)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snoozed_until: Option<DateTime<Utc>>,
+}
+
+impl Repository {
+    /// Primary language for display, or "—" when GitHub detected none
+    pub fn language_label(&self) -> &str {
+        self.language.as_deref().unwrap_or("—")
+    }
+
+    /// Whether a snooze is still in effect at `now`; expired ones are ignored
+    pub fn is_snoozed(&self, now: DateTime<Utc>) -> bool {
+        self.snoozed_until.is_some_and(|until| until > now)
+    }
+}
+
+/// GitHub's limit on repository name length
+const MAX_REPO_NAME_LEN: usize = 100;
+
+/// A validated `owner/name` repository id
+///
+/// API inputs are parsed into this before they reach `gh` or SQL, so a
+/// malformed id is rejected up front. Surrounding whitespace is trimmed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RepoId(String);
+
+impl RepoId {
+    /// Build an id from its two halves, e.g. from `/api/repos/:owner/:name`
+    pub fn from_parts(owner: &str, name: &str) -> crate::Result<Self> {
+        format!("{}/{}", owner, name).parse()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn owner(&self) -> &str {
+        self.0.split_once('/').map_or("", |(owner, _)| owner)
+    }
+
+    pub fn name(&self) -> &str {
+        self.0.split_once('/').map_or("", |(_, name)| name)
+    }
+}
+
+/// Check a GitHub user or organization name, the owner half of a `RepoId`
+pub(crate) fn validate_owner(owner: &str) -> crate::Result<()> {
+    if owner.is_empty() {
+        return Err(Error::InvalidOwner("Owner cannot be empty".to_string()));
+    }
+
+    // GitHub usernames: alphanumeric, hyphens, max 39 chars
+    if !owner.chars().all(|c| c.is_alphanumeric() || c == '-') {
+        return Err(Error::InvalidOwner(format!(
+            "Invalid owner name '{}': must be alphanumeric or hyphens",
+            owner
+        )));
+    }
+
+    if owner.len() > 39 {
+        return Err(Error::InvalidOwner(format!(
+            "Owner name too long: {} characters (max 39)",
+            owner.len()
+        )));
+    }
+
+    Ok(())
+}
+
+fn validate_repo_name(name: &str) -> crate::Result<()> {
+    if name.is_empty() || name == "." || name == ".." {
+        return Err(Error::InvalidRepoId(format!(
+            "Invalid repository name '{}'",
+            name
+        )));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(Error::InvalidRepoId(format!(
+            "Invalid repository name '{}': must be letters, digits, '-', '_' or '.'",
+            name
+        )));
+    }
+    if name.len() > MAX_REPO_NAME_LEN {
+        return Err(Error::InvalidRepoId(format!(
+            "Repository name too long: {} characters (max {})",
+            name.len(),
+            MAX_REPO_NAME_LEN
+        )));
+    }
+    Ok(())
+}
+
+impl FromStr for RepoId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let id = s.trim();
+        let Some((owner, name)) = id.split_once('/') else {
+            return Err(Error::InvalidRepoId(format!(
+                "'{}' is not of the form owner/name",
+                id
+            )));
+        };
+        validate_owner(owner).map_err(|e| Error::InvalidRepoId(format!("{}: {}", id, e)))?;
+        validate_repo_name(name)?;
+        Ok(RepoId(id.to_string()))
+    }
+}
+
+impl TryFrom<String> for RepoId {
+    type Error = Error;
+
+    fn try_from(s: String) -> crate::Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<RepoId> for String {
+    fn from(id: RepoId) -> Self {
+        id.0
+    }
+}
+
+impl Deref for RepoId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for RepoId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -73,6 +225,14 @@ pub struct PullRequest {
     pub number: u32,
     pub state: PRState,
     pub title: String,
+    pub head_ref_name: String,
+    /// Link to the PR on GitHub
+    #[serde(default)]
+    pub url: Option<String>,
+    /// GitHub's review decision; `None` when no review is required or the
+    /// PR predates tracking it
+    #[serde(default)]
+    pub review_decision: Option<ReviewDecision>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -107,6 +267,131 @@ impl FromStr for PRState {
     }
 }
 
+/// A pull request's review outcome, as GitHub's `reviewDecision`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ReviewDecision {
+    Approved,
+    ChangesRequested,
+    ReviewRequired,
+}
+
+impl ReviewDecision {
+    /// Parse gh's `reviewDecision` (`APPROVED`, ...); empty means none
+    pub fn from_github(value: &str) -> Option<Self> {
+        match value {
+            "APPROVED" => Some(ReviewDecision::Approved),
+            "CHANGES_REQUESTED" => Some(ReviewDecision::ChangesRequested),
+            "REVIEW_REQUIRED" => Some(ReviewDecision::ReviewRequired),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ReviewDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReviewDecision::Approved => write!(f, "Approved"),
+            ReviewDecision::ChangesRequested => write!(f, "ChangesRequested"),
+            ReviewDecision::ReviewRequired => write!(f, "ReviewRequired"),
+        }
+    }
+}
+
+impl FromStr for ReviewDecision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Approved" => Ok(ReviewDecision::Approved),
+            "ChangesRequested" => Ok(ReviewDecision::ChangesRequested),
+            "ReviewRequired" => Ok(ReviewDecision::ReviewRequired),
+            _ => Err(format!("Unknown review decision: {}", s)),
+        }
+    }
+}
+
+/// How a pull request is merged, as `gh pr merge --squash/--merge/--rebase`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMethod {
+    #[default]
+    Squash,
+    Merge,
+    Rebase,
+}
+
+impl MergeMethod {
+    /// The `gh pr merge` flag selecting this method
+    pub fn flag(&self) -> &'static str {
+        match self {
+            MergeMethod::Squash => "--squash",
+            MergeMethod::Merge => "--merge",
+            MergeMethod::Rebase => "--rebase",
+        }
+    }
+}
+
+/// Combined result of the check runs on a repository's default branch
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CiStatus {
+    Passing,
+    Failing,
+    Pending,
+    /// No check runs, e.g. a repository without CI
+    #[default]
+    None,
+}
+
+impl CiStatus {
+    /// Combine check runs given as (`status`, `conclusion`) pairs from
+    /// GitHub's check-runs API
+    ///
+    /// Any failed run fails the whole; otherwise any unfinished run makes it
+    /// pending. Neutral, skipped and cancelled runs don't count as failures.
+    pub fn from_check_runs<'a>(runs: impl IntoIterator<Item = (&'a str, Option<&'a str>)>) -> Self {
+        let mut combined = CiStatus::None;
+        for (status, conclusion) in runs {
+            let run = match (status, conclusion) {
+                ("completed", Some("failure" | "timed_out" | "action_required")) => {
+                    return CiStatus::Failing
+                }
+                ("completed", _) => CiStatus::Passing,
+                _ => CiStatus::Pending,
+            };
+            if combined != CiStatus::Pending {
+                combined = run;
+            }
+        }
+        combined
+    }
+}
+
+impl fmt::Display for CiStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CiStatus::Passing => write!(f, "passing"),
+            CiStatus::Failing => write!(f, "failing"),
+            CiStatus::Pending => write!(f, "pending"),
+            CiStatus::None => write!(f, "none"),
+        }
+    }
+}
+
+impl FromStr for CiStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "passing" => Ok(CiStatus::Passing),
+            "failing" => Ok(CiStatus::Failing),
+            "pending" => Ok(CiStatus::Pending),
+            "none" => Ok(CiStatus::None),
+            _ => Err(format!("Unknown CI status: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Group {
     pub id: i64,
@@ -115,6 +400,35 @@ pub struct Group {
     pub created_at: DateTime<Utc>,
 }
 
+/// A group's repository count and most urgent repository status, without
+/// the per-repo payload
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupSummary {
+    /// `None` for the synthetic "Ungrouped" section
+    pub id: Option<i64>,
+    pub name: String,
+    pub repo_count: usize,
+    /// diverged, needsSync, localChanges, stale or complete (see `analysis::repo_status`)
+    pub status: String,
+}
+
+/// Totals across every tracked repository, for the UI header
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardCounts {
+    pub total_repos: usize,
+    /// Red in the traffic light: needsSync or diverged
+    pub needs_sync: usize,
+    pub local_changes: usize,
+    pub stale: usize,
+    /// Includes snoozed repositories
+    pub complete: usize,
+    pub open_prs: usize,
+    /// Non-protected branches ahead of the default branch (see `analysis::is_unmerged`)
+    pub unmerged_branches: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Commit {
     pub id: i64,
@@ -127,6 +441,16 @@ pub struct Commit {
     pub committer_name: String,
     pub committer_email: String,
     pub committed_date: DateTime<Utc>,
+    /// GitHub verified the commit's GPG/SSH/S/MIME signature
+    #[serde(default)]
+    pub is_verified: bool,
+}
+
+impl Commit {
+    /// First line of the commit message
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -158,5 +482,458 @@ pub struct LocalRepoStatus {
     pub unpushed_commits: u32,
     pub behind_commits: u32,
     pub is_dirty: bool,
+    /// Mid-merge or mid-rebase with unresolved conflicts
+    #[serde(default)]
+    pub has_conflicts: bool,
+    /// Both unpushed and behind: the branch has diverged from its upstream
+    /// and needs a rebase or merge before it can be pushed
+    #[serde(default)]
+    pub is_diverged: bool,
+    /// The branch has no upstream and isn't on the remote; `unpushed_commits`
+    /// counts commits ahead of the remote's default branch
+    #[serde(default)]
+    pub is_unpublished: bool,
     pub last_checked: DateTime<Utc>,
 }
+
+/// Abbreviated SHA for display: the first 7 characters, or all of a shorter one
+pub fn short_sha(sha: &str) -> &str {
+    sha.char_indices()
+        .nth(7)
+        .map_or(sha, |(end, _)| &sha[..end])
+}
+
+/// Name of the synthetic section holding repositories outside every group
+pub const UNGROUPED_GROUP_NAME: &str = "Ungrouped";
+
+/// Check a name for a created or renamed group
+///
+/// Names must not be blank or collide (ignoring ASCII case) with the
+/// synthetic "Ungrouped" section the export and UI add.
+pub fn validate_group_name(name: &str) -> crate::Result<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(crate::Error::InvalidGroupName(
+            "Group name must not be empty".to_string(),
+        ));
+    }
+    if name.eq_ignore_ascii_case(UNGROUPED_GROUP_NAME) {
+        return Err(crate::Error::InvalidGroupName(format!(
+            "'{}' is reserved for repositories outside every group",
+            UNGROUPED_GROUP_NAME
+        )));
+    }
+    Ok(())
+}
+
+/// Normalize a user-supplied repository tag: trimmed and lowercased, so
+/// "OSS" and "oss" are the same tag
+///
+/// Tags must be non-empty single words (no whitespace).
+pub fn normalize_tag(tag: &str) -> crate::Result<String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err(crate::Error::InvalidTag(
+            "Tag must not be empty".to_string(),
+        ));
+    }
+    if tag.chars().any(char::is_whitespace) {
+        return Err(crate::Error::InvalidTag(format!(
+            "'{}' must not contain whitespace",
+            tag
+        )));
+    }
+    Ok(tag.to_lowercase())
+}
+
+/// Parse a snooze length such as `30m`, `12h`, `2d` or `1w`
+pub fn parse_snooze_duration(input: &str) -> crate::Result<chrono::Duration> {
+    let input = input.trim();
+    let invalid = || {
+        crate::Error::InvalidDuration(format!(
+            "'{}' is not a positive number followed by m, h, d or w (e.g. 2d)",
+            input
+        ))
+    };
+
+    let mut chars = input.chars();
+    let unit = chars.next_back().ok_or_else(invalid)?;
+    let count: i64 = chars.as_str().parse().map_err(|_| invalid())?;
+    if count <= 0 {
+        return Err(invalid());
+    }
+    let duration = match unit {
+        'm' => chrono::Duration::try_minutes(count),
+        'h' => chrono::Duration::try_hours(count),
+        'd' => chrono::Duration::try_days(count),
+        'w' => chrono::Duration::try_weeks(count),
+        _ => return Err(invalid()),
+    };
+    duration.ok_or_else(|| too_long(input))
+}
+
+/// When a snooze of `input` (see `parse_snooze_duration`) started now ends
+pub fn snooze_until(input: &str) -> crate::Result<DateTime<Utc>> {
+    let duration = parse_snooze_duration(input)?;
+    Utc::now()
+        .checked_add_signed(duration)
+        .ok_or_else(|| too_long(input))
+}
+
+fn too_long(input: &str) -> crate::Error {
+    crate::Error::InvalidDuration(format!("'{}' is too long", input.trim()))
+}
+
+/// Whether a branch is in the configured protected list
+///
+/// Entries ending in `*` match any branch name starting with the rest.
+pub fn is_protected_branch(name: &str, config: &Config) -> bool {
+    config
+        .github
+        .protected_branches
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == pattern,
+        })
+}
+
+/// Whether a repository id matches an exclude pattern: an exact id, or a
+/// glob where `*` matches any run of characters (e.g. `owner/test-*`)
+pub fn matches_repo_pattern(pattern: &str, repo_id: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // split always yields at least one part
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = repo_id.strip_prefix(first) else {
+        return false;
+    };
+
+    let mut parts = parts.peekable();
+    if parts.peek().is_none() {
+        return rest.is_empty();
+    }
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            // The last part must anchor at the end
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// A local clone or GitHub branch that is ahead of or behind its counterpart
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncNeed {
+    pub repo_id: String,
+    /// Branch name; for a local clone, its checked-out branch if known
+    pub branch: Option<String>,
+    /// `true` for a local clone, `false` for a branch on GitHub
+    pub is_local: bool,
+    pub ahead: u32,
+    pub behind: u32,
+    pub has_conflicts: bool,
+}
+
+/// A local clone whose repository isn't tracked (never scanned from GitHub)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalOnlyRepo {
+    /// Id derived from the clone's path, as a local scan would store it
+    pub repo_id: String,
+    pub local_path: String,
+    /// `owner/name` from the clone's `origin` remote when it points at
+    /// GitHub; this is what a GitHub scan would track it as
+    pub remote_repo_id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{BranchBuilder, PRBuilder, RepoBuilder, TestDatabase};
+
+    fn all_branch_statuses() -> Vec<BranchStatus> {
+        let statuses = vec![
+            BranchStatus::ReadyForPR,
+            BranchStatus::InReview,
+            BranchStatus::ReadyToMerge,
+            BranchStatus::NeedsUpdate,
+            BranchStatus::HasConflicts,
+        ];
+
+        // Fails to compile when a variant is added, forcing this list to be updated
+        for status in &statuses {
+            match status {
+                BranchStatus::ReadyForPR
+                | BranchStatus::InReview
+                | BranchStatus::ReadyToMerge
+                | BranchStatus::NeedsUpdate
+                | BranchStatus::HasConflicts => {}
+            }
+        }
+
+        statuses
+    }
+
+    #[test]
+    fn test_review_decision_round_trips() {
+        for decision in [
+            ReviewDecision::Approved,
+            ReviewDecision::ChangesRequested,
+            ReviewDecision::ReviewRequired,
+        ] {
+            assert_eq!(decision.to_string().parse(), Ok(decision));
+        }
+        assert_eq!(
+            ReviewDecision::from_github("CHANGES_REQUESTED"),
+            Some(ReviewDecision::ChangesRequested)
+        );
+        assert_eq!(ReviewDecision::from_github(""), None);
+    }
+
+    #[test]
+    fn test_validate_owner_valid() {
+        assert!(validate_owner("softwarewrighter").is_ok());
+        assert!(validate_owner("rust-lang").is_ok());
+        assert!(validate_owner("test123").is_ok());
+    }
+
+    #[test]
+    fn test_validate_owner_invalid() {
+        assert!(validate_owner("").is_err());
+        assert!(validate_owner("invalid@name").is_err());
+        assert!(validate_owner("invalid.name").is_err());
+        assert!(validate_owner(&"a".repeat(40)).is_err());
+    }
+
+    #[test]
+    fn test_repo_id_validation() {
+        let id: RepoId = " softwarewrighter/overall.rs ".parse().unwrap();
+        assert_eq!(id.as_str(), "softwarewrighter/overall.rs");
+        assert_eq!((id.owner(), id.name()), ("softwarewrighter", "overall.rs"));
+        assert_eq!(
+            RepoId::from_parts("rust-lang", "rust").unwrap(),
+            "rust-lang/rust".parse().unwrap()
+        );
+
+        for bad in [
+            "",
+            "overall",
+            "/overall",
+            "owner/",
+            "owner/..",
+            "owner/a/b",
+            "own er/repo",
+            "owner/repo;rm -rf",
+            "owner/--help x",
+        ] {
+            assert!(
+                bad.parse::<RepoId>().is_err(),
+                "{:?} should be rejected",
+                bad
+            );
+        }
+
+        let parsed: RepoId = serde_json::from_str("\"owner/repo\"").unwrap();
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), "\"owner/repo\"");
+        assert!(serde_json::from_str::<RepoId>("\"owner\"").is_err());
+    }
+
+    fn all_pr_states() -> Vec<PRState> {
+        let states = vec![PRState::Open, PRState::Closed, PRState::Merged];
+
+        // Fails to compile when a variant is added, forcing this list to be updated
+        for state in &states {
+            match state {
+                PRState::Open | PRState::Closed | PRState::Merged => {}
+            }
+        }
+
+        states
+    }
+
+    #[test]
+    fn test_matches_repo_pattern() {
+        assert!(matches_repo_pattern("owner/repo", "owner/repo"));
+        assert!(!matches_repo_pattern("owner/repo", "owner/repo-2"));
+        assert!(matches_repo_pattern("owner/test-*", "owner/test-demo"));
+        assert!(matches_repo_pattern("owner/test-*", "owner/test-"));
+        assert!(!matches_repo_pattern("owner/test-*", "other/test-demo"));
+        assert!(matches_repo_pattern(
+            "*/archive-*-old",
+            "any/archive-2019-old"
+        ));
+        assert!(!matches_repo_pattern("*/archive-*-old", "any/archive-2019"));
+        assert!(matches_repo_pattern("*", "owner/anything"));
+    }
+
+    #[test]
+    fn test_short_sha() {
+        assert_eq!(short_sha("a1b2c3d4e5f6a7b8"), "a1b2c3d");
+        assert_eq!(short_sha("a1b2c3d"), "a1b2c3d");
+        assert_eq!(short_sha("abc"), "abc");
+        assert_eq!(short_sha(""), "");
+    }
+
+    #[test]
+    fn test_parse_snooze_duration() {
+        assert_eq!(
+            parse_snooze_duration("2d").unwrap(),
+            chrono::Duration::days(2)
+        );
+        assert_eq!(
+            parse_snooze_duration(" 12h ").unwrap(),
+            chrono::Duration::hours(12)
+        );
+        assert_eq!(
+            parse_snooze_duration("30m").unwrap(),
+            chrono::Duration::minutes(30)
+        );
+        assert_eq!(
+            parse_snooze_duration("1w").unwrap(),
+            chrono::Duration::weeks(1)
+        );
+        for bad in ["", "d", "2", "0d", "-1d", "2x", "1.5d", "2 d", "2é"] {
+            assert!(parse_snooze_duration(bad).is_err(), "{:?}", bad);
+        }
+
+        // Counts too large for a duration are errors, not panics
+        for huge in ["99999999999w", "9223372036854775807m"] {
+            assert!(matches!(
+                parse_snooze_duration(huge),
+                Err(crate::Error::InvalidDuration(_))
+            ));
+        }
+        // Representable durations that overflow the end date are rejected too
+        assert!(matches!(
+            snooze_until("99999999w"),
+            Err(crate::Error::InvalidDuration(_))
+        ));
+        assert!(snooze_until("2d").unwrap() > Utc::now() + chrono::Duration::days(1));
+    }
+
+    #[test]
+    fn test_is_protected_branch() {
+        let mut config = Config::default();
+        assert!(is_protected_branch("main", &config));
+        assert!(is_protected_branch("develop", &config));
+        assert!(!is_protected_branch("feature/main", &config));
+
+        config.github.protected_branches = vec!["trunk".to_string(), "release/*".to_string()];
+        assert!(is_protected_branch("trunk", &config));
+        assert!(is_protected_branch("release/2.1", &config));
+        assert!(!is_protected_branch("main", &config));
+        assert!(!is_protected_branch("releases", &config));
+    }
+
+    #[test]
+    fn test_branch_status_display_from_str_round_trip() {
+        for status in all_branch_statuses() {
+            let parsed: BranchStatus = status.to_string().parse().unwrap();
+            assert_eq!(parsed, status);
+        }
+        assert!("Bogus".parse::<BranchStatus>().is_err());
+    }
+
+    #[test]
+    fn test_pr_state_display_from_str_round_trip() {
+        for state in all_pr_states() {
+            let parsed: PRState = state.to_string().parse().unwrap();
+            assert_eq!(parsed, state);
+        }
+        assert!("Bogus".parse::<PRState>().is_err());
+    }
+
+    #[test]
+    fn test_ci_status_from_check_runs() {
+        let passed = ("completed", Some("success"));
+        let failed = ("completed", Some("failure"));
+        let running = ("in_progress", None);
+
+        assert_eq!(CiStatus::from_check_runs([]), CiStatus::None);
+        assert_eq!(
+            CiStatus::from_check_runs([passed, ("completed", Some("skipped"))]),
+            CiStatus::Passing
+        );
+        assert_eq!(
+            CiStatus::from_check_runs([passed, running]),
+            CiStatus::Pending
+        );
+        assert_eq!(
+            CiStatus::from_check_runs([running, passed]),
+            CiStatus::Pending
+        );
+        assert_eq!(
+            CiStatus::from_check_runs([running, failed, passed]),
+            CiStatus::Failing
+        );
+
+        for status in [
+            CiStatus::Passing,
+            CiStatus::Failing,
+            CiStatus::Pending,
+            CiStatus::None,
+        ] {
+            assert_eq!(status.to_string().parse::<CiStatus>().unwrap(), status);
+            assert_eq!(serde_json::to_value(status).unwrap(), status.to_string());
+        }
+    }
+
+    #[test]
+    fn test_enums_serde_round_trip() {
+        for status in all_branch_statuses() {
+            let json = serde_json::to_string(&status).unwrap();
+            assert_eq!(serde_json::from_str::<BranchStatus>(&json).unwrap(), status);
+        }
+        for state in all_pr_states() {
+            let json = serde_json::to_string(&state).unwrap();
+            assert_eq!(serde_json::from_str::<PRState>(&json).unwrap(), state);
+        }
+    }
+
+    #[test]
+    fn test_branch_status_database_round_trip() -> crate::Result<()> {
+        let repo_id = "testowner/repo";
+        let mut test_db = TestDatabase::new()?.with_repo(RepoBuilder::new("repo"))?;
+        for status in all_branch_statuses() {
+            test_db = test_db.with_branch(
+                repo_id,
+                BranchBuilder::new(&status.to_string()).with_status(status.clone()),
+            )?;
+        }
+
+        let branches = test_db.db.get_branches_for_repo(repo_id)?;
+        assert_eq!(branches.len(), all_branch_statuses().len());
+        for branch in branches {
+            assert_eq!(branch.status.to_string(), branch.name);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pr_state_database_round_trip() -> crate::Result<()> {
+        let repo_id = "testowner/repo";
+        let mut test_db = TestDatabase::new()?.with_repo(RepoBuilder::new("repo"))?;
+        for (number, state) in all_pr_states().into_iter().enumerate() {
+            test_db = test_db.with_pull_request(
+                repo_id,
+                PRBuilder::new(number as u32 + 1, &state.to_string())
+                    .state(state)
+                    .build(repo_id),
+            )?;
+        }
+
+        let prs = test_db.db.get_pull_requests_for_repo(repo_id)?;
+        assert_eq!(prs.len(), all_pr_states().len());
+        for pr in prs {
+            assert_eq!(pr.state.to_string(), pr.title);
+        }
+
+        Ok(())
+    }
+}
@@ -1,35 +1,133 @@
 // Copyright (c) 2025 Michael A Wright
 // SPDX-License-Identifier: MIT
 
+use crate::config::{Config, SortConfig};
+use crate::export::RegenerateSummary;
+use crate::github::{GitHubClient, RealGitHubClient};
+use crate::models::{
+    is_protected_branch, snooze_until, LocalRepoStatus, MergeMethod, PRState, RepoId,
+};
 use crate::storage::Database;
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{rejection::JsonRejection, FromRequest, Path, Query, Request, State},
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     path::PathBuf,
     sync::{Arc, Mutex},
+    time::Duration,
 };
+use tokio::sync::mpsc;
 use tower_http::{
-    cors::{Any, CorsLayer},
+    cors::{AllowOrigin, Any, CorsLayer},
     services::ServeDir,
+    trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer},
 };
+use tracing::Level;
+
+/// How long the regeneration worker lets a burst of changes settle before
+/// rewriting repos.json, so at most one write happens per window
+const REGENERATE_DEBOUNCE: Duration = Duration::from_millis(200);
 
 #[derive(Clone)]
 pub struct AppState {
     db: Arc<Mutex<Database>>,
     static_dir: PathBuf,
+    github: Arc<dyn GitHubClient>,
+    /// Settings loaded once at startup and shared by all handlers
+    config: Arc<Config>,
+    /// Address the server listens on, reported by `/api/config`; `None` for
+    /// routers that are never bound (tests)
+    listen_addr: Option<SocketAddr>,
+    regenerate_tx: mpsc::UnboundedSender<()>,
+}
+
+impl AppState {
+    /// Create the shared state and spawn the repos.json regeneration worker
+    ///
+    /// Must be called from within a tokio runtime.
+    fn new(
+        db: Database,
+        static_dir: PathBuf,
+        github: Arc<dyn GitHubClient>,
+        config: Config,
+    ) -> Self {
+        let (regenerate_tx, regenerate_rx) = mpsc::unbounded_channel();
+        let state = AppState {
+            db: Arc::new(Mutex::new(db)),
+            static_dir,
+            github,
+            config: Arc::new(config),
+            listen_addr: None,
+            regenerate_tx,
+        };
+
+        tokio::spawn(regeneration_worker(state.clone(), regenerate_rx));
+
+        state
+    }
+
+    /// Queue a regeneration of repos.json without waiting for it to happen
+    fn request_regeneration(&self) {
+        if self.regenerate_tx.send(()).is_err() {
+            eprintln!("Warning: repos.json regeneration worker is not running");
+        }
+    }
+}
+
+/// Serializes repos.json writes, coalescing requests that arrive in bursts
+/// (e.g. multi-repo drag operations) into a single regeneration
+async fn regeneration_worker(state: AppState, mut rx: mpsc::UnboundedReceiver<()>) {
+    while rx.recv().await.is_some() {
+        tokio::time::sleep(REGENERATE_DEBOUNCE).await;
+
+        // Drop requests that arrived while waiting - one write covers them all
+        while rx.try_recv().is_ok() {}
+
+        let worker_state = state.clone();
+        match tokio::task::spawn_blocking(move || {
+            regenerate_repos_json(&worker_state).map_err(|e| e.to_string())
+        })
+        .await
+        {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => eprintln!("Warning: Failed to regenerate repos.json: {}", e),
+            Err(e) => eprintln!("Warning: repos.json regeneration task failed: {}", e),
+        }
+    }
+}
+
+/// A manual priority from 0.0 to 1.0, or `null` to unlock it
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetPriorityRequest {
+    priority: Option<f32>,
+}
+
+/// How long to snooze, e.g. `"2d"` (see `models::parse_snooze_duration`),
+/// or `null` to wake the repository now
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnoozeRequest {
+    duration: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct MoveRepoRequest {
-    repo_id: String,
+    repo_id: RepoId,
+    target_group_id: Option<i64>, // None means move to ungrouped
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MoveReposRequest {
+    repo_ids: Vec<RepoId>,
     target_group_id: Option<i64>, // None means move to ungrouped
 }
 
@@ -37,17 +135,31 @@ struct MoveRepoRequest {
 #[serde(rename_all = "camelCase")]
 struct CreateGroupRequest {
     group_name: String,
-    repo_ids: Vec<String>,
+    repo_ids: Vec<RepoId>,
     target_group_id: Option<i64>, // If None, create new group
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct CreatePRRequest {
-    repo_id: String,
+    repo_id: RepoId,
     branch_name: String,
     title: Option<String>,
     body: Option<String>,
+    /// Open the PR as a draft, without requesting review
+    #[serde(default)]
+    draft: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MergePRRequest {
+    repo_id: RepoId,
+    #[serde(default)]
+    method: MergeMethod,
+    /// Merge even when the PR targets a protected branch
+    #[serde(default)]
+    confirm_protected: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -56,6 +168,37 @@ struct ApiResponse {
     message: String,
 }
 
+/// `Json`, but a body that fails to parse - including a malformed `RepoId` -
+/// is a 400 with an `ApiResponse` rather than axum's plain-text 422
+struct ApiJson<T>(T);
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Json::<T>::from_request(req, state).await {
+            Ok(Json(value)) => Ok(ApiJson(value)),
+            Err(rejection) => Err(bad_request(rejection.body_text())),
+        }
+    }
+}
+
+fn bad_request(message: String) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ApiResponse {
+            success: false,
+            message,
+        }),
+    )
+        .into_response()
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct CreatePRResponse {
@@ -67,7 +210,7 @@ struct CreatePRResponse {
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct CreateAllPRsRequest {
-    repo_id: String,
+    repo_id: RepoId,
 }
 
 #[derive(Debug, Serialize)]
@@ -93,41 +236,156 @@ struct AddLocalRepoRootRequest {
     path: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct RemoveLocalRepoRootRequest {
-    id: i64,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ScanLocalReposRequest {
     // Empty - scans all enabled roots
 }
 
+/// PEM certificate chain and private key for serving HTTPS
+pub struct TlsFiles {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+/// Serve the API and static UI on loopback, over HTTPS when `tls` is given
+///
+/// `cors_origins` lists the origins other pages may call the API from; see
+/// [`cors_layer`] for the policy when it is empty.
 pub async fn serve(
     port: u16,
     db_path: PathBuf,
     static_dir: PathBuf,
     _debug: bool,
+    tls: Option<TlsFiles>,
+    cors_origins: Vec<String>,
 ) -> anyhow::Result<()> {
     let db = Database::open_or_create(&db_path)?;
-    let state = AppState {
-        db: Arc::new(Mutex::new(db)),
-        static_dir: static_dir.clone(),
-    };
+    let config = Config::load().unwrap_or_default();
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let mut state = AppState::new(db, static_dir, Arc::new(RealGitHubClient::new()), config);
+    state.listen_addr = Some(addr);
+
+    match ensure_repos_json(&state) {
+        Ok(Some(summary)) => println!(
+            "Created {} from the database ({} groups, {} repos)",
+            summary.path.display(),
+            summary.groups,
+            summary.repos
+        ),
+        Ok(None) => {}
+        Err(e) => eprintln!("Warning: Failed to create repos.json: {}", e),
+    }
+
+    let app = build_router(state)
+        .layer(cors_layer(addr.ip(), &cors_origins)?)
+        // One debug line per request (CORS preflights included) with method,
+        // path, status and latency; enable with RUST_LOG=tower_http=debug
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(DefaultMakeSpan::new().level(Level::DEBUG))
+                .on_response(DefaultOnResponse::new().level(Level::DEBUG)),
+        );
+
+    match tls {
+        Some(tls) => {
+            let rustls =
+                axum_server::tls_rustls::RustlsConfig::from_pem_file(&tls.cert, &tls.key).await?;
+            println!("Server listening on https://{}", addr);
+            axum_server::bind_rustls(addr, rustls)
+                .serve(app.into_make_service())
+                .await?;
+        }
+        None => {
+            println!("Server listening on http://{}", addr);
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            axum::serve(listener, app).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Cross-origin policy for the API
+///
+/// An explicit allowlist always wins. Without one, any origin may call a
+/// server bound to loopback (only local pages can reach it anyway), while a
+/// server on any other address sends no CORS headers, so browsers keep it
+/// same-origin.
+fn cors_layer(host: IpAddr, allowed_origins: &[String]) -> anyhow::Result<CorsLayer> {
+    if !allowed_origins.is_empty() {
+        let origins = allowed_origins
+            .iter()
+            .map(|origin| {
+                HeaderValue::from_str(origin.trim_end_matches('/'))
+                    .map_err(|_| anyhow::anyhow!("invalid CORS origin: {}", origin))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        return Ok(CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods(Any)
+            .allow_headers(Any));
+    }
+
+    if host.is_loopback() {
+        Ok(CorsLayer::new()
+            .allow_origin(Any)
+            .allow_methods(Any)
+            .allow_headers(Any))
+    } else {
+        Ok(CorsLayer::new())
+    }
+}
+
+fn build_router(state: AppState) -> Router {
+    let static_dir = state.static_dir.clone();
 
-    let app = Router::new()
+    Router::new()
         // API routes
+        .route("/api/ui-config", get(ui_config))
+        .route("/api/config", get(effective_config))
+        .route("/api/focus", get(focus))
         .route("/api/groups", get(list_groups))
+        .route("/api/groups/summary", get(group_summaries))
+        .route("/api/dashboard", get(dashboard))
+        .route("/api/repositories", get(list_repositories))
+        .route("/api/repositories/:owner/:name", get(get_repository))
         .route("/api/groups/add-repos", post(add_repos_to_group))
         .route("/api/groups/delete/:id", post(delete_group))
+        .route("/api/groups/:id/create-all-prs", post(create_group_prs))
         .route("/api/repos/move", post(move_repo))
+        .route("/api/repos/move-batch", post(move_repos))
+        .route("/api/repos/recent", get(recent_repos))
+        .route("/api/repos/by-tag/:tag", get(repos_by_tag))
         .route("/api/repos/export", post(export_repos))
+        .route("/api/export/regenerate", post(regenerate_export))
         .route("/api/repos/sync-all", post(sync_all_repos))
+        .route("/api/refresh-all", post(refresh_all))
         .route("/api/repos/sync", post(sync_single_repo))
+        // Repo ids contain a slash, so they are split into owner and name
+        .route(
+            "/api/repos/:owner/:name",
+            axum::routing::delete(delete_repo),
+        )
+        .route("/api/repos/:owner/:name/refresh", post(refresh_repo))
+        .route("/api/repos/:owner/:name/priority", post(set_repo_priority))
+        .route("/api/repos/:owner/:name/snooze", post(snooze_repo))
+        // Branch names may contain slashes and must be URL-encoded
+        .route(
+            "/api/repos/:owner/:name/branches/:branch/diff",
+            get(get_branch_diff),
+        )
+        .route(
+            "/api/repos/:owner/:name/branches/:branch/commits",
+            get(get_branch_commits),
+        )
+        .route(
+            "/api/repos/:owner/:name/commits/:sha/message",
+            get(get_commit_message),
+        )
         .route("/api/pr/create", post(create_pr))
         .route("/api/pr/create-all", post(create_all_prs))
+        .route("/api/pr/:number/merge", post(merge_pr))
         // Local repos routes
         .route("/api/local-repos/roots", get(list_local_repo_roots))
         .route("/api/local-repos/roots", post(add_local_repo_root))
@@ -137,23 +395,108 @@ pub async fn serve(
         )
         .route("/api/local-repos/scan", post(scan_local_repos))
         .route("/api/local-repos/status", get(get_local_repos_status))
+        .route("/api/local-only", get(local_only_repos))
         // Static files
         .nest_service("/", ServeDir::new(static_dir))
-        .layer(
-            CorsLayer::new()
-                .allow_origin(Any)
-                .allow_methods(Any)
-                .allow_headers(Any),
+        .with_state(state)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UiConfigResponse {
+    default_sort: SortConfig,
+    /// Repositories currently red in the traffic light, shown in the tab title
+    needs_attention: usize,
+}
+
+/// UI preferences from the `[ui]` config section, plus the live
+/// needs-attention count
+async fn ui_config(State(state): State<AppState>) -> Response {
+    let db = state.db.lock().unwrap();
+    match db.count_needing_attention(&state.config) {
+        Ok(needs_attention) => Json(UiConfigResponse {
+            default_sort: state.config.ui.default_sort,
+            needs_attention,
+        })
+        .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to count repositories needing attention: {}", e),
+            }),
         )
-        .with_state(state);
+            .into_response(),
+    }
+}
 
-    let addr = SocketAddr::from(([127, 0, 0, 1], port));
-    println!("Server listening on http://{}", addr);
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EffectiveConfigResponse {
+    host: Option<String>,
+    port: Option<u16>,
+    static_dir: String,
+    owners: Vec<String>,
+    /// Team slug per owner that restricts its scans
+    teams: std::collections::BTreeMap<String, String>,
+    protected_branches: Vec<String>,
+    default_sort: SortConfig,
+    auto_export: bool,
+    stale_threshold_days: i64,
+    abandoned_threshold_days: i64,
+    gh_path: String,
+    git_path: String,
+}
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+/// The settings this server is actually running with, for diagnosing
+/// differences between environments
+///
+/// Built field by field rather than serializing `Config` so nothing secret
+/// (e.g. a GitHub token from the environment) can leak through a new field.
+/// Tool paths include the `OVERALL_GH_PATH`/`OVERALL_GIT_PATH` overrides.
+async fn effective_config(State(state): State<AppState>) -> Json<EffectiveConfigResponse> {
+    let config = &state.config;
+    Json(EffectiveConfigResponse {
+        host: state.listen_addr.map(|addr| addr.ip().to_string()),
+        port: state.listen_addr.map(|addr| addr.port()),
+        static_dir: state.static_dir.to_string_lossy().to_string(),
+        owners: config
+            .github
+            .owners
+            .iter()
+            .map(|owner| owner.name.clone())
+            .collect(),
+        teams: config
+            .github
+            .owners
+            .iter()
+            .filter_map(|owner| Some((owner.name.clone(), owner.team.clone()?)))
+            .collect(),
+        protected_branches: config.github.protected_branches.clone(),
+        default_sort: config.ui.default_sort,
+        auto_export: config.ui.auto_export,
+        stale_threshold_days: config.analysis.stale_threshold_days,
+        abandoned_threshold_days: config.analysis.abandoned_threshold_days,
+        gh_path: config.tools.gh(),
+        git_path: config.tools.git(),
+    })
+}
 
-    Ok(())
+/// Concrete next actions across every repository, most urgent first, for
+/// minimal heads-up displays
+async fn focus(State(state): State<AppState>) -> Response {
+    let db = state.db.lock().unwrap();
+    match crate::analysis::focus_actions(&db, &state.config) {
+        Ok(actions) => Json(actions).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to compute focus actions: {}", e),
+            }),
+        )
+            .into_response(),
+    }
 }
 
 async fn list_groups(State(state): State<AppState>) -> Response {
@@ -171,149 +514,147 @@ async fn list_groups(State(state): State<AppState>) -> Response {
     }
 }
 
-/// Helper function to regenerate repos.json from current database state
-fn regenerate_repos_json(state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
-    use serde_json::json;
-
+async fn group_summaries(State(state): State<AppState>) -> Response {
     let db = state.db.lock().unwrap();
+    match db.get_group_summaries(&state.config) {
+        Ok(summaries) => Json(summaries).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to summarize groups: {}", e),
+            }),
+        )
+            .into_response(),
+    }
+}
 
-    // Build JSON structure with groups
-    let mut export_data = json!({
-        "groups": [],
-        "ungrouped": []
-    });
+/// Header totals: repositories per status, open PRs and unmerged branches
+async fn dashboard(State(state): State<AppState>) -> Response {
+    let db = state.db.lock().unwrap();
+    match db.get_dashboard_counts(&state.config) {
+        Ok(counts) => Json(counts).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to count repositories: {}", e),
+            }),
+        )
+            .into_response(),
+    }
+}
 
-    // Export all groups
-    let groups = db.get_all_groups()?;
-    for group in &groups {
-        let repos = db.get_repos_in_group(group.id).unwrap_or_default();
-        let mut group_repos = Vec::new();
+/// Every tracked repository as the canonical model, for API consumers that
+/// do not want the UI-shaped export
+async fn list_repositories(State(state): State<AppState>) -> Response {
+    let db = state.db.lock().unwrap();
+    match db.get_all_repositories() {
+        Ok(repos) => Json(repos).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to list repositories: {}", e),
+            }),
+        )
+            .into_response(),
+    }
+}
 
-        for repo in repos {
-            let branches = db.get_branches_for_repo(&repo.id).unwrap_or_default();
-            let prs = db.get_pull_requests_for_repo(&repo.id).unwrap_or_default();
+/// A single repository as the canonical model
+async fn get_repository(
+    State(state): State<AppState>,
+    Path((owner, name)): Path<(String, String)>,
+) -> Response {
+    let repo_id = match RepoId::from_parts(&owner, &name) {
+        Ok(repo_id) => repo_id,
+        Err(e) => return bad_request(e.to_string()),
+    };
+    let db = state.db.lock().unwrap();
+    match db.get_repository(&repo_id) {
+        Ok(Some(repo)) => Json(repo).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Repository {} not found", repo_id),
+            }),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to get repository {}: {}", repo_id, e),
+            }),
+        )
+            .into_response(),
+    }
+}
 
-            let unmerged_count = branches
-                .iter()
-                .filter(|b| b.ahead_by > 0 && b.behind_by == 0)
-                .count();
-            let open_pr_count = prs
-                .iter()
-                .filter(|pr| matches!(pr.state, crate::models::PRState::Open))
-                .count();
-
-            group_repos.push(json!({
-                "id": repo.id,
-                "owner": repo.owner,
-                "name": repo.name,
-                "language": repo.language.unwrap_or_else(|| "Unknown".to_string()),
-                "lastPush": repo.pushed_at.to_rfc3339(),
-                "branches": branches.iter().map(|b| {
-                    let commits = db.get_commits_for_branch(b.id).unwrap_or_default();
-                    json!({
-                        "name": b.name,
-                        "sha": b.sha,
-                        "aheadBy": b.ahead_by,
-                        "behindBy": b.behind_by,
-                        "status": b.status.to_string(),
-                        "lastCommitDate": b.last_commit_date.to_rfc3339(),
-                        "commits": commits.iter().map(|c| json!({
-                            "sha": c.sha,
-                            "message": c.message,
-                            "authorName": c.author_name,
-                            "authorEmail": c.author_email,
-                            "authoredDate": c.authored_date.to_rfc3339(),
-                            "committerName": c.committer_name,
-                            "committerEmail": c.committer_email,
-                            "committedDate": c.committed_date.to_rfc3339(),
-                        })).collect::<Vec<_>>(),
-                    })
-                }).collect::<Vec<_>>(),
-                "pullRequests": prs.iter().map(|pr| json!({
-                    "number": pr.number,
-                    "title": pr.title,
-                    "state": pr.state.to_string(),
-                    "createdAt": pr.created_at.to_rfc3339(),
-                    "updatedAt": pr.updated_at.to_rfc3339(),
-                })).collect::<Vec<_>>(),
-                "unmergedCount": unmerged_count,
-                "prCount": open_pr_count,
-            }));
-        }
-
-        export_data["groups"].as_array_mut().unwrap().push(json!({
-            "id": group.id,
-            "name": group.name,
-            "repos": group_repos
-        }));
-    }
-
-    // Export ungrouped repositories
-    let ungrouped = db.get_ungrouped_repositories().unwrap_or_default();
-    for repo in ungrouped {
-        let branches = db.get_branches_for_repo(&repo.id).unwrap_or_default();
-        let prs = db.get_pull_requests_for_repo(&repo.id).unwrap_or_default();
-
-        let unmerged_count = branches
-            .iter()
-            .filter(|b| b.ahead_by > 0 && b.behind_by == 0)
-            .count();
-        let open_pr_count = prs
-            .iter()
-            .filter(|pr| matches!(pr.state, crate::models::PRState::Open))
-            .count();
+/// Helper function to regenerate repos.json from current database state
+fn regenerate_repos_json(state: &AppState) -> crate::Result<RegenerateSummary> {
+    let db = state.db.lock().unwrap();
+    crate::export::regenerate_repos_json(&db, &state.static_dir.join("repos.json"), &state.config)
+}
 
-        export_data["ungrouped"]
-            .as_array_mut()
-            .unwrap()
-            .push(json!({
-                "id": repo.id,
-                "owner": repo.owner,
-                "name": repo.name,
-                "language": repo.language.unwrap_or_else(|| "Unknown".to_string()),
-                "lastPush": repo.pushed_at.to_rfc3339(),
-                "branches": branches.iter().map(|b| {
-                    let commits = db.get_commits_for_branch(b.id).unwrap_or_default();
-                    json!({
-                        "name": b.name,
-                        "sha": b.sha,
-                        "aheadBy": b.ahead_by,
-                        "behindBy": b.behind_by,
-                        "status": b.status.to_string(),
-                        "lastCommitDate": b.last_commit_date.to_rfc3339(),
-                        "commits": commits.iter().map(|c| json!({
-                            "sha": c.sha,
-                            "message": c.message,
-                            "authorName": c.author_name,
-                            "authorEmail": c.author_email,
-                            "authoredDate": c.authored_date.to_rfc3339(),
-                            "committerName": c.committer_name,
-                            "committerEmail": c.committer_email,
-                            "committedDate": c.committed_date.to_rfc3339(),
-                        })).collect::<Vec<_>>(),
-                    })
-                }).collect::<Vec<_>>(),
-                "pullRequests": prs.iter().map(|pr| json!({
-                    "number": pr.number,
-                    "title": pr.title,
-                    "state": pr.state.to_string(),
-                    "createdAt": pr.created_at.to_rfc3339(),
-                    "updatedAt": pr.updated_at.to_rfc3339(),
-                })).collect::<Vec<_>>(),
-                "unmergedCount": unmerged_count,
-                "prCount": open_pr_count,
-            }));
+/// Write repos.json from the database if it doesn't exist yet, so the UI
+/// loads (possibly empty) data on a fresh install instead of failing until
+/// the first `overall export`; returns `None` when the file was already there
+fn ensure_repos_json(state: &AppState) -> crate::Result<Option<RegenerateSummary>> {
+    if state.static_dir.join("repos.json").exists() {
+        return Ok(None);
     }
+    std::fs::create_dir_all(&state.static_dir)?;
+    regenerate_repos_json(state).map(Some)
+}
 
-    // Write to static/repos.json
-    let output_path = state.static_dir.join("repos.json");
-    let json_str = serde_json::to_string_pretty(&export_data)?;
-    std::fs::write(&output_path, json_str)?;
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RegenerateResponse {
+    success: bool,
+    message: String,
+    #[serde(flatten)]
+    summary: Option<RegenerateSummary>,
+}
 
-    Ok(())
+/// Force an immediate repos.json regeneration, e.g. after editing the
+/// database by hand, bypassing the debounced background worker
+async fn regenerate_export(State(state): State<AppState>) -> Response {
+    match tokio::task::spawn_blocking(move || {
+        regenerate_repos_json(&state).map_err(|e| e.to_string())
+    })
+    .await
+    .unwrap_or_else(|e| Err(e.to_string()))
+    {
+        Ok(summary) => Json(RegenerateResponse {
+            success: true,
+            message: format!(
+                "Wrote {} repositories in {} groups to {}",
+                summary.repos,
+                summary.groups,
+                summary.path.display()
+            ),
+            summary: Some(summary),
+        })
+        .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(RegenerateResponse {
+                success: false,
+                message: format!("Failed to regenerate repos.json: {}", e),
+                summary: None,
+            }),
+        )
+            .into_response(),
+    }
 }
 
-async fn move_repo(State(state): State<AppState>, Json(req): Json<MoveRepoRequest>) -> Response {
+async fn move_repo(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<MoveRepoRequest>,
+) -> Response {
     let result = {
         let db = state.db.lock().unwrap();
         if let Some(target_group_id) = req.target_group_id {
@@ -326,16 +667,7 @@ async fn move_repo(State(state): State<AppState>, Json(req): Json<MoveRepoReques
     match result {
         Ok(()) => {
             // Regenerate repos.json after successful move
-            if let Err(e) = regenerate_repos_json(&state) {
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponse {
-                        success: false,
-                        message: format!("Repository moved but failed to update repos.json: {}", e),
-                    }),
-                )
-                    .into_response();
-            }
+            state.request_regeneration();
 
             Json(ApiResponse {
                 success: true,
@@ -354,26 +686,61 @@ async fn move_repo(State(state): State<AppState>, Json(req): Json<MoveRepoReques
     }
 }
 
+/// Move several repositories at once, regenerating repos.json only once
+async fn move_repos(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<MoveReposRequest>,
+) -> Response {
+    {
+        let db = state.db.lock().unwrap();
+        for repo_id in &req.repo_ids {
+            let result = match req.target_group_id {
+                Some(target_group_id) => db.move_repo_to_group(repo_id, target_group_id),
+                None => db.remove_repo_from_all_groups(repo_id),
+            };
+            if let Err(e) = result {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse {
+                        success: false,
+                        message: format!("Failed to move repository {}: {}", repo_id.as_str(), e),
+                    }),
+                )
+                    .into_response();
+            }
+        }
+    }
+    state.request_regeneration();
+
+    Json(ApiResponse {
+        success: true,
+        message: format!("Moved {} repositories", req.repo_ids.len()),
+    })
+    .into_response()
+}
+
 async fn add_repos_to_group(
     State(state): State<AppState>,
-    Json(req): Json<CreateGroupRequest>,
+    ApiJson(req): ApiJson<CreateGroupRequest>,
 ) -> Response {
     let group_id = if let Some(existing_group_id) = req.target_group_id {
         // Use existing group
         existing_group_id
     } else {
-        // Create new group
+        // Reuse a group with the same name rather than creating a duplicate
         let db = state.db.lock().unwrap();
-        let max_order = db
-            .get_all_groups()
-            .unwrap_or_default()
-            .iter()
-            .map(|g| g.display_order)
-            .max()
-            .unwrap_or(-1);
-
-        match db.create_group(&req.group_name, max_order + 1) {
+        match db.get_or_create_group(&req.group_name) {
             Ok(id) => id,
+            Err(e @ crate::Error::InvalidGroupName(_)) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse {
+                        success: false,
+                        message: e.to_string(),
+                    }),
+                )
+                    .into_response();
+            }
             Err(e) => {
                 return (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -405,16 +772,7 @@ async fn add_repos_to_group(
     }
 
     // Regenerate repos.json
-    if let Err(e) = regenerate_repos_json(&state) {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse {
-                success: false,
-                message: format!("Repositories added but failed to update repos.json: {}", e),
-            }),
-        )
-            .into_response();
-    }
+    state.request_regeneration();
 
     Json(ApiResponse {
         success: true,
@@ -430,6 +788,19 @@ async fn delete_group(State(state): State<AppState>, Path(group_id): Path<i64>)
     // Delete the group - repos will automatically become ungrouped due to CASCADE
     {
         let db = state.db.lock().unwrap();
+
+        // Deleting loses the group's name and membership, so snapshot first
+        if let Err(e) = db.backup_snapshot(state.config.storage.backups_to_keep) {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    success: false,
+                    message: format!("Failed to back up database before deleting group: {}", e),
+                }),
+            )
+                .into_response();
+        }
+
         if let Err(e) = db.delete_group(group_id) {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -443,16 +814,7 @@ async fn delete_group(State(state): State<AppState>, Path(group_id): Path<i64>)
     }
 
     // Regenerate repos.json
-    if let Err(e) = regenerate_repos_json(&state) {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse {
-                success: false,
-                message: format!("Group deleted but failed to update repos.json: {}", e),
-            }),
-        )
-            .into_response();
-    }
+    state.request_regeneration();
 
     Json(ApiResponse {
         success: true,
@@ -462,155 +824,26 @@ async fn delete_group(State(state): State<AppState>, Path(group_id): Path<i64>)
 }
 
 async fn export_repos(State(state): State<AppState>) -> Response {
-    use serde_json::json;
-
-    let db = state.db.lock().unwrap();
-
-    // Build JSON structure with groups
-    let mut export_data = json!({
-        "groups": [],
-        "ungrouped": []
-    });
-
-    // Export all groups
-    let groups = match db.get_all_groups() {
-        Ok(g) => g,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse {
-                    success: false,
-                    message: format!("Failed to get groups: {}", e),
-                }),
-            )
-                .into_response()
+    let export_data = {
+        let db = state.db.lock().unwrap();
+        match crate::export::build_full_export(&db, &state.config) {
+            Ok(data) => data,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse {
+                        success: false,
+                        message: format!("Failed to get groups: {}", e),
+                    }),
+                )
+                    .into_response()
+            }
         }
     };
 
-    for group in &groups {
-        let repos = db.get_repos_in_group(group.id).unwrap_or_default();
-        let mut group_repos = Vec::new();
-
-        for repo in repos {
-            let branches = db.get_branches_for_repo(&repo.id).unwrap_or_default();
-            let prs = db.get_pull_requests_for_repo(&repo.id).unwrap_or_default();
-
-            let unmerged_count = branches
-                .iter()
-                .filter(|b| b.ahead_by > 0 && b.behind_by == 0)
-                .count();
-            let open_pr_count = prs
-                .iter()
-                .filter(|pr| matches!(pr.state, crate::models::PRState::Open))
-                .count();
-
-            group_repos.push(json!({
-                "id": repo.id,
-                "owner": repo.owner,
-                "name": repo.name,
-                "language": repo.language.unwrap_or_else(|| "Unknown".to_string()),
-                "lastPush": repo.pushed_at.to_rfc3339(),
-                "branches": branches.iter().map(|b| {
-                    let commits = db.get_commits_for_branch(b.id).unwrap_or_default();
-                    json!({
-                        "name": b.name,
-                        "sha": b.sha,
-                        "aheadBy": b.ahead_by,
-                        "behindBy": b.behind_by,
-                        "status": b.status.to_string(),
-                        "lastCommitDate": b.last_commit_date.to_rfc3339(),
-                        "commits": commits.iter().map(|c| json!({
-                            "sha": c.sha,
-                            "message": c.message,
-                            "authorName": c.author_name,
-                            "authorEmail": c.author_email,
-                            "authoredDate": c.authored_date.to_rfc3339(),
-                            "committerName": c.committer_name,
-                            "committerEmail": c.committer_email,
-                            "committedDate": c.committed_date.to_rfc3339(),
-                        })).collect::<Vec<_>>(),
-                    })
-                }).collect::<Vec<_>>(),
-                "pullRequests": prs.iter().map(|pr| json!({
-                    "number": pr.number,
-                    "title": pr.title,
-                    "state": pr.state.to_string(),
-                    "createdAt": pr.created_at.to_rfc3339(),
-                    "updatedAt": pr.updated_at.to_rfc3339(),
-                })).collect::<Vec<_>>(),
-                "unmergedCount": unmerged_count,
-                "prCount": open_pr_count,
-            }));
-        }
-
-        export_data["groups"].as_array_mut().unwrap().push(json!({
-            "id": group.id,
-            "name": group.name,
-            "repos": group_repos
-        }));
-    }
-
-    // Export ungrouped repositories
-    let ungrouped = db.get_ungrouped_repositories().unwrap_or_default();
-    for repo in ungrouped {
-        let branches = db.get_branches_for_repo(&repo.id).unwrap_or_default();
-        let prs = db.get_pull_requests_for_repo(&repo.id).unwrap_or_default();
-
-        let unmerged_count = branches
-            .iter()
-            .filter(|b| b.ahead_by > 0 && b.behind_by == 0)
-            .count();
-        let open_pr_count = prs
-            .iter()
-            .filter(|pr| matches!(pr.state, crate::models::PRState::Open))
-            .count();
-
-        export_data["ungrouped"]
-            .as_array_mut()
-            .unwrap()
-            .push(json!({
-                "id": repo.id,
-                "owner": repo.owner,
-                "name": repo.name,
-                "language": repo.language.unwrap_or_else(|| "Unknown".to_string()),
-                "lastPush": repo.pushed_at.to_rfc3339(),
-                "branches": branches.iter().map(|b| {
-                    let commits = db.get_commits_for_branch(b.id).unwrap_or_default();
-                    json!({
-                        "name": b.name,
-                        "sha": b.sha,
-                        "aheadBy": b.ahead_by,
-                        "behindBy": b.behind_by,
-                        "status": b.status.to_string(),
-                        "lastCommitDate": b.last_commit_date.to_rfc3339(),
-                        "commits": commits.iter().map(|c| json!({
-                            "sha": c.sha,
-                            "message": c.message,
-                            "authorName": c.author_name,
-                            "authorEmail": c.author_email,
-                            "authoredDate": c.authored_date.to_rfc3339(),
-                            "committerName": c.committer_name,
-                            "committerEmail": c.committer_email,
-                            "committedDate": c.committed_date.to_rfc3339(),
-                        })).collect::<Vec<_>>(),
-                    })
-                }).collect::<Vec<_>>(),
-                "pullRequests": prs.iter().map(|pr| json!({
-                    "number": pr.number,
-                    "title": pr.title,
-                    "state": pr.state.to_string(),
-                    "createdAt": pr.created_at.to_rfc3339(),
-                    "updatedAt": pr.updated_at.to_rfc3339(),
-                })).collect::<Vec<_>>(),
-                "unmergedCount": unmerged_count,
-                "prCount": open_pr_count,
-            }));
-    }
-
     // Write to static/repos.json
     let output_path = state.static_dir.join("repos.json");
-    let json_str = serde_json::to_string_pretty(&export_data).unwrap();
-    if let Err(e) = std::fs::write(&output_path, json_str) {
+    if let Err(e) = crate::export::write_export(&output_path, &export_data) {
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ApiResponse {
@@ -628,22 +861,49 @@ async fn export_repos(State(state): State<AppState>) -> Response {
     .into_response()
 }
 
-async fn create_pr(State(state): State<AppState>, Json(req): Json<CreatePRRequest>) -> Response {
-    // Call github::create_pull_request
+/// Open a pull request for a branch; protected branches (the ones PRs are
+/// merged into) are refused with 400
+async fn create_pr(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<CreatePRRequest>,
+) -> Response {
+    if is_protected_branch(&req.branch_name, &state.config) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(CreatePRResponse {
+                success: false,
+                pr_url: None,
+                message: format!(
+                    "Branch '{}' is protected; pull requests are opened from feature branches",
+                    req.branch_name
+                ),
+            }),
+        )
+            .into_response();
+    }
+
+    // Create the PR through the injected GitHub client
     let title_ref = req.title.as_deref();
     let body_ref = req.body.as_deref();
 
-    match crate::github::create_pull_request(&req.repo_id, &req.branch_name, title_ref, body_ref) {
+    match state.github.create_pull_request(
+        &req.repo_id,
+        &req.branch_name,
+        title_ref,
+        body_ref,
+        req.draft,
+    ) {
         Ok(pr_url) => {
-            // Sync PRs from GitHub to update the database
-            if let Err(e) = crate::github::fetch_pull_requests(&req.repo_id) {
-                eprintln!("Warning: Failed to sync PRs after creation: {}", e);
-            } else {
-                // Update the repos.json file
-                if let Err(e) = regenerate_repos_json(&state) {
-                    eprintln!("Warning: Failed to regenerate repos.json: {}", e);
+            // Record the PR right away so the UI reflects it without a rescan
+            {
+                let db = state.db.lock().unwrap();
+                if let Err(e) =
+                    record_created_pr(&db, &req.repo_id, &req.branch_name, title_ref, &pr_url)
+                {
+                    eprintln!("Warning: Failed to record created PR: {}", e);
                 }
             }
+            state.request_regeneration();
 
             Json(CreatePRResponse {
                 success: true,
@@ -664,35 +924,113 @@ async fn create_pr(State(state): State<AppState>, Json(req): Json<CreatePRReques
     }
 }
 
-async fn create_all_prs(
+/// Merge a pull request, then record it as merged and reclassify the
+/// repo's branches so the UI reflects it without a rescan
+///
+/// PRs targeting a protected branch are refused with 409 until the request
+/// repeats with `confirmProtected`.
+async fn merge_pr(
     State(state): State<AppState>,
-    Json(req): Json<CreateAllPRsRequest>,
+    Path(number): Path<u32>,
+    ApiJson(req): ApiJson<MergePRRequest>,
 ) -> Response {
-    let db = state.db.lock().unwrap();
-
-    // Get all branches for this repo
-    let branches = match db.get_branches_for_repo(&req.repo_id) {
-        Ok(branches) => branches,
+    let base = match state.github.fetch_pull_request_base(&req.repo_id, number) {
+        Ok(base) => base,
         Err(e) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(CreateAllPRsResponse {
+                Json(ApiResponse {
                     success: false,
-                    results: vec![],
-                    message: format!("Failed to get branches: {}", e),
+                    message: format!("Failed to look up PR #{}: {}", number, e),
                 }),
             )
                 .into_response()
         }
     };
 
-    // Release the lock before making external calls
-    drop(db);
+    if is_protected_branch(&base, &state.config) && !req.confirm_protected {
+        return (
+            StatusCode::CONFLICT,
+            Json(ApiResponse {
+                success: false,
+                message: format!(
+                    "PR #{} targets protected branch '{}'. Merge anyway?",
+                    number, base
+                ),
+            }),
+        )
+            .into_response();
+    }
+
+    if let Err(e) = state
+        .github
+        .merge_pull_request(&req.repo_id, number, req.method)
+    {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to merge PR #{}: {}", number, e),
+            }),
+        )
+            .into_response();
+    }
+
+    {
+        let db = state.db.lock().unwrap();
+        if let Err(e) = db.update_pull_request_state(&req.repo_id, number, PRState::Merged) {
+            eprintln!("Warning: Failed to record merged PR #{}: {}", number, e);
+        }
+        // The head branch's commits are on the base now, so it stops counting
+        // as unmerged (and as a create-all-prs candidate)
+        let head = db
+            .get_pull_requests_for_repo(&req.repo_id)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|pr| pr.number == number)
+            .map(|pr| pr.head_ref_name);
+        if let Some(head) = head {
+            if let Err(e) = db.mark_branch_merged(&req.repo_id, &head) {
+                eprintln!("Warning: Failed to update branch {}: {}", head, e);
+            }
+        }
+        if let Err(e) = crate::analysis::reclassify_repo(&db, &req.repo_id, &state.config) {
+            eprintln!(
+                "Warning: Failed to reclassify branches for {}: {}",
+                req.repo_id.as_str(),
+                e
+            );
+        }
+    }
+    state.request_regeneration();
+
+    Json(ApiResponse {
+        success: true,
+        message: format!("PR #{} merged", number),
+    })
+    .into_response()
+}
 
-    // Filter branches with unmerged work (ahead > 0)
-    let branches_to_pr: Vec<_> = branches.into_iter().filter(|b| b.ahead_by > 0).collect();
+async fn create_all_prs(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<CreateAllPRsRequest>,
+) -> Response {
+    let results = match create_prs_for_unmerged_branches(&state, &req.repo_id) {
+        Ok(results) => results,
+        Err(message) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(CreateAllPRsResponse {
+                    success: false,
+                    results: vec![],
+                    message,
+                }),
+            )
+                .into_response()
+        }
+    };
 
-    if branches_to_pr.is_empty() {
+    if results.is_empty() {
         return Json(CreateAllPRsResponse {
             success: true,
             results: vec![],
@@ -701,10 +1039,48 @@ async fn create_all_prs(
         .into_response();
     }
 
+    let success_count = results.iter().filter(|r| r.success).count();
+    let total_count = results.len();
+
+    Json(CreateAllPRsResponse {
+        success: true,
+        results,
+        message: format!(
+            "Created {} of {} PRs successfully",
+            success_count, total_count
+        ),
+    })
+    .into_response()
+}
+
+/// Open a PR for every unmerged, non-protected branch of a repository
+///
+/// Returns one result per attempted branch (empty when there was nothing to
+/// do). Queues a repos.json regeneration if any PR was created.
+fn create_prs_for_unmerged_branches(
+    state: &AppState,
+    repo_id: &str,
+) -> Result<Vec<PRResult>, String> {
+    // Release the lock before making external calls
+    let branches = {
+        let db = state.db.lock().unwrap();
+        db.get_branches_for_repo(repo_id)
+            .map_err(|e| format!("Failed to get branches: {}", e))?
+    };
+
+    // Filter branches with unmerged work, skipping protected branches
+    let branches_to_pr: Vec<_> = branches
+        .into_iter()
+        .filter(|b| crate::analysis::is_unmerged(b, &state.config))
+        .collect();
+
     // Create PRs for each branch
     let mut results = Vec::new();
     for branch in branches_to_pr {
-        match crate::github::create_pull_request(&req.repo_id, &branch.name, None, None) {
+        match state
+            .github
+            .create_pull_request(repo_id, &branch.name, None, None, false)
+        {
             Ok(pr_url) => {
                 results.push(PRResult {
                     branch_name: branch.name,
@@ -724,28 +1100,156 @@ async fn create_all_prs(
         }
     }
 
-    let success_count = results.iter().filter(|r| r.success).count();
-    let total_count = results.len();
+    // Record created PRs right away so the UI reflects them without a rescan
+    if results.iter().any(|r| r.success) {
+        let db = state.db.lock().unwrap();
+        for result in &results {
+            if let Some(ref pr_url) = result.pr_url {
+                if let Err(e) = record_created_pr(&db, repo_id, &result.branch_name, None, pr_url) {
+                    eprintln!("Warning: Failed to record created PR: {}", e);
+                }
+            }
+        }
+        drop(db);
 
-    // Sync PRs from GitHub to update the database
-    if success_count > 0 {
-        if let Err(e) = crate::github::fetch_pull_requests(&req.repo_id) {
-            eprintln!("Warning: Failed to sync PRs after batch creation: {}", e);
-        } else {
-            // Update the repos.json file
-            if let Err(e) = regenerate_repos_json(&state) {
-                eprintln!("Warning: Failed to regenerate repos.json: {}", e);
+        state.request_regeneration();
+    }
+
+    Ok(results)
+}
+
+/// Store a just-created PR as open, unless it is already stored
+///
+/// `gh pr create` only reports the PR URL, so the number is taken from it and
+/// the title falls back to the one gh was given by default.
+fn record_created_pr(
+    db: &Database,
+    repo_id: &str,
+    branch_name: &str,
+    title: Option<&str>,
+    pr_url: &str,
+) -> crate::Result<()> {
+    use crate::github::commands::{default_pr_title, pr_number_from_url};
+    use crate::models::{PRState, PullRequest};
+
+    let number = pr_number_from_url(pr_url)
+        .ok_or_else(|| crate::Error::GitHubCLI(format!("Unrecognized PR URL: {}", pr_url)))?;
+
+    // gh returns the existing PR's URL when one is already open
+    if db
+        .get_pull_requests_for_repo(repo_id)?
+        .iter()
+        .any(|pr| pr.number == number)
+    {
+        return Ok(());
+    }
+
+    let branch_id = db
+        .get_branches_for_repo(repo_id)?
+        .into_iter()
+        .find(|b| b.name == branch_name)
+        .map(|b| b.id);
+
+    let now = chrono::Utc::now();
+    db.save_pull_request(&PullRequest {
+        id: 0,
+        repo_id: repo_id.to_string(),
+        branch_id,
+        number,
+        state: PRState::Open,
+        title: title.map_or_else(|| default_pr_title(branch_name), str::to_string),
+        head_ref_name: branch_name.to_string(),
+        url: Some(pr_url.to_string()),
+        review_decision: None,
+        created_at: now,
+        updated_at: now,
+    })?;
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateGroupPRsResponse {
+    success: bool,
+    message: String,
+    created: usize,
+    failed: usize,
+    /// Per-branch results for each repository in the group, keyed by repo id
+    results: std::collections::BTreeMap<String, Vec<PRResult>>,
+}
+
+async fn create_group_prs(State(state): State<AppState>, Path(group_id): Path<i64>) -> Response {
+    let repos = {
+        let db = state.db.lock().unwrap();
+        let group_exists = db
+            .get_all_groups()
+            .map(|groups| groups.iter().any(|g| g.id == group_id))
+            .unwrap_or(false);
+        if !group_exists {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse {
+                    success: false,
+                    message: format!("Group {} not found", group_id),
+                }),
+            )
+                .into_response();
+        }
+
+        match db.get_repos_in_group(group_id) {
+            Ok(repos) => repos,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse {
+                        success: false,
+                        message: format!("Failed to get repositories: {}", e),
+                    }),
+                )
+                    .into_response()
             }
         }
+    };
+
+    let mut results = std::collections::BTreeMap::new();
+    for repo in &repos {
+        let repo_results = match create_prs_for_unmerged_branches(&state, &repo.id) {
+            Ok(repo_results) => repo_results,
+            Err(message) => vec![PRResult {
+                branch_name: String::new(),
+                success: false,
+                pr_url: None,
+                error: Some(message),
+            }],
+        };
+        if !repo_results.is_empty() {
+            results.insert(repo.id.clone(), repo_results);
+        }
     }
 
-    Json(CreateAllPRsResponse {
+    let (created, failed) = results
+        .values()
+        .flatten()
+        .fold((0, 0), |(created, failed), r| {
+            if r.success {
+                (created + 1, failed)
+            } else {
+                (created, failed + 1)
+            }
+        });
+
+    Json(CreateGroupPRsResponse {
         success: true,
-        results,
         message: format!(
-            "Created {} of {} PRs successfully",
-            success_count, total_count
+            "Created {} PRs across {} repositories ({} failed)",
+            created,
+            results.len(),
+            failed
         ),
+        created,
+        failed,
+        results,
     })
     .into_response()
 }
@@ -841,8 +1345,6 @@ async fn scan_local_repos(
     State(state): State<AppState>,
     Json(_req): Json<ScanLocalReposRequest>,
 ) -> Response {
-    use std::path::Path;
-
     let db = state.db.lock().unwrap();
 
     // Get all enabled repo roots
@@ -862,8 +1364,36 @@ async fn scan_local_repos(
 
     drop(db); // Release lock before doing git operations
 
+    let (total_repos, scan_results) = scan_local_roots(&state.db, &roots);
+
+    // Exported repo statuses depend on local status
+    state.request_regeneration();
+
+    Json(ApiResponse {
+        success: true,
+        message: format!(
+            "Scanned {} repositories. Results: {}",
+            total_repos,
+            scan_results.join("; ")
+        ),
+    })
+    .into_response()
+}
+
+/// Check every git repository under the enabled roots and store its status
+///
+/// Returns the number of repositories found and a line per repository
+/// describing the outcome. The database lock is only held while saving.
+pub fn scan_local_roots(
+    db: &Mutex<Database>,
+    roots: &[crate::models::LocalRepoRoot],
+) -> (usize, Vec<String>) {
+    use std::path::Path;
+
     let mut scan_results = Vec::new();
     let mut total_repos = 0;
+    // Overlapping roots can reach the same repo; scan each canonical path once
+    let mut seen_paths = std::collections::HashSet::new();
 
     for root in roots.iter().filter(|r| r.enabled) {
         let root_path = Path::new(&root.path);
@@ -872,14 +1402,44 @@ async fn scan_local_repos(
         match crate::local_git::scan_for_git_repos(root_path) {
             Ok(repo_paths) => {
                 for repo_path in repo_paths {
+                    if !seen_paths.insert(repo_path.clone()) {
+                        continue;
+                    }
                     total_repos += 1;
 
+                    if crate::local_git::is_lossy_path(&repo_path) {
+                        let warning = format!(
+                            "Warning: {} is not valid UTF-8; its repo id may not match GitHub",
+                            repo_path.display()
+                        );
+                        eprintln!("{}", warning);
+                        scan_results.push(warning);
+                    }
+
                     // Get repo status
                     match crate::local_git::get_repo_status(&repo_path) {
-                        Ok(status) => {
+                        Ok(mut status) => {
                             // Save to database
-                            let db = state.db.lock().unwrap();
-                            if let Err(e) = db.save_local_repo_status(&status) {
+                            let db = db.lock().unwrap();
+                            // A clone whose directory differs from its GitHub
+                            // name belongs to the repository origin points at
+                            if matches!(db.get_repository(&status.repo_id), Ok(None)) {
+                                if let Some(origin_id) =
+                                    crate::local_git::origin_repo_id(&repo_path).ok().flatten()
+                                {
+                                    if matches!(db.get_repository(&origin_id), Ok(Some(_))) {
+                                        status.repo_id = origin_id;
+                                    }
+                                }
+                            }
+                            // Statuses belong to tracked repositories; untracked
+                            // clones are listed by `analysis::find_local_only_repos`
+                            if matches!(db.get_repository(&status.repo_id), Ok(None)) {
+                                scan_results.push(format!(
+                                    "Not tracked from GitHub: {} (see overall local-only)",
+                                    status.repo_id
+                                ));
+                            } else if let Err(e) = db.save_local_repo_status(&status) {
                                 scan_results.push(format!(
                                     "Error saving status for {}: {}",
                                     repo_path.display(),
@@ -905,15 +1465,23 @@ async fn scan_local_repos(
         }
     }
 
-    Json(ApiResponse {
-        success: true,
-        message: format!(
-            "Scanned {} repositories. Results: {}",
-            total_repos,
-            scan_results.join("; ")
-        ),
-    })
-    .into_response()
+    (total_repos, scan_results)
+}
+
+/// Clones under the local roots that aren't tracked from GitHub
+async fn local_only_repos(State(state): State<AppState>) -> Response {
+    let db = state.db.lock().unwrap();
+    match crate::analysis::find_local_only_repos(&db) {
+        Ok(repos) => Json(repos).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to find local-only repositories: {}", e),
+            }),
+        )
+            .into_response(),
+    }
 }
 
 async fn get_local_repos_status(State(state): State<AppState>) -> Response {
@@ -1011,7 +1579,9 @@ async fn sync_all_repos(
             failed_repos.push(format!("{} (branches)", repo.id));
         } else {
             // Sync PRs
-            if let Err(e) = crate::github::fetch_pull_requests(&repo.id) {
+            if let Err(e) =
+                crate::github::fetch_pull_requests(&repo.id, state.config.github.pr_limit)
+            {
                 eprintln!("Warning: Failed to sync PRs for {}: {}", repo.id, e);
                 failed_repos.push(format!("{} (PRs)", repo.id));
             } else {
@@ -1030,9 +1600,7 @@ async fn sync_all_repos(
     }
 
     // Regenerate repos.json
-    if let Err(e) = regenerate_repos_json(&state) {
-        eprintln!("Warning: Failed to regenerate repos.json: {}", e);
-    }
+    state.request_regeneration();
 
     let message = if failed_repos.is_empty() {
         if total_repos == 0 {
@@ -1056,45 +1624,210 @@ async fn sync_all_repos(
     .into_response()
 }
 
-#[derive(Deserialize)]
-struct SyncRepoRequest {
-    repo_id: String,
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RefreshAllResponse {
+    success: bool,
+    message: String,
+    repos_synced: usize,
+    local_repos_scanned: usize,
+    errors: Vec<String>,
 }
 
-async fn sync_single_repo(
-    State(state): State<AppState>,
-    Json(req): Json<SyncRepoRequest>,
-) -> Response {
-    let repo_id = &req.repo_id;
-    let db = state.db.lock().unwrap();
+/// Refresh everything the dashboard shows in one pass: re-list each tracked
+/// owner's repositories, sync every repository's branches and PRs from
+/// GitHub, rescan local clones, then regenerate repos.json once
+async fn refresh_all(State(state): State<AppState>) -> Response {
+    let mut errors = Vec::new();
 
-    // Fetch branches from GitHub
-    let branches = match crate::github::fetch_branches(repo_id) {
-        Ok(b) => b,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse {
-                    success: false,
-                    message: format!("Failed to fetch branches for {}: {}", repo_id, e),
-                }),
-            )
-                .into_response();
+    let tracked = {
+        let db = state.db.lock().unwrap();
+        match db.get_all_repositories() {
+            Ok(repos) => repos,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse {
+                        success: false,
+                        message: format!("Failed to get repositories: {}", e),
+                    }),
+                )
+                    .into_response()
+            }
         }
     };
 
-    // Clear old branches BEFORE saving new ones
-    if let Err(e) = db.clear_branches_for_repo(repo_id) {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse {
-                success: false,
-                message: format!("Failed to clear old branches for {}: {}", repo_id, e),
-            }),
-        )
-            .into_response();
+    // Pick up repositories pushed or created since the last scan
+    let mut owners: Vec<&str> = tracked.iter().map(|r| r.owner.as_str()).collect();
+    owners.sort_unstable();
+    owners.dedup();
+    for owner in owners {
+        match state
+            .github
+            .list_repos(owner, state.config.github.repo_limit_for(owner))
+        {
+            Ok(listed) => {
+                let db = state.db.lock().unwrap();
+                for mut repo in listed {
+                    if db
+                        .is_excluded(&repo.id, &state.config.github.repo_exclude)
+                        .unwrap_or(false)
+                    {
+                        continue;
+                    }
+                    // Keep the locally computed priority
+                    if let Some(existing) = tracked.iter().find(|r| r.id == repo.id) {
+                        repo.priority = existing.priority;
+                    }
+                    if let Err(e) = db.save_repository(&repo) {
+                        errors.push(format!("Failed to save {}: {}", repo.id, e));
+                    }
+                }
+            }
+            Err(e) => errors.push(format!("Failed to list repositories for {}: {}", owner, e)),
+        }
+    }
+
+    let repo_ids = {
+        let db = state.db.lock().unwrap();
+        db.get_all_repositories()
+            .map(|repos| repos.into_iter().map(|r| r.id).collect::<Vec<_>>())
+            .unwrap_or_default()
+    };
+
+    let mut repos_synced = 0;
+    for repo_id in &repo_ids {
+        let db = state.db.lock().unwrap();
+        match sync_repo_from_github(
+            &db,
+            state.github.as_ref(),
+            repo_id,
+            state.config.github.pr_limit,
+        ) {
+            Ok(()) => repos_synced += 1,
+            Err(message) => errors.push(message),
+        }
+    }
+
+    let roots = {
+        let db = state.db.lock().unwrap();
+        db.get_all_local_repo_roots().unwrap_or_default()
+    };
+    let (local_repos_scanned, local_results) = scan_local_roots(&state.db, &roots);
+    errors.extend(
+        local_results
+            .into_iter()
+            .filter(|line| line.starts_with("Error")),
+    );
+
+    {
+        let db = state.db.lock().unwrap();
+        if let Err(e) = db.set_config("last_refresh_time", &chrono::Utc::now().to_rfc3339()) {
+            eprintln!("Warning: Failed to update last_refresh_time: {}", e);
+        }
+    }
+
+    state.request_regeneration();
+
+    Json(RefreshAllResponse {
+        success: true,
+        message: format!(
+            "Synced {}/{} repositories from GitHub and scanned {} local repositories",
+            repos_synced,
+            repo_ids.len(),
+            local_repos_scanned
+        ),
+        repos_synced,
+        local_repos_scanned,
+        errors,
+    })
+    .into_response()
+}
+
+#[derive(Deserialize)]
+struct SyncRepoRequest {
+    repo_id: RepoId,
+}
+
+async fn sync_single_repo(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<SyncRepoRequest>,
+) -> Response {
+    let repo_id = &req.repo_id;
+
+    {
+        let db = state.db.lock().unwrap();
+        if let Err(message) = sync_repo_from_github(
+            &db,
+            state.github.as_ref(),
+            repo_id,
+            state.config.github.pr_limit,
+        ) {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    success: false,
+                    message,
+                }),
+            )
+                .into_response();
+        }
+    }
+
+    // Regenerate repos.json
+    state.request_regeneration();
+
+    Json(ApiResponse {
+        success: true,
+        message: format!("Successfully synced repository {}", repo_id),
+    })
+    .into_response()
+}
+
+/// Refresh branches and PRs for a single repository from GitHub,
+/// replacing what is stored and reclassifying branch statuses
+fn sync_repo_from_github(
+    db: &Database,
+    github: &dyn GitHubClient,
+    repo_id: &str,
+    pr_limit: usize,
+) -> Result<(), String> {
+    // Resolve the default branch once for comparison and classification
+    let default_branch = github
+        .get_default_branch(repo_id)
+        .map_err(|e| format!("Failed to get default branch for {}: {}", repo_id, e))?;
+
+    match github.fetch_ci_status(repo_id, &default_branch) {
+        Ok(status) => {
+            if let Err(e) = db.save_ci_status(repo_id, status) {
+                eprintln!("Warning: Failed to save CI status for {}: {}", repo_id, e);
+            }
+        }
+        Err(e) => eprintln!("Warning: Failed to fetch CI status for {}: {}", repo_id, e),
     }
 
+    // Fetch branches from GitHub, skipping comparisons for unchanged ones
+    // (compared in the local clone, when there is one)
+    let previous_branches = db.get_branches_for_repo(repo_id).unwrap_or_default();
+    let local_clone = db
+        .get_local_repo_status(repo_id)
+        .ok()
+        .flatten()
+        .map(|status| PathBuf::from(status.local_path))
+        .filter(|path| path.is_dir());
+    let branches = github
+        .fetch_branches_since(
+            repo_id,
+            &default_branch,
+            &previous_branches,
+            local_clone.as_deref(),
+        )
+        .map_err(|e| format!("Failed to fetch branches for {}: {}", repo_id, e))?;
+
+    // Clear old branches BEFORE saving new ones
+    db.clear_branches_for_repo(repo_id)
+        .map_err(|e| format!("Failed to clear old branches for {}: {}", repo_id, e))?;
+
     // Save new branches
     for branch in &branches {
         if let Err(e) = db.save_branch(branch) {
@@ -1103,288 +1836,2118 @@ async fn sync_single_repo(
     }
 
     // Fetch PRs from GitHub
-    let prs = match crate::github::fetch_pull_requests(repo_id) {
-        Ok(p) => p,
+    let mut prs = github
+        .fetch_pull_requests(repo_id, pr_limit)
+        .map_err(|e| format!("Failed to fetch PRs for {}: {}", repo_id, e))?;
+
+    // Clear old PRs BEFORE saving new ones
+    db.clear_pull_requests_for_repo(repo_id)
+        .map_err(|e| format!("Failed to clear old PRs for {}: {}", repo_id, e))?;
+
+    // Link PRs to the freshly saved branches by head ref
+    let stored_branches = db.get_branches_for_repo(repo_id).unwrap_or_default();
+    crate::github::link_pull_requests_to_branches(&mut prs, &stored_branches);
+
+    // Save new PRs
+    for pr in &prs {
+        if let Err(e) = db.save_pull_request(pr) {
+            eprintln!("Warning: Failed to save PR #{}: {}", pr.number, e);
+        }
+    }
+
+    // Classify branches now that their PRs are known
+    for branch in &stored_branches {
+        let status = github.classify_branch_status(branch, &prs, &default_branch);
+        if status != branch.status {
+            if let Err(e) = db.update_branch_status(branch.id, &status) {
+                eprintln!(
+                    "Warning: Failed to update status for {}: {}",
+                    branch.name, e
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Re-run the local git status check for a repository that has a stored
+/// local clone, returning the freshest status available
+///
+/// If the stored path no longer exists or the check fails, the previously
+/// stored status is returned unchanged.
+fn refresh_local_status(db: &Database, repo_id: &str) -> Option<LocalRepoStatus> {
+    let stored = db.get_local_repo_status(repo_id).ok().flatten()?;
+
+    let local_path = std::path::Path::new(&stored.local_path);
+    if !local_path.is_dir() {
+        return Some(stored);
+    }
+
+    match crate::local_git::get_repo_status(local_path) {
+        Ok(mut status) => {
+            // The clone's path may not spell the id (see `scan_local_roots`)
+            status.repo_id = stored.repo_id.clone();
+            if let Err(e) = db.save_local_repo_status(&status) {
+                eprintln!(
+                    "Warning: Failed to save local status for {}: {}",
+                    repo_id, e
+                );
+                return Some(stored);
+            }
+            db.get_local_repo_status(repo_id)
+                .ok()
+                .flatten()
+                .or(Some(stored))
+        }
         Err(e) => {
+            eprintln!("Warning: Failed to get local status for {}: {}", repo_id, e);
+            Some(stored)
+        }
+    }
+}
+
+/// Stop tracking a repository and everything stored for it
+async fn delete_repo(
+    State(state): State<AppState>,
+    Path((owner, name)): Path<(String, String)>,
+) -> Response {
+    let repo_id = match RepoId::from_parts(&owner, &name) {
+        Ok(repo_id) => repo_id,
+        Err(e) => return bad_request(e.to_string()),
+    };
+
+    let result = {
+        let db = state.db.lock().unwrap();
+
+        // Deleting drops the repo's branches, commits and PRs, so snapshot first
+        if let Err(e) = db.backup_snapshot(state.config.storage.backups_to_keep) {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ApiResponse {
                     success: false,
-                    message: format!("Failed to fetch PRs for {}: {}", repo_id, e),
+                    message: format!(
+                        "Failed to back up database before deleting repository: {}",
+                        e
+                    ),
                 }),
             )
                 .into_response();
         }
+
+        db.delete_repository(&repo_id)
     };
 
-    // Clear old PRs BEFORE saving new ones
-    if let Err(e) = db.clear_pull_requests_for_repo(repo_id) {
-        return (
+    match result {
+        Ok(true) => {
+            state.request_regeneration();
+            Json(ApiResponse {
+                success: true,
+                message: format!("Stopped tracking {}", repo_id),
+            })
+            .into_response()
+        }
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Repository {} not found", repo_id),
+            }),
+        )
+            .into_response(),
+        Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ApiResponse {
                 success: false,
-                message: format!("Failed to clear old PRs for {}: {}", repo_id, e),
+                message: format!("Failed to delete repository: {}", e),
             }),
         )
-            .into_response();
+            .into_response(),
     }
+}
 
-    // Save new PRs
-    for pr in &prs {
-        if let Err(e) = db.save_pull_request(pr) {
-            eprintln!("Warning: Failed to save PR #{}: {}", pr.number, e);
+/// Refresh a single repository from GitHub and its local clone, returning
+/// the repo detail (same shape as a repos.json entry) plus `localStatus`
+async fn refresh_repo(
+    State(state): State<AppState>,
+    Path((owner, name)): Path<(String, String)>,
+) -> Response {
+    let repo_id = match RepoId::from_parts(&owner, &name) {
+        Ok(repo_id) => repo_id,
+        Err(e) => return bad_request(e.to_string()),
+    };
+    let db = state.db.lock().unwrap();
+
+    let repo = match db.get_repository(&repo_id) {
+        Ok(Some(repo)) => repo,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse {
+                    success: false,
+                    message: format!("Repository {} not found", repo_id),
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    success: false,
+                    message: format!("Failed to get repository {}: {}", repo_id, e),
+                }),
+            )
+                .into_response()
         }
+    };
+
+    if let Err(message) = sync_repo_from_github(
+        &db,
+        state.github.as_ref(),
+        &repo_id,
+        state.config.github.pr_limit,
+    ) {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message,
+            }),
+        )
+            .into_response();
     }
 
+    let local_status = refresh_local_status(&db, &repo_id);
+
+    let mut detail = crate::export::repo_to_json(&db, repo, &state.config);
+    detail["localStatus"] = serde_json::to_value(local_status).unwrap_or_default();
+
     // Release lock before regenerating JSON
     drop(db);
 
-    // Regenerate repos.json
-    if let Err(e) = regenerate_repos_json(&state) {
-        eprintln!("Warning: Failed to regenerate repos.json: {}", e);
+    state.request_regeneration();
+
+    Json(detail).into_response()
+}
+
+/// Override a repository's priority; the override is kept by reprioritize
+/// and scans until it is cleared with `{"priority": null}`
+async fn set_repo_priority(
+    State(state): State<AppState>,
+    Path((owner, name)): Path<(String, String)>,
+    Json(req): Json<SetPriorityRequest>,
+) -> Response {
+    let repo_id = match RepoId::from_parts(&owner, &name) {
+        Ok(repo_id) => repo_id,
+        Err(e) => return bad_request(e.to_string()),
+    };
+
+    if let Some(priority) = req.priority {
+        if !(0.0..=1.0).contains(&priority) {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse {
+                    success: false,
+                    message: format!("Priority must be between 0 and 1, got {}", priority),
+                }),
+            )
+                .into_response();
+        }
     }
 
-    Json(ApiResponse {
-        success: true,
-        message: format!("Successfully synced repository {}", repo_id),
-    })
-    .into_response()
+    let result = {
+        let db = state.db.lock().unwrap();
+        db.set_priority_override(&repo_id, req.priority)
+    };
+
+    match result {
+        Ok(true) => {
+            state.request_regeneration();
+
+            let message = match req.priority {
+                Some(priority) => format!("Priority of {} set to {:.2}", repo_id, priority),
+                None => format!("Priority of {} unlocked", repo_id),
+            };
+            Json(ApiResponse {
+                success: true,
+                message,
+            })
+            .into_response()
+        }
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Repository {} not found", repo_id),
+            }),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to set priority: {}", e),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Dismiss a repository from attention for a while; it counts as complete
+/// until the snooze expires or is cleared with `{"duration": null}`
+async fn snooze_repo(
+    State(state): State<AppState>,
+    Path((owner, name)): Path<(String, String)>,
+    Json(req): Json<SnoozeRequest>,
+) -> Response {
+    let repo_id = match RepoId::from_parts(&owner, &name) {
+        Ok(repo_id) => repo_id,
+        Err(e) => return bad_request(e.to_string()),
+    };
+    let until = match req.duration.as_deref().map(snooze_until) {
+        Some(Ok(until)) => Some(until),
+        Some(Err(e)) => return bad_request(e.to_string()),
+        None => None,
+    };
+
+    let result = {
+        let db = state.db.lock().unwrap();
+        db.set_snooze(&repo_id, until)
+    };
+
+    match result {
+        Ok(true) => {
+            state.request_regeneration();
+
+            let message = match until {
+                Some(until) => format!(
+                    "{} snoozed until {}",
+                    repo_id,
+                    until.format("%Y-%m-%d %H:%M UTC")
+                ),
+                None => format!("{} is no longer snoozed", repo_id),
+            };
+            Json(ApiResponse {
+                success: true,
+                message,
+            })
+            .into_response()
+        }
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Repository {} not found", repo_id),
+            }),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to snooze: {}", e),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct RecentReposQuery {
+    /// Look-back window in days; defaults to a week
+    days: Option<i64>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::models::Repository;
-    use chrono::Utc;
-    use std::sync::{Arc, Mutex};
-    use tempfile::tempdir;
+/// Repositories pushed to within the last `days` days, most recent first
+async fn recent_repos(
+    State(state): State<AppState>,
+    Query(query): Query<RecentReposQuery>,
+) -> Response {
+    let days = query.days.unwrap_or(7);
+    if days < 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                success: false,
+                message: "days must not be negative".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let db = state.db.lock().unwrap();
+    match db.get_recently_pushed(days) {
+        Ok(repos) => Json(repos).into_response(),
+        Err(e @ crate::Error::InvalidDuration(_)) => bad_request(e.to_string()),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to list recent repositories: {}", e),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Repositories carrying a local tag, highest priority first
+async fn repos_by_tag(State(state): State<AppState>, Path(tag): Path<String>) -> Response {
+    let db = state.db.lock().unwrap();
+    match db.get_repos_by_tag(&tag) {
+        Ok(repos) => Json(repos).into_response(),
+        Err(e @ crate::Error::InvalidTag(_)) => bad_request(e.to_string()),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to list repositories tagged '{}': {}", tag, e),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct BranchDiffQuery {
+    /// Branch to compare against; defaults to the repository's default branch
+    base: Option<String>,
+}
+
+/// Return the unified diff of a branch against its base as plain text
+async fn get_branch_diff(
+    State(state): State<AppState>,
+    Path((owner, name, branch)): Path<(String, String, String)>,
+    Query(query): Query<BranchDiffQuery>,
+) -> Response {
+    let repo_id = match RepoId::from_parts(&owner, &name) {
+        Ok(repo_id) => repo_id,
+        Err(e) => return bad_request(e.to_string()),
+    };
+
+    let base = match query.base {
+        Some(base) => base,
+        None => match state.github.get_default_branch(&repo_id) {
+            Ok(base) => base,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse {
+                        success: false,
+                        message: format!("Failed to get default branch for {}: {}", repo_id, e),
+                    }),
+                )
+                    .into_response()
+            }
+        },
+    };
+
+    match state.github.fetch_branch_diff(&repo_id, &base, &branch) {
+        Ok(diff) => ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], diff).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to fetch diff for {}: {}", branch, e),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Return the full message of a commit as plain text; stored messages may be
+/// cut to `max_commit_body_len`
+async fn get_commit_message(
+    State(state): State<AppState>,
+    Path((owner, name, sha)): Path<(String, String, String)>,
+) -> Response {
+    let repo_id = match RepoId::from_parts(&owner, &name) {
+        Ok(repo_id) => repo_id,
+        Err(e) => return bad_request(e.to_string()),
+    };
+
+    match state.github.fetch_commit_message(&repo_id, &sha) {
+        Ok(message) => (
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            message,
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to fetch commit {}: {}", sha, e),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Every stored commit of a branch, newest first; repos.json only inlines the
+/// most recent ones (see `export::EXPORT_COMMITS_PER_BRANCH`)
+async fn get_branch_commits(
+    State(state): State<AppState>,
+    Path((owner, name, branch)): Path<(String, String, String)>,
+) -> Response {
+    let repo_id = match RepoId::from_parts(&owner, &name) {
+        Ok(repo_id) => repo_id,
+        Err(e) => return bad_request(e.to_string()),
+    };
+
+    let db = state.db.lock().unwrap();
+    let commits = db
+        .get_branches_for_repo(repo_id.as_str())
+        .and_then(|branches| {
+            branches
+                .iter()
+                .find(|b| b.name == branch)
+                .map(|b| db.get_commits_for_branch(b.id))
+                .transpose()
+        });
+
+    match commits {
+        Ok(Some(commits)) => Json(
+            commits
+                .iter()
+                .map(crate::export::export_commit)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Branch {} not found in {}", branch, repo_id),
+            }),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                success: false,
+                message: format!("Failed to get commits for {}: {}", branch, e),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{BranchStatus, Repository, ReviewDecision};
+    use crate::test_support::{BranchBuilder, MockGitHubClient, PRBuilder, RepoBuilder};
+    use axum::{body::Body, http::Request};
+    use chrono::Utc;
+    use std::sync::{Arc, Mutex};
+    use tempfile::tempdir;
+    use tower::ServiceExt;
+
+    fn setup_test_db() -> (tempfile::TempDir, PathBuf, Database) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db = Database::open_or_create(&db_path).unwrap();
+        (temp_dir, db_path, db)
+    }
+
+    fn create_test_repo(id: &str, owner: &str, name: &str) -> Repository {
+        Repository {
+            id: id.to_string(),
+            owner: owner.to_string(),
+            name: name.to_string(),
+            language: Some("Rust".to_string()),
+            description: Some("Test repo".to_string()),
+            pushed_at: Utc::now(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            is_fork: false,
+            priority: 0.5,
+            is_private: false,
+            priority_locked: false,
+            github_id: None,
+            snoozed_until: None,
+        }
+    }
+
+    #[test]
+    fn test_regenerate_repos_json() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let static_dir = temp_dir.path().join("static");
+        std::fs::create_dir_all(&static_dir).unwrap();
+
+        let db = Database::open_or_create(&db_path).unwrap();
+
+        // Add a test repository
+        let repo = create_test_repo("test/repo", "test", "repo");
+        db.save_repository(&repo).unwrap();
+
+        let state = AppState {
+            db: Arc::new(Mutex::new(db)),
+            static_dir: static_dir.clone(),
+            github: Arc::new(MockGitHubClient::new()),
+            config: Arc::new(Config::default()),
+            listen_addr: None,
+            regenerate_tx: mpsc::unbounded_channel().0,
+        };
+
+        // Test regeneration
+        let result = regenerate_repos_json(&state);
+        assert!(result.is_ok(), "Should successfully regenerate repos.json");
+
+        // Verify file was created
+        let json_path = static_dir.join("repos.json");
+        assert!(json_path.exists(), "repos.json should be created");
+
+        // Verify JSON content
+        let content = std::fs::read_to_string(&json_path).unwrap();
+        assert!(content.contains("\"groups\""), "Should have groups array");
+        assert!(
+            content.contains("\"ungrouped\""),
+            "Should have ungrouped array"
+        );
+    }
+
+    #[test]
+    fn test_config_get_set() {
+        let (_temp_dir, _path, db) = setup_test_db();
+
+        // Test setting and getting config
+        db.set_config("test_key", "test_value").unwrap();
+        let value = db.get_config("test_key").unwrap();
+        assert_eq!(value, Some("test_value".to_string()));
+
+        // Test updating config
+        db.set_config("test_key", "new_value").unwrap();
+        let value = db.get_config("test_key").unwrap();
+        assert_eq!(value, Some("new_value".to_string()));
+
+        // Test non-existent key
+        let value = db.get_config("nonexistent").unwrap();
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    fn test_get_repositories_updated_since_filters_correctly() {
+        let (_temp_dir, _path, db) = setup_test_db();
+
+        let now = Utc::now();
+        let old_time = now - chrono::Duration::days(5);
+        let recent_time = now - chrono::Duration::hours(1);
+
+        // Create old repository
+        let mut old_repo = create_test_repo("test/old", "test", "old");
+        old_repo.pushed_at = old_time;
+        db.save_repository(&old_repo).unwrap();
+
+        // Create recent repository
+        let mut recent_repo = create_test_repo("test/recent", "test", "recent");
+        recent_repo.pushed_at = recent_time;
+        db.save_repository(&recent_repo).unwrap();
+
+        // Get repos updated since 2 days ago
+        let cutoff = (now - chrono::Duration::days(2)).to_rfc3339();
+        let repos = db.get_repositories_updated_since(&cutoff).unwrap();
+
+        // Should only return recent repo
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].id, "test/recent");
+    }
+
+    #[test]
+    fn test_sync_all_repos_first_run_no_last_refresh() {
+        let (_temp_dir, _path, db) = setup_test_db();
+
+        // Verify no last_refresh_time config exists
+        let last_refresh = db.get_config("last_refresh_time").unwrap();
+        assert_eq!(
+            last_refresh, None,
+            "Should have no last refresh time initially"
+        );
+
+        // Add test repository
+        let repo = create_test_repo("test/repo1", "test", "repo1");
+        db.save_repository(&repo).unwrap();
+
+        // On first run, should get all repositories
+        let all_repos = db.get_all_repositories().unwrap();
+        assert_eq!(all_repos.len(), 1);
+        assert_eq!(all_repos[0].id, "test/repo1");
+    }
+
+    #[test]
+    fn test_sync_all_repos_subsequent_run_filters_by_timestamp() {
+        let (_temp_dir, _path, db) = setup_test_db();
+
+        let now = Utc::now();
+        let old_time = now - chrono::Duration::days(5);
+
+        // Set last refresh time to 2 days ago
+        let last_refresh = (now - chrono::Duration::days(2)).to_rfc3339();
+        db.set_config("last_refresh_time", &last_refresh).unwrap();
+
+        // Add old repo (before last refresh)
+        let mut old_repo = create_test_repo("test/old", "test", "old");
+        old_repo.pushed_at = old_time;
+        db.save_repository(&old_repo).unwrap();
+
+        // Add recent repo (after last refresh)
+        let mut recent_repo = create_test_repo("test/recent", "test", "recent");
+        recent_repo.pushed_at = now - chrono::Duration::hours(1);
+        db.save_repository(&recent_repo).unwrap();
+
+        // Should only get repos updated since last refresh
+        let updated_repos = db.get_repositories_updated_since(&last_refresh).unwrap();
+        assert_eq!(updated_repos.len(), 1);
+        assert_eq!(updated_repos[0].id, "test/recent");
+    }
+
+    #[test]
+    fn test_sync_updates_last_refresh_timestamp() {
+        let (_temp_dir, _path, db) = setup_test_db();
+
+        let before_sync = Utc::now();
+
+        // Simulate sync by setting last_refresh_time
+        let timestamp = Utc::now().to_rfc3339();
+        db.set_config("last_refresh_time", &timestamp).unwrap();
+
+        // Verify it was set
+        let stored_time = db.get_config("last_refresh_time").unwrap();
+        assert!(stored_time.is_some());
+
+        let stored_timestamp: chrono::DateTime<Utc> = stored_time.unwrap().parse().unwrap();
+        assert!(stored_timestamp >= before_sync);
+    }
+
+    #[test]
+    fn test_clear_removes_all_old_data() {
+        use crate::models::{Branch, BranchStatus, PRState, PullRequest};
+
+        let (_temp_dir, _path, db) = setup_test_db();
+
+        // Create test repository
+        let repo = create_test_repo("test/repo", "test", "repo");
+        db.save_repository(&repo).unwrap();
+
+        // Add 3 branches
+        for i in 1..=3 {
+            let branch = Branch {
+                id: i,
+                repo_id: repo.id.clone(),
+                name: format!("branch-{}", i),
+                sha: format!("sha{}", i),
+                ahead_by: 1,
+                behind_by: 0,
+                status: BranchStatus::ReadyForPR,
+                last_commit_date: Utc::now(),
+            };
+            db.save_branch(&branch).unwrap();
+        }
+
+        // Verify 3 branches exist
+        let branches = db.get_branches_for_repo(&repo.id).unwrap();
+        assert_eq!(branches.len(), 3);
+
+        // Clear branches
+        db.clear_branches_for_repo(&repo.id).unwrap();
+
+        // Verify all branches are gone
+        let branches = db.get_branches_for_repo(&repo.id).unwrap();
+        assert_eq!(branches.len(), 0, "Stale branches were not cleared!");
+
+        // Same test for PRs
+        for i in 1..=2 {
+            let pr = PullRequest {
+                id: i,
+                repo_id: repo.id.clone(),
+                branch_id: None,
+                number: i as u32,
+                state: PRState::Open,
+                title: format!("PR {}", i),
+                head_ref_name: format!("branch-{}", i),
+                url: None,
+                review_decision: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            };
+            db.save_pull_request(&pr).unwrap();
+        }
+
+        let prs = db.get_pull_requests_for_repo(&repo.id).unwrap();
+        assert_eq!(prs.len(), 2);
+
+        db.clear_pull_requests_for_repo(&repo.id).unwrap();
+
+        let prs = db.get_pull_requests_for_repo(&repo.id).unwrap();
+        assert_eq!(prs.len(), 0, "Stale PRs were not cleared!");
+    }
+
+    #[tokio::test]
+    async fn test_ensure_repos_json_creates_missing_file_only() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
+        let json_path = state.static_dir.join("repos.json");
+
+        // An empty database still yields a loadable export
+        let summary = ensure_repos_json(&state).unwrap().unwrap();
+        assert_eq!((summary.groups, summary.repos), (0, 0));
+        let exported: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(exported["ungrouped"], serde_json::json!([]));
+
+        std::fs::write(&json_path, "{}").unwrap();
+        assert!(ensure_repos_json(&state).unwrap().is_none());
+        assert_eq!(std::fs::read_to_string(&json_path).unwrap(), "{}");
+    }
+
+    #[test]
+    fn test_regenerate_repos_json_uses_state_config() {
+        let (temp_dir, _db_path, db) = setup_test_db();
+        let static_dir = temp_dir.path().join("static");
+        std::fs::create_dir_all(&static_dir).unwrap();
+
+        db.save_repository(&create_test_repo("test/repo", "test", "repo"))
+            .unwrap();
+
+        // A zero-day threshold flags even a just-pushed repo as stale
+        let mut config = Config::default();
+        config.analysis.stale_threshold_days = 0;
+
+        let state = AppState {
+            db: Arc::new(Mutex::new(db)),
+            static_dir: static_dir.clone(),
+            github: Arc::new(MockGitHubClient::new()),
+            config: Arc::new(config),
+            listen_addr: None,
+            regenerate_tx: mpsc::unbounded_channel().0,
+        };
+
+        regenerate_repos_json(&state).unwrap();
+
+        let content = std::fs::read_to_string(static_dir.join("repos.json")).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(json["ungrouped"][0]["staleness"], "Stale");
+    }
+
+    fn create_test_state(
+        temp_dir: &tempfile::TempDir,
+        db: Database,
+        github: Arc<MockGitHubClient>,
+    ) -> AppState {
+        let static_dir = temp_dir.path().join("static");
+        std::fs::create_dir_all(&static_dir).unwrap();
+
+        AppState::new(db, static_dir, github, Config::default())
+    }
+
+    async fn post_json(
+        app: Router,
+        uri: &str,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    async fn get_json(app: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    /// The Access-Control-Allow-Origin a cross-origin GET from `origin` gets back
+    async fn allowed_origin(cors: CorsLayer, origin: &str) -> Option<String> {
+        let app = Router::new()
+            .route("/api/ping", get(|| async { "pong" }))
+            .layer(cors);
+        let request = Request::builder()
+            .uri("/api/ping")
+            .header(header::ORIGIN, origin)
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_cors_allows_any_origin_on_loopback() {
+        let cors = cors_layer(IpAddr::from([127, 0, 0, 1]), &[]).unwrap();
+
+        let allowed = allowed_origin(cors, "http://example.com").await;
+
+        assert_eq!(allowed.as_deref(), Some("*"));
+    }
+
+    #[tokio::test]
+    async fn test_cors_is_same_origin_off_loopback() {
+        let cors = cors_layer(IpAddr::from([0, 0, 0, 0]), &[]).unwrap();
+
+        assert_eq!(allowed_origin(cors, "http://example.com").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_cors_allowlist_admits_only_listed_origins() {
+        let origins = vec!["http://localhost:3000/".to_string()];
+        let host = IpAddr::from([127, 0, 0, 1]);
+
+        let listed =
+            allowed_origin(cors_layer(host, &origins).unwrap(), "http://localhost:3000").await;
+        let other = allowed_origin(cors_layer(host, &origins).unwrap(), "http://example.com").await;
+
+        assert_eq!(listed.as_deref(), Some("http://localhost:3000"));
+        assert_eq!(other, None);
+        assert!(cors_layer(host, &["bad\norigin".to_string()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_repositories_endpoints_return_models() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let mut repo = create_test_repo("owner/repo", "owner", "repo");
+        repo.priority = 0.5;
+        db.save_repository(&repo).unwrap();
+        let app = build_router(create_test_state(
+            &temp_dir,
+            db,
+            Arc::new(MockGitHubClient::new()),
+        ));
+
+        let (status, body) = get_json(app.clone(), "/api/repositories").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.as_array().unwrap().len(), 1);
+        // Model field names, not the export's camelCase
+        assert!(body[0]["pushed_at"].is_string());
+
+        let (status, body) = get_json(app.clone(), "/api/repositories/owner/repo").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], "owner/repo");
+        assert_eq!(body["priority"], 0.5);
+
+        let (status, _) = get_json(app, "/api/repositories/owner/missing").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_delete_repo_untracks_repository() {
+        let (temp_dir, _path, db) = setup_test_db();
+        db.save_repository(&create_test_repo("owner/repo", "owner", "repo"))
+            .unwrap();
+        let state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
+        let app = build_router(state.clone());
+
+        let delete = |uri: &str| {
+            Request::builder()
+                .method("DELETE")
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(delete("/api/repos/owner/repo"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state
+            .db
+            .lock()
+            .unwrap()
+            .get_repository("owner/repo")
+            .unwrap()
+            .is_none());
+
+        let response = app.oneshot(delete("/api/repos/owner/repo")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_set_repo_priority_locks_and_unlocks() {
+        let (temp_dir, _path, db) = setup_test_db();
+        db.save_repository(&create_test_repo("owner/repo", "owner", "repo"))
+            .unwrap();
+        let state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
+        let app = build_router(state.clone());
+
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/repos/owner/repo/priority",
+            serde_json::json!({ "priority": 0.75 }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["success"], true);
+        {
+            let db = state.db.lock().unwrap();
+            let repo = db.get_repository("owner/repo").unwrap().unwrap();
+            assert_eq!(repo.priority, 0.75);
+            assert!(repo.priority_locked);
+        }
+
+        let (status, _) = post_json(
+            app.clone(),
+            "/api/repos/owner/repo/priority",
+            serde_json::json!({ "priority": 1.5 }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = post_json(
+            app.clone(),
+            "/api/repos/owner/missing/priority",
+            serde_json::json!({ "priority": 0.5 }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = post_json(
+            app,
+            "/api/repos/owner/repo/priority",
+            serde_json::json!({ "priority": null }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let db = state.db.lock().unwrap();
+        assert!(
+            !db.get_repository("owner/repo")
+                .unwrap()
+                .unwrap()
+                .priority_locked
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snooze_repo_hides_it_from_attention_until_cleared() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let repo = create_test_repo("owner/repo", "owner", "repo");
+        db.save_repository(&repo).unwrap();
+        db.save_branch(&BranchBuilder::new("feature").ahead(2).build(&repo.id))
+            .unwrap();
+        let state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
+        let app = build_router(state.clone());
+        let needs_attention = |state: &AppState| {
+            state
+                .db
+                .lock()
+                .unwrap()
+                .count_needing_attention(&state.config)
+                .unwrap()
+        };
+        assert_eq!(needs_attention(&state), 1);
+
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/repos/owner/repo/snooze",
+            serde_json::json!({ "duration": "2d" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["success"], true);
+        assert_eq!(needs_attention(&state), 0);
+        {
+            let db = state.db.lock().unwrap();
+            let until = db
+                .get_repository("owner/repo")
+                .unwrap()
+                .unwrap()
+                .snoozed_until
+                .unwrap();
+            assert!(until > Utc::now() + chrono::Duration::hours(47));
+        }
+
+        let (status, _) = post_json(
+            app.clone(),
+            "/api/repos/owner/repo/snooze",
+            serde_json::json!({ "duration": "soon" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        for huge in ["99999999999w", "99999999w"] {
+            let (status, _) = post_json(
+                app.clone(),
+                "/api/repos/owner/repo/snooze",
+                serde_json::json!({ "duration": huge }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", huge);
+        }
+
+        let (status, _) = post_json(
+            app.clone(),
+            "/api/repos/owner/missing/snooze",
+            serde_json::json!({ "duration": "1h" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = post_json(
+            app,
+            "/api/repos/owner/repo/snooze",
+            serde_json::json!({ "duration": null }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(needs_attention(&state), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_uses_injected_client() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let mock = Arc::new(MockGitHubClient::new());
+        let app = build_router(create_test_state(&temp_dir, db, mock.clone()));
+
+        let (status, body) = post_json(
+            app,
+            "/api/pr/create",
+            serde_json::json!({
+                "repoId": "owner/repo",
+                "branchName": "feature-x",
+                "title": "Add feature X"
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["success"], true);
+        assert_eq!(body["prUrl"], "https://github.com/owner/repo/pull/123");
+
+        let created = mock.get_created_prs();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].branch_name, "feature-x");
+        assert_eq!(created[0].title, "Add feature X");
+        assert!(!created[0].draft, "PRs are ready for review by default");
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_rejects_protected_branch() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let mock = Arc::new(MockGitHubClient::new().expect_no_create_pr_for("owner/repo"));
+        let app = build_router(create_test_state(&temp_dir, db, mock.clone()));
+
+        for branch in ["main", "develop"] {
+            let (status, body) = post_json(
+                app.clone(),
+                "/api/pr/create",
+                serde_json::json!({ "repoId": "owner/repo", "branchName": branch }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", branch);
+            assert_eq!(body["success"], false);
+            assert!(body["message"].as_str().unwrap().contains("protected"));
+        }
+        mock.verify();
+    }
+
+    #[tokio::test]
+    async fn test_malformed_repo_ids_are_rejected_before_gh() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let mock = Arc::new(MockGitHubClient::new());
+        let app = build_router(create_test_state(&temp_dir, db, mock.clone()));
+
+        for repo_id in ["no-slash", "owner/repo --web", "owner/a/b"] {
+            let (status, body) = post_json(
+                app.clone(),
+                "/api/pr/create",
+                serde_json::json!({ "repoId": repo_id, "branchName": "feature-x" }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", repo_id);
+            assert_eq!(body["success"], false);
+        }
+        assert!(mock.get_created_prs().is_empty());
+
+        let (status, _) = post_json(
+            app,
+            "/api/repos/bad%20owner/repo/priority",
+            serde_json::json!({ "priority": 0.5 }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_passes_draft_flag() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let mock = Arc::new(MockGitHubClient::new());
+        let app = build_router(create_test_state(&temp_dir, db, mock.clone()));
+
+        let (status, _body) = post_json(
+            app,
+            "/api/pr/create",
+            serde_json::json!({
+                "repoId": "owner/repo",
+                "branchName": "feature-x",
+                "draft": true
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(mock.get_created_prs()[0].draft);
+    }
+
+    #[tokio::test]
+    async fn test_create_all_prs_only_for_ahead_branches() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let repo = RepoBuilder::new("repo").owner("owner").build();
+        db.save_repository(&repo).unwrap();
+        db.save_branch(&BranchBuilder::new("main").build(&repo.id))
+            .unwrap();
+        db.save_branch(&BranchBuilder::new("feature-ahead").ahead(3).build(&repo.id))
+            .unwrap();
+        db.save_branch(
+            &BranchBuilder::new("feature-merged")
+                .behind(2)
+                .build(&repo.id),
+        )
+        .unwrap();
+
+        let mock = Arc::new(MockGitHubClient::new().expect_create_pr_for("owner/repo"));
+        let app = build_router(create_test_state(&temp_dir, db, mock.clone()));
+
+        let (status, body) = post_json(
+            app,
+            "/api/pr/create-all",
+            serde_json::json!({ "repoId": "owner/repo" }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["results"].as_array().unwrap().len(), 1);
+        assert_eq!(body["results"][0]["branchName"], "feature-ahead");
+        assert_eq!(body["results"][0]["success"], true);
+
+        mock.verify();
+        let created = mock.get_created_prs();
+        assert_eq!(created.len(), 1, "Only ahead branches should get PRs");
+        assert_eq!(created[0].branch_name, "feature-ahead");
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_records_open_pr_with_url() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let repo = RepoBuilder::new("repo").owner("owner").build();
+        db.save_repository(&repo).unwrap();
+        db.save_branch(&BranchBuilder::new("feature-x").ahead(1).build(&repo.id))
+            .unwrap();
+
+        let mock = Arc::new(MockGitHubClient::new());
+        let state = create_test_state(&temp_dir, db, mock);
+
+        // Creating twice (gh reports the existing PR) stores it only once
+        for _ in 0..2 {
+            let (status, _body) = post_json(
+                build_router(state.clone()),
+                "/api/pr/create",
+                serde_json::json!({ "repoId": "owner/repo", "branchName": "feature-x" }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        let db = state.db.lock().unwrap();
+        let prs = db.get_pull_requests_for_repo("owner/repo").unwrap();
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].number, 123);
+        assert_eq!(prs[0].state, crate::models::PRState::Open);
+        assert_eq!(prs[0].title, "feature x");
+        assert_eq!(
+            prs[0].url.as_deref(),
+            Some("https://github.com/owner/repo/pull/123")
+        );
+        assert!(prs[0].branch_id.is_some(), "PR should link to its branch");
+    }
+
+    #[tokio::test]
+    async fn test_merge_pr_into_protected_branch_requires_confirmation() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let repo = RepoBuilder::new("repo").owner("owner").build();
+        db.save_repository(&repo).unwrap();
+        db.save_branch(&BranchBuilder::new("main").build(&repo.id))
+            .unwrap();
+        db.save_branch(
+            &BranchBuilder::new("feature-x")
+                .ahead(1)
+                .with_status(BranchStatus::InReview)
+                .build(&repo.id),
+        )
+        .unwrap();
+        db.save_pull_request(
+            &PRBuilder::new(7, "Add feature X")
+                .head_ref_name("feature-x")
+                .review_decision(ReviewDecision::Approved)
+                .build(&repo.id),
+        )
+        .unwrap();
+
+        let mock = Arc::new(MockGitHubClient::new());
+        let state = create_test_state(&temp_dir, db, mock.clone());
+
+        let (status, body) = post_json(
+            build_router(state.clone()),
+            "/api/pr/7/merge",
+            serde_json::json!({ "repoId": "owner/repo", "method": "rebase" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(body["message"].as_str().unwrap().contains("'main'"));
+        assert!(mock.get_merged_prs().is_empty());
+
+        let (status, body) = post_json(
+            build_router(state.clone()),
+            "/api/pr/7/merge",
+            serde_json::json!({
+                "repoId": "owner/repo",
+                "method": "rebase",
+                "confirmProtected": true
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["success"], true);
+
+        let merged = mock.get_merged_prs();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].number, 7);
+        assert_eq!(merged[0].method, MergeMethod::Rebase);
+
+        {
+            let db = state.db.lock().unwrap();
+            let prs = db.get_pull_requests_for_repo("owner/repo").unwrap();
+            assert_eq!(prs[0].state, PRState::Merged);
+            let branch = db
+                .get_branches_for_repo("owner/repo")
+                .unwrap()
+                .into_iter()
+                .find(|b| b.name == "feature-x")
+                .unwrap();
+            assert_eq!(branch.ahead_by, 0);
+            assert!(!crate::analysis::is_unmerged(&branch, &state.config));
+        }
+
+        let (_, body) = post_json(
+            build_router(state),
+            "/api/pr/create-all",
+            serde_json::json!({ "repoId": "owner/repo" }),
+        )
+        .await;
+        assert_eq!(body["message"], "No branches with unmerged work found");
+    }
+
+    #[tokio::test]
+    async fn test_merge_pr_into_unprotected_branch_squashes_by_default() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let mock = Arc::new(MockGitHubClient::new().with_pr_base("owner/repo", 9, "feature-base"));
+        let app = build_router(create_test_state(&temp_dir, db, mock.clone()));
+
+        let (status, _body) = post_json(
+            app,
+            "/api/pr/9/merge",
+            serde_json::json!({ "repoId": "owner/repo" }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let merged = mock.get_merged_prs();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].method, MergeMethod::Squash);
+    }
+
+    #[tokio::test]
+    async fn test_create_all_prs_skips_repo_without_ahead_branches() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let repo = RepoBuilder::new("repo").owner("owner").build();
+        db.save_repository(&repo).unwrap();
+        db.save_branch(&BranchBuilder::new("main").build(&repo.id))
+            .unwrap();
+
+        let mock = Arc::new(MockGitHubClient::new().expect_no_create_pr_for("owner/repo"));
+        let app = build_router(create_test_state(&temp_dir, db, mock.clone()));
+
+        let (status, body) = post_json(
+            app,
+            "/api/pr/create-all",
+            serde_json::json!({ "repoId": "owner/repo" }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message"], "No branches with unmerged work found");
+        mock.verify();
+    }
+
+    #[tokio::test]
+    async fn test_create_group_prs_covers_every_repo_in_group() {
+        let (temp_dir, _path, db) = setup_test_db();
+        for name in ["alpha", "beta", "outside"] {
+            let repo = RepoBuilder::new(name).owner("owner").build();
+            db.save_repository(&repo).unwrap();
+            db.save_branch(&BranchBuilder::new("main").build(&repo.id))
+                .unwrap();
+            db.save_branch(&BranchBuilder::new("feature").ahead(1).build(&repo.id))
+                .unwrap();
+        }
+        // Protected branches are skipped even when ahead
+        db.save_branch(&BranchBuilder::new("develop").ahead(2).build("owner/beta"))
+            .unwrap();
+        let group_id = db.create_group("Sprint", 0).unwrap();
+        db.add_repo_to_group("owner/alpha", group_id).unwrap();
+        db.add_repo_to_group("owner/beta", group_id).unwrap();
+
+        let mock = Arc::new(
+            MockGitHubClient::new()
+                .expect_create_pr_for("owner/alpha")
+                .expect_create_pr_for("owner/beta")
+                .expect_no_create_pr_for("owner/outside"),
+        );
+        let app = build_router(create_test_state(&temp_dir, db, mock.clone()));
 
-    fn setup_test_db() -> (tempfile::TempDir, PathBuf, Database) {
-        let temp_dir = tempdir().unwrap();
-        let db_path = temp_dir.path().join("test.db");
-        let db = Database::open_or_create(&db_path).unwrap();
-        (temp_dir, db_path, db)
+        let (status, body) = post_json(
+            app,
+            &format!("/api/groups/{}/create-all-prs", group_id),
+            serde_json::json!({}),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["created"], 2);
+        assert_eq!(body["failed"], 0);
+        assert_eq!(body["results"]["owner/alpha"][0]["branchName"], "feature");
+        assert_eq!(body["results"]["owner/beta"].as_array().unwrap().len(), 1);
+        mock.verify();
     }
 
-    fn create_test_repo(id: &str, owner: &str, name: &str) -> Repository {
-        Repository {
-            id: id.to_string(),
-            owner: owner.to_string(),
-            name: name.to_string(),
-            language: Some("Rust".to_string()),
-            description: Some("Test repo".to_string()),
-            pushed_at: Utc::now(),
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-            is_fork: false,
-            priority: 0.5,
+    #[tokio::test]
+    async fn test_add_repos_to_new_group_reuses_same_name() {
+        let (temp_dir, _path, db) = setup_test_db();
+        for name in ["one", "two"] {
+            db.save_repository(&create_test_repo(&format!("owner/{}", name), "owner", name))
+                .unwrap();
+        }
+        let state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
+
+        for (repo_id, group_name) in [("owner/one", "Focus"), ("owner/two", "focus")] {
+            let (status, _) = post_json(
+                build_router(state.clone()),
+                "/api/groups/add-repos",
+                serde_json::json!({ "groupName": group_name, "repoIds": [repo_id] }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
         }
+
+        let db = state.db.lock().unwrap();
+        let groups = db.get_all_groups().unwrap();
+        assert_eq!(groups.len(), 1, "Same-named group should be reused");
+        assert_eq!(db.get_repos_in_group(groups[0].id).unwrap().len(), 2);
     }
 
-    #[test]
-    fn test_regenerate_repos_json() {
-        let temp_dir = tempdir().unwrap();
-        let db_path = temp_dir.path().join("test.db");
-        let static_dir = temp_dir.path().join("static");
-        std::fs::create_dir_all(&static_dir).unwrap();
+    #[tokio::test]
+    async fn test_move_repos_batch() {
+        let (temp_dir, _path, db) = setup_test_db();
+        for name in ["one", "two", "three"] {
+            db.save_repository(&create_test_repo(&format!("owner/{}", name), "owner", name))
+                .unwrap();
+        }
+        let old_group = db.create_group("Old", 0).unwrap();
+        let new_group = db.create_group("New", 1).unwrap();
+        for repo_id in ["owner/one", "owner/two", "owner/three"] {
+            db.add_repo_to_group(repo_id, old_group).unwrap();
+        }
+        let state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
 
-        let db = Database::open_or_create(&db_path).unwrap();
+        let (status, body) = post_json(
+            build_router(state.clone()),
+            "/api/repos/move-batch",
+            serde_json::json!({ "repoIds": ["owner/one", "owner/two"], "targetGroupId": new_group }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message"], "Moved 2 repositories");
+
+        let (status, _) = post_json(
+            build_router(state.clone()),
+            "/api/repos/move-batch",
+            serde_json::json!({ "repoIds": ["owner/three"], "targetGroupId": null }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
 
-        // Add a test repository
-        let repo = create_test_repo("test/repo", "test", "repo");
-        db.save_repository(&repo).unwrap();
+        let db = state.db.lock().unwrap();
+        assert_eq!(db.get_repos_in_group(new_group).unwrap().len(), 2);
+        assert!(db.get_repos_in_group(old_group).unwrap().is_empty());
+    }
 
-        let state = AppState {
-            db: Arc::new(Mutex::new(db)),
-            static_dir: static_dir.clone(),
-        };
+    #[tokio::test]
+    async fn test_add_repos_rejects_reserved_group_name() {
+        let (temp_dir, _path, db) = setup_test_db();
+        db.save_repository(&create_test_repo("owner/one", "owner", "one"))
+            .unwrap();
+        let state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
+
+        let (status, body) = post_json(
+            build_router(state.clone()),
+            "/api/groups/add-repos",
+            serde_json::json!({ "groupName": "ungrouped", "repoIds": ["owner/one"] }),
+        )
+        .await;
 
-        // Test regeneration
-        let result = regenerate_repos_json(&state);
-        assert!(result.is_ok(), "Should successfully regenerate repos.json");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["success"], false);
+        assert!(state
+            .db
+            .lock()
+            .unwrap()
+            .get_all_groups()
+            .unwrap()
+            .is_empty());
+    }
 
-        // Verify file was created
-        let json_path = static_dir.join("repos.json");
-        assert!(json_path.exists(), "repos.json should be created");
+    #[tokio::test]
+    async fn test_focus_lists_actions_most_urgent_repo_first() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let calm = create_test_repo("owner/calm", "owner", "calm");
+        let urgent = create_test_repo("owner/urgent", "owner", "urgent");
+        db.save_repository(&calm).unwrap();
+        db.save_repository(&urgent).unwrap();
+        db.save_branch(&BranchBuilder::new("feature/x").ahead(2).build(&urgent.id))
+            .unwrap();
+        db.save_local_repo_status(&LocalRepoStatus {
+            id: 0,
+            repo_id: calm.id.clone(),
+            local_path: "/tmp/calm".to_string(),
+            current_branch: Some("main".to_string()),
+            uncommitted_files: 1,
+            unpushed_commits: 0,
+            behind_commits: 0,
+            is_dirty: true,
+            has_conflicts: false,
+            is_diverged: false,
+            is_unpublished: false,
+            last_checked: chrono::Utc::now(),
+        })
+        .unwrap();
+        let state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
 
-        // Verify JSON content
-        let content = std::fs::read_to_string(&json_path).unwrap();
-        assert!(content.contains("\"groups\""), "Should have groups array");
-        assert!(
-            content.contains("\"ungrouped\""),
-            "Should have ungrouped array"
+        let (status, body) = get_json(build_router(state), "/api/focus").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::json!([
+                { "repoId": "owner/urgent", "action": "Create PR for owner/urgent feature/x" },
+                { "repoId": "owner/calm", "action": "Commit 1 changed file in owner/calm" }
+            ])
         );
     }
 
-    #[test]
-    fn test_config_get_set() {
-        let (_temp_dir, _path, db) = setup_test_db();
-
-        // Test setting and getting config
-        db.set_config("test_key", "test_value").unwrap();
-        let value = db.get_config("test_key").unwrap();
-        assert_eq!(value, Some("test_value".to_string()));
+    #[tokio::test]
+    async fn test_ui_config_serves_default_sort_and_attention_count() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let repo = create_test_repo("owner/repo", "owner", "repo");
+        db.save_repository(&repo).unwrap();
+        db.save_branch(&BranchBuilder::new("feature").ahead(2).build(&repo.id))
+            .unwrap();
+        let mut state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
+        let mut config = Config::default();
+        config.ui.default_sort = SortConfig {
+            column: crate::config::SortColumn::LastUpdated,
+            ascending: true,
+        };
+        state.config = Arc::new(config);
 
-        // Test updating config
-        db.set_config("test_key", "new_value").unwrap();
-        let value = db.get_config("test_key").unwrap();
-        assert_eq!(value, Some("new_value".to_string()));
+        let (status, body) = get_json(build_router(state), "/api/ui-config").await;
 
-        // Test non-existent key
-        let value = db.get_config("nonexistent").unwrap();
-        assert_eq!(value, None);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "defaultSort": { "column": "lastUpdated", "ascending": true },
+                "needsAttention": 1
+            })
+        );
     }
 
-    #[test]
-    fn test_get_repositories_updated_since_filters_correctly() {
-        let (_temp_dir, _path, db) = setup_test_db();
+    #[tokio::test]
+    async fn test_effective_config_endpoint() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let mut state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
+        let mut config = Config::default();
+        config.github.protected_branches = vec!["main".to_string(), "release/*".to_string()];
+        config.ui.auto_export = true;
+        state.config = Arc::new(config);
+        state.listen_addr = Some(SocketAddr::from(([127, 0, 0, 1], 8459)));
+
+        let (status, body) = get_json(build_router(state), "/api/config").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["host"], "127.0.0.1");
+        assert_eq!(body["port"], 8459);
+        assert_eq!(
+            body["protectedBranches"],
+            serde_json::json!(["main", "release/*"])
+        );
+        assert_eq!(
+            body["defaultSort"],
+            serde_json::json!({ "column": "status", "ascending": false })
+        );
+        assert_eq!(body["autoExport"], true);
+        assert_eq!(body["owners"], serde_json::json!(["softwarewrighter"]));
+        assert!(body["staticDir"].as_str().unwrap().ends_with("static"));
+        assert!(body.get("token").is_none());
+    }
 
-        let now = Utc::now();
-        let old_time = now - chrono::Duration::days(5);
-        let recent_time = now - chrono::Duration::hours(1);
+    #[tokio::test]
+    async fn test_recent_repos_endpoint() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let mut old = create_test_repo("owner/old", "owner", "old");
+        old.pushed_at = Utc::now() - chrono::Duration::days(10);
+        db.save_repository(&old).unwrap();
+        db.save_repository(&create_test_repo("owner/new", "owner", "new"))
+            .unwrap();
+        let state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
+
+        let (status, body) = get_json(build_router(state.clone()), "/api/repos/recent").await;
+        assert_eq!(status, StatusCode::OK);
+        let ids: Vec<_> = body.as_array().unwrap().iter().map(|r| &r["id"]).collect();
+        assert_eq!(ids, vec!["owner/new"]);
+
+        let (_, body) = get_json(build_router(state.clone()), "/api/repos/recent?days=30").await;
+        assert_eq!(body.as_array().unwrap().len(), 2);
+
+        let (status, _) = get_json(build_router(state.clone()), "/api/repos/recent?days=-1").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Out-of-range windows are rejected without poisoning the database lock
+        let (status, _) = get_json(
+            build_router(state.clone()),
+            "/api/repos/recent?days=100000000",
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get_json(build_router(state), "/api/repos/recent").await;
+        assert_eq!(status, StatusCode::OK);
+    }
 
-        // Create old repository
-        let mut old_repo = create_test_repo("test/old", "test", "old");
-        old_repo.pushed_at = old_time;
-        db.save_repository(&old_repo).unwrap();
+    #[tokio::test]
+    async fn test_repos_by_tag_endpoint() {
+        let (temp_dir, _path, db) = setup_test_db();
+        for name in ["one", "two"] {
+            db.save_repository(&create_test_repo(&format!("owner/{}", name), "owner", name))
+                .unwrap();
+        }
+        db.add_tag("owner/two", "oss").unwrap();
+        let state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
 
-        // Create recent repository
-        let mut recent_repo = create_test_repo("test/recent", "test", "recent");
-        recent_repo.pushed_at = recent_time;
-        db.save_repository(&recent_repo).unwrap();
+        let (status, body) = get_json(build_router(state.clone()), "/api/repos/by-tag/OSS").await;
+        assert_eq!(status, StatusCode::OK);
+        let ids: Vec<_> = body.as_array().unwrap().iter().map(|r| &r["id"]).collect();
+        assert_eq!(ids, vec!["owner/two"]);
 
-        // Get repos updated since 2 days ago
-        let cutoff = (now - chrono::Duration::days(2)).to_rfc3339();
-        let repos = db.get_repositories_updated_since(&cutoff).unwrap();
+        let (_, body) = get_json(build_router(state.clone()), "/api/repos/by-tag/missing").await;
+        assert!(body.as_array().unwrap().is_empty());
 
-        // Should only return recent repo
-        assert_eq!(repos.len(), 1);
-        assert_eq!(repos[0].id, "test/recent");
+        let (status, _) = get_json(build_router(state), "/api/repos/by-tag/two%20words").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
     }
 
-    #[test]
-    fn test_sync_all_repos_first_run_no_last_refresh() {
-        let (_temp_dir, _path, db) = setup_test_db();
+    #[tokio::test]
+    async fn test_group_summaries_endpoint() {
+        let (temp_dir, _path, db) = setup_test_db();
+        for name in ["one", "two"] {
+            db.save_repository(&create_test_repo(&format!("owner/{}", name), "owner", name))
+                .unwrap();
+        }
+        db.save_branch(&BranchBuilder::new("feature").ahead(1).build("owner/one"))
+            .unwrap();
+        let group_id = db.create_group("Focus", 0).unwrap();
+        db.add_repo_to_group("owner/one", group_id).unwrap();
+        let app = build_router(create_test_state(
+            &temp_dir,
+            db,
+            Arc::new(MockGitHubClient::new()),
+        ));
+
+        let (status, body) = get_json(app, "/api/groups/summary").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::json!([
+                { "id": group_id, "name": "Focus", "repoCount": 1, "status": "needsSync" },
+                { "id": null, "name": "Ungrouped", "repoCount": 1, "status": "complete" },
+            ])
+        );
+    }
 
-        // Verify no last_refresh_time config exists
-        let last_refresh = db.get_config("last_refresh_time").unwrap();
+    #[tokio::test]
+    async fn test_dashboard_endpoint() {
+        let (temp_dir, _path, db) = setup_test_db();
+        for name in ["one", "two"] {
+            db.save_repository(&create_test_repo(&format!("owner/{}", name), "owner", name))
+                .unwrap();
+        }
+        db.save_branch(&BranchBuilder::new("feature").ahead(1).build("owner/one"))
+            .unwrap();
+        db.save_pull_request(&PRBuilder::new(3, "Feature").build("owner/one"))
+            .unwrap();
+        let app = build_router(create_test_state(
+            &temp_dir,
+            db,
+            Arc::new(MockGitHubClient::new()),
+        ));
+
+        let (status, body) = get_json(app, "/api/dashboard").await;
+
+        assert_eq!(status, StatusCode::OK);
         assert_eq!(
-            last_refresh, None,
-            "Should have no last refresh time initially"
+            body,
+            serde_json::json!({
+                "totalRepos": 2,
+                "needsSync": 1,
+                "localChanges": 0,
+                "stale": 0,
+                "complete": 1,
+                "openPrs": 1,
+                "unmergedBranches": 1,
+            })
         );
+    }
 
-        // Add test repository
-        let repo = create_test_repo("test/repo1", "test", "repo1");
-        db.save_repository(&repo).unwrap();
+    #[tokio::test]
+    async fn test_create_group_prs_unknown_group() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let app = build_router(create_test_state(
+            &temp_dir,
+            db,
+            Arc::new(MockGitHubClient::new()),
+        ));
 
-        // On first run, should get all repositories
-        let all_repos = db.get_all_repositories().unwrap();
-        assert_eq!(all_repos.len(), 1);
-        assert_eq!(all_repos[0].id, "test/repo1");
-    }
+        let (status, _body) =
+            post_json(app, "/api/groups/999/create-all-prs", serde_json::json!({})).await;
 
-    #[test]
-    fn test_sync_all_repos_subsequent_run_filters_by_timestamp() {
-        let (_temp_dir, _path, db) = setup_test_db();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
 
-        let now = Utc::now();
-        let old_time = now - chrono::Duration::days(5);
+    #[tokio::test]
+    async fn test_regeneration_requests_are_debounced() {
+        let (temp_dir, _path, db) = setup_test_db();
+        db.save_repository(&create_test_repo("test/repo", "test", "repo"))
+            .unwrap();
+        let state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
+        let json_path = state.static_dir.join("repos.json");
+
+        // A burst of requests returns immediately without writing synchronously
+        for _ in 0..5 {
+            state.request_regeneration();
+        }
+        assert!(!json_path.exists(), "Regeneration should be deferred");
 
-        // Set last refresh time to 2 days ago
-        let last_refresh = (now - chrono::Duration::days(2)).to_rfc3339();
-        db.set_config("last_refresh_time", &last_refresh).unwrap();
+        tokio::time::sleep(REGENERATE_DEBOUNCE * 3).await;
+        assert!(json_path.exists(), "Worker should regenerate repos.json");
+        let first_write = std::fs::metadata(&json_path).unwrap().modified().unwrap();
 
-        // Add old repo (before last refresh)
-        let mut old_repo = create_test_repo("test/old", "test", "old");
-        old_repo.pushed_at = old_time;
-        db.save_repository(&old_repo).unwrap();
+        // No further writes happen once the burst has been handled
+        tokio::time::sleep(REGENERATE_DEBOUNCE * 2).await;
+        let after_idle = std::fs::metadata(&json_path).unwrap().modified().unwrap();
+        assert_eq!(first_write, after_idle);
+    }
 
-        // Add recent repo (after last refresh)
-        let mut recent_repo = create_test_repo("test/recent", "test", "recent");
-        recent_repo.pushed_at = now - chrono::Duration::hours(1);
-        db.save_repository(&recent_repo).unwrap();
+    #[tokio::test]
+    async fn test_refresh_repo_unknown_repo_returns_not_found() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let app = build_router(create_test_state(
+            &temp_dir,
+            db,
+            Arc::new(MockGitHubClient::new()),
+        ));
+
+        let (status, body) = post_json(
+            app,
+            "/api/repos/owner/missing/refresh",
+            serde_json::json!({}),
+        )
+        .await;
 
-        // Should only get repos updated since last refresh
-        let updated_repos = db.get_repositories_updated_since(&last_refresh).unwrap();
-        assert_eq!(updated_repos.len(), 1);
-        assert_eq!(updated_repos[0].id, "test/recent");
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["success"], false);
     }
 
     #[test]
-    fn test_sync_updates_last_refresh_timestamp() {
-        let (_temp_dir, _path, db) = setup_test_db();
+    fn test_refresh_local_status_rechecks_stored_path() {
+        let (temp_dir, _path, db) = setup_test_db();
+        db.save_repository(&create_test_repo("owner/repo", "owner", "repo"))
+            .unwrap();
+
+        // Local clones live at <root>/<owner>/<name>
+        let repo_path = temp_dir.path().join("owner").join("repo");
+        std::fs::create_dir_all(&repo_path).unwrap();
+        std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&repo_path)
+            .status()
+            .unwrap();
+        std::fs::write(repo_path.join("untracked.txt"), "changes").unwrap();
+
+        let stale = LocalRepoStatus {
+            id: 0,
+            repo_id: "owner/repo".to_string(),
+            local_path: repo_path.to_string_lossy().to_string(),
+            current_branch: None,
+            uncommitted_files: 0,
+            unpushed_commits: 0,
+            behind_commits: 0,
+            is_dirty: false,
+            has_conflicts: false,
+            is_diverged: false,
+            is_unpublished: false,
+            last_checked: Utc::now() - chrono::Duration::days(1),
+        };
+        db.save_local_repo_status(&stale).unwrap();
 
-        let before_sync = Utc::now();
+        let fresh = refresh_local_status(&db, "owner/repo").unwrap();
+        assert_eq!(fresh.uncommitted_files, 1);
+        assert!(fresh.is_dirty);
+        assert!(fresh.last_checked > stale.last_checked);
+    }
 
-        // Simulate sync by setting last_refresh_time
-        let timestamp = Utc::now().to_rfc3339();
-        db.set_config("last_refresh_time", &timestamp).unwrap();
+    #[test]
+    fn test_refresh_local_status_keeps_stored_status_for_missing_path() {
+        let (temp_dir, _path, db) = setup_test_db();
+        db.save_repository(&create_test_repo("owner/gone", "owner", "gone"))
+            .unwrap();
+
+        let stored = LocalRepoStatus {
+            id: 0,
+            repo_id: "owner/gone".to_string(),
+            local_path: temp_dir.path().join("gone").to_string_lossy().to_string(),
+            current_branch: Some("main".to_string()),
+            uncommitted_files: 2,
+            unpushed_commits: 0,
+            behind_commits: 0,
+            is_dirty: true,
+            has_conflicts: false,
+            is_diverged: false,
+            is_unpublished: false,
+            last_checked: Utc::now(),
+        };
+        db.save_local_repo_status(&stored).unwrap();
 
-        // Verify it was set
-        let stored_time = db.get_config("last_refresh_time").unwrap();
-        assert!(stored_time.is_some());
+        let status = refresh_local_status(&db, "owner/gone").unwrap();
+        assert_eq!(status.uncommitted_files, 2);
+        assert!(refresh_local_status(&db, "owner/untracked").is_none());
+    }
 
-        let stored_timestamp: chrono::DateTime<Utc> = stored_time.unwrap().parse().unwrap();
-        assert!(stored_timestamp >= before_sync);
+    #[tokio::test]
+    async fn test_get_branch_diff_returns_plain_text() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let mock = Arc::new(MockGitHubClient::new().with_diff(
+            "owner/repo",
+            "main",
+            "feature/x",
+            "diff --git a/file b/file\n",
+        ));
+        let app = build_router(create_test_state(&temp_dir, db, mock));
+
+        let request = Request::builder()
+            .uri("/api/repos/owner/repo/branches/feature%2Fx/diff?base=main")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"diff --git a/file b/file\n");
     }
 
-    #[test]
-    fn test_clear_removes_all_old_data() {
-        use crate::models::{Branch, BranchStatus, PRState, PullRequest};
+    #[tokio::test]
+    async fn test_get_commit_message_returns_full_message() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let message = format!("Squash merge\n\n{}", "* change\n".repeat(500));
+        let mock = Arc::new(MockGitHubClient::new().with_commit_message(
+            "owner/repo",
+            "abc1234",
+            &message,
+        ));
+        let app = build_router(create_test_state(&temp_dir, db, mock));
+
+        let request = Request::builder()
+            .uri("/api/repos/owner/repo/commits/abc1234/message")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], message.as_bytes());
+
+        let (status, body) = get_json(app, "/api/repos/owner/repo/commits/fff0000/message").await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["success"], false);
+    }
 
-        let (_temp_dir, _path, db) = setup_test_db();
+    #[tokio::test]
+    async fn test_get_branch_commits_returns_every_commit() {
+        use crate::test_support::fixtures::CommitBuilder;
+
+        let (temp_dir, _path, db) = setup_test_db();
+        db.save_repository(&create_test_repo("owner/repo", "owner", "repo"))
+            .unwrap();
+        let branch_id = db
+            .save_branch(&BranchBuilder::new("feature/x").build("owner/repo"))
+            .unwrap();
+        let count = crate::export::EXPORT_COMMITS_PER_BRANCH + 5;
+        for i in 0..count {
+            db.save_commit(&CommitBuilder::new(&format!("sha{}", i), "Work").build(branch_id))
+                .unwrap();
+        }
+        let app = build_router(create_test_state(
+            &temp_dir,
+            db,
+            Arc::new(MockGitHubClient::new()),
+        ));
+
+        let (status, body) = get_json(
+            app.clone(),
+            "/api/repos/owner/repo/branches/feature%2Fx/commits",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.as_array().unwrap().len(), count);
+        assert!(body[0]["shortSha"].is_string());
 
-        // Create test repository
-        let repo = create_test_repo("test/repo", "test", "repo");
-        db.save_repository(&repo).unwrap();
+        let (status, _) = get_json(app, "/api/repos/owner/repo/branches/missing/commits").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
 
-        // Add 3 branches
-        for i in 1..=3 {
-            let branch = Branch {
-                id: i,
-                repo_id: repo.id.clone(),
-                name: format!("branch-{}", i),
-                sha: format!("sha{}", i),
-                ahead_by: 1,
-                behind_by: 0,
-                status: BranchStatus::ReadyForPR,
-                last_commit_date: Utc::now(),
-            };
-            db.save_branch(&branch).unwrap();
+    #[tokio::test]
+    async fn test_scan_local_repos_dedupes_overlapping_roots() {
+        let (temp_dir, _path, db) = setup_test_db();
+        db.save_repository(&create_test_repo("owner/repo", "owner", "repo"))
+            .unwrap();
+
+        let owner_dir = temp_dir.path().join("owner");
+        let repo_path = owner_dir.join("repo");
+        std::fs::create_dir_all(&repo_path).unwrap();
+        std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&repo_path)
+            .status()
+            .unwrap();
+
+        // Two spellings of the same root both discover the repo
+        db.add_local_repo_root(&owner_dir.to_string_lossy())
+            .unwrap();
+        db.add_local_repo_root(&owner_dir.join(".").to_string_lossy())
+            .unwrap();
+
+        let state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
+        let (status, body) = post_json(
+            build_router(state.clone()),
+            "/api/local-repos/scan",
+            serde_json::json!({}),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Scanned 1 repositories"));
+        let statuses = state
+            .db
+            .lock()
+            .unwrap()
+            .get_all_local_repo_statuses()
+            .unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].repo_id, "owner/repo");
+    }
+
+    #[tokio::test]
+    async fn test_local_scan_matches_clones_by_origin() {
+        let (temp_dir, _path, db) = setup_test_db();
+        db.save_repository(&create_test_repo("owner/repo", "owner", "repo"))
+            .unwrap();
+
+        // Cloned into a directory named after neither the owner nor the repo
+        let clones = temp_dir.path().join("work");
+        let repo_path = clones.join("my-checkout");
+        std::fs::create_dir_all(&repo_path).unwrap();
+        for args in [
+            vec!["init", "-q"],
+            vec![
+                "remote",
+                "add",
+                "origin",
+                "https://github.com/owner/repo.git",
+            ],
+        ] {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .status()
+                .unwrap();
         }
+        db.add_local_repo_root(&clones.to_string_lossy()).unwrap();
+        let db = Mutex::new(db);
 
-        // Verify 3 branches exist
-        let branches = db.get_branches_for_repo(&repo.id).unwrap();
-        assert_eq!(branches.len(), 3);
+        let roots = db.lock().unwrap().get_all_local_repo_roots().unwrap();
+        let (scanned, results) = scan_local_roots(&db, &roots);
 
-        // Clear branches
-        db.clear_branches_for_repo(&repo.id).unwrap();
+        assert_eq!(scanned, 1);
+        assert_eq!(results, vec!["Scanned: owner/repo".to_string()]);
+        let db = db.lock().unwrap();
+        let status = db.get_local_repo_status("owner/repo").unwrap().unwrap();
+        assert_eq!(
+            std::path::Path::new(&status.local_path),
+            repo_path.canonicalize().unwrap()
+        );
+        assert!(crate::analysis::find_local_only_repos(&db)
+            .unwrap()
+            .is_empty());
+    }
 
-        // Verify all branches are gone
-        let branches = db.get_branches_for_repo(&repo.id).unwrap();
-        assert_eq!(branches.len(), 0, "Stale branches were not cleared!");
+    #[tokio::test]
+    async fn test_local_scan_reports_untracked_clones() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let owner_dir = temp_dir.path().join("owner");
+        let repo_path = owner_dir.join("scratch");
+        std::fs::create_dir_all(&repo_path).unwrap();
+        std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&repo_path)
+            .status()
+            .unwrap();
+        db.add_local_repo_root(&owner_dir.to_string_lossy())
+            .unwrap();
+        let app = build_router(create_test_state(
+            &temp_dir,
+            db,
+            Arc::new(MockGitHubClient::new()),
+        ));
+
+        let (status, body) =
+            post_json(app.clone(), "/api/local-repos/scan", serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["message"]
+            .as_str()
+            .unwrap()
+            .contains("Not tracked from GitHub: owner/scratch"));
 
-        // Same test for PRs
-        for i in 1..=2 {
-            let pr = PullRequest {
-                id: i,
-                repo_id: repo.id.clone(),
-                branch_id: None,
-                number: i as u32,
-                state: PRState::Open,
-                title: format!("PR {}", i),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-            };
-            db.save_pull_request(&pr).unwrap();
-        }
+        let (status, body) = get_json(app, "/api/local-only").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body[0]["repoId"], "owner/scratch");
+        assert!(body[0]["remoteRepoId"].is_null());
+    }
 
-        let prs = db.get_pull_requests_for_repo(&repo.id).unwrap();
-        assert_eq!(prs.len(), 2);
+    #[tokio::test]
+    async fn test_refresh_all_lists_owners_and_scans_local_roots() {
+        let (temp_dir, _path, db) = setup_test_db();
+        let mut tracked = create_test_repo("owner/repo", "owner", "repo");
+        tracked.priority = 0.9;
+        db.save_repository(&tracked).unwrap();
+
+        let repo_path = temp_dir.path().join("clones").join("repo");
+        std::fs::create_dir_all(&repo_path).unwrap();
+        std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&repo_path)
+            .status()
+            .unwrap();
+        db.add_local_repo_root(&temp_dir.path().join("clones").to_string_lossy())
+            .unwrap();
+
+        let mut relisted = create_test_repo("owner/repo", "owner", "repo");
+        relisted.priority = 0.0;
+        let mock = Arc::new(
+            MockGitHubClient::new()
+                .with_repo("owner", relisted)
+                .with_repo("owner", create_test_repo("owner/new", "owner", "new"))
+                .with_branches(
+                    "owner/repo",
+                    vec![BranchBuilder::new("feature").ahead(1).build("owner/repo")],
+                )
+                .with_failing_pull_requests("owner/new"),
+        );
+        let state = create_test_state(&temp_dir, db, mock);
 
-        db.clear_pull_requests_for_repo(&repo.id).unwrap();
+        let (status, body) = post_json(
+            build_router(state.clone()),
+            "/api/refresh-all",
+            serde_json::json!({}),
+        )
+        .await;
 
-        let prs = db.get_pull_requests_for_repo(&repo.id).unwrap();
-        assert_eq!(prs.len(), 0, "Stale PRs were not cleared!");
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["localReposScanned"], 1);
+        assert_eq!(body["reposSynced"], 1, "Only owner/repo should sync");
+        assert_eq!(
+            body["errors"],
+            serde_json::json!(["Failed to fetch PRs for owner/new: GitHub API error: 502 - Bad gateway fetching PRs for owner/new"])
+        );
+
+        let db = state.db.lock().unwrap();
+        let repos = db.get_all_repositories().unwrap();
+        assert_eq!(repos.len(), 2, "Newly listed repo should be tracked");
+        let kept = repos.iter().find(|r| r.id == "owner/repo").unwrap();
+        assert_eq!(kept.priority, 0.9, "Priority should survive the re-list");
+        let branches = db.get_branches_for_repo("owner/repo").unwrap();
+        assert_eq!(branches.len(), 1, "Branches should come from the client");
+        assert_eq!(branches[0].name, "feature");
+    }
+
+    #[tokio::test]
+    async fn test_regenerate_export_reports_path_and_counts() {
+        let (temp_dir, _path, db) = setup_test_db();
+        db.save_repository(&create_test_repo("owner/grouped", "owner", "grouped"))
+            .unwrap();
+        db.save_repository(&create_test_repo("owner/loose", "owner", "loose"))
+            .unwrap();
+        let group_id = db.create_group("Work", 0).unwrap();
+        db.add_repo_to_group("owner/grouped", group_id).unwrap();
+
+        let state = create_test_state(&temp_dir, db, Arc::new(MockGitHubClient::new()));
+        let (status, body) = post_json(
+            build_router(state.clone()),
+            "/api/export/regenerate",
+            serde_json::json!({}),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["groups"], 1);
+        assert_eq!(body["repos"], 2);
+        let written = state.static_dir.join("repos.json");
+        assert_eq!(body["path"], written.to_string_lossy().as_ref());
+        assert!(written.exists());
     }
 }
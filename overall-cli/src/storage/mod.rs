@@ -1,17 +1,25 @@
 //! Local SQLite storage
 
 use crate::{
-    models::{Branch, BranchStatus, Commit, Group, PRState, PullRequest, Repository},
+    analysis::{snoozable_repo_status, RepoStatus},
+    config::Config,
+    models::{
+        is_protected_branch, matches_repo_pattern, normalize_tag, validate_group_name, Branch,
+        BranchStatus, CiStatus, Commit, DashboardCounts, Group, GroupSummary, LocalRepoStatus,
+        PRState, PullRequest, Repository, SyncNeed, UNGROUPED_GROUP_NAME,
+    },
     Result,
 };
-use chrono::Utc;
-use rusqlite::{params, Connection};
-use std::path::Path;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, DatabaseName, OptionalExtension};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 const SCHEMA_SQL: &str = include_str!("schema.sql");
 
 pub struct Database {
     conn: Connection,
+    max_commit_body_len: Option<usize>,
 }
 
 impl Database {
@@ -24,13 +32,215 @@ impl Database {
         // Create schema if not exists
         conn.execute_batch(SCHEMA_SQL)?;
 
-        Ok(Database { conn })
+        let db = Database {
+            conn,
+            max_commit_body_len: None,
+        };
+        db.migrate()?;
+
+        Ok(db)
+    }
+
+    /// Limit the commit body stored by `save_commit`
+    ///
+    /// Subjects are always kept; bodies longer than `max_len` characters are
+    /// cut off. `None` (the default) stores messages in full.
+    pub fn set_max_commit_body_len(&mut self, max_len: Option<usize>) {
+        self.max_commit_body_len = max_len;
+    }
+
+    /// Copy the database to `path` using SQLite's online backup API
+    pub fn backup_to(&self, path: &Path) -> Result<()> {
+        self.conn.backup(DatabaseName::Main, path, None)?;
+        Ok(())
+    }
+
+    /// Back up to `backups/overall-<timestamp>.db` next to the database file,
+    /// then delete all but the newest `keep` backups
+    ///
+    /// Snapshots taken within the same millisecond get a `-<n>` suffix rather
+    /// than overwriting each other.
+    pub fn backup_snapshot(&self, keep: usize) -> Result<PathBuf> {
+        let db_path = self.conn.path().map(PathBuf::from).unwrap_or_default();
+        let backup_dir = db_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("backups");
+        std::fs::create_dir_all(&backup_dir)?;
+
+        let timestamp = Utc::now().format("%Y%m%d-%H%M%S%.3f").to_string();
+        let mut attempt = 0;
+        let backup_path = loop {
+            let name = match attempt {
+                0 => format!("overall-{}.db", timestamp),
+                n => format!("overall-{}-{}.db", timestamp, n),
+            };
+            let path = backup_dir.join(name);
+            // Claim the name first so a concurrent snapshot can't take it too
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => break path,
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => attempt += 1,
+                Err(e) => return Err(e.into()),
+            }
+        };
+        self.backup_to(&backup_path)?;
+
+        // Timestamps (then suffixes) sort lexically by file stem, so the
+        // oldest backups come first
+        let mut backups: Vec<PathBuf> = std::fs::read_dir(&backup_dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with("overall-") && n.ends_with(".db"))
+            })
+            .collect();
+        backups.sort_by(|a, b| a.file_stem().cmp(&b.file_stem()));
+        let excess = backups.len().saturating_sub(keep.max(1));
+        for old in &backups[..excess] {
+            std::fs::remove_file(old)?;
+        }
+
+        Ok(backup_path)
+    }
+
+    /// Bring databases created by older versions up to the current schema
+    ///
+    /// `CREATE TABLE IF NOT EXISTS` never alters an existing table, so columns
+    /// added after a table was first created are added here.
+    fn migrate(&self) -> Result<()> {
+        self.add_column_if_missing("pull_requests", "head_ref_name", "TEXT NOT NULL DEFAULT ''")?;
+        self.add_column_if_missing(
+            "local_repo_status",
+            "has_conflicts",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+        self.add_column_if_missing(
+            "local_repo_status",
+            "is_diverged",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+        self.add_column_if_missing(
+            "local_repo_status",
+            "is_unpublished",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+        self.add_column_if_missing("commits", "is_verified", "INTEGER NOT NULL DEFAULT 0")?;
+        self.add_column_if_missing("pull_requests", "url", "TEXT")?;
+        self.add_column_if_missing("pull_requests", "review_decision", "TEXT")?;
+        self.add_column_if_missing("repositories", "is_private", "INTEGER NOT NULL DEFAULT 0")?;
+        self.add_column_if_missing(
+            "repositories",
+            "priority_locked",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+        self.add_column_if_missing("repositories", "github_id", "TEXT")?;
+        self.add_column_if_missing("repositories", "snoozed_until", "TEXT")?;
+        self.conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_repositories_github_id
+                 ON repositories(github_id) WHERE github_id IS NOT NULL",
+            [],
+        )?;
+
+        // Group names are unique ignoring case. Databases that already hold
+        // names differing only in case keep working without the index.
+        let has_case_duplicates: bool = self.conn.query_row(
+            "SELECT EXISTS (
+                 SELECT 1 FROM groups GROUP BY name COLLATE NOCASE HAVING COUNT(*) > 1
+             )",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_case_duplicates {
+            self.conn.execute(
+                "CREATE UNIQUE INDEX IF NOT EXISTS idx_groups_name_nocase
+                     ON groups(name COLLATE NOCASE)",
+                [],
+            )?;
+        }
+
+        // repo_id is the stable key for local statuses. Older databases may
+        // hold several rows per repo (one per discovered path); keep the most
+        // recently checked one before enforcing uniqueness.
+        self.conn.execute_batch(
+            "DELETE FROM local_repo_status
+             WHERE id NOT IN (
+                 SELECT id FROM local_repo_status AS s
+                 WHERE s.last_checked = (
+                     SELECT MAX(last_checked) FROM local_repo_status
+                     WHERE repo_id = s.repo_id
+                 )
+                 GROUP BY repo_id
+             );
+             CREATE UNIQUE INDEX IF NOT EXISTS idx_local_repo_status_repo_id_unique
+                 ON local_repo_status(repo_id);",
+        )?;
+        Ok(())
+    }
+
+    fn add_column_if_missing(&self, table: &str, column: &str, definition: &str) -> Result<()> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT name FROM pragma_table_info('{}')", table))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?
+            .iter()
+            .any(|name| name == column);
+
+        if !exists {
+            self.conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                [],
+            )?;
+        }
+        Ok(())
     }
 
+    /// Insert or update a repository
+    ///
+    /// Updating in place (rather than replacing the row) keeps the repo's
+    /// group memberships and other cascading rows. A locked priority and the
+    /// lock itself are never changed here; see `set_priority_override`.
+    ///
+    /// When another row already holds `repo.github_id`, the repository was
+    /// renamed or transferred on GitHub and that row is moved to the new id
+    /// first, keeping its history and groups.
     pub fn save_repository(&self, repo: &Repository) -> Result<()> {
+        if let Some(github_id) = &repo.github_id {
+            let previous_id: Option<String> = self
+                .conn
+                .query_row(
+                    "SELECT id FROM repositories WHERE github_id = ?1 AND id != ?2",
+                    params![github_id, &repo.id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if let Some(previous_id) = previous_id {
+                self.rename_repository(&previous_id, &repo.id)?;
+            }
+        }
+
         self.conn.execute(
-            "INSERT OR REPLACE INTO repositories (id, owner, name, language, description, pushed_at, created_at, updated_at, is_fork, priority)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            "INSERT INTO repositories (id, owner, name, language, description, pushed_at, created_at, updated_at, is_fork, priority, is_private, priority_locked, github_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+             ON CONFLICT(id) DO UPDATE SET
+                 owner = excluded.owner,
+                 name = excluded.name,
+                 language = excluded.language,
+                 description = excluded.description,
+                 pushed_at = excluded.pushed_at,
+                 created_at = excluded.created_at,
+                 updated_at = excluded.updated_at,
+                 is_fork = excluded.is_fork,
+                 priority = CASE WHEN repositories.priority_locked
+                     THEN repositories.priority ELSE excluded.priority END,
+                 is_private = excluded.is_private,
+                 github_id = COALESCE(excluded.github_id, repositories.github_id)",
             params![
                 &repo.id,
                 &repo.owner,
@@ -42,44 +252,176 @@ impl Database {
                 &repo.updated_at.to_rfc3339(),
                 repo.is_fork as i32,
                 repo.priority,
+                repo.is_private as i32,
+                repo.priority_locked as i32,
+                &repo.github_id,
             ],
         )?;
         Ok(())
     }
 
+    /// Re-key a repository and every row that references it
+    ///
+    /// A row already stored under `new_id` (saved by a scan before the rename
+    /// was detected) is dropped in favour of the older one. Owner and name
+    /// are left for the caller's upsert to refresh.
+    fn rename_repository(&self, old_id: &str, new_id: &str) -> Result<()> {
+        self.atomically(|db| {
+            // The parent and its children are updated one at a time, so check
+            // the foreign keys once, at commit
+            db.conn.execute_batch("PRAGMA defer_foreign_keys = ON")?;
+            db.conn
+                .execute("DELETE FROM repositories WHERE id = ?1", [new_id])?;
+            db.conn.execute(
+                "UPDATE repositories SET id = ?2 WHERE id = ?1",
+                params![old_id, new_id],
+            )?;
+            for table in [
+                "branches",
+                "pull_requests",
+                "repo_groups",
+                "repo_tags",
+                "repo_ci_status",
+                "local_repo_status",
+            ] {
+                db.conn.execute(
+                    &format!("UPDATE {} SET repo_id = ?2 WHERE repo_id = ?1", table),
+                    params![old_id, new_id],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Run `f` as one unit: if it fails, everything it wrote is rolled back
+    ///
+    /// Uses a savepoint rather than BEGIN, so calls nest: writes that are
+    /// atomic on their own can be part of a larger atomic operation.
+    pub fn atomically<T>(&self, f: impl FnOnce(&Self) -> Result<T>) -> Result<T> {
+        self.conn.execute_batch("SAVEPOINT atomically")?;
+        match f(self) {
+            Ok(value) => {
+                self.conn.execute_batch("RELEASE atomically")?;
+                Ok(value)
+            }
+            Err(e) => {
+                let _ = self
+                    .conn
+                    .execute_batch("ROLLBACK TO atomically; RELEASE atomically");
+                Err(e)
+            }
+        }
+    }
+
+    /// Ids of tracked repositories starting with `prefix` (ignoring case),
+    /// sorted, for shell completion
+    pub fn get_repo_ids_with_prefix(&self, prefix: &str) -> Result<Vec<String>> {
+        // substr rather than LIKE, so `_` and `%` in the prefix match literally
+        let mut stmt = self.conn.prepare(
+            "SELECT id FROM repositories
+             WHERE lower(substr(id, 1, length(?1))) = lower(?1)
+             ORDER BY id",
+        )?;
+
+        let ids = stmt
+            .query_map(params![prefix], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(ids)
+    }
+
     pub fn get_all_repositories(&self) -> Result<Vec<Repository>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, owner, name, language, description, pushed_at, created_at, updated_at, is_fork, priority
+            "SELECT id, owner, name, language, description, pushed_at, created_at, updated_at, is_fork, priority, is_private, priority_locked, github_id, snoozed_until
              FROM repositories
              ORDER BY priority DESC, pushed_at DESC"
         )?;
 
         let repos = stmt
-            .query_map([], |row| {
-                Ok(Repository {
-                    id: row.get(0)?,
-                    owner: row.get(1)?,
-                    name: row.get(2)?,
-                    language: row.get(3)?,
-                    description: row.get(4)?,
-                    pushed_at: row.get::<_, String>(5)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                    created_at: row.get::<_, String>(6)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                    updated_at: row.get::<_, String>(7)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                    is_fork: row.get::<_, i32>(8)? != 0,
-                    priority: row.get(9)?,
-                })
-            })?
+            .query_map([], repository_from_row)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(repos)
     }
 
+    /// Tracked repositories with no local clone recorded by a local scan,
+    /// ordered by id
+    pub fn get_repositories_without_local_clone(&self) -> Result<Vec<Repository>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, owner, name, language, description, pushed_at, created_at, updated_at, is_fork, priority, is_private, priority_locked, github_id, snoozed_until
+             FROM repositories
+             WHERE id NOT IN (SELECT repo_id FROM local_repo_status)
+             ORDER BY id"
+        )?;
+
+        let repos = stmt
+            .query_map([], repository_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(repos)
+    }
+
+    /// Visit every repository in `get_all_repositories` order, one row at a
+    /// time, without loading them all into memory
+    ///
+    /// Stops at the first error returned by `f`.
+    pub fn for_each_repository(&self, mut f: impl FnMut(Repository) -> Result<()>) -> Result<()> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, owner, name, language, description, pushed_at, created_at, updated_at, is_fork, priority, is_private, priority_locked, github_id, snoozed_until
+             FROM repositories
+             ORDER BY priority DESC, pushed_at DESC"
+        )?;
+
+        for repo in stmt.query_map([], repository_from_row)? {
+            f(repo?)?;
+        }
+        Ok(())
+    }
+
+    pub fn get_repository(&self, repo_id: &str) -> Result<Option<Repository>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, owner, name, language, description, pushed_at, created_at, updated_at, is_fork, priority, is_private, priority_locked, github_id, snoozed_until
+             FROM repositories
+             WHERE id = ?1"
+        )?;
+
+        let mut rows = stmt.query(params![repo_id])?;
+
+        if let Some(row) = rows.next()? {
+            Ok(Some(repository_from_row(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Stop tracking a repository, removing its branches, commits, PRs,
+    /// group memberships and local status; returns false if it wasn't tracked
+    pub fn delete_repository(&self, repo_id: &str) -> Result<bool> {
+        self.atomically(|db| {
+            db.conn.execute(
+                "DELETE FROM commits WHERE branch_id IN (SELECT id FROM branches WHERE repo_id = ?1)",
+                [repo_id],
+            )?;
+            for table in [
+                "pull_requests",
+                "branches",
+                "repo_groups",
+                "repo_tags",
+                "repo_ci_status",
+                "local_repo_status",
+            ] {
+                db.conn.execute(
+                    &format!("DELETE FROM {} WHERE repo_id = ?1", table),
+                    [repo_id],
+                )?;
+            }
+            let deleted = db
+                .conn
+                .execute("DELETE FROM repositories WHERE id = ?1", [repo_id])?;
+            Ok(deleted > 0)
+        })
+    }
+
     pub fn save_branch(&self, branch: &Branch) -> Result<i64> {
         self.conn.execute(
             "INSERT OR REPLACE INTO branches (repo_id, name, sha, ahead_by, behind_by, status, last_commit_date)
@@ -106,36 +448,84 @@ impl Database {
         )?;
 
         let branches = stmt
-            .query_map([repo_id], |row| {
-                let status_str: String = row.get(6)?;
-                Ok(Branch {
-                    id: row.get(0)?,
-                    repo_id: row.get(1)?,
-                    name: row.get(2)?,
-                    sha: row.get(3)?,
-                    ahead_by: row.get::<_, i64>(4)? as u32,
-                    behind_by: row.get::<_, i64>(5)? as u32,
-                    status: status_str.parse().unwrap_or(BranchStatus::ReadyForPR),
-                    last_commit_date: row.get::<_, String>(7)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                })
-            })?
+            .query_map([repo_id], branch_from_row)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(branches)
     }
 
+    /// Store a computed priority, returning false when the repo's priority
+    /// is locked by a manual override (or the repo does not exist)
+    pub fn update_repository_priority(&self, repo_id: &str, priority: f32) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE repositories SET priority = ?1 WHERE id = ?2 AND priority_locked = 0",
+            params![priority, repo_id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Manually set a repository's priority and lock it, or unlock it with
+    /// `None` so the next `reprioritize` recomputes it
+    ///
+    /// Returns false when the repository does not exist.
+    pub fn set_priority_override(&self, repo_id: &str, priority: Option<f32>) -> Result<bool> {
+        let updated = match priority {
+            Some(priority) => self.conn.execute(
+                "UPDATE repositories SET priority = ?1, priority_locked = 1 WHERE id = ?2",
+                params![priority, repo_id],
+            )?,
+            None => self.conn.execute(
+                "UPDATE repositories SET priority_locked = 0 WHERE id = ?1",
+                params![repo_id],
+            )?,
+        };
+        Ok(updated > 0)
+    }
+
+    /// Snooze a repository until `until`, or wake it with `None`
+    ///
+    /// A snoozed repository counts as complete in statuses and attention
+    /// counts until the time passes; nothing needs to clear an expired one.
+    /// Returns false when the repository does not exist.
+    pub fn set_snooze(&self, repo_id: &str, until: Option<DateTime<Utc>>) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE repositories SET snoozed_until = ?1 WHERE id = ?2",
+            params![until.map(|until| until.to_rfc3339()), repo_id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    pub fn update_branch_status(&self, branch_id: i64, status: &BranchStatus) -> Result<()> {
+        self.conn.execute(
+            "UPDATE branches SET status = ?1 WHERE id = ?2",
+            params![status.to_string(), branch_id],
+        )?;
+        Ok(())
+    }
+
+    /// Record that a branch's work landed on the default branch (e.g. its PR
+    /// was just merged) until the next scan compares it again
+    pub fn mark_branch_merged(&self, repo_id: &str, name: &str) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE branches SET ahead_by = 0 WHERE repo_id = ?1 AND name = ?2",
+            params![repo_id, name],
+        )?;
+        Ok(updated > 0)
+    }
+
     pub fn save_pull_request(&self, pr: &PullRequest) -> Result<i64> {
         self.conn.execute(
-            "INSERT OR REPLACE INTO pull_requests (repo_id, branch_id, number, state, title, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT OR REPLACE INTO pull_requests (repo_id, branch_id, number, state, title, head_ref_name, url, review_decision, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 &pr.repo_id,
                 pr.branch_id,
                 pr.number as i64,
                 pr.state.to_string(),
                 &pr.title,
+                &pr.head_ref_name,
+                &pr.url,
+                pr.review_decision.map(|d| d.to_string()),
                 &pr.created_at.to_rfc3339(),
                 &pr.updated_at.to_rfc3339(),
             ],
@@ -143,9 +533,24 @@ impl Database {
         Ok(self.conn.last_insert_rowid())
     }
 
+    /// Set the state of a stored pull request, returning false when it is
+    /// not stored
+    pub fn update_pull_request_state(
+        &self,
+        repo_id: &str,
+        number: u32,
+        state: PRState,
+    ) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE pull_requests SET state = ?1 WHERE repo_id = ?2 AND number = ?3",
+            params![state.to_string(), repo_id, number as i64],
+        )?;
+        Ok(updated > 0)
+    }
+
     pub fn get_pull_requests_for_repo(&self, repo_id: &str) -> Result<Vec<PullRequest>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, repo_id, branch_id, number, state, title, created_at, updated_at
+            "SELECT id, repo_id, branch_id, number, state, title, head_ref_name, url, review_decision, created_at, updated_at
              FROM pull_requests
              WHERE repo_id = ?1
              ORDER BY number DESC",
@@ -161,10 +566,15 @@ impl Database {
                     number: row.get::<_, i64>(3)? as u32,
                     state: state_str.parse().unwrap_or(PRState::Closed),
                     title: row.get(5)?,
-                    created_at: row.get::<_, String>(6)?.parse().map_err(|_| {
+                    head_ref_name: row.get(6)?,
+                    url: row.get(7)?,
+                    review_decision: row
+                        .get::<_, Option<String>>(8)?
+                        .and_then(|d| d.parse().ok()),
+                    created_at: row.get::<_, String>(9)?.parse().map_err(|_| {
                         rusqlite::Error::InvalidParameterName("Invalid date".to_string())
                     })?,
-                    updated_at: row.get::<_, String>(7)?.parse().map_err(|_| {
+                    updated_at: row.get::<_, String>(10)?.parse().map_err(|_| {
                         rusqlite::Error::InvalidParameterName("Invalid date".to_string())
                     })?,
                 })
@@ -188,57 +598,92 @@ impl Database {
         Ok(())
     }
 
+    /// Insert a commit, or update the one already stored for the same branch
+    /// and SHA, returning its row id
+    ///
+    /// `commit.id` is ignored: fetched commits carry a synthetic index, so
+    /// `(branch_id, sha)` is the only stable key. An existing row keeps its id.
     pub fn save_commit(&self, commit: &Commit) -> Result<i64> {
-        self.conn.execute(
-            "INSERT OR REPLACE INTO commits (branch_id, sha, message, author_name, author_email, authored_date, committer_name, committer_email, committed_date)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        let message = match self.max_commit_body_len {
+            Some(max_len) => truncate_commit_message(&commit.message, max_len),
+            None => commit.message.clone(),
+        };
+
+        let id = self.conn.query_row(
+            "INSERT INTO commits (branch_id, sha, message, author_name, author_email, authored_date, committer_name, committer_email, committed_date, is_verified)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(branch_id, sha) DO UPDATE SET
+                 message = excluded.message,
+                 author_name = excluded.author_name,
+                 author_email = excluded.author_email,
+                 authored_date = excluded.authored_date,
+                 committer_name = excluded.committer_name,
+                 committer_email = excluded.committer_email,
+                 committed_date = excluded.committed_date,
+                 is_verified = excluded.is_verified
+             RETURNING id",
             params![
                 commit.branch_id,
                 &commit.sha,
-                &commit.message,
+                &message,
                 &commit.author_name,
                 &commit.author_email,
                 &commit.authored_date.to_rfc3339(),
                 &commit.committer_name,
                 &commit.committer_email,
                 &commit.committed_date.to_rfc3339(),
+                commit.is_verified as i32,
             ],
+            |row| row.get(0),
         )?;
-        Ok(self.conn.last_insert_rowid())
+        Ok(id)
     }
 
     pub fn get_commits_for_branch(&self, branch_id: i64) -> Result<Vec<Commit>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, branch_id, sha, message, author_name, author_email, authored_date, committer_name, committer_email, committed_date
+            "SELECT id, branch_id, sha, message, author_name, author_email, authored_date, committer_name, committer_email, committed_date, is_verified
              FROM commits
              WHERE branch_id = ?1
              ORDER BY committed_date DESC"
         )?;
 
         let commits = stmt
-            .query_map([branch_id], |row| {
-                Ok(Commit {
-                    id: row.get(0)?,
-                    branch_id: row.get(1)?,
-                    sha: row.get(2)?,
-                    message: row.get(3)?,
-                    author_name: row.get(4)?,
-                    author_email: row.get(5)?,
-                    authored_date: row.get::<_, String>(6)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                    committer_name: row.get(7)?,
-                    committer_email: row.get(8)?,
-                    committed_date: row.get::<_, String>(9)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                })
-            })?
+            .query_map([branch_id], commit_from_row)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(commits)
     }
 
+    /// The `limit` most recent commits of a branch, newest first
+    pub fn get_commits_for_branch_limited(
+        &self,
+        branch_id: i64,
+        limit: usize,
+    ) -> Result<Vec<Commit>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, branch_id, sha, message, author_name, author_email, authored_date, committer_name, committer_email, committed_date, is_verified
+             FROM commits
+             WHERE branch_id = ?1
+             ORDER BY committed_date DESC
+             LIMIT ?2"
+        )?;
+
+        let commits = stmt
+            .query_map(params![branch_id, limit as i64], commit_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(commits)
+    }
+
+    pub fn count_commits_for_branch(&self, branch_id: i64) -> Result<usize> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM commits WHERE branch_id = ?1",
+            params![branch_id],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
     pub fn clear_commits_for_branch(&self, branch_id: i64) -> Result<()> {
         self.conn.execute(
             "DELETE FROM commits WHERE branch_id = ?1",
@@ -249,6 +694,7 @@ impl Database {
 
     // Group management methods
     pub fn create_group(&self, name: &str, display_order: i32) -> Result<i64> {
+        validate_group_name(name)?;
         self.conn.execute(
             "INSERT INTO groups (name, display_order, created_at) VALUES (?1, ?2, ?3)",
             params![name, display_order, Utc::now().to_rfc3339()],
@@ -256,6 +702,30 @@ impl Database {
         Ok(self.conn.last_insert_rowid())
     }
 
+    /// Return the id of the group with this name (ignoring ASCII case), or
+    /// create it after all existing groups
+    pub fn get_or_create_group(&self, name: &str) -> Result<i64> {
+        validate_group_name(name)?;
+        let existing = self
+            .conn
+            .query_row(
+                "SELECT id FROM groups WHERE name = ?1 COLLATE NOCASE",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(id) = existing {
+            return Ok(id);
+        }
+
+        let next_order: i32 = self.conn.query_row(
+            "SELECT COALESCE(MAX(display_order), -1) + 1 FROM groups",
+            [],
+            |row| row.get(0),
+        )?;
+        self.create_group(name, next_order)
+    }
+
     pub fn get_all_groups(&self) -> Result<Vec<Group>> {
         let mut stmt = self.conn.prepare(
             "SELECT id, name, display_order, created_at FROM groups ORDER BY display_order",
@@ -293,9 +763,81 @@ impl Database {
         Ok(())
     }
 
+    /// Tag a repository, returning false when it already had the tag
+    ///
+    /// The tag is normalized with `models::normalize_tag`.
+    pub fn add_tag(&self, repo_id: &str, tag: &str) -> Result<bool> {
+        let tag = normalize_tag(tag)?;
+        let added = self.conn.execute(
+            "INSERT OR IGNORE INTO repo_tags (repo_id, tag) VALUES (?1, ?2)",
+            params![repo_id, tag],
+        )?;
+        Ok(added > 0)
+    }
+
+    /// Remove a tag from a repository, returning false when it wasn't tagged
+    pub fn remove_tag(&self, repo_id: &str, tag: &str) -> Result<bool> {
+        let tag = normalize_tag(tag)?;
+        let removed = self.conn.execute(
+            "DELETE FROM repo_tags WHERE repo_id = ?1 AND tag = ?2",
+            params![repo_id, tag],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// A repository's tags, sorted
+    pub fn get_tags_for_repo(&self, repo_id: &str) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT tag FROM repo_tags WHERE repo_id = ?1 ORDER BY tag")?;
+        let tags = stmt
+            .query_map([repo_id], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(tags)
+    }
+
+    /// Record the latest CI status of a repository's default branch
+    pub fn save_ci_status(&self, repo_id: &str, status: CiStatus) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO repo_ci_status (repo_id, status, checked_at)
+             VALUES (?1, ?2, ?3)",
+            params![repo_id, status.to_string(), Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// The last recorded CI status, `CiStatus::None` when never checked
+    pub fn get_ci_status(&self, repo_id: &str) -> Result<CiStatus> {
+        let status: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT status FROM repo_ci_status WHERE repo_id = ?1",
+                [repo_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(status.and_then(|s| s.parse().ok()).unwrap_or_default())
+    }
+
+    /// Repositories carrying a tag, highest priority first
+    pub fn get_repos_by_tag(&self, tag: &str) -> Result<Vec<Repository>> {
+        let tag = normalize_tag(tag)?;
+        let mut stmt = self.conn.prepare(
+            "SELECT r.id, r.owner, r.name, r.language, r.description, r.pushed_at, r.created_at, r.updated_at, r.is_fork, r.priority, r.is_private, r.priority_locked, r.github_id, r.snoozed_until
+             FROM repositories r
+             INNER JOIN repo_tags t ON r.id = t.repo_id
+             WHERE t.tag = ?1
+             ORDER BY r.priority DESC, r.id",
+        )?;
+        let repos = stmt
+            .query_map([tag], repository_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(repos)
+    }
+
     pub fn get_repos_in_group(&self, group_id: i64) -> Result<Vec<Repository>> {
         let mut stmt = self.conn.prepare(
-            "SELECT r.id, r.owner, r.name, r.language, r.description, r.pushed_at, r.created_at, r.updated_at, r.is_fork, r.priority
+            "SELECT r.id, r.owner, r.name, r.language, r.description, r.pushed_at, r.created_at, r.updated_at, r.is_fork, r.priority, r.is_private, r.priority_locked, r.github_id, r.snoozed_until
              FROM repositories r
              INNER JOIN repo_groups rg ON r.id = rg.repo_id
              WHERE rg.group_id = ?1
@@ -303,60 +845,182 @@ impl Database {
         )?;
 
         let repos = stmt
-            .query_map([group_id], |row| {
-                Ok(Repository {
-                    id: row.get(0)?,
-                    owner: row.get(1)?,
-                    name: row.get(2)?,
-                    language: row.get(3)?,
-                    description: row.get(4)?,
-                    pushed_at: row.get::<_, String>(5)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                    created_at: row.get::<_, String>(6)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                    updated_at: row.get::<_, String>(7)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                    is_fork: row.get::<_, i32>(8)? != 0,
-                    priority: row.get(9)?,
-                })
-            })?
+            .query_map([group_id], repository_from_row)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(repos)
     }
 
+    /// Every repository's `analysis::repo_status`, keyed by repo id, with
+    /// snoozed repositories counted as complete
+    ///
+    /// Only branches ahead of or behind the default branch can change a
+    /// repository's status, so only those are loaded, in a single query.
+    pub fn get_repo_statuses(&self, config: &Config) -> Result<HashMap<String, RepoStatus>> {
+        let now = Utc::now();
+        let repo_ids: Vec<(String, Option<String>)> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT id, snoozed_until FROM repositories")?;
+            let ids = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            ids
+        };
+
+        let mut diverged: HashMap<String, Vec<Branch>> = HashMap::new();
+        {
+            let mut stmt = self.conn.prepare(
+                "SELECT id, repo_id, name, sha, ahead_by, behind_by, status, last_commit_date
+                 FROM branches
+                 WHERE ahead_by > 0 OR behind_by > 0",
+            )?;
+            for branch in stmt.query_map([], branch_from_row)? {
+                let branch = branch?;
+                diverged
+                    .entry(branch.repo_id.clone())
+                    .or_default()
+                    .push(branch);
+            }
+        }
+
+        let local: HashMap<String, LocalRepoStatus> = self
+            .get_all_local_repo_statuses()?
+            .into_iter()
+            .map(|s| (s.repo_id.clone(), s))
+            .collect();
+
+        Ok(repo_ids
+            .into_iter()
+            .map(|(repo_id, snoozed_until)| {
+                let snoozed_until = snoozed_until.and_then(|until| until.parse().ok());
+                let status = snoozable_repo_status(
+                    snoozed_until,
+                    now,
+                    diverged.get(&repo_id).map_or(&[][..], Vec::as_slice),
+                    local.get(&repo_id),
+                    config,
+                );
+                (repo_id, status)
+            })
+            .collect())
+    }
+
+    /// How many repositories are red in the traffic light (see
+    /// `RepoStatus::needs_attention`)
+    pub fn count_needing_attention(&self, config: &Config) -> Result<usize> {
+        Ok(self
+            .get_repo_statuses(config)?
+            .values()
+            .filter(|status| status.needs_attention())
+            .count())
+    }
+
+    /// Each group's repository count and most urgent `analysis::repo_status`,
+    /// followed by an "Ungrouped" entry when any repository is outside every
+    /// group
+    pub fn get_group_summaries(&self, config: &Config) -> Result<Vec<GroupSummary>> {
+        let mut members: HashMap<i64, Vec<String>> = HashMap::new();
+        {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT group_id, repo_id FROM repo_groups")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            for row in rows {
+                let (group_id, repo_id) = row?;
+                members.entry(group_id).or_default().push(repo_id);
+            }
+        }
+
+        let ungrouped: Vec<String> = {
+            let mut stmt = self.conn.prepare(
+                "SELECT id FROM repositories WHERE id NOT IN (SELECT repo_id FROM repo_groups)",
+            )?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            ids
+        };
+
+        let statuses = self.get_repo_statuses(config)?;
+
+        let summarize = |id: Option<i64>, name: String, repo_ids: &[String]| {
+            let status = repo_ids
+                .iter()
+                .filter_map(|repo_id| statuses.get(repo_id).copied())
+                .min()
+                .unwrap_or(RepoStatus::Complete);
+            GroupSummary {
+                id,
+                name,
+                repo_count: repo_ids.len(),
+                status: status.to_string(),
+            }
+        };
+
+        let mut summaries: Vec<GroupSummary> = self
+            .get_all_groups()?
+            .into_iter()
+            .map(|group| {
+                let repo_ids = members.get(&group.id).map_or(&[][..], Vec::as_slice);
+                summarize(Some(group.id), group.name, repo_ids)
+            })
+            .collect();
+        if !ungrouped.is_empty() {
+            summaries.push(summarize(
+                None,
+                UNGROUPED_GROUP_NAME.to_string(),
+                &ungrouped,
+            ));
+        }
+
+        Ok(summaries)
+    }
+
+    /// Repository counts per traffic-light status plus open PR and unmerged
+    /// branch totals, without loading any repository
+    pub fn get_dashboard_counts(&self, config: &Config) -> Result<DashboardCounts> {
+        let mut counts = DashboardCounts::default();
+        for status in self.get_repo_statuses(config)?.into_values() {
+            counts.total_repos += 1;
+            match status {
+                RepoStatus::Diverged | RepoStatus::NeedsSync => counts.needs_sync += 1,
+                RepoStatus::LocalChanges => counts.local_changes += 1,
+                RepoStatus::Stale => counts.stale += 1,
+                RepoStatus::Complete => counts.complete += 1,
+            }
+        }
+
+        counts.open_prs = self.conn.query_row(
+            "SELECT COUNT(*) FROM pull_requests WHERE state = ?1",
+            [PRState::Open.to_string()],
+            |row| row.get(0),
+        )?;
+
+        // Protected branches are matched by pattern, so only the ahead
+        // filter can run in SQL
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name FROM branches WHERE ahead_by > 0")?;
+        for name in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            if !is_protected_branch(&name?, config) {
+                counts.unmerged_branches += 1;
+            }
+        }
+
+        Ok(counts)
+    }
+
     pub fn get_ungrouped_repositories(&self) -> Result<Vec<Repository>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, owner, name, language, description, pushed_at, created_at, updated_at, is_fork, priority
+            "SELECT id, owner, name, language, description, pushed_at, created_at, updated_at, is_fork, priority, is_private, priority_locked, github_id, snoozed_until
              FROM repositories
              WHERE id NOT IN (SELECT repo_id FROM repo_groups)
              ORDER BY pushed_at DESC"
         )?;
 
         let repos = stmt
-            .query_map([], |row| {
-                Ok(Repository {
-                    id: row.get(0)?,
-                    owner: row.get(1)?,
-                    name: row.get(2)?,
-                    language: row.get(3)?,
-                    description: row.get(4)?,
-                    pushed_at: row.get::<_, String>(5)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                    created_at: row.get::<_, String>(6)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                    updated_at: row.get::<_, String>(7)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                    is_fork: row.get::<_, i32>(8)? != 0,
-                    priority: row.get(9)?,
-                })
-            })?
+            .query_map([], repository_from_row)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(repos)
@@ -369,6 +1033,7 @@ impl Database {
     }
 
     pub fn rename_group(&self, group_id: i64, new_name: &str) -> Result<()> {
+        validate_group_name(new_name)?;
         self.conn.execute(
             "UPDATE groups SET name = ?1 WHERE id = ?2",
             params![new_name, group_id],
@@ -449,11 +1114,14 @@ impl Database {
     }
 
     // Local repository status management
+
+    /// Save a local status, replacing any previous status for the same repo
+    /// (even one recorded under a different path)
     pub fn save_local_repo_status(&self, status: &crate::models::LocalRepoStatus) -> Result<()> {
         self.conn.execute(
             "INSERT OR REPLACE INTO local_repo_status
-             (repo_id, local_path, current_branch, uncommitted_files, unpushed_commits, behind_commits, is_dirty, last_checked)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+             (repo_id, local_path, current_branch, uncommitted_files, unpushed_commits, behind_commits, is_dirty, has_conflicts, is_diverged, is_unpublished, last_checked)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 &status.repo_id,
                 &status.local_path,
@@ -462,6 +1130,9 @@ impl Database {
                 status.unpushed_commits as i64,
                 status.behind_commits as i64,
                 status.is_dirty as i32,
+                status.has_conflicts as i32,
+                status.is_diverged as i32,
+                status.is_unpublished as i32,
                 &status.last_checked.to_rfc3339(),
             ],
         )?;
@@ -475,7 +1146,7 @@ impl Database {
         use crate::models::LocalRepoStatus;
 
         let mut stmt = self.conn.prepare(
-            "SELECT id, repo_id, local_path, current_branch, uncommitted_files, unpushed_commits, behind_commits, is_dirty, last_checked
+            "SELECT id, repo_id, local_path, current_branch, uncommitted_files, unpushed_commits, behind_commits, is_dirty, has_conflicts, last_checked, is_diverged, is_unpublished
              FROM local_repo_status
              WHERE repo_id = ?1"
         )?;
@@ -492,7 +1163,10 @@ impl Database {
                 unpushed_commits: row.get::<_, i64>(5)? as u32,
                 behind_commits: row.get::<_, i64>(6)? as u32,
                 is_dirty: row.get::<_, i32>(7)? != 0,
-                last_checked: row.get::<_, String>(8)?.parse().map_err(|_| {
+                has_conflicts: row.get::<_, i32>(8)? != 0,
+                is_diverged: row.get::<_, i32>(10)? != 0,
+                is_unpublished: row.get::<_, i32>(11)? != 0,
+                last_checked: row.get::<_, String>(9)?.parse().map_err(|_| {
                     rusqlite::Error::InvalidParameterName("Invalid date".to_string())
                 })?,
             }))
@@ -505,7 +1179,7 @@ impl Database {
         use crate::models::LocalRepoStatus;
 
         let mut stmt = self.conn.prepare(
-            "SELECT id, repo_id, local_path, current_branch, uncommitted_files, unpushed_commits, behind_commits, is_dirty, last_checked
+            "SELECT id, repo_id, local_path, current_branch, uncommitted_files, unpushed_commits, behind_commits, is_dirty, has_conflicts, last_checked, is_diverged, is_unpublished
              FROM local_repo_status
              ORDER BY last_checked DESC"
         )?;
@@ -521,7 +1195,10 @@ impl Database {
                     unpushed_commits: row.get::<_, i64>(5)? as u32,
                     behind_commits: row.get::<_, i64>(6)? as u32,
                     is_dirty: row.get::<_, i32>(7)? != 0,
-                    last_checked: row.get::<_, String>(8)?.parse().map_err(|_| {
+                    has_conflicts: row.get::<_, i32>(8)? != 0,
+                    is_diverged: row.get::<_, i32>(10)? != 0,
+                    is_unpublished: row.get::<_, i32>(11)? != 0,
+                    last_checked: row.get::<_, String>(9)?.parse().map_err(|_| {
                         rusqlite::Error::InvalidParameterName("Invalid date".to_string())
                     })?,
                 })
@@ -531,6 +1208,41 @@ impl Database {
         Ok(statuses)
     }
 
+    /// Every local clone and GitHub branch that needs a push or pull, most
+    /// urgent first
+    ///
+    /// Matches the UI's RED traffic light: local clones (conflicts first) come
+    /// before GitHub branches, then larger ahead + behind totals first.
+    pub fn get_sync_needs(&self) -> Result<Vec<SyncNeed>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT repo_id, current_branch AS branch, 1 AS is_local,
+                    unpushed_commits AS ahead, behind_commits AS behind, has_conflicts,
+                    unpushed_commits + behind_commits AS total
+             FROM local_repo_status
+             WHERE unpushed_commits > 0 OR behind_commits > 0 OR has_conflicts = 1
+             UNION ALL
+             SELECT repo_id, name, 0, ahead_by, behind_by, 0, ahead_by + behind_by
+             FROM branches
+             WHERE ahead_by > 0 OR behind_by > 0
+             ORDER BY is_local DESC, has_conflicts DESC, total DESC, repo_id, branch",
+        )?;
+
+        let needs = stmt
+            .query_map([], |row| {
+                Ok(SyncNeed {
+                    repo_id: row.get(0)?,
+                    branch: row.get(1)?,
+                    is_local: row.get::<_, i32>(2)? != 0,
+                    ahead: row.get::<_, i64>(3)? as u32,
+                    behind: row.get::<_, i64>(4)? as u32,
+                    has_conflicts: row.get::<_, i32>(5)? != 0,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(needs)
+    }
+
     pub fn get_config(&self, key: &str) -> Result<Option<String>> {
         let mut stmt = self
             .conn
@@ -552,44 +1264,199 @@ impl Database {
         Ok(())
     }
 
+    // Repository exclusion
+
+    /// Store an exclude pattern; adding an existing pattern is a no-op
+    pub fn add_exclude_pattern(&self, pattern: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO repo_excludes (pattern, created_at) VALUES (?1, ?2)",
+            params![pattern, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a stored exclude pattern, returning whether it existed
+    pub fn remove_exclude_pattern(&self, pattern: &str) -> Result<bool> {
+        let removed = self.conn.execute(
+            "DELETE FROM repo_excludes WHERE pattern = ?1",
+            params![pattern],
+        )?;
+        Ok(removed > 0)
+    }
+
+    pub fn get_exclude_patterns(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT pattern FROM repo_excludes ORDER BY pattern")?;
+        let patterns = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(patterns)
+    }
+
+    /// Whether a repository matches a stored pattern or one of `extra_patterns`
+    /// (the configured `github.repo_exclude` list)
+    pub fn is_excluded(&self, repo_id: &str, extra_patterns: &[String]) -> Result<bool> {
+        Ok(extra_patterns
+            .iter()
+            .chain(self.get_exclude_patterns()?.iter())
+            .any(|pattern| matches_repo_pattern(pattern, repo_id)))
+    }
+
+    /// Delete tracked repositories that are now excluded, along with their
+    /// branches, PRs, group memberships and local status; returns their ids
+    ///
+    /// Either every excluded repository is removed or none is.
+    pub fn prune_excluded_repositories(&self, extra_patterns: &[String]) -> Result<Vec<String>> {
+        self.atomically(|db| {
+            let mut pruned = Vec::new();
+            for repo in db.get_all_repositories()? {
+                if db.is_excluded(&repo.id, extra_patterns)? {
+                    db.conn
+                        .execute("DELETE FROM repositories WHERE id = ?1", params![&repo.id])?;
+                    pruned.push(repo.id);
+                }
+            }
+            Ok(pruned)
+        })
+    }
+
     pub fn get_repositories_updated_since(&self, since: &str) -> Result<Vec<Repository>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, owner, name, language, description, pushed_at, created_at, updated_at, is_fork, priority
+            "SELECT id, owner, name, language, description, pushed_at, created_at, updated_at, is_fork, priority, is_private, priority_locked, github_id, snoozed_until
              FROM repositories
              WHERE pushed_at > ?1
              ORDER BY priority DESC, pushed_at DESC"
         )?;
 
         let repos = stmt
-            .query_map([since], |row| {
-                Ok(Repository {
-                    id: row.get(0)?,
-                    owner: row.get(1)?,
-                    name: row.get(2)?,
-                    language: row.get(3)?,
-                    description: row.get(4)?,
-                    pushed_at: row.get::<_, String>(5)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                    created_at: row.get::<_, String>(6)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                    updated_at: row.get::<_, String>(7)?.parse().map_err(|_| {
-                        rusqlite::Error::InvalidParameterName("Invalid date".to_string())
-                    })?,
-                    is_fork: row.get::<_, i32>(8)? != 0,
-                    priority: row.get(9)?,
-                })
-            })?
+            .query_map([since], repository_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(repos)
+    }
+
+    /// Repositories pushed to within the last `days` days, most recent first
+    ///
+    /// A window reaching before the earliest representable date is an
+    /// `InvalidDuration` error.
+    pub fn get_recently_pushed(&self, days: i64) -> Result<Vec<Repository>> {
+        let since = chrono::Duration::try_days(days)
+            .and_then(|window| chrono::Utc::now().checked_sub_signed(window))
+            .ok_or_else(|| crate::Error::InvalidDuration(format!("{} days is out of range", days)))?
+            .to_rfc3339();
+        let mut stmt = self.conn.prepare(
+            "SELECT id, owner, name, language, description, pushed_at, created_at, updated_at, is_fork, priority, is_private, priority_locked, github_id, snoozed_until
+             FROM repositories
+             WHERE pushed_at > ?1
+             ORDER BY pushed_at DESC",
+        )?;
+
+        let repos = stmt
+            .query_map([since], repository_from_row)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(repos)
     }
 }
 
+/// Keep a commit's subject line plus at most `max_body_len` characters of
+/// its body, marking cut-off bodies with an ellipsis
+pub fn truncate_commit_message(message: &str, max_body_len: usize) -> String {
+    let (subject, body) = message.split_once('\n').unwrap_or((message, ""));
+    let body = body.trim();
+
+    if body.is_empty() || max_body_len == 0 {
+        return subject.to_string();
+    }
+
+    match body.char_indices().nth(max_body_len) {
+        Some((cut, _)) => format!("{}\n\n{}…", subject, body[..cut].trim_end()),
+        None => format!("{}\n\n{}", subject, body),
+    }
+}
+
+/// Map a row selecting `id, repo_id, name, sha, ahead_by, behind_by, status,
+/// last_commit_date`
+fn branch_from_row(row: &rusqlite::Row) -> rusqlite::Result<Branch> {
+    let status_str: String = row.get(6)?;
+    Ok(Branch {
+        id: row.get(0)?,
+        repo_id: row.get(1)?,
+        name: row.get(2)?,
+        sha: row.get(3)?,
+        ahead_by: row.get::<_, i64>(4)? as u32,
+        behind_by: row.get::<_, i64>(5)? as u32,
+        status: status_str.parse().unwrap_or(BranchStatus::ReadyForPR),
+        last_commit_date: row
+            .get::<_, String>(7)?
+            .parse()
+            .map_err(|_| rusqlite::Error::InvalidParameterName("Invalid date".to_string()))?,
+    })
+}
+
+/// Map a row selecting `id, branch_id, sha, message, author_name,
+/// author_email, authored_date, committer_name, committer_email,
+/// committed_date, is_verified`
+fn commit_from_row(row: &rusqlite::Row) -> rusqlite::Result<Commit> {
+    Ok(Commit {
+        id: row.get(0)?,
+        branch_id: row.get(1)?,
+        sha: row.get(2)?,
+        message: row.get(3)?,
+        author_name: row.get(4)?,
+        author_email: row.get(5)?,
+        authored_date: row
+            .get::<_, String>(6)?
+            .parse()
+            .map_err(|_| rusqlite::Error::InvalidParameterName("Invalid date".to_string()))?,
+        committer_name: row.get(7)?,
+        committer_email: row.get(8)?,
+        committed_date: row
+            .get::<_, String>(9)?
+            .parse()
+            .map_err(|_| rusqlite::Error::InvalidParameterName("Invalid date".to_string()))?,
+        is_verified: row.get::<_, i32>(10)? != 0,
+    })
+}
+
+/// Map a row selecting `id, owner, name, language, description, pushed_at,
+/// created_at, updated_at, is_fork, priority, is_private, priority_locked,
+/// github_id, snoozed_until`
+fn repository_from_row(row: &rusqlite::Row) -> rusqlite::Result<Repository> {
+    Ok(Repository {
+        id: row.get(0)?,
+        owner: row.get(1)?,
+        name: row.get(2)?,
+        language: row.get(3)?,
+        description: row.get(4)?,
+        pushed_at: row
+            .get::<_, String>(5)?
+            .parse()
+            .map_err(|_| rusqlite::Error::InvalidParameterName("Invalid date".to_string()))?,
+        created_at: row
+            .get::<_, String>(6)?
+            .parse()
+            .map_err(|_| rusqlite::Error::InvalidParameterName("Invalid date".to_string()))?,
+        updated_at: row
+            .get::<_, String>(7)?
+            .parse()
+            .map_err(|_| rusqlite::Error::InvalidParameterName("Invalid date".to_string()))?,
+        is_fork: row.get::<_, i32>(8)? != 0,
+        priority: row.get(9)?,
+        is_private: row.get::<_, i32>(10)? != 0,
+        priority_locked: row.get::<_, i32>(11)? != 0,
+        github_id: row.get(12)?,
+        snoozed_until: row
+            .get::<_, Option<String>>(13)?
+            .and_then(|until| until.parse().ok()),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::LocalRepoStatus;
     use chrono::Utc;
     use tempfile::tempdir;
 
@@ -618,6 +1485,10 @@ mod tests {
             updated_at: Utc::now(),
             is_fork: false,
             priority: 0.5,
+            is_private: false,
+            priority_locked: false,
+            github_id: None,
+            snoozed_until: None,
         };
 
         db.save_repository(&repo).unwrap();
@@ -628,6 +1499,280 @@ mod tests {
         assert_eq!(repos[0].owner, "test");
     }
 
+    #[test]
+    fn test_for_each_repository_streams_in_priority_order() {
+        let temp = tempdir().unwrap();
+        let db = Database::open_or_create(&temp.path().join("test.db")).unwrap();
+        for (name, priority) in [("low", 0.1), ("high", 0.9), ("mid", 0.5)] {
+            let repo = crate::test_support::RepoBuilder::new(name)
+                .priority(priority)
+                .build();
+            db.save_repository(&repo).unwrap();
+        }
+
+        let mut seen = Vec::new();
+        db.for_each_repository(|repo| {
+            seen.push(repo.name);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(seen, vec!["high", "mid", "low"]);
+
+        // An error from the callback stops the iteration
+        let mut visited = 0;
+        let result = db.for_each_repository(|_| {
+            visited += 1;
+            Err(crate::Error::Config("stop".to_string()))
+        });
+        assert!(result.is_err());
+        assert_eq!(visited, 1);
+    }
+
+    #[test]
+    fn test_get_repository_by_id() {
+        let temp = tempdir().unwrap();
+        let db_path = temp.path().join("test.db");
+        let db = Database::open_or_create(&db_path).unwrap();
+
+        let repo = crate::test_support::RepoBuilder::new("repo").build();
+        db.save_repository(&repo).unwrap();
+
+        let loaded = db.get_repository("testowner/repo").unwrap();
+        assert_eq!(loaded.map(|r| r.name), Some("repo".to_string()));
+        assert!(db.get_repository("testowner/missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_backup_snapshot_keeps_newest() {
+        let temp = tempdir().unwrap();
+        let db = Database::open_or_create(&temp.path().join("test.db")).unwrap();
+        db.save_repository(&crate::test_support::RepoBuilder::new("repo").build())
+            .unwrap();
+
+        let mut paths = Vec::new();
+        for _ in 0..3 {
+            paths.push(db.backup_snapshot(2).unwrap());
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        let backup_dir = temp.path().join("backups");
+        assert_eq!(std::fs::read_dir(&backup_dir).unwrap().count(), 2);
+        assert!(!paths[0].exists());
+
+        let restored = Database::open_or_create(&paths[2]).unwrap();
+        assert!(restored.get_repository("testowner/repo").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_backup_snapshots_in_quick_succession_are_all_kept() {
+        let temp = tempdir().unwrap();
+        let db = Database::open_or_create(&temp.path().join("test.db")).unwrap();
+        db.save_repository(&crate::test_support::RepoBuilder::new("repo").build())
+            .unwrap();
+
+        // No sleep: several of these land in the same millisecond
+        let paths: Vec<PathBuf> = (0..5).map(|_| db.backup_snapshot(10).unwrap()).collect();
+
+        let mut unique = paths.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 5);
+        for path in &paths {
+            let restored = Database::open_or_create(path).unwrap();
+            assert!(restored.get_repository("testowner/repo").unwrap().is_some());
+        }
+
+        // The newest snapshot survives pruning even when it shares a timestamp
+        let newest = db.backup_snapshot(1).unwrap();
+        assert!(newest.exists());
+        assert_eq!(
+            std::fs::read_dir(temp.path().join("backups"))
+                .unwrap()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_get_or_create_group_is_case_insensitive() {
+        let temp = tempdir().unwrap();
+        let db = Database::open_or_create(&temp.path().join("test.db")).unwrap();
+        let first = db.create_group("Active", 0).unwrap();
+
+        assert_eq!(db.get_or_create_group("active").unwrap(), first);
+
+        let second = db.get_or_create_group("Archive").unwrap();
+        assert_ne!(second, first);
+        let groups = db.get_all_groups().unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(
+            groups
+                .iter()
+                .find(|g| g.id == second)
+                .unwrap()
+                .display_order,
+            1
+        );
+
+        // The unique index rejects names that differ only in case
+        assert!(db.create_group("ACTIVE", 2).is_err());
+    }
+
+    #[test]
+    fn test_group_names_reserve_ungrouped() {
+        let temp = tempdir().unwrap();
+        let db = Database::open_or_create(&temp.path().join("test.db")).unwrap();
+
+        for name in ["Ungrouped", "ungrouped", " UNGROUPED ", "", "  "] {
+            assert!(
+                matches!(
+                    db.create_group(name, 0),
+                    Err(crate::Error::InvalidGroupName(_))
+                ),
+                "{:?} should be rejected",
+                name
+            );
+            assert!(db.get_or_create_group(name).is_err());
+        }
+
+        let id = db.create_group("Ungrouped work", 0).unwrap();
+        assert!(matches!(
+            db.rename_group(id, "Ungrouped"),
+            Err(crate::Error::InvalidGroupName(_))
+        ));
+        assert_eq!(db.get_all_groups().unwrap()[0].name, "Ungrouped work");
+    }
+
+    #[test]
+    fn test_exclude_patterns_and_prune() {
+        let temp = tempdir().unwrap();
+        let db = Database::open_or_create(&temp.path().join("test.db")).unwrap();
+        for name in ["keep", "test-a", "test-b"] {
+            db.save_repository(&crate::test_support::RepoBuilder::new(name).build())
+                .unwrap();
+        }
+
+        db.add_exclude_pattern("testowner/test-*").unwrap();
+        db.add_exclude_pattern("testowner/test-*").unwrap();
+        assert_eq!(db.get_exclude_patterns().unwrap(), vec!["testowner/test-*"]);
+        assert!(db.is_excluded("testowner/test-a", &[]).unwrap());
+        assert!(!db.is_excluded("testowner/keep", &[]).unwrap());
+        assert!(db
+            .is_excluded("testowner/keep", &["testowner/keep".to_string()])
+            .unwrap());
+
+        let mut pruned = db.prune_excluded_repositories(&[]).unwrap();
+        pruned.sort();
+        assert_eq!(pruned, vec!["testowner/test-a", "testowner/test-b"]);
+        let remaining: Vec<String> = db
+            .get_all_repositories()
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(remaining, vec!["testowner/keep"]);
+
+        assert!(db.remove_exclude_pattern("testowner/test-*").unwrap());
+        assert!(!db.remove_exclude_pattern("testowner/test-*").unwrap());
+    }
+
+    #[test]
+    fn test_repository_visibility_round_trips() {
+        let temp = tempdir().unwrap();
+        let db = Database::open_or_create(&temp.path().join("test.db")).unwrap();
+
+        let repo = crate::test_support::RepoBuilder::new("secret")
+            .is_private(true)
+            .build();
+        db.save_repository(&repo).unwrap();
+
+        let loaded = db.get_repository("testowner/secret").unwrap().unwrap();
+        assert!(loaded.is_private);
+    }
+
+    #[test]
+    fn test_snooze_counts_repo_complete_until_it_expires() {
+        let temp = tempdir().unwrap();
+        let db = Database::open_or_create(&temp.path().join("test.db")).unwrap();
+        let config = Config::default();
+
+        let repo = crate::test_support::RepoBuilder::new("proj").build();
+        db.save_repository(&repo).unwrap();
+        db.save_branch(
+            &crate::test_support::BranchBuilder::new("feature")
+                .ahead(1)
+                .build(&repo.id),
+        )
+        .unwrap();
+        assert_eq!(
+            db.get_repo_statuses(&config).unwrap()[&repo.id],
+            RepoStatus::NeedsSync
+        );
+
+        let until = Utc::now() + chrono::Duration::days(2);
+        assert!(db.set_snooze(&repo.id, Some(until)).unwrap());
+        assert!(!db.set_snooze("testowner/missing", Some(until)).unwrap());
+        assert_eq!(
+            db.get_repo_statuses(&config).unwrap()[&repo.id],
+            RepoStatus::Complete
+        );
+        assert_eq!(db.count_needing_attention(&config).unwrap(), 0);
+
+        // Rescans keep the snooze
+        db.save_repository(&repo).unwrap();
+        let loaded = db.get_repository(&repo.id).unwrap().unwrap();
+        assert_eq!(
+            loaded.snoozed_until.map(|t| t.timestamp()),
+            Some(until.timestamp())
+        );
+
+        // An expired snooze needs no cleanup to stop applying
+        let past = Utc::now() - chrono::Duration::minutes(1);
+        db.set_snooze(&repo.id, Some(past)).unwrap();
+        assert_eq!(
+            db.get_repo_statuses(&config).unwrap()[&repo.id],
+            RepoStatus::NeedsSync
+        );
+
+        db.set_snooze(&repo.id, None).unwrap();
+        assert_eq!(
+            db.get_repository(&repo.id).unwrap().unwrap().snoozed_until,
+            None
+        );
+    }
+
+    #[test]
+    fn test_priority_override_survives_rescans() {
+        let temp = tempdir().unwrap();
+        let db = Database::open_or_create(&temp.path().join("test.db")).unwrap();
+
+        let repo = crate::test_support::RepoBuilder::new("proj").build();
+        db.save_repository(&repo).unwrap();
+        let group_id = db.create_group("Active", 0).unwrap();
+        db.add_repo_to_group(&repo.id, group_id).unwrap();
+
+        assert!(db.set_priority_override(&repo.id, Some(0.9)).unwrap());
+        assert!(!db
+            .set_priority_override("testowner/missing", Some(0.9))
+            .unwrap());
+
+        // Reprioritize and rescans leave the override alone
+        assert!(!db.update_repository_priority(&repo.id, 0.1).unwrap());
+        db.save_repository(&repo).unwrap();
+        let loaded = db.get_repository(&repo.id).unwrap().unwrap();
+        assert_eq!(loaded.priority, 0.9);
+        assert!(loaded.priority_locked);
+
+        // Saving updates in place, so group membership is kept
+        assert_eq!(db.get_repos_in_group(group_id).unwrap().len(), 1);
+
+        assert!(db.set_priority_override(&repo.id, None).unwrap());
+        assert!(db.update_repository_priority(&repo.id, 0.1).unwrap());
+        let loaded = db.get_repository(&repo.id).unwrap().unwrap();
+        assert_eq!(loaded.priority, 0.1);
+        assert!(!loaded.priority_locked);
+    }
+
     #[test]
     fn test_config_get_set() {
         let temp = tempdir().unwrap();
@@ -649,6 +1794,33 @@ mod tests {
         assert_eq!(value, None);
     }
 
+    #[test]
+    fn test_get_recently_pushed() -> Result<()> {
+        use crate::test_support::{RepoBuilder, TestDatabase};
+
+        let now = Utc::now();
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("old").pushed_at(now - chrono::Duration::days(30)))?
+            .with_repo(RepoBuilder::new("yesterday").pushed_at(now - chrono::Duration::days(1)))?
+            .with_repo(RepoBuilder::new("today").pushed_at(now - chrono::Duration::hours(1)))?;
+
+        let ids: Vec<_> = test_db
+            .db
+            .get_recently_pushed(7)?
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec!["testowner/today", "testowner/yesterday"]);
+        assert_eq!(test_db.db.get_recently_pushed(60)?.len(), 3);
+        assert!(matches!(
+            test_db.db.get_recently_pushed(100_000_000),
+            Err(crate::Error::InvalidDuration(_))
+        ));
+        assert!(test_db.db.get_recently_pushed(i64::MAX).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_get_repositories_updated_since() {
         let temp = tempdir().unwrap();
@@ -671,6 +1843,10 @@ mod tests {
             updated_at: old_time,
             is_fork: false,
             priority: 0.5,
+            is_private: false,
+            priority_locked: false,
+            github_id: None,
+            snoozed_until: None,
         };
 
         // Create a recent repository
@@ -685,6 +1861,10 @@ mod tests {
             updated_at: recent_time,
             is_fork: false,
             priority: 0.5,
+            is_private: false,
+            priority_locked: false,
+            github_id: None,
+            snoozed_until: None,
         };
 
         db.save_repository(&old_repo).unwrap();
@@ -705,4 +1885,569 @@ mod tests {
         // Should return both repositories
         assert_eq!(repos.len(), 2);
     }
+
+    #[test]
+    fn test_migrate_adds_head_ref_name_to_old_pull_requests_table() {
+        let temp = tempdir().unwrap();
+        let db_path = temp.path().join("test.db");
+
+        // Simulate a database created before head_ref_name existed
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE pull_requests (
+                    id INTEGER PRIMARY KEY,
+                    repo_id TEXT NOT NULL,
+                    branch_id INTEGER,
+                    number INTEGER NOT NULL,
+                    state TEXT NOT NULL,
+                    title TEXT NOT NULL,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );",
+            )
+            .unwrap();
+        }
+
+        let db = Database::open_or_create(&db_path).unwrap();
+        let pr = PullRequest {
+            id: 0,
+            repo_id: "test/repo".to_string(),
+            branch_id: None,
+            number: 7,
+            state: PRState::Open,
+            title: "Add feature".to_string(),
+            head_ref_name: "feature".to_string(),
+            url: None,
+            review_decision: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        db.save_pull_request(&pr).unwrap();
+
+        let prs = db.get_pull_requests_for_repo("test/repo").unwrap();
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].head_ref_name, "feature");
+    }
+
+    #[test]
+    fn test_update_branch_status() {
+        let temp = tempdir().unwrap();
+        let db_path = temp.path().join("test.db");
+        let db = Database::open_or_create(&db_path).unwrap();
+
+        let repo = Repository {
+            id: "test/repo".to_string(),
+            owner: "test".to_string(),
+            name: "repo".to_string(),
+            language: None,
+            description: None,
+            pushed_at: Utc::now(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            is_fork: false,
+            priority: 0.0,
+            is_private: false,
+            priority_locked: false,
+            github_id: None,
+            snoozed_until: None,
+        };
+        db.save_repository(&repo).unwrap();
+
+        let branch = Branch {
+            id: 0,
+            repo_id: repo.id.clone(),
+            name: "feature".to_string(),
+            sha: "abc123".to_string(),
+            ahead_by: 1,
+            behind_by: 0,
+            status: BranchStatus::ReadyForPR,
+            last_commit_date: Utc::now(),
+        };
+        let branch_id = db.save_branch(&branch).unwrap();
+
+        db.update_branch_status(branch_id, &BranchStatus::InReview)
+            .unwrap();
+
+        let branches = db.get_branches_for_repo(&repo.id).unwrap();
+        assert_eq!(branches[0].status, BranchStatus::InReview);
+    }
+
+    #[test]
+    fn test_truncate_commit_message() {
+        let message = "Squash merge\n\nFirst line of body\nSecond line of body";
+
+        assert_eq!(truncate_commit_message(message, 0), "Squash merge");
+        assert_eq!(
+            truncate_commit_message(message, 10),
+            "Squash merge\n\nFirst line…"
+        );
+        assert_eq!(truncate_commit_message(message, 1000), message);
+        assert_eq!(truncate_commit_message("Subject only", 5), "Subject only");
+    }
+
+    #[test]
+    fn test_save_commit_upserts_on_branch_and_sha() -> Result<()> {
+        use crate::test_support::fixtures::CommitBuilder;
+        use crate::test_support::{BranchBuilder, RepoBuilder, TestDatabase};
+
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("repo"))?
+            .with_branch("testowner/repo", BranchBuilder::new("feature"))?;
+        let branch_id = test_db.db.get_branches_for_repo("testowner/repo")?[0].id;
+
+        // Synthetic ids from two fetches differ; the SHA decides the row
+        let mut first = CommitBuilder::new("abc1234", "Add feature").build(branch_id);
+        first.id = 0;
+        let mut again = CommitBuilder::new("abc1234", "Add feature, reworded").build(branch_id);
+        again.id = 7;
+
+        let id = test_db.db.save_commit(&first)?;
+        assert_eq!(test_db.db.save_commit(&again)?, id);
+
+        let commits = test_db.db.get_commits_for_branch(branch_id)?;
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].id, id);
+        assert_eq!(commits[0].message, "Add feature, reworded");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_commits_for_branch_limited_keeps_newest() -> Result<()> {
+        use crate::test_support::fixtures::CommitBuilder;
+        use crate::test_support::{BranchBuilder, RepoBuilder, TestDatabase};
+
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("repo"))?
+            .with_branch("testowner/repo", BranchBuilder::new("feature"))?;
+        let branch_id = test_db.db.get_branches_for_repo("testowner/repo")?[0].id;
+        let now = Utc::now();
+        for (sha, days_ago) in [("old", 3), ("newest", 0), ("middle", 1)] {
+            test_db.db.save_commit(
+                &CommitBuilder::new(sha, "Work")
+                    .committed_date(now - chrono::Duration::days(days_ago))
+                    .build(branch_id),
+            )?;
+        }
+
+        let commits = test_db.db.get_commits_for_branch_limited(branch_id, 2)?;
+        let shas: Vec<&str> = commits.iter().map(|c| c.sha.as_str()).collect();
+        assert_eq!(shas, vec!["newest", "middle"]);
+        assert_eq!(test_db.db.count_commits_for_branch(branch_id)?, 3);
+        assert_eq!(test_db.db.get_commits_for_branch(branch_id)?.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_commit_respects_max_body_len() -> Result<()> {
+        use crate::test_support::fixtures::CommitBuilder;
+        use crate::test_support::{BranchBuilder, RepoBuilder, TestDatabase};
+
+        let mut test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("repo"))?
+            .with_branch("testowner/repo", BranchBuilder::new("feature"))?;
+        let branch_id = test_db.db.get_branches_for_repo("testowner/repo")?[0].id;
+
+        test_db.db.set_max_commit_body_len(Some(0));
+        test_db
+            .db
+            .save_commit(&CommitBuilder::new("abc", "Subject\n\nLong body").build(branch_id))?;
+
+        let commits = test_db.db.get_commits_for_branch(branch_id)?;
+        assert_eq!(commits[0].message, "Subject");
+
+        Ok(())
+    }
+
+    fn local_status(repo_id: &str, path: &str, checked: chrono::DateTime<Utc>) -> LocalRepoStatus {
+        LocalRepoStatus {
+            id: 0,
+            repo_id: repo_id.to_string(),
+            local_path: path.to_string(),
+            current_branch: Some("main".to_string()),
+            uncommitted_files: 0,
+            unpushed_commits: 0,
+            behind_commits: 0,
+            is_dirty: false,
+            has_conflicts: false,
+            is_diverged: false,
+            is_unpublished: false,
+            last_checked: checked,
+        }
+    }
+
+    #[test]
+    fn test_local_repo_status_keyed_by_repo_id() -> Result<()> {
+        let test_db = crate::test_support::TestDatabase::new()?
+            .with_repo(crate::test_support::RepoBuilder::new("repo"))?;
+
+        let repo_id = "testowner/repo";
+        test_db.db.save_local_repo_status(&local_status(
+            repo_id,
+            "/code/testowner/repo",
+            Utc::now(),
+        ))?;
+        test_db.db.save_local_repo_status(&local_status(
+            repo_id,
+            "/code/./testowner/repo",
+            Utc::now(),
+        ))?;
+
+        let statuses = test_db.db.get_all_local_repo_statuses()?;
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].local_path, "/code/./testowner/repo");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_repositories_without_local_clone() -> Result<()> {
+        use crate::test_support::{RepoBuilder, TestDatabase};
+
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("cloned"))?
+            .with_repo(RepoBuilder::new("remote-only"))?;
+        test_db.db.save_local_repo_status(&local_status(
+            "testowner/cloned",
+            "/code/testowner/cloned",
+            Utc::now(),
+        ))?;
+
+        let missing = test_db.db.get_repositories_without_local_clone()?;
+        let ids: Vec<&str> = missing.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["testowner/remote-only"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_sync_needs_orders_by_urgency() -> Result<()> {
+        use crate::test_support::{BranchBuilder, RepoBuilder, TestDatabase};
+
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("alpha"))?
+            .with_repo(RepoBuilder::new("beta"))?
+            .with_branch("testowner/alpha", BranchBuilder::new("main"))?
+            .with_branch("testowner/alpha", BranchBuilder::new("small").ahead(1))?
+            .with_branch(
+                "testowner/beta",
+                BranchBuilder::new("large").ahead(2).behind(3),
+            )?;
+
+        let mut unpushed = local_status("testowner/alpha", "/code/alpha", Utc::now());
+        unpushed.unpushed_commits = 1;
+        test_db.db.save_local_repo_status(&unpushed)?;
+
+        let mut conflicted = local_status("testowner/beta", "/code/beta", Utc::now());
+        conflicted.has_conflicts = true;
+        test_db.db.save_local_repo_status(&conflicted)?;
+
+        let needs = test_db.db.get_sync_needs()?;
+
+        let order: Vec<_> = needs
+            .iter()
+            .map(|n| (n.repo_id.as_str(), n.branch.as_deref(), n.is_local))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("testowner/beta", Some("main"), true),
+                ("testowner/alpha", Some("main"), true),
+                ("testowner/beta", Some("large"), false),
+                ("testowner/alpha", Some("small"), false),
+            ]
+        );
+        assert!(needs[0].has_conflicts);
+        assert_eq!((needs[2].ahead, needs[2].behind), (2, 3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_repository_follows_github_renames() -> Result<()> {
+        use crate::test_support::{BranchBuilder, RepoBuilder, TestDatabase};
+
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("old-name").github_id("R_1"))?
+            .with_branch("testowner/old-name", BranchBuilder::new("feature").ahead(1))?
+            .with_group("Work", 0, vec!["testowner/old-name"])?;
+        test_db.db.save_local_repo_status(&local_status(
+            "testowner/old-name",
+            "/code/old-name",
+            Utc::now(),
+        ))?;
+        // Saved by a scan before the rename was recognised
+        test_db
+            .db
+            .save_repository(&RepoBuilder::new("new-name").build())?;
+
+        test_db
+            .db
+            .save_repository(&RepoBuilder::new("new-name").github_id("R_1").build())?;
+
+        assert!(test_db.db.get_repository("testowner/old-name")?.is_none());
+        let renamed = test_db.db.get_repository("testowner/new-name")?.unwrap();
+        assert_eq!(renamed.name, "new-name");
+        assert_eq!(renamed.github_id.as_deref(), Some("R_1"));
+        assert_eq!(test_db.db.get_all_repositories()?.len(), 1);
+        assert_eq!(
+            test_db.db.get_branches_for_repo("testowner/new-name")?[0].name,
+            "feature"
+        );
+        let group_id = test_db.db.get_all_groups()?[0].id;
+        assert_eq!(
+            test_db.db.get_repos_in_group(group_id)?[0].id,
+            "testowner/new-name"
+        );
+        assert_eq!(
+            test_db.db.get_all_local_repo_statuses()?[0].repo_id,
+            "testowner/new-name"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_repo_ids_with_prefix() -> Result<()> {
+        use crate::test_support::{RepoBuilder, TestDatabase};
+
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("overall"))?
+            .with_repo(RepoBuilder::new("other"))?
+            .with_repo(RepoBuilder::new("ask"))?
+            .with_repo(RepoBuilder::new("my_tool"))?
+            .with_repo(RepoBuilder::new("myxtool"))?;
+
+        assert_eq!(
+            test_db.db.get_repo_ids_with_prefix("testowner/o")?,
+            vec!["testowner/other", "testowner/overall"]
+        );
+        assert_eq!(
+            test_db.db.get_repo_ids_with_prefix("TestOwner/A")?,
+            vec!["testowner/ask"]
+        );
+        assert_eq!(
+            test_db.db.get_repo_ids_with_prefix("testowner/my_")?,
+            vec!["testowner/my_tool"]
+        );
+        assert_eq!(test_db.db.get_repo_ids_with_prefix("")?.len(), 5);
+        assert!(test_db.db.get_repo_ids_with_prefix("nobody/")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_repo_tags() -> Result<()> {
+        use crate::test_support::{RepoBuilder, TestDatabase};
+
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("one").priority(0.2))?
+            .with_repo(RepoBuilder::new("two").priority(0.9))?;
+        let db = &test_db.db;
+
+        assert!(db.add_tag("testowner/one", "OSS")?);
+        assert!(!db.add_tag("testowner/one", " oss ")?, "tags ignore case");
+        assert!(db.add_tag("testowner/one", "client-work")?);
+        assert!(db.add_tag("testowner/two", "oss")?);
+        assert!(db.add_tag("testowner/two", "two words").is_err());
+
+        assert_eq!(
+            db.get_tags_for_repo("testowner/one")?,
+            vec!["client-work", "oss"]
+        );
+        let ids: Vec<_> = db
+            .get_repos_by_tag("Oss")?
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec!["testowner/two", "testowner/one"]);
+
+        assert!(db.remove_tag("testowner/one", "oss")?);
+        assert!(!db.remove_tag("testowner/one", "oss")?);
+        assert_eq!(db.get_tags_for_repo("testowner/one")?, vec!["client-work"]);
+
+        db.delete_repository("testowner/two")?;
+        assert!(db.get_repos_by_tag("oss")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ci_status_keeps_latest() -> Result<()> {
+        use crate::test_support::{RepoBuilder, TestDatabase};
+
+        let test_db = TestDatabase::new()?.with_repo(RepoBuilder::new("repo"))?;
+        let db = &test_db.db;
+        assert_eq!(db.get_ci_status("testowner/repo")?, CiStatus::None);
+
+        db.save_ci_status("testowner/repo", CiStatus::Pending)?;
+        db.save_ci_status("testowner/repo", CiStatus::Failing)?;
+        assert_eq!(db.get_ci_status("testowner/repo")?, CiStatus::Failing);
+
+        db.delete_repository("testowner/repo")?;
+        assert_eq!(db.get_ci_status("testowner/repo")?, CiStatus::None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_pull_request_state() -> Result<()> {
+        use crate::test_support::{PRBuilder, RepoBuilder, TestDatabase};
+
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("repo"))?
+            .with_pull_request(
+                "testowner/repo",
+                PRBuilder::new(4, "Fix").build("testowner/repo"),
+            )?;
+
+        assert!(test_db
+            .db
+            .update_pull_request_state("testowner/repo", 4, PRState::Merged)?);
+        assert!(!test_db
+            .db
+            .update_pull_request_state("testowner/repo", 5, PRState::Merged)?);
+        assert_eq!(
+            test_db.db.get_pull_requests_for_repo("testowner/repo")?[0].state,
+            PRState::Merged
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_repository_removes_related_rows() -> Result<()> {
+        use crate::test_support::fixtures::CommitBuilder;
+        use crate::test_support::{BranchBuilder, RepoBuilder, TestDatabase};
+
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("gone"))?
+            .with_repo(RepoBuilder::new("kept"))?
+            .with_branch("testowner/gone", BranchBuilder::new("feature").ahead(1))?
+            .with_group("Work", 0, vec!["testowner/gone", "testowner/kept"])?;
+        let branch_id = test_db.db.get_branches_for_repo("testowner/gone")?[0].id;
+        test_db
+            .db
+            .save_commit(&CommitBuilder::new("abc1234", "Add feature").build(branch_id))?;
+        test_db.db.save_local_repo_status(&local_status(
+            "testowner/gone",
+            "/code/gone",
+            Utc::now(),
+        ))?;
+
+        assert!(test_db.db.delete_repository("testowner/gone")?);
+        assert!(!test_db.db.delete_repository("testowner/gone")?);
+
+        assert!(test_db.db.get_repository("testowner/gone")?.is_none());
+        assert!(test_db
+            .db
+            .get_branches_for_repo("testowner/gone")?
+            .is_empty());
+        assert!(test_db.db.get_commits_for_branch(branch_id)?.is_empty());
+        assert!(test_db.db.get_all_local_repo_statuses()?.is_empty());
+        let group_id = test_db.db.get_all_groups()?[0].id;
+        let members: Vec<_> = test_db
+            .db
+            .get_repos_in_group(group_id)?
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(members, vec!["testowner/kept"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_summaries_report_worst_status() -> Result<()> {
+        use crate::test_support::{BranchBuilder, RepoBuilder, TestDatabase};
+
+        let test_db = TestDatabase::new()?
+            .with_repo(RepoBuilder::new("clean"))?
+            .with_repo(RepoBuilder::new("dirty"))?
+            .with_repo(RepoBuilder::new("diverged"))?
+            .with_repo(RepoBuilder::new("loose"))?
+            .with_branch("testowner/clean", BranchBuilder::new("main"))?
+            .with_branch("testowner/diverged", BranchBuilder::new("feature").ahead(1))?
+            .with_group("Work", 0, vec!["testowner/clean", "testowner/dirty"])?
+            .with_group("Hot", 1, vec!["testowner/diverged"])?
+            .with_group("Empty", 2, vec![])?;
+
+        let mut dirty = local_status("testowner/dirty", "/code/dirty", Utc::now());
+        dirty.uncommitted_files = 2;
+        test_db.db.save_local_repo_status(&dirty)?;
+
+        let summaries = test_db.db.get_group_summaries(&Config::default())?;
+
+        let rows: Vec<_> = summaries
+            .iter()
+            .map(|s| {
+                (
+                    s.name.as_str(),
+                    s.id.is_some(),
+                    s.repo_count,
+                    s.status.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("Work", true, 2, "localChanges"),
+                ("Hot", true, 1, "needsSync"),
+                ("Empty", true, 0, "complete"),
+                (UNGROUPED_GROUP_NAME, false, 1, "complete"),
+            ]
+        );
+        assert_eq!(test_db.db.count_needing_attention(&Config::default())?, 1);
+
+        test_db
+            .db
+            .save_branch(&BranchBuilder::new("main").ahead(3).build("testowner/loose"))?;
+        assert_eq!(
+            test_db.db.get_dashboard_counts(&Config::default())?,
+            DashboardCounts {
+                total_repos: 4,
+                needs_sync: 2,
+                local_changes: 1,
+                stale: 0,
+                complete: 1,
+                open_prs: 0,
+                // main is protected, so only the diverged feature branch counts
+                unmerged_branches: 1,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_dedupes_local_repo_statuses() {
+        let temp = tempdir().unwrap();
+        let db_path = temp.path().join("test.db");
+        let db = Database::open_or_create(&db_path).unwrap();
+        db.save_repository(&crate::test_support::RepoBuilder::new("repo").build())
+            .unwrap();
+
+        // Simulate a database from before repo_id was unique
+        db.conn
+            .execute_batch("DROP INDEX idx_local_repo_status_repo_id_unique;")
+            .unwrap();
+        let older = Utc::now() - chrono::Duration::hours(1);
+        for (path, checked) in [
+            ("/a/testowner/repo", older),
+            ("/b/testowner/repo", Utc::now()),
+        ] {
+            let status = local_status("testowner/repo", path, checked);
+            db.conn
+                .execute(
+                    "INSERT INTO local_repo_status (repo_id, local_path, last_checked) VALUES (?1, ?2, ?3)",
+                    params![status.repo_id, status.local_path, status.last_checked.to_rfc3339()],
+                )
+                .unwrap();
+        }
+
+        db.migrate().unwrap();
+
+        let statuses = db.get_all_local_repo_statuses().unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].local_path, "/b/testowner/repo");
+    }
 }
@@ -9,7 +9,14 @@ CREATE TABLE IF NOT EXISTS repositories (
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL,
     is_fork INTEGER NOT NULL,
-    priority REAL NOT NULL DEFAULT 0.0
+    priority REAL NOT NULL DEFAULT 0.0,
+    is_private INTEGER NOT NULL DEFAULT 0,
+    -- Set by a manual priority override; reprioritize leaves locked repos alone
+    priority_locked INTEGER NOT NULL DEFAULT 0,
+    -- GitHub node id; unlike owner/name it survives renames and transfers
+    github_id TEXT,
+    -- Deliberately deferred until this time; counts as complete until then
+    snoozed_until TEXT
 );
 
 CREATE INDEX IF NOT EXISTS idx_repositories_pushed_at ON repositories(pushed_at DESC);
@@ -38,6 +45,9 @@ CREATE TABLE IF NOT EXISTS pull_requests (
     number INTEGER NOT NULL,
     state TEXT NOT NULL,
     title TEXT NOT NULL,
+    head_ref_name TEXT NOT NULL DEFAULT '',
+    url TEXT,
+    review_decision TEXT,
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL,
     FOREIGN KEY (repo_id) REFERENCES repositories(id) ON DELETE CASCADE,
@@ -58,6 +68,7 @@ CREATE TABLE IF NOT EXISTS commits (
     committer_name TEXT NOT NULL,
     committer_email TEXT NOT NULL,
     committed_date TEXT NOT NULL,
+    is_verified INTEGER NOT NULL DEFAULT 0,
     FOREIGN KEY (branch_id) REFERENCES branches(id) ON DELETE CASCADE,
     UNIQUE(branch_id, sha)
 );
@@ -88,12 +99,36 @@ CREATE TABLE IF NOT EXISTS repo_groups (
 CREATE INDEX IF NOT EXISTS idx_repo_groups_repo_id ON repo_groups(repo_id);
 CREATE INDEX IF NOT EXISTS idx_repo_groups_group_id ON repo_groups(group_id);
 
+-- Local repository tags, orthogonal to groups (a repo can have many)
+CREATE TABLE IF NOT EXISTS repo_tags (
+    repo_id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (repo_id, tag),
+    FOREIGN KEY (repo_id) REFERENCES repositories(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_repo_tags_tag ON repo_tags(tag);
+
+-- Latest CI status of each repository's default branch
+CREATE TABLE IF NOT EXISTS repo_ci_status (
+    repo_id TEXT PRIMARY KEY,
+    status TEXT NOT NULL,
+    checked_at TEXT NOT NULL,
+    FOREIGN KEY (repo_id) REFERENCES repositories(id) ON DELETE CASCADE
+);
+
 -- Configuration table
 CREATE TABLE IF NOT EXISTS config (
     key TEXT PRIMARY KEY,
     value TEXT NOT NULL
 );
 
+-- Repository id patterns never tracked (exact ids or globs with *)
+CREATE TABLE IF NOT EXISTS repo_excludes (
+    pattern TEXT PRIMARY KEY,
+    created_at TEXT NOT NULL
+);
+
 -- Local repository root paths
 CREATE TABLE IF NOT EXISTS local_repo_roots (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -114,6 +149,9 @@ CREATE TABLE IF NOT EXISTS local_repo_status (
     unpushed_commits INTEGER NOT NULL DEFAULT 0,
     behind_commits INTEGER NOT NULL DEFAULT 0,
     is_dirty INTEGER NOT NULL DEFAULT 0,
+    has_conflicts INTEGER NOT NULL DEFAULT 0,
+    is_diverged INTEGER NOT NULL DEFAULT 0,
+    is_unpublished INTEGER NOT NULL DEFAULT 0,
     last_checked TEXT NOT NULL,
     FOREIGN KEY (repo_id) REFERENCES repositories(id) ON DELETE CASCADE
 );
@@ -87,6 +87,8 @@ pub struct RepoBuilder {
     updated_at: DateTime<Utc>,
     is_fork: bool,
     priority: f32,
+    is_private: bool,
+    github_id: Option<String>,
 }
 
 impl RepoBuilder {
@@ -102,6 +104,8 @@ impl RepoBuilder {
             updated_at: now,
             is_fork: false,
             priority: 0.0,
+            is_private: false,
+            github_id: None,
         }
     }
 
@@ -135,6 +139,16 @@ impl RepoBuilder {
         self
     }
 
+    pub fn is_private(mut self, is_private: bool) -> Self {
+        self.is_private = is_private;
+        self
+    }
+
+    pub fn github_id(mut self, github_id: &str) -> Self {
+        self.github_id = Some(github_id.to_string());
+        self
+    }
+
     pub fn build(self) -> Repository {
         Repository {
             id: format!("{}/{}", self.owner, self.name),
@@ -147,6 +161,10 @@ impl RepoBuilder {
             updated_at: self.updated_at,
             is_fork: self.is_fork,
             priority: self.priority,
+            is_private: self.is_private,
+            priority_locked: false,
+            github_id: self.github_id,
+            snoozed_until: None,
         }
     }
 }
@@ -228,9 +246,9 @@ impl BranchBuilder {
 ///
 /// # Example
 /// ```ignore
-/// let pr = PRBuilder::new(123, "feature-auth")
+/// let pr = PRBuilder::new(123, "Add authentication")
 ///     .state(PRState::Open)
-///     .title("Add authentication")
+///     .head_ref_name("feature-auth")
 ///     .build("owner/repo");
 /// ```
 #[derive(Clone)]
@@ -238,7 +256,9 @@ pub struct PRBuilder {
     number: u32,
     state: PRState,
     title: String,
+    head_ref_name: String,
     branch_id: Option<i64>,
+    review_decision: Option<ReviewDecision>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
@@ -250,7 +270,9 @@ impl PRBuilder {
             number,
             state: PRState::Open,
             title: title.to_string(),
+            head_ref_name: String::new(),
             branch_id: None,
+            review_decision: None,
             created_at: now,
             updated_at: now,
         }
@@ -261,11 +283,21 @@ impl PRBuilder {
         self
     }
 
+    pub fn head_ref_name(mut self, name: &str) -> Self {
+        self.head_ref_name = name.to_string();
+        self
+    }
+
     pub fn branch_id(mut self, id: i64) -> Self {
         self.branch_id = Some(id);
         self
     }
 
+    pub fn review_decision(mut self, decision: ReviewDecision) -> Self {
+        self.review_decision = Some(decision);
+        self
+    }
+
     pub fn created_at(mut self, time: DateTime<Utc>) -> Self {
         self.created_at = time;
         self
@@ -284,6 +316,9 @@ impl PRBuilder {
             number: self.number,
             state: self.state,
             title: self.title,
+            head_ref_name: self.head_ref_name,
+            url: None,
+            review_decision: self.review_decision,
             created_at: self.created_at,
             updated_at: self.updated_at,
         }
@@ -300,6 +335,7 @@ pub struct CommitBuilder {
     committer_name: String,
     committer_email: String,
     committed_date: DateTime<Utc>,
+    is_verified: bool,
 }
 
 impl CommitBuilder {
@@ -314,6 +350,7 @@ impl CommitBuilder {
             committer_name: "Test Committer".to_string(),
             committer_email: "committer@test.com".to_string(),
             committed_date: now,
+            is_verified: false,
         }
     }
 
@@ -328,6 +365,16 @@ impl CommitBuilder {
         self
     }
 
+    pub fn committed_date(mut self, date: DateTime<Utc>) -> Self {
+        self.committed_date = date;
+        self
+    }
+
+    pub fn verified(mut self, is_verified: bool) -> Self {
+        self.is_verified = is_verified;
+        self
+    }
+
     pub fn build(self, branch_id: i64) -> Commit {
         Commit {
             id: 0, // Will be assigned by database
@@ -340,6 +387,7 @@ impl CommitBuilder {
             committer_name: self.committer_name,
             committer_email: self.committer_email,
             committed_date: self.committed_date,
+            is_verified: self.is_verified,
         }
     }
 }
@@ -386,6 +434,7 @@ mod tests {
     fn test_pr_builder() {
         let pr = PRBuilder::new(123, "Add feature")
             .state(PRState::Open)
+            .head_ref_name("feature-auth")
             .branch_id(42)
             .build("owner/repo");
 
@@ -393,6 +442,7 @@ mod tests {
         assert_eq!(pr.title, "Add feature");
         assert_eq!(pr.state, PRState::Open);
         assert_eq!(pr.repo_id, "owner/repo");
+        assert_eq!(pr.head_ref_name, "feature-auth");
         assert_eq!(pr.branch_id, Some(42));
     }
 
@@ -3,8 +3,9 @@
 use crate::github::client_trait::GitHubClient;
 use crate::models::*;
 use crate::Result;
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 /// Mock GitHub client for testing
 ///
@@ -19,9 +20,27 @@ pub struct MockGitHubClient {
     pub branches: HashMap<String, Vec<Branch>>,
     pub pull_requests: HashMap<String, Vec<PullRequest>>,
     pub commits: HashMap<(String, String), Vec<Commit>>,
-
-    // Tracking what was called (using RefCell for interior mutability in trait methods)
-    pub created_prs: RefCell<Vec<CreatedPR>>,
+    pub diffs: HashMap<(String, String, String), String>,
+    /// Full commit message per (repo_id, sha)
+    pub commit_messages: HashMap<(String, String), String>,
+    pub team_repos: HashMap<(String, String), Vec<String>>,
+    /// Default branch per repo; repos not listed default to "main"
+    pub default_branches: HashMap<String, String>,
+    /// Repos whose pull request fetch fails, to exercise error handling
+    pub failing_pull_requests: HashSet<String>,
+    /// Base branch per (repo_id, PR number); PRs not listed target "main"
+    pub pr_bases: HashMap<(String, u32), String>,
+    /// CI status per repo; repos not listed have none
+    pub ci_statuses: HashMap<String, CiStatus>,
+    /// HTTP status the check-runs API fails with per repo
+    pub ci_errors: HashMap<String, u16>,
+
+    // Tracking what was called (using Mutex for interior mutability in trait methods,
+    // so the mock can be shared with server handlers)
+    pub created_prs: Mutex<Vec<CreatedPR>>,
+    pub merged_prs: Mutex<Vec<MergedPR>>,
+    /// Local clone passed with each branch fetch, by repo id
+    pub branch_fetch_clones: Mutex<Vec<(String, Option<PathBuf>)>>,
 
     // Expectations for verification
     pub expect_create_pr: Vec<String>, // repo_ids that should have PR created
@@ -35,6 +54,15 @@ pub struct CreatedPR {
     pub branch_name: String,
     pub title: String,
     pub body: String,
+    pub draft: bool,
+}
+
+/// Record of a merged PR for verification
+#[derive(Debug, Clone)]
+pub struct MergedPR {
+    pub repo_id: String,
+    pub number: u32,
+    pub method: MergeMethod,
 }
 
 impl MockGitHubClient {
@@ -67,6 +95,63 @@ impl MockGitHubClient {
         self
     }
 
+    /// Add the diff returned for `head` compared against `base`
+    pub fn with_diff(mut self, repo_id: &str, base: &str, head: &str, diff: &str) -> Self {
+        self.diffs.insert(
+            (repo_id.to_string(), base.to_string(), head.to_string()),
+            diff.to_string(),
+        );
+        self
+    }
+
+    /// Add the full message returned for a commit
+    pub fn with_commit_message(mut self, repo_id: &str, sha: &str, message: &str) -> Self {
+        self.commit_messages
+            .insert((repo_id.to_string(), sha.to_string()), message.to_string());
+        self
+    }
+
+    /// Add the repository ids a team has access to
+    pub fn with_team_repos(mut self, org: &str, team: &str, repo_ids: Vec<&str>) -> Self {
+        self.team_repos.insert(
+            (org.to_string(), team.to_string()),
+            repo_ids.into_iter().map(String::from).collect(),
+        );
+        self
+    }
+
+    /// Set the default branch for a repository
+    pub fn with_default_branch(mut self, repo_id: &str, branch: &str) -> Self {
+        self.default_branches
+            .insert(repo_id.to_string(), branch.to_string());
+        self
+    }
+
+    /// Set the CI status reported for a repository's default branch
+    pub fn with_ci_status(mut self, repo_id: &str, status: CiStatus) -> Self {
+        self.ci_statuses.insert(repo_id.to_string(), status);
+        self
+    }
+
+    /// Make fetching pull requests fail for a repository
+    pub fn with_failing_pull_requests(mut self, repo_id: &str) -> Self {
+        self.failing_pull_requests.insert(repo_id.to_string());
+        self
+    }
+
+    /// Make fetching CI status fail with an HTTP status
+    pub fn with_ci_error(mut self, repo_id: &str, status: u16) -> Self {
+        self.ci_errors.insert(repo_id.to_string(), status);
+        self
+    }
+
+    /// Set the base branch a pull request targets
+    pub fn with_pr_base(mut self, repo_id: &str, number: u32, base: &str) -> Self {
+        self.pr_bases
+            .insert((repo_id.to_string(), number), base.to_string());
+        self
+    }
+
     /// Expect that create_pr will be called for this repo
     pub fn expect_create_pr_for(mut self, repo_id: &str) -> Self {
         self.expect_create_pr.push(repo_id.to_string());
@@ -83,7 +168,7 @@ impl MockGitHubClient {
     ///
     /// Call this at the end of your test to ensure the mock was used as expected
     pub fn verify(&self) {
-        let created_prs = self.created_prs.borrow();
+        let created_prs = self.created_prs.lock().unwrap();
 
         // Check that all expected PRs were created
         for expected_repo in &self.expect_create_pr {
@@ -108,34 +193,125 @@ impl MockGitHubClient {
 
     /// Get all created PRs for inspection
     pub fn get_created_prs(&self) -> Vec<CreatedPR> {
-        self.created_prs.borrow().clone()
+        self.created_prs.lock().unwrap().clone()
+    }
+
+    /// Get all merged PRs for inspection
+    pub fn get_merged_prs(&self) -> Vec<MergedPR> {
+        self.merged_prs.lock().unwrap().clone()
+    }
+
+    /// Get the local clone each branch fetch was given, in call order
+    pub fn get_branch_fetch_clones(&self) -> Vec<(String, Option<PathBuf>)> {
+        self.branch_fetch_clones.lock().unwrap().clone()
     }
 }
 
 impl GitHubClient for MockGitHubClient {
-    fn list_repos(&self, owner: &str, _limit: usize) -> Result<Vec<Repository>> {
-        Ok(self.repos.get(owner).cloned().unwrap_or_default())
+    fn list_repos(&self, owner: &str, limit: usize) -> Result<Vec<Repository>> {
+        let mut repos = self.repos.get(owner).cloned().unwrap_or_default();
+        repos.truncate(limit);
+        Ok(repos)
+    }
+
+    fn get_repository(&self, repo_id: &str) -> Result<Repository> {
+        self.repos
+            .values()
+            .flatten()
+            .find(|repo| repo.id == repo_id)
+            .cloned()
+            .ok_or_else(|| crate::Error::GitHubCLI(format!("No repository {}", repo_id)))
+    }
+
+    fn list_team_repos(&self, org: &str, team: &str) -> Result<Vec<String>> {
+        self.team_repos
+            .get(&(org.to_string(), team.to_string()))
+            .cloned()
+            .ok_or_else(|| crate::Error::GitHubCLI(format!("No team {} in {}", team, org)))
+    }
+
+    fn get_default_branch(&self, repo_id: &str) -> Result<String> {
+        Ok(self
+            .default_branches
+            .get(repo_id)
+            .cloned()
+            .unwrap_or_else(|| "main".to_string()))
     }
 
     fn fetch_branches(&self, repo_id: &str) -> Result<Vec<Branch>> {
         Ok(self.branches.get(repo_id).cloned().unwrap_or_default())
     }
 
-    fn fetch_pull_requests(&self, repo_id: &str) -> Result<Vec<PullRequest>> {
-        Ok(self.pull_requests.get(repo_id).cloned().unwrap_or_default())
+    fn fetch_branches_since(
+        &self,
+        repo_id: &str,
+        _default_branch: &str,
+        _previous: &[Branch],
+        local_clone: Option<&Path>,
+    ) -> Result<Vec<Branch>> {
+        self.branch_fetch_clones
+            .lock()
+            .unwrap()
+            .push((repo_id.to_string(), local_clone.map(Path::to_path_buf)));
+        self.fetch_branches(repo_id)
+    }
+
+    fn fetch_pull_requests(&self, repo_id: &str, limit: usize) -> Result<Vec<PullRequest>> {
+        if self.failing_pull_requests.contains(repo_id) {
+            return Err(crate::Error::GitHubAPI {
+                status: 502,
+                message: format!("Bad gateway fetching PRs for {}", repo_id),
+            });
+        }
+        let mut prs = self.pull_requests.get(repo_id).cloned().unwrap_or_default();
+        prs.truncate(limit);
+        Ok(prs)
     }
 
     fn fetch_commits(
         &self,
         repo_id: &str,
         branch_name: &str,
-        _branch_id: i64,
+        branch_id: i64,
+        limit: usize,
     ) -> Result<Vec<Commit>> {
-        Ok(self
+        let mut commits = self
             .commits
             .get(&(repo_id.to_string(), branch_name.to_string()))
             .cloned()
-            .unwrap_or_default())
+            .unwrap_or_default();
+        commits.truncate(limit);
+        // Like the real client, stamp commits with the branch they were fetched for
+        for commit in &mut commits {
+            commit.branch_id = branch_id;
+        }
+        Ok(commits)
+    }
+
+    fn fetch_branch_diff(&self, repo_id: &str, base: &str, head: &str) -> Result<String> {
+        self.diffs
+            .get(&(repo_id.to_string(), base.to_string(), head.to_string()))
+            .cloned()
+            .ok_or_else(|| {
+                crate::Error::GitHubCLI(format!("No diff for {} {}...{}", repo_id, base, head))
+            })
+    }
+
+    fn fetch_commit_message(&self, repo_id: &str, sha: &str) -> Result<String> {
+        self.commit_messages
+            .get(&(repo_id.to_string(), sha.to_string()))
+            .cloned()
+            .ok_or_else(|| crate::Error::GitHubCLI(format!("No commit {} in {}", sha, repo_id)))
+    }
+
+    fn fetch_ci_status(&self, repo_id: &str, _git_ref: &str) -> Result<CiStatus> {
+        if let Some(&status) = self.ci_errors.get(repo_id) {
+            return Err(crate::Error::GitHubAPI {
+                status,
+                message: format!("HTTP {} fetching check runs for {}", status, repo_id),
+            });
+        }
+        Ok(self.ci_statuses.get(repo_id).copied().unwrap_or_default())
     }
 
     fn classify_branch_status(
@@ -154,21 +330,40 @@ impl GitHubClient for MockGitHubClient {
         branch_name: &str,
         title: Option<&str>,
         body: Option<&str>,
+        draft: bool,
     ) -> Result<String> {
         // Record that this was called
-        let default_title = branch_name.replace(['-', '_'], " ");
+        let default_title = crate::github::commands::default_pr_title(branch_name);
         let default_body = "Created via Overall";
 
-        self.created_prs.borrow_mut().push(CreatedPR {
+        self.created_prs.lock().unwrap().push(CreatedPR {
             repo_id: repo_id.to_string(),
             branch_name: branch_name.to_string(),
             title: title.unwrap_or(&default_title).to_string(),
             body: body.unwrap_or(default_body).to_string(),
+            draft,
         });
 
         // Return a fake PR URL
         Ok(format!("https://github.com/{}/pull/123", repo_id))
     }
+
+    fn fetch_pull_request_base(&self, repo_id: &str, number: u32) -> Result<String> {
+        Ok(self
+            .pr_bases
+            .get(&(repo_id.to_string(), number))
+            .cloned()
+            .unwrap_or_else(|| "main".to_string()))
+    }
+
+    fn merge_pull_request(&self, repo_id: &str, number: u32, method: MergeMethod) -> Result<()> {
+        self.merged_prs.lock().unwrap().push(MergedPR {
+            repo_id: repo_id.to_string(),
+            number,
+            method,
+        });
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -189,6 +384,10 @@ mod tests {
             updated_at: Utc::now(),
             is_fork: false,
             priority: 0.0,
+            is_private: false,
+            priority_locked: false,
+            github_id: None,
+            snoozed_until: None,
         };
 
         let mock = MockGitHubClient::new().with_repo("owner", repo.clone());
@@ -203,13 +402,14 @@ mod tests {
         let mock = MockGitHubClient::new();
 
         let _url = mock
-            .create_pull_request("owner/repo", "feature-branch", None, None)
+            .create_pull_request("owner/repo", "feature-branch", None, None, true)
             .unwrap();
 
         let created = mock.get_created_prs();
         assert_eq!(created.len(), 1);
         assert_eq!(created[0].repo_id, "owner/repo");
         assert_eq!(created[0].branch_name, "feature-branch");
+        assert!(created[0].draft);
     }
 
     #[test]
@@ -220,7 +420,7 @@ mod tests {
 
         // Create PR for repo1
         let _url = mock
-            .create_pull_request("owner/repo1", "feature", None, None)
+            .create_pull_request("owner/repo1", "feature", None, None, false)
             .unwrap();
 
         // Verify should pass
@@ -243,7 +443,7 @@ mod tests {
 
         // Create the PR anyway - verification should fail
         let _url = mock
-            .create_pull_request("owner/repo", "feature", None, None)
+            .create_pull_request("owner/repo", "feature", None, None, false)
             .unwrap();
 
         mock.verify();
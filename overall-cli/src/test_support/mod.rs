@@ -14,7 +14,7 @@ pub mod fixtures;
 pub mod mock_github;
 
 #[cfg(test)]
-pub use fixtures::{BranchBuilder, RepoBuilder, TestDatabase};
+pub use fixtures::{BranchBuilder, PRBuilder, RepoBuilder, TestDatabase};
 
 #[cfg(test)]
 pub use mock_github::MockGitHubClient;
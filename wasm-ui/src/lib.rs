@@ -10,12 +10,26 @@ struct Repository {
     id: String,
     owner: String,
     name: String,
-    language: String,
+    /// `None` when GitHub detected no primary language
+    language: Option<String>,
     last_push: String,
+    staleness: String,
+    /// diverged, needsSync, localChanges, stale or complete, computed by the exporter
+    status: String,
     branches: Vec<BranchInfo>,
     pull_requests: Vec<PullRequestInfo>,
     unmerged_count: u32,
     pr_count: u32,
+    /// 0.0-1.0 score from `overall reprioritize` or a manual override
+    priority: f32,
+    /// The priority is a manual override kept across reprioritize runs
+    priority_locked: bool,
+    /// Unmerged branch with the most commits ahead, highlighted in the detail modal
+    focus_branch: Option<String>,
+    /// passing, failing, pending or none: check runs on the default branch
+    ci_status: String,
+    /// RFC 3339 time until which the repo counts as complete (see `is_snoozed`)
+    snoozed_until: Option<String>,
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -23,21 +37,35 @@ struct Repository {
 struct BranchInfo {
     name: String,
     sha: String,
+    /// Abbreviated `sha` from the export, for display
+    short_sha: String,
     status: String,
     ahead: u32,
     behind: u32,
     last_commit_date: String,
+    /// Parsed `last_commit_date`, used to order branches by recent activity
+    last_commit_at: Option<chrono::DateTime<chrono::Utc>>,
+    has_open_pr: bool,
+    /// Counts towards `unmerged_count`; computed by the CLI so both agree
+    is_unmerged: bool,
+    /// Commits ahead, no open PR and no recent commits; a cleanup candidate
+    is_abandoned: bool,
+    /// Most recent commits only; see `more_commits`
     commits: Vec<CommitInfo>,
+    /// Commits left out of the export, loaded on demand in the detail modal
+    more_commits: usize,
 }
 
 #[cfg(target_arch = "wasm32")]
 #[derive(Clone, PartialEq)]
 struct CommitInfo {
     sha: String,
+    short_sha: String,
     message: String,
     author_name: String,
     author_email: String,
     authored_date: String,
+    is_verified: bool,
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -46,6 +74,10 @@ struct PullRequestInfo {
     number: u32,
     title: String,
     state: String,
+    head_ref_name: String,
+    url: Option<String>,
+    /// `Approved`, `ChangesRequested` or `ReviewRequired`
+    review_decision: Option<String>,
     created_at: String,
     updated_at: String,
 }
@@ -58,6 +90,14 @@ struct RepoGroup {
     repos: Vec<Repository>,
 }
 
+/// Everything `fetch_repos` reads from repos.json
+#[cfg(target_arch = "wasm32")]
+struct RepoData {
+    groups: Vec<RepoGroup>,
+    /// The CLI's `github.protected_branches`; see `is_protected_branch`
+    protected_branches: Vec<String>,
+}
+
 #[cfg(target_arch = "wasm32")]
 #[derive(Clone, PartialEq)]
 struct BuildInfo {
@@ -88,43 +128,157 @@ struct LocalRepoStatus {
     unpushed_commits: u32,
     behind_commits: u32,
     is_dirty: bool,
+    has_conflicts: bool,
+    /// Both unpushed and behind its upstream; needs a rebase or merge
+    is_diverged: bool,
+    /// Never pushed; `unpushed_commits` counts commits ahead of the remote default
+    is_unpublished: bool,
     last_checked: String,
 }
 
+/// Image and accessible label for one traffic-light status
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, PartialEq, serde::Deserialize)]
+struct StatusIcon {
+    path: String,
+    label: String,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl StatusIcon {
+    fn new(path: &str, label: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            label: label.to_string(),
+        }
+    }
+}
+
+/// Status icons, loaded from /icons/manifest.json so users can swap in their
+/// own set; statuses missing from the manifest keep the built-in icon
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, PartialEq, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct IconSet {
+    needs_sync: StatusIcon,
+    local_changes: StatusIcon,
+    stale: StatusIcon,
+    complete: StatusIcon,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Default for IconSet {
+    fn default() -> Self {
+        Self {
+            needs_sync: StatusIcon::new(
+                "/icons/needs-sync.png",
+                "Stop: merge conflicts, unpushed or unfetched commits",
+            ),
+            local_changes: StatusIcon::new(
+                "/icons/local-changes.png",
+                "Yield: uncommitted local changes",
+            ),
+            stale: StatusIcon::new("/icons/stale.png", "Clean up: unmerged feature branches"),
+            complete: StatusIcon::new("/icons/complete.png", "Proceed: everything up to date"),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl IconSet {
+    /// Icon for a `calculate_repo_status_priority` value
+    fn for_priority(&self, priority: u8) -> &StatusIcon {
+        match priority {
+            0 => &self.needs_sync,
+            1 => &self.local_changes,
+            2 => &self.stale,
+            _ => &self.complete,
+        }
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
-#[derive(Clone, PartialEq, Copy)]
+#[derive(Clone, PartialEq, Copy, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 enum SortColumn {
     Name,
     Language,
     LastUpdated,
+    Priority,
     Status,
 }
 
 #[cfg(target_arch = "wasm32")]
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, serde::Deserialize)]
 struct SortState {
     column: SortColumn,
     ascending: bool,
 }
 
+/// Branch order in the detail modal
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Copy, PartialEq)]
+enum BranchSort {
+    Recent,
+    Name,
+    Ahead,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl BranchSort {
+    fn next(self) -> Self {
+        match self {
+            BranchSort::Recent => BranchSort::Name,
+            BranchSort::Name => BranchSort::Ahead,
+            BranchSort::Ahead => BranchSort::Recent,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            BranchSort::Recent => "Sort: Recent",
+            BranchSort::Name => "Sort: Name",
+            BranchSort::Ahead => "Sort: Ahead",
+        }
+    }
+}
+
+/// Rows rendered per step of the repo list; more are added on scroll
+#[cfg(target_arch = "wasm32")]
+const REPO_PAGE_SIZE: usize = 100;
+
 #[cfg(target_arch = "wasm32")]
 #[function_component(App)]
 fn app() -> Html {
     let groups = use_state(|| Vec::<RepoGroup>::new());
+    // Branch patterns never offered for a PR, from repos.json
+    let protected_branches = use_state(Vec::<String>::new);
     let active_tab = use_state(|| 0usize);
     let selected_repo = use_state(|| None::<Repository>);
     let show_add_dialog = use_state(|| false);
     let show_settings = use_state(|| false);
     let dragged_repo_id = use_state(|| None::<String>);
     let loading_repo = use_state(|| None::<String>);
+    // Selection mode shows checkboxes on each row for the bulk action bar
+    let selection_mode = use_state(|| false);
+    let selected = use_state(std::collections::HashSet::<String>::new);
+    // How many rows of the active tab are rendered; large groups grow by
+    // REPO_PAGE_SIZE as the list is scrolled to the bottom
+    let visible_rows = use_state(|| REPO_PAGE_SIZE);
     let refreshing = use_state(|| false);
     let last_refresh = use_state(|| None::<f64>);
+    // Why repos.json could not be loaded, e.g. a schema version mismatch
+    let load_error = use_state(|| None::<String>);
     let local_repo_statuses =
         use_state(|| std::collections::HashMap::<String, LocalRepoStatus>::new());
+    // Header totals from /api/dashboard, refreshed with the repo data
+    let dashboard = use_state(|| None::<DashboardJson>);
+    // Replaced by the configured default once /api/ui-config loads
     let sort_state = use_state(|| SortState {
         column: SortColumn::Status,
         ascending: false,
     });
+    let icons = use_state(IconSet::default);
     let build_info = use_state(|| BuildInfo {
         version: "0.1.0".to_string(),
         build_date: "Loading...".to_string(),
@@ -136,10 +290,88 @@ fn app() -> Html {
     // Load repository data on mount
     {
         let groups = groups.clone();
+        let protected_branches = protected_branches.clone();
+        let load_error = load_error.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match fetch_repos().await {
+                    Ok(data) => {
+                        groups.set(data.groups);
+                        protected_branches.set(data.protected_branches);
+                    }
+                    Err(e) => load_error.set(Some(e)),
+                }
+            });
+            || ()
+        });
+    }
+
+    // Apply the configured default sort on mount
+    {
+        let sort_state = sort_state.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(config) = fetch_ui_config().await {
+                    sort_state.set(config.default_sort);
+                }
+            });
+            || ()
+        });
+    }
+
+    // Show the needs-attention count in the tab title and the header totals
+    // whenever data (re)loads
+    {
+        let loaded_groups = (*groups).clone();
+        let dashboard = dashboard.clone();
+        use_effect_with(loaded_groups, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(config) = fetch_ui_config().await {
+                    set_document_title(config.needs_attention);
+                }
+                if let Ok(counts) = fetch_dashboard().await {
+                    dashboard.set(Some(counts));
+                }
+            });
+            || ()
+        });
+    }
+
+    // Render more rows when the page is scrolled near the end of the list;
+    // the listener is replaced whenever the window or the group size changes
+    {
+        let visible_rows = visible_rows.clone();
+        let total = groups.get(*active_tab).map_or(0, |g| g.repos.len());
+        use_effect_with((*visible_rows, total), move |&(visible, total)| {
+            let listener = web_sys::window().filter(|_| visible < total).map(|window| {
+                let target = window.clone();
+                gloo::events::EventListener::new(&target, "scroll", move |_| {
+                    let viewport = window.inner_height().ok().and_then(|h| h.as_f64());
+                    let scrolled = window.scroll_y().ok();
+                    let height = window
+                        .document()
+                        .and_then(|d| d.document_element())
+                        .map(|e| e.scroll_height() as f64);
+                    if let (Some(viewport), Some(scrolled), Some(height)) =
+                        (viewport, scrolled, height)
+                    {
+                        if scrolled + viewport >= height - 600.0 {
+                            visible_rows.set((visible + REPO_PAGE_SIZE).min(total));
+                        }
+                    }
+                })
+            });
+            move || drop(listener)
+        });
+    }
+
+    // Load the status icon set on mount, keeping the built-in one if absent
+    {
+        let icons = icons.clone();
         use_effect_with((), move |_| {
             wasm_bindgen_futures::spawn_local(async move {
-                if let Ok(loaded_groups) = fetch_repos().await {
-                    groups.set(loaded_groups);
+                if let Ok(loaded) = fetch_icon_set().await {
+                    icons.set(loaded);
                 }
             });
             || ()
@@ -218,11 +450,157 @@ fn app() -> Html {
 
     let on_tab_click = {
         let active_tab = active_tab.clone();
+        let selected = selected.clone();
+        let visible_rows = visible_rows.clone();
         Callback::from(move |idx: usize| {
             active_tab.set(idx);
+            visible_rows.set(REPO_PAGE_SIZE);
+            // Bulk actions only apply to repos visible in the current tab
+            selected.set(std::collections::HashSet::new());
+        })
+    };
+
+    let on_toggle_selection_mode = {
+        let selection_mode = selection_mode.clone();
+        let selected = selected.clone();
+        Callback::from(move |_| {
+            selection_mode.set(!*selection_mode);
+            selected.set(std::collections::HashSet::new());
+        })
+    };
+
+    let on_toggle_select = {
+        let selected = selected.clone();
+        Callback::from(move |repo_id: String| {
+            let mut next = (*selected).clone();
+            if !next.remove(&repo_id) {
+                next.insert(repo_id);
+            }
+            selected.set(next);
+        })
+    };
+
+    let on_select_all = {
+        let selected = selected.clone();
+        let groups = groups.clone();
+        let active_tab = active_tab.clone();
+        Callback::from(move |_| {
+            if let Some(group) = groups.get(*active_tab) {
+                selected.set(group.repos.iter().map(|r| r.id.clone()).collect());
+            }
+        })
+    };
+
+    let on_clear_selection = {
+        let selected = selected.clone();
+        Callback::from(move |_| selected.set(std::collections::HashSet::new()))
+    };
+
+    let on_bulk_move = {
+        let selected = selected.clone();
+        let groups = groups.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let value = select.value();
+            // Reset to the placeholder so the same group can be picked again
+            select.set_value("");
+            let target_group_id = match value.as_str() {
+                "" => return,
+                "ungrouped" => None,
+                id => match id.parse::<i64>() {
+                    Ok(id) => Some(id),
+                    Err(_) => return,
+                },
+            };
+
+            let repo_ids: Vec<String> = selected.iter().cloned().collect();
+            let selected = selected.clone();
+            let groups = groups.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match move_repos_to_group(&repo_ids, target_group_id).await {
+                    Ok(()) => {
+                        selected.set(std::collections::HashSet::new());
+                        if let Ok(data) = fetch_repos().await {
+                            groups.set(data.groups);
+                        }
+                    }
+                    Err(e) => {
+                        web_sys::console::error_1(&format!("Failed to move repos: {}", e).into());
+                        if let Some(window) = web_sys::window() {
+                            let _ = window.alert_with_message(&format!("Error: {}", e));
+                        }
+                    }
+                }
+            });
+        })
+    };
+
+    let on_bulk_create_prs = {
+        let selected = selected.clone();
+        let groups = groups.clone();
+        Callback::from(move |_| {
+            let mut repo_ids: Vec<String> = selected.iter().cloned().collect();
+            repo_ids.sort();
+            let groups = groups.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut lines = Vec::new();
+                for repo_id in &repo_ids {
+                    match create_all_pull_requests(repo_id).await {
+                        Ok(message) => lines.push(format!("{}: {}", repo_id, message)),
+                        Err(e) => lines.push(format!("{}: failed - {}", repo_id, e)),
+                    }
+                }
+                reload_after_regeneration(&groups).await;
+                if let Some(window) = web_sys::window() {
+                    let _ = window.alert_with_message(&lines.join("\n"));
+                }
+            });
+        })
+    };
+
+    // Snooze the selection for the picked duration, or wake it with `None`
+    let on_bulk_snooze = {
+        let selected = selected.clone();
+        let groups = groups.clone();
+        Callback::from(move |duration: Option<String>| {
+            let mut repo_ids: Vec<String> = selected.iter().cloned().collect();
+            repo_ids.sort();
+            let groups = groups.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut failures = Vec::new();
+                for repo_id in &repo_ids {
+                    if let Err(e) = snooze_repo(repo_id, duration.as_deref()).await {
+                        failures.push(format!("{}: {}", repo_id, e));
+                    }
+                }
+                reload_after_regeneration(&groups).await;
+                if !failures.is_empty() {
+                    if let Some(window) = web_sys::window() {
+                        let _ = window.alert_with_message(&failures.join("\n"));
+                    }
+                }
+            });
+        })
+    };
+
+    let on_bulk_snooze_select = {
+        let on_bulk_snooze = on_bulk_snooze.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let value = select.value();
+            // Reset to the placeholder so the same duration can be picked again
+            select.set_value("");
+            if !value.is_empty() {
+                on_bulk_snooze.emit(Some(value));
+            }
         })
     };
 
+    let on_bulk_wake = {
+        let on_bulk_snooze = on_bulk_snooze.clone();
+        Callback::from(move |_| on_bulk_snooze.emit(None))
+    };
+
     let on_repo_click = {
         let selected_repo = selected_repo.clone();
         let loading_repo = loading_repo.clone();
@@ -241,49 +619,37 @@ fn app() -> Html {
             let local_repo_statuses = local_repo_statuses.clone();
 
             wasm_bindgen_futures::spawn_local(async move {
-                // Step 1: Sync this specific repo from GitHub
-                if let Err(e) = sync_single_repo(&repo_id).await {
-                    web_sys::console::error_1(
-                        &format!("Failed to sync repo {}: {}", repo_id, e).into(),
-                    );
-                    loading_repo.set(None);
-                    return;
-                }
-
-                // Step 2: Fetch fresh /repos.json
-                let fresh_groups = match fetch_repos().await {
-                    Ok(g) => g,
+                // Step 1: Refresh this repo from GitHub and its local clone
+                let (fresh_repo, local_status) = match refresh_repo(&repo_id).await {
+                    Ok(refreshed) => refreshed,
                     Err(e) => {
-                        web_sys::console::error_1(&format!("Failed to fetch repos: {}", e).into());
+                        web_sys::console::error_1(
+                            &format!("Failed to refresh repo {}: {}", repo_id, e).into(),
+                        );
                         loading_repo.set(None);
                         return;
                     }
                 };
 
-                // Step 3: Fetch fresh local repo statuses
-                if let Ok(statuses) = fetch_local_repo_statuses().await {
-                    let status_map: std::collections::HashMap<String, LocalRepoStatus> = statuses
-                        .into_iter()
-                        .map(|s| (s.repo_id.clone(), s))
-                        .collect();
-                    local_repo_statuses.set(status_map);
+                // Step 2: Replace just this repo in the group data
+                let mut fresh_groups = (*groups).clone();
+                for group in fresh_groups.iter_mut() {
+                    for repo in group.repos.iter_mut().filter(|r| r.id == repo_id) {
+                        *repo = fresh_repo.clone();
+                    }
                 }
-
-                // Step 4: Find the fresh repo data
-                let fresh_repo = fresh_groups
-                    .iter()
-                    .flat_map(|g| &g.repos)
-                    .find(|r| r.id == repo_id)
-                    .cloned();
-
-                // Step 5: Update state
                 groups.set(fresh_groups);
 
-                // Step 6: Hide spinner and show dialog
-                loading_repo.set(None);
-                if let Some(fresh_repo) = fresh_repo {
-                    selected_repo.set(Some(fresh_repo));
+                // Step 3: Update just this repo's local status
+                if let Some(status) = local_status {
+                    let mut status_map = (*local_repo_statuses).clone();
+                    status_map.insert(status.repo_id.clone(), status);
+                    local_repo_statuses.set(status_map);
                 }
+
+                // Step 4: Hide spinner and show dialog
+                loading_repo.set(None);
+                selected_repo.set(Some(fresh_repo));
             });
         })
     };
@@ -325,31 +691,40 @@ fn app() -> Html {
 
     let on_refresh = {
         let groups = groups.clone();
+        let protected_branches = protected_branches.clone();
         let local_repo_statuses = local_repo_statuses.clone();
         let refreshing = refreshing.clone();
         let last_refresh = last_refresh.clone();
+        let load_error = load_error.clone();
         Callback::from(move |_| {
             // Set refreshing state
             refreshing.set(true);
 
             let groups = groups.clone();
+            let protected_branches = protected_branches.clone();
             let local_repo_statuses = local_repo_statuses.clone();
             let refreshing = refreshing.clone();
             let last_refresh = last_refresh.clone();
+            let load_error = load_error.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                // Trigger the scan
-                if let Err(e) = trigger_local_repo_scan().await {
-                    web_sys::console::error_1(&format!("Failed to trigger scan: {}", e).into());
+                // Sync GitHub and rescan local clones
+                if let Err(e) = trigger_refresh_all().await {
+                    web_sys::console::error_1(&format!("Failed to refresh: {}", e).into());
                     refreshing.set(false);
                     return;
                 }
 
-                // Wait a bit for scan to complete
+                // Wait a bit for repos.json to be regenerated
                 gloo::timers::future::sleep(std::time::Duration::from_millis(1000)).await;
 
                 // Reload repo data
-                if let Ok(loaded_groups) = fetch_repos().await {
-                    groups.set(loaded_groups);
+                match fetch_repos().await {
+                    Ok(data) => {
+                        groups.set(data.groups);
+                        protected_branches.set(data.protected_branches);
+                        load_error.set(None);
+                    }
+                    Err(e) => load_error.set(Some(e)),
                 }
 
                 // Reload local repo statuses
@@ -388,8 +763,8 @@ fn app() -> Html {
                 wasm_bindgen_futures::spawn_local(async move {
                     if move_repo_to_group(&repo_id, target_group_id).await.is_ok() {
                         // Reload data after successful move
-                        if let Ok(loaded_groups) = fetch_repos().await {
-                            groups_state.set(loaded_groups);
+                        if let Ok(data) = fetch_repos().await {
+                            groups_state.set(data.groups);
                         }
                     }
                     dragged_repo_id.set(None);
@@ -409,9 +784,9 @@ fn app() -> Html {
                     ascending: !current.ascending,
                 });
             } else {
-                // New column - default to descending for Status, ascending for others
+                // New column - default to descending for Status and Priority, ascending for others
                 let ascending = match column {
-                    SortColumn::Status => false,
+                    SortColumn::Status | SortColumn::Priority => false,
                     _ => true,
                 };
                 sort_state.set(SortState { column, ascending });
@@ -426,6 +801,7 @@ fn app() -> Html {
                     <div class="header-left">
                         <h1>{ "Overall" }</h1>
                         <span class="tagline">{ "Repository Manager" }</span>
+                        { dashboard_chips(dashboard.as_ref()) }
                     </div>
                     <div class="header-right">
                         { if let Some(timestamp) = *last_refresh {
@@ -441,16 +817,61 @@ fn app() -> Html {
                             class={if *refreshing { "btn-refresh refreshing" } else { "btn-refresh" }}
                             onclick={on_refresh}
                             disabled={*refreshing}
-                            title="Refresh local repository status"
+                            title="Refresh GitHub and local repository status"
                         >
                             { "🔄" }
                         </button>
+                        <button
+                            class={classes!("btn-select", selection_mode.then_some("active"))}
+                            onclick={on_toggle_selection_mode}
+                            title="Select repositories for bulk actions"
+                        >
+                            { "☑" }
+                        </button>
                         <button class="btn-settings" onclick={on_open_settings} title="Local Repository Settings">
                             { "⚙️" }
                         </button>
                     </div>
                 </header>
 
+                { if let Some(error) = (*load_error).clone() {
+                    html! { <div class="load-error">{ error }</div> }
+                } else {
+                    html! {}
+                }}
+
+                { if *selection_mode {
+                    let nothing_selected = selected.is_empty();
+                    html! {
+                        <div class="bulk-action-bar">
+                            <span class="bulk-count">{ format!("{} selected", selected.len()) }</span>
+                            <button class="btn-bulk" onclick={on_select_all}>{ "Select all" }</button>
+                            <button class="btn-bulk" onclick={on_clear_selection} disabled={nothing_selected}>{ "Clear" }</button>
+                            <select class="bulk-move" onchange={on_bulk_move} disabled={nothing_selected}>
+                                <option value="" selected=true>{ "Move to group..." }</option>
+                                { for groups.iter().map(|group| {
+                                    let value = group.id.map_or_else(|| "ungrouped".to_string(), |id| id.to_string());
+                                    html! { <option {value}>{ &group.name }</option> }
+                                })}
+                            </select>
+                            <button class="btn-bulk" onclick={on_bulk_create_prs} disabled={nothing_selected} title="Create PRs for every unmerged branch in the selected repositories">
+                                { "Create all PRs" }
+                            </button>
+                            <select class="bulk-snooze" onchange={on_bulk_snooze_select} disabled={nothing_selected}>
+                                <option value="" selected=true>{ "Snooze for..." }</option>
+                                <option value="1d">{ "1 day" }</option>
+                                <option value="1w">{ "1 week" }</option>
+                                <option value="4w">{ "4 weeks" }</option>
+                            </select>
+                            <button class="btn-bulk" onclick={on_bulk_wake} disabled={nothing_selected} title="Clear the snooze on the selected repositories">
+                                { "Wake" }
+                            </button>
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }}
+
                 <nav class="tabs">
                     { for groups.iter().enumerate().map(|(idx, group)| {
                         let onclick = {
@@ -477,17 +898,23 @@ fn app() -> Html {
                         // TRAFFIC LIGHT PRIORITY: 0=RED (STOP), 1=YELLOW (YIELD), 2=WHITE (cleanup), 3=GREEN (GO)
                         // CRITICAL: Use the SAME calculation as individual repos, then take minimum (worst)
                         let worst_priority = group.repos.iter()
-                            .map(|repo| calculate_repo_status_priority(repo, local_repo_statuses.get(&repo.id)))
+                            .map(calculate_repo_status_priority)
                             .min()
                             .unwrap_or(3); // Default to complete if no repos
 
-                        // Use PNG icons from static/icons/ - show only worst-case status
-                        let (status_icon, tab_class) = match worst_priority {
-                            0 => (html! { <img class="tab-status-icon" src="/icons/needs-sync.png" alt="Needs sync" title="🛑 STOP: Has uncommitted, unpushed, or unfetched commits" /> }, "tab-needs-sync"),
-                            1 => (html! { <img class="tab-status-icon" src="/icons/local-changes.png" alt="Local changes" title="⚠️ YIELD: Has local uncommitted changes" /> }, "tab-local-changes"),
-                            2 => (html! { <img class="tab-status-icon" src="/icons/stale.png" alt="Stale" title="ℹ️ CLEAN UP: Has unmerged feature branches" /> }, "tab-stale"),
-                            3 if !group.repos.is_empty() => (html! { <img class="tab-status-icon" src="/icons/complete.png" alt="Complete" title="✅ PROCEED: All repositories up to date" /> }, "tab-complete"),
-                            _ => (html! {}, ""),
+                        // Show only the worst-case status, using the loaded icon set
+                        let tab_class = match worst_priority {
+                            0 => "tab-needs-sync",
+                            1 => "tab-local-changes",
+                            2 => "tab-stale",
+                            3 if !group.repos.is_empty() => "tab-complete",
+                            _ => "",
+                        };
+                        let status_icon = if tab_class.is_empty() {
+                            html! {}
+                        } else {
+                            let icon = icons.for_priority(worst_priority);
+                            html! { <img class="tab-status-icon" src={icon.path.clone()} alt={icon.label.clone()} title={icon.label.clone()} /> }
                         };
 
                         html! {
@@ -510,7 +937,7 @@ fn app() -> Html {
                     { if let Some(group) = groups.get(*active_tab) {
                         // Clone repos and sort them
                         let mut sorted_repos = group.repos.clone();
-                        sort_repositories(&mut sorted_repos, &*sort_state, &*local_repo_statuses);
+                        sort_repositories(&mut sorted_repos, &sort_state);
 
                         html! {
                             <>
@@ -518,13 +945,15 @@ fn app() -> Html {
                                     sort_state={(*sort_state).clone()}
                                     on_column_click={on_sort_column_click.clone()}
                                 />
-                                { for sorted_repos.iter().map(|repo| {
+                                { for sorted_repos.iter().take(*visible_rows).map(|repo| {
                                     let onclick = {
                                         let on_repo_click = on_repo_click.clone();
                                         let repo = repo.clone();
                                         Callback::from(move |_| on_repo_click.emit(repo.clone()))
                                     };
                                     let on_drag_start = on_drag_start.clone();
+                                    let on_toggle_select = on_toggle_select.clone();
+                                    let is_selected = selected.contains(&repo.id);
                                     let local_status = local_repo_statuses.get(&repo.id).cloned();
 
                                     // Debug log for sw-install
@@ -537,9 +966,35 @@ fn app() -> Html {
                                     }
 
                                     html! {
-                                        <RepoRow repo={repo.clone()} {onclick} {on_drag_start} {local_status} />
+                                        <RepoRow
+                                            repo={repo.clone()}
+                                            {onclick}
+                                            {on_drag_start}
+                                            {local_status}
+                                            icons={(*icons).clone()}
+                                            selectable={*selection_mode}
+                                            selected={is_selected}
+                                            {on_toggle_select}
+                                        />
                                     }
                                 })}
+                                { if sorted_repos.len() > *visible_rows {
+                                    let on_show_more = {
+                                        let visible_rows = visible_rows.clone();
+                                        let total = sorted_repos.len();
+                                        Callback::from(move |_| {
+                                            visible_rows.set((*visible_rows + REPO_PAGE_SIZE).min(total));
+                                        })
+                                    };
+                                    html! {
+                                        <div class="repo-list-more">
+                                            <span>{ format!("Showing {} of {}", *visible_rows, sorted_repos.len()) }</span>
+                                            <button class="btn btn-secondary" onclick={on_show_more}>{ "Show more" }</button>
+                                        </div>
+                                    }
+                                } else {
+                                    html! {}
+                                }}
                             </>
                         }
                     } else {
@@ -568,7 +1023,7 @@ fn app() -> Html {
             </div>
 
             { if let Some(repo) = (*selected_repo).clone() {
-                html! { <RepoDetailModal repo={repo} groups={(*groups).clone()} on_close={on_close_modal} /> }
+                html! { <RepoDetailModal repo={repo} groups={(*groups).clone()} protected_branches={(*protected_branches).clone()} on_close={on_close_modal} /> }
             } else {
                 html! {}
             }}
@@ -626,6 +1081,11 @@ fn repo_list_header(props: &RepoListHeaderProps) -> Html {
         Callback::from(move |_| on_column_click.emit(SortColumn::LastUpdated))
     };
 
+    let on_priority_click = {
+        let on_column_click = props.on_column_click.clone();
+        Callback::from(move |_| on_column_click.emit(SortColumn::Priority))
+    };
+
     let on_status_click = {
         let on_column_click = props.on_column_click.clone();
         Callback::from(move |_| on_column_click.emit(SortColumn::Status))
@@ -654,6 +1114,9 @@ fn repo_list_header(props: &RepoListHeaderProps) -> Html {
             <div class="header-column col-last-updated" onclick={on_last_updated_click}>
                 { "Last Updated" }{ sort_indicator(SortColumn::LastUpdated) }
             </div>
+            <div class="header-column col-priority" onclick={on_priority_click}>
+                { "Priority" }{ sort_indicator(SortColumn::Priority) }
+            </div>
             <div class="header-column col-status" onclick={on_status_click}>
                 { "Status" }{ sort_indicator(SortColumn::Status) }
             </div>
@@ -668,12 +1131,32 @@ struct RepoRowProps {
     onclick: Callback<()>,
     on_drag_start: Callback<String>,
     local_status: Option<LocalRepoStatus>,
+    icons: IconSet,
+    /// Show a checkbox; clicking the row then toggles selection instead
+    /// of opening the detail
+    selectable: bool,
+    selected: bool,
+    on_toggle_select: Callback<String>,
+}
+
+/// Red/green/amber dot for the default branch's CI; nothing without checks
+#[cfg(target_arch = "wasm32")]
+fn ci_dot(ci_status: &str) -> Html {
+    let title = match ci_status {
+        "passing" => "CI passing on the default branch",
+        "failing" => "CI failing on the default branch",
+        "pending" => "CI running on the default branch",
+        _ => return html! {},
+    };
+    html! { <span class={classes!("ci-dot", format!("ci-{}", ci_status))} {title}></span> }
 }
 
 #[cfg(target_arch = "wasm32")]
 #[function_component(RepoRow)]
 fn repo_row(props: &RepoRowProps) -> Html {
     let repo = &props.repo;
+    let icons = &props.icons;
+    let abandoned_count = repo.branches.iter().filter(|b| b.is_abandoned).count();
 
     // Debug logging for sw-install
     if repo.id.contains("sw-install") {
@@ -692,7 +1175,16 @@ fn repo_row(props: &RepoRowProps) -> Html {
 
     let onclick = {
         let onclick = props.onclick.clone();
-        Callback::from(move |_| onclick.emit(()))
+        let on_toggle_select = props.on_toggle_select.clone();
+        let selectable = props.selectable;
+        let repo_id = repo.id.clone();
+        Callback::from(move |_| {
+            if selectable {
+                on_toggle_select.emit(repo_id.clone());
+            } else {
+                onclick.emit(());
+            }
+        })
     };
 
     let ondragstart = {
@@ -705,31 +1197,74 @@ fn repo_row(props: &RepoRowProps) -> Html {
     };
 
     html! {
-        <div class="repo-row" draggable="true" {ondragstart} {onclick}>
+        <div class={classes!("repo-row", props.selected.then_some("selected"))} draggable="true" {ondragstart} {onclick}>
             <div class="col-name">
+                { if props.selectable {
+                    // The row's click handler does the toggling
+                    html! { <input type="checkbox" class="repo-select" checked={props.selected} /> }
+                } else {
+                    html! {}
+                }}
                 <span class="repo-name">{ &repo.id }</span>
+                { ci_dot(&repo.ci_status) }
+                if is_snoozed(repo) {
+                    <span class="snoozed-marker" title={format!("Snoozed until {}", repo.snoozed_until.as_deref().unwrap_or_default())}>{ "snoozed" }</span>
+                }
             </div>
             <div class="col-language">
-                <span class="language-badge">{ &repo.language }</span>
+                { language_badge(repo.language.as_deref(), "language-badge") }
             </div>
             <div class="col-last-updated">
-                <span class="last-push">{ &repo.last_push }</span>
+                <span class={classes!("last-push", format!("staleness-{}", repo.staleness.to_lowercase()))}>{ &repo.last_push }</span>
+            </div>
+            <div class="col-priority">
+                <span
+                    class={classes!("priority-score", repo.priority_locked.then_some("locked"))}
+                    title={if repo.priority_locked { "Priority set manually" } else { "Computed priority" }}
+                >
+                    { format!("{:.2}", repo.priority) }
+                    if repo.priority_locked {
+                        { " 🔒" }
+                    }
+                </span>
             </div>
             <div class="col-status repo-status">
                 { if let Some(status) = &props.local_status {
-                    // Priority: local-changes (yellow) FIRST - commit before push!
+                    // Priority: diverged FIRST - pushing or pulling blindly loses work
+                    // Then: unresolved conflicts - the repo is stuck mid-merge
+                    // Then: local-changes (yellow) - commit before push!
                     // Then: needs-sync (red) for unpushed/behind
-                    if status.uncommitted_files > 0 {
+                    if status.is_diverged {
+                        html! {
+                            <span class="status-indicator needs-sync diverged" title={format!("Diverged - needs rebase/merge ({} unpushed, {} behind)", status.unpushed_commits, status.behind_commits)}>
+                                <img class="status-icon" src={icons.needs_sync.path.clone()} alt={icons.needs_sync.label.clone()} />
+                                <span class="count">{ "diverged" }</span>
+                            </span>
+                        }
+                    } else if status.has_conflicts {
+                        html! {
+                            <span class="status-indicator needs-sync" title="Merge or rebase in progress with unresolved conflicts">
+                                <img class="status-icon" src={icons.needs_sync.path.clone()} alt={icons.needs_sync.label.clone()} />
+                                <span class="count">{ "conflicts" }</span>
+                            </span>
+                        }
+                    } else if status.uncommitted_files > 0 {
                         html! {
                             <span class="status-indicator local-changes" title={format!("{} uncommitted files", status.uncommitted_files)}>
-                                <img class="status-icon" src="/icons/local-changes.png" alt="Local changes" />
+                                <img class="status-icon" src={icons.local_changes.path.clone()} alt={icons.local_changes.label.clone()} />
                                 <span class="count">{ status.uncommitted_files }</span>
                             </span>
                         }
                     } else if status.unpushed_commits > 0 || status.behind_commits > 0 {
                         html! {
-                            <span class="status-indicator needs-sync" title={format!("{} unpushed commits", status.unpushed_commits)}>
-                                <img class="status-icon" src="/icons/needs-sync.png" alt="Needs sync" />
+                            <span class="status-indicator needs-sync" title={
+                                if status.is_unpublished {
+                                    format!("Unpublished branch - {} commits not on the remote", status.unpushed_commits)
+                                } else {
+                                    format!("{} unpushed commits", status.unpushed_commits)
+                                }
+                            }>
+                                <img class="status-icon" src={icons.needs_sync.path.clone()} alt={icons.needs_sync.label.clone()} />
                                 <span class="count">{ status.unpushed_commits }</span>
                             </span>
                         }
@@ -749,7 +1284,7 @@ fn repo_row(props: &RepoRowProps) -> Html {
                     if branches_needing_sync > 0 {
                         html! {
                             <span class="status-indicator needs-sync" title={format!("{} branches need sync", branches_needing_sync)}>
-                                <img class="status-icon" src="/icons/needs-sync.png" alt="Needs sync" />
+                                <img class="status-icon" src={icons.needs_sync.path.clone()} alt={icons.needs_sync.label.clone()} />
                                 <span class="count">{ branches_needing_sync }</span>
                             </span>
                         }
@@ -762,17 +1297,26 @@ fn repo_row(props: &RepoRowProps) -> Html {
                 { if repo.unmerged_count > 0 {
                     html! {
                         <span class="status-indicator stale" title="Unmerged branches">
-                            <img class="status-icon" src="/icons/stale.png" alt="Stale" />
+                            <img class="status-icon" src={icons.stale.path.clone()} alt={icons.stale.label.clone()} />
                             <span class="count">{ repo.unmerged_count }</span>
                         </span>
                     }
                 } else {
                     html! {}
                 }}
+                { if abandoned_count > 0 {
+                    html! {
+                        <span class="status-indicator abandoned" title={format!("{} abandoned branches (no open PR, no recent commits)", abandoned_count)}>
+                            <span class="count">{ format!("{} abandoned", abandoned_count) }</span>
+                        </span>
+                    }
+                } else {
+                    html! {}
+                }}
                 { if repo.pr_count > 0 {
                     html! {
                         <span class="status-indicator info" title="Pending pull requests">
-                            <img class="status-icon" src="/icons/stale.png" alt="Pull requests" />
+                            <img class="status-icon" src={icons.stale.path.clone()} alt="Pull requests" />
                             <span class="count">{ repo.pr_count }</span>
                         </span>
                     }
@@ -782,7 +1326,7 @@ fn repo_row(props: &RepoRowProps) -> Html {
                 { if repo.unmerged_count == 0 && repo.pr_count == 0 && props.local_status.as_ref().map_or(true, |s| !s.is_dirty) {
                     html! {
                         <span class="status-indicator success" title="No pending work">
-                            <img class="status-icon" src="/icons/complete.png" alt="Complete" />
+                            <img class="status-icon" src={icons.complete.path.clone()} alt={icons.complete.label.clone()} />
                         </span>
                     }
                 } else {
@@ -798,6 +1342,7 @@ fn repo_row(props: &RepoRowProps) -> Html {
 struct RepoDetailModalProps {
     repo: Repository,
     groups: Vec<RepoGroup>,
+    protected_branches: Vec<String>,
     on_close: Callback<()>,
 }
 
@@ -806,36 +1351,140 @@ struct RepoDetailModalProps {
 fn repo_detail_modal(props: &RepoDetailModalProps) -> Html {
     let repo = &props.repo;
 
-    // Find current group for this repo
-    let current_group_id = props
-        .groups
-        .iter()
-        .find(|g| g.repos.iter().any(|r| r.id == repo.id))
-        .and_then(|g| g.id);
+    // Diffs of expanded branches, keyed by branch name
+    let branch_diffs = use_state(std::collections::HashMap::<String, String>::new);
 
-    let on_backdrop_click = {
-        let on_close = props.on_close.clone();
-        Callback::from(move |_| on_close.emit(()))
-    };
+    // Full commit lists of branches whose older commits were loaded, keyed by branch name
+    let branch_commits = use_state(std::collections::HashMap::<String, Vec<CommitInfo>>::new);
 
-    let on_modal_click = Callback::from(|e: MouseEvent| {
-        e.stop_propagation();
-    });
+    // Untruncated messages of expanded commits, keyed by SHA
+    let commit_messages = use_state(std::collections::HashMap::<String, String>::new);
 
-    let on_group_change = {
-        let repo_id = repo.id.clone();
-        Callback::from(move |e: Event| {
-            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
-            let new_group_id = if select.value() == "ungrouped" {
-                None
-            } else {
-                Some(select.value().parse::<i64>().unwrap())
-            };
+    // Most recently worked branch first by default, alphabetical, or biggest first
+    let branch_sort = use_state(|| BranchSort::Recent);
+    let sorted_branches = sort_branches(&repo.branches, *branch_sort);
 
-            let repo_id = repo_id.clone();
-            wasm_bindgen_futures::spawn_local(async move {
-                if let Err(e) = move_repo_to_group(&repo_id, new_group_id).await {
-                    web_sys::console::error_1(&format!("Failed to move repo: {}", e).into());
+    let on_toggle_branch_sort = {
+        let branch_sort = branch_sort.clone();
+        Callback::from(move |_| branch_sort.set(branch_sort.next()))
+    };
+
+    // Open PRs are listed first, approved ones (ready to merge) at the top;
+    // merged and closed ones are kept for history
+    let (mut open_prs, closed_prs): (Vec<&PullRequestInfo>, Vec<&PullRequestInfo>) =
+        repo.pull_requests.iter().partition(|pr| pr.state == "Open");
+    open_prs.sort_by_key(|pr| pr.review_decision.as_deref() != Some("Approved"));
+    let render_pr = |pr: &PullRequestInfo| {
+        let repo_full_name = repo.id.clone();
+
+        html! {
+            <div class={classes!("pr-detail", pr.state.to_lowercase())}>
+                <div class="pr-header">
+                    <div class="pr-info">
+                        <span class="pr-number">{ format!("#{}", pr.number) }</span>
+                        <span class="pr-title">{ &pr.title }</span>
+                        <span class={classes!("pr-state-badge", pr.state.to_lowercase())}>
+                            { &pr.state }
+                        </span>
+                        { match (pr.state.as_str(), pr.review_decision.as_deref()) {
+                            ("Open", Some("Approved")) => html! {
+                                <span class="pr-review-badge approved">{ "Ready to merge" }</span>
+                            },
+                            ("Open", Some("ChangesRequested")) => html! {
+                                <span class="pr-review-badge changes-requested">{ "Changes requested" }</span>
+                            },
+                            ("Open", Some("ReviewRequired")) => html! {
+                                <span class="pr-review-badge review-required">{ "Review required" }</span>
+                            },
+                            _ => html! {},
+                        }}
+                    </div>
+                    <div class="pr-actions">
+                        { if pr.state == "Open" && pr.review_decision.as_deref() == Some("Approved") {
+                            let repo_id = repo_full_name.clone();
+                            let number = pr.number;
+                            let on_merge = Callback::from(move |_| {
+                                let repo_id = repo_id.clone();
+                                wasm_bindgen_futures::spawn_local(async move {
+                                    match merge_pull_request(&repo_id, number).await {
+                                        Ok(true) => {
+                                            web_sys::window().and_then(|w| w.location().reload().ok());
+                                        }
+                                        Ok(false) => {}
+                                        Err(e) => {
+                                            web_sys::console::error_1(&format!("Failed to merge PR: {}", e).into());
+                                            if let Some(window) = web_sys::window() {
+                                                let _ = window.alert_with_message(&format!("Error: {}", e));
+                                            }
+                                        }
+                                    }
+                                });
+                            });
+                            html! {
+                                <button onclick={on_merge} class="btn-merge-pr" title="Squash and merge this approved PR">
+                                    { "Merge" }
+                                </button>
+                            }
+                        } else {
+                            html! {}
+                        }}
+                        {{
+                            let pr_url = pr_url(&repo_full_name, pr);
+                            html! {
+                                <a href={pr_url} target="_blank" class="btn-view-pr" title="View on GitHub">
+                                    { "View PR" }
+                                </a>
+                            }
+                        }}
+                    </div>
+                </div>
+                <div class="pr-meta">
+                    <span class="pr-created">{ format!("Created: {}", &pr.created_at) }</span>
+                    <span class="pr-updated">{ format!("Updated: {}", &pr.updated_at) }</span>
+                </div>
+            </div>
+        }
+    };
+
+    // Find current group for this repo
+    let current_group_id = props
+        .groups
+        .iter()
+        .find(|g| g.repos.iter().any(|r| r.id == repo.id))
+        .and_then(|g| g.id);
+
+    let on_backdrop_click = {
+        let on_close = props.on_close.clone();
+        Callback::from(move |_| on_close.emit(()))
+    };
+
+    let on_modal_click = Callback::from(|e: MouseEvent| {
+        e.stop_propagation();
+    });
+
+    let on_group_change = {
+        let repo_id = repo.id.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let value = select.value();
+            let new_group_id = if value == "ungrouped" {
+                None
+            } else {
+                match value.parse::<i64>() {
+                    Ok(id) => Some(id),
+                    Err(_) => {
+                        web_sys::console::error_1(
+                            &format!("Unexpected group selection '{}'", value).into(),
+                        );
+                        return;
+                    }
+                }
+            };
+
+            let repo_id = repo_id.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(e) = move_repo_to_group(&repo_id, new_group_id).await {
+                    web_sys::console::error_1(&format!("Failed to move repo: {}", e).into());
                 } else {
                     // Reload page to reflect changes
                     web_sys::window().unwrap().location().reload().ok();
@@ -844,15 +1493,56 @@ fn repo_detail_modal(props: &RepoDetailModalProps) -> Html {
         })
     };
 
-    // Exclude main/master/develop branches - they should never have PRs created
+    // Manual priority override, entered as 0.0-1.0
+    let priority_input = use_state(|| format!("{:.2}", repo.priority));
+
+    let on_priority_input = {
+        let priority_input = priority_input.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            priority_input.set(input.value());
+        })
+    };
+
+    let save_priority = {
+        let repo_id = repo.id.clone();
+        move |priority: Option<f32>| {
+            let repo_id = repo_id.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(e) = set_repo_priority(&repo_id, priority).await {
+                    web_sys::console::error_1(&format!("Failed to set priority: {}", e).into());
+                } else {
+                    // Reload page to reflect changes
+                    web_sys::window().unwrap().location().reload().ok();
+                }
+            });
+        }
+    };
+
+    let on_priority_save = {
+        let priority_input = priority_input.clone();
+        let save_priority = save_priority.clone();
+        Callback::from(move |_| match priority_input.trim().parse::<f32>() {
+            Ok(priority) if (0.0..=1.0).contains(&priority) => save_priority(Some(priority)),
+            _ => web_sys::console::error_1(
+                &format!(
+                    "Priority must be between 0 and 1, got '{}'",
+                    *priority_input
+                )
+                .into(),
+            ),
+        })
+    };
+
+    let on_priority_unlock = Callback::from(move |_| save_priority(None));
+
     let ready_for_pr = repo
         .branches
         .iter()
         .filter(|b| {
             b.status == "ReadyForPR"
-                && b.name != "main"
-                && b.name != "master"
-                && b.name != "develop"
+                && b.is_unmerged
+                && !is_protected_branch(&b.name, &props.protected_branches)
         })
         .count();
     let in_review = repo
@@ -881,7 +1571,7 @@ fn repo_detail_modal(props: &RepoDetailModalProps) -> Html {
 
                 <div class="modal-body">
                     <div class="repo-detail-meta">
-                        <span class="language-badge">{ &repo.language }</span>
+                        { language_badge(repo.language.as_deref(), "language-badge") }
                         <span class="last-push">{ format!("Last push: {}", &repo.last_push) }</span>
                     </div>
 
@@ -891,10 +1581,11 @@ fn repo_detail_modal(props: &RepoDetailModalProps) -> Html {
                             <option value="ungrouped" selected={current_group_id.is_none()}>
                                 { "Ungrouped" }
                             </option>
-                            { for props.groups.iter().map(|group| {
-                                let is_selected = Some(group.id.unwrap_or(0)) == current_group_id;
+                            // The ungrouped pseudo-group has no id and is covered by the option above
+                            { for props.groups.iter().filter_map(|group| group.id.map(|id| (id, group))).map(|(id, group)| {
+                                let is_selected = Some(id) == current_group_id;
                                 html! {
-                                    <option value={group.id.unwrap_or(0).to_string()} selected={is_selected}>
+                                    <option value={id.to_string()} selected={is_selected}>
                                         { &group.name }
                                     </option>
                                 }
@@ -902,6 +1593,27 @@ fn repo_detail_modal(props: &RepoDetailModalProps) -> Html {
                         </select>
                     </div>
 
+                    <div class="priority-editor">
+                        <label for="priority-input">{ "Priority: " }</label>
+                        <input
+                            id="priority-input"
+                            type="number"
+                            min="0"
+                            max="1"
+                            step="0.05"
+                            value={(*priority_input).clone()}
+                            oninput={on_priority_input}
+                        />
+                        <button class="btn-priority-save" onclick={on_priority_save} title="Set this priority and keep it across reprioritize runs">
+                            { "Set" }
+                        </button>
+                        if repo.priority_locked {
+                            <button class="btn-priority-unlock" onclick={on_priority_unlock} title="Let reprioritize compute this repository's priority again">
+                                { "Unlock" }
+                            </button>
+                        }
+                    </div>
+
                     <div class="branch-summary">
                         if ready_for_pr > 0 {
                             <span class="badge ready">{ format!("{} Ready for PR", ready_for_pr) }</span>
@@ -943,46 +1655,168 @@ fn repo_detail_modal(props: &RepoDetailModalProps) -> Html {
                         }
                     </div>
 
-                    <h3>{ format!("Branches ({})", repo.branches.len()) }</h3>
+                    <div class="branches-header">
+                        <h3>{ format!("Branches ({})", repo.branches.len()) }</h3>
+                        <button class="btn-branch-sort" onclick={on_toggle_branch_sort} title="Cycle between recent activity, name and commits-ahead order">
+                            { branch_sort.label() }
+                        </button>
+                    </div>
                     <div class="branches-detail">
-                        { for repo.branches.iter().map(|branch| {
-                            let has_unmerged_work = branch.ahead > 0; // Show button if branch has commits ahead
+                        { for sorted_branches.into_iter().map(|branch| {
+                            let is_protected = is_protected_branch(&branch.name, &props.protected_branches);
+                            // Show button if branch has unmerged commits; protected branches are PR targets
+                            let has_unmerged_work = branch.is_unmerged && !is_protected;
+                            let is_focus = repo.focus_branch.as_deref() == Some(branch.name.as_str());
+                            // Link to the existing PR instead of offering to create a duplicate
+                            let open_pr = repo
+                                .pull_requests
+                                .iter()
+                                .find(|pr| pr.state == "Open" && pr.head_ref_name == branch.name);
                             let needs_sync = branch.behind > 0;
                             let repo_full_name = repo.id.clone();
                             let branch_name = branch.name.clone();
+                            let branch_diff = branch_diffs.get(&branch.name).cloned();
+                            let loaded_commits = branch_commits.get(&branch.name).cloned();
+                            let more_commits = if loaded_commits.is_some() { 0 } else { branch.more_commits };
+                            let commits = loaded_commits.unwrap_or_else(|| branch.commits.clone());
+
+                            // Replace the exported commits with the full list from the server
+                            let on_load_commits = {
+                                let branch_commits = branch_commits.clone();
+                                let repo_id = repo_full_name.clone();
+                                let branch_name = branch_name.clone();
+                                Callback::from(move |_| {
+                                    let branch_commits = branch_commits.clone();
+                                    let repo_id = repo_id.clone();
+                                    let branch_name = branch_name.clone();
+                                    wasm_bindgen_futures::spawn_local(async move {
+                                        match fetch_branch_commits(&repo_id, &branch_name).await {
+                                            Ok(commits) => {
+                                                let mut loaded = (*branch_commits).clone();
+                                                loaded.insert(branch_name, commits);
+                                                branch_commits.set(loaded);
+                                            }
+                                            Err(e) => web_sys::console::log_1(
+                                                &format!("Failed to load commits: {}", e).into(),
+                                            ),
+                                        }
+                                    });
+                                })
+                            };
+
+                            // Toggle the diff panel, fetching the diff when expanding
+                            let on_toggle_diff = {
+                                let branch_diffs = branch_diffs.clone();
+                                let repo_id = repo_full_name.clone();
+                                let branch_name = branch_name.clone();
+                                Callback::from(move |_| {
+                                    let mut diffs = (*branch_diffs).clone();
+                                    if diffs.remove(&branch_name).is_some() {
+                                        branch_diffs.set(diffs);
+                                        return;
+                                    }
+
+                                    let branch_diffs = branch_diffs.clone();
+                                    let repo_id = repo_id.clone();
+                                    let branch_name = branch_name.clone();
+                                    wasm_bindgen_futures::spawn_local(async move {
+                                        let diff = fetch_branch_diff(&repo_id, &branch_name)
+                                            .await
+                                            .unwrap_or_else(|e| format!("Failed to load diff: {}", e));
+                                        let mut diffs = (*branch_diffs).clone();
+                                        diffs.insert(branch_name, diff);
+                                        branch_diffs.set(diffs);
+                                    });
+                                })
+                            };
 
                             html! {
-                            <div class={classes!("branch-detail", branch.status.to_lowercase())}>
+                            <div class={classes!("branch-detail", branch.status.to_lowercase(), is_focus.then_some("focus-branch"))}>
                                 <div class="branch-header">
                                     <div class="branch-info">
                                         <span class="branch-name">{ &branch.name }</span>
                                         <span class="branch-status-badge">{ &branch.status }</span>
+                                        { if branch.is_abandoned {
+                                            html! {
+                                                <span class="branch-abandoned-badge" title="Commits ahead but no open PR and no recent commits - finish it or delete it">
+                                                    { "Abandoned" }
+                                                </span>
+                                            }
+                                        } else {
+                                            html! {}
+                                        }}
+                                        { if is_protected {
+                                            html! {
+                                                <span class="branch-protected-badge" title="Matches github.protected_branches - PRs are opened into it, not from it">
+                                                    { "Protected" }
+                                                </span>
+                                            }
+                                        } else {
+                                            html! {}
+                                        }}
+                                        { if is_focus {
+                                            html! {
+                                                <span class="branch-focus-badge" title="Most commits ahead of the default branch - usually worth shipping first">
+                                                    { format!("Biggest: +{}", branch.ahead) }
+                                                </span>
+                                            }
+                                        } else {
+                                            html! {}
+                                        }}
                                     </div>
                                     <div class="branch-actions">
                                         {{
                                             let repo_id_for_pr = repo_full_name.clone();
                                             let branch_name_for_pr = branch_name.clone();
 
-                                            let on_create_pr = {
+                                            let make_create_pr = |draft: bool| {
+                                                let repo_id_for_pr = repo_id_for_pr.clone();
+                                                let branch_name_for_pr = branch_name_for_pr.clone();
                                                 Callback::from(move |_| {
                                                     let repo_id = repo_id_for_pr.clone();
                                                     let branch_name = branch_name_for_pr.clone();
 
                                                     wasm_bindgen_futures::spawn_local(async move {
-                                                        if let Err(e) = create_pull_request(&repo_id, &branch_name).await {
+                                                        if let Err(e) = create_pull_request(&repo_id, &branch_name, draft).await {
                                                             web_sys::console::error_1(&format!("Failed to create PR: {}", e).into());
                                                         }
                                                     });
                                                 })
                                             };
+                                            let on_create_pr = make_create_pr(false);
+                                            let on_create_draft_pr = make_create_pr(true);
 
                                             html! {
                                                 <>
-                                                { if has_unmerged_work {
+                                                { if branch.ahead > 0 {
                                                     html! {
+                                                        <button onclick={on_toggle_diff} class="btn-view-branch" title="Show changes compared to the default branch">
+                                                            { if branch_diff.is_some() { "Hide Diff" } else { "Diff" } }
+                                                        </button>
+                                                    }
+                                                } else {
+                                                    html! {}
+                                                }}
+                                                { if branch.has_open_pr {
+                                                    let pr_url = match open_pr {
+                                                        Some(pr) => pr_url(&repo_full_name, pr),
+                                                        None => format!("https://github.com/{}/pulls", repo_full_name),
+                                                    };
+                                                    html! {
+                                                        <a href={pr_url} target="_blank" class="btn-view-pr" title="View on GitHub">
+                                                            { "View PR" }
+                                                        </a>
+                                                    }
+                                                } else if has_unmerged_work {
+                                                    html! {
+                                                        <>
                                                         <button onclick={on_create_pr} class="btn-create-pr" title="Create Pull Request">
                                                             { "Create PR" }
                                                         </button>
+                                                        <button onclick={on_create_draft_pr} class="btn-create-draft-pr" title="Create Pull Request as a draft">
+                                                            { "Create Draft PR" }
+                                                        </button>
+                                                        </>
                                                     }
                                                 } else {
                                                     html! {}
@@ -995,7 +1829,7 @@ fn repo_detail_modal(props: &RepoDetailModalProps) -> Html {
                                 <div class="branch-meta">
                                     <div class="branch-commit-info">
                                         <span class="commit-sha" title={branch.sha.clone()}>
-                                            { if branch.sha.len() > 7 { &branch.sha[..7] } else { &branch.sha } }
+                                            { &branch.short_sha }
                                         </span>
                                         <span class="commit-timestamp">{ &branch.last_commit_date }</span>
                                     </div>
@@ -1017,24 +1851,83 @@ fn repo_detail_modal(props: &RepoDetailModalProps) -> Html {
                                         }}
                                     </div>
                                 </div>
-                                { if !branch.commits.is_empty() {
+                                { if let Some(diff) = branch_diff {
+                                    html! {
+                                        <div class="branch-diff">
+                                            <pre>{ diff }</pre>
+                                        </div>
+                                    }
+                                } else {
+                                    html! {}
+                                }}
+                                { if !commits.is_empty() {
                                     html! {
                                         <div class="commits-list">
-                                            <h4>{ format!("Commits ({})", branch.commits.len()) }</h4>
-                                            { for branch.commits.iter().map(|commit| {
-                                                let short_sha = if commit.sha.len() > 7 { &commit.sha[..7] } else { &commit.sha };
+                                            <h4>{ format!("Commits ({})", commits.len() + more_commits) }</h4>
+                                            { for commits.iter().map(|commit| {
                                                 let first_line = commit.message.lines().next().unwrap_or(&commit.message);
+                                                let full_message = commit_messages.get(&commit.sha).cloned();
+
+                                                // Toggle the full message, fetching it when expanding
+                                                let on_toggle_message = {
+                                                    let commit_messages = commit_messages.clone();
+                                                    let repo_id = repo_full_name.clone();
+                                                    let sha = commit.sha.clone();
+                                                    Callback::from(move |_| {
+                                                        if commit_messages.contains_key(&sha) {
+                                                            let mut expanded = (*commit_messages).clone();
+                                                            expanded.remove(&sha);
+                                                            commit_messages.set(expanded);
+                                                            return;
+                                                        }
+                                                        let commit_messages = commit_messages.clone();
+                                                        let repo_id = repo_id.clone();
+                                                        let sha = sha.clone();
+                                                        wasm_bindgen_futures::spawn_local(async move {
+                                                            match fetch_commit_message(&repo_id, &sha).await {
+                                                                Ok(message) => {
+                                                                    let mut expanded = (*commit_messages).clone();
+                                                                    expanded.insert(sha, message);
+                                                                    commit_messages.set(expanded);
+                                                                }
+                                                                Err(e) => web_sys::console::log_1(
+                                                                    &format!("Failed to load commit message: {}", e).into(),
+                                                                ),
+                                                            }
+                                                        });
+                                                    })
+                                                };
                                                 html! {
                                                     <div class="commit-item">
                                                         <div class="commit-header">
-                                                            <span class="commit-sha" title={commit.sha.clone()}>{ short_sha }</span>
+                                                            <span class="commit-sha" title={commit.sha.clone()}>{ &commit.short_sha }</span>
                                                             <span class="commit-author">{ &commit.author_name }</span>
+                                                            { if commit.is_verified {
+                                                                html! { <span class="commit-verified" title="Signature verified by GitHub">{ "Verified" }</span> }
+                                                            } else {
+                                                                html! {}
+                                                            }}
                                                             <span class="commit-date">{ &commit.authored_date }</span>
                                                         </div>
-                                                        <div class="commit-message">{ first_line }</div>
+                                                        { if let Some(message) = full_message {
+                                                            html! { <pre class="commit-message-full" onclick={on_toggle_message}>{ message }</pre> }
+                                                        } else {
+                                                            html! {
+                                                                <div class="commit-message" onclick={on_toggle_message} title="Click to show the full message">{ first_line }</div>
+                                                            }
+                                                        }}
                                                     </div>
                                                 }
                                             })}
+                                            { if more_commits > 0 {
+                                                html! {
+                                                    <button onclick={on_load_commits} class="btn-load-commits" title="Load the older commits left out of repos.json">
+                                                        { format!("Show {} more", more_commits) }
+                                                    </button>
+                                                }
+                                            } else {
+                                                html! {}
+                                            }}
                                         </div>
                                     }
                                 } else {
@@ -1045,49 +1938,29 @@ fn repo_detail_modal(props: &RepoDetailModalProps) -> Html {
                         })}
                     </div>
 
-                    <h3>{ format!("Pull Requests ({})", repo.pull_requests.len()) }</h3>
+                    <h3>{ format!("Pull Requests ({})", open_prs.len()) }</h3>
                     <div class="pull-requests-detail">
-                        { if repo.pull_requests.is_empty() {
+                        { if open_prs.is_empty() {
                             html! {
                                 <div class="no-prs">{ "No open pull requests" }</div>
                             }
                         } else {
-                            html! {
-                                { for repo.pull_requests.iter().map(|pr| {
-                                    let repo_full_name = repo.id.clone();
-                                    let pr_number = pr.number;
-
-                                    html! {
-                                        <div class={classes!("pr-detail", pr.state.to_lowercase())}>
-                                            <div class="pr-header">
-                                                <div class="pr-info">
-                                                    <span class="pr-number">{ format!("#{}", pr.number) }</span>
-                                                    <span class="pr-title">{ &pr.title }</span>
-                                                    <span class={classes!("pr-state-badge", pr.state.to_lowercase())}>
-                                                        { &pr.state }
-                                                    </span>
-                                                </div>
-                                                <div class="pr-actions">
-                                                    {{
-                                                        let pr_url = format!("https://github.com/{}/pull/{}", repo_full_name, pr_number);
-                                                        html! {
-                                                            <a href={pr_url} target="_blank" class="btn-view-pr" title="View on GitHub">
-                                                                { "View PR" }
-                                                            </a>
-                                                        }
-                                                    }}
-                                                </div>
-                                            </div>
-                                            <div class="pr-meta">
-                                                <span class="pr-created">{ format!("Created: {}", &pr.created_at) }</span>
-                                                <span class="pr-updated">{ format!("Updated: {}", &pr.updated_at) }</span>
-                                            </div>
-                                        </div>
-                                    }
-                                })}
-                            }
+                            html! { for open_prs.iter().map(|pr| render_pr(pr)) }
                         }}
                     </div>
+
+                    { if closed_prs.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <details class="recent-prs">
+                                <summary>{ format!("Recently merged ({})", closed_prs.len()) }</summary>
+                                <div class="pull-requests-detail">
+                                    { for closed_prs.iter().map(|pr| render_pr(pr)) }
+                                </div>
+                            </details>
+                        }
+                    }}
                 </div>
             </div>
         </div>
@@ -1322,7 +2195,7 @@ fn add_repo_dialog(props: &AddRepoDialogProps) -> Html {
                                                     <div class="repo-checkbox-info">
                                                         <span class="repo-name">{ &repo.id }</span>
                                                         <span class="repo-meta-small">
-                                                            <span class="language-badge-small">{ &repo.language }</span>
+                                                            { language_badge(repo.language.as_deref(), "language-badge-small") }
                                                             <span>{ &repo.last_push }</span>
                                                         </span>
                                                     </div>
@@ -1624,38 +2497,63 @@ fn get_mock_groups() -> Vec<RepoGroup> {
                     id: "softwarewrighter/overall".to_string(),
                     owner: "softwarewrighter".to_string(),
                     name: "overall".to_string(),
-                    language: "Rust".to_string(),
+                    language: Some("Rust".to_string()),
                     last_push: "2 hours ago".to_string(),
+                    staleness: "Fresh".to_string(),
+                    status: "needsSync".to_string(),
                     unmerged_count: 2,
                     pr_count: 1,
+                    priority: 0.0,
+                    priority_locked: false,
+                    focus_branch: Some("feature/yew-ui".to_string()),
+                    ci_status: "passing".to_string(),
+                    snoozed_until: None,
                     pull_requests: vec![],
                     branches: vec![
                         BranchInfo {
                             name: "main".to_string(),
                             sha: "a1b2c3d4e5f6".to_string(),
+                            short_sha: "a1b2c3d".to_string(),
                             status: "InReview".to_string(),
                             ahead: 0,
                             behind: 0,
                             last_commit_date: "2 hours ago".to_string(),
+                            last_commit_at: None,
+                            has_open_pr: false,
+                            is_unmerged: false,
+                            is_abandoned: false,
                             commits: vec![],
+                            more_commits: 0,
                         },
                         BranchInfo {
                             name: "feature/yew-ui".to_string(),
                             sha: "f6e5d4c3b2a1".to_string(),
+                            short_sha: "f6e5d4c".to_string(),
                             status: "ReadyForPR".to_string(),
                             ahead: 15,
                             behind: 0,
                             last_commit_date: "3 hours ago".to_string(),
+                            last_commit_at: None,
+                            has_open_pr: false,
+                            is_unmerged: true,
+                            is_abandoned: false,
                             commits: vec![],
+                            more_commits: 0,
                         },
                         BranchInfo {
                             name: "feature/ai-analysis".to_string(),
                             sha: "9876543210ab".to_string(),
+                            short_sha: "9876543".to_string(),
                             status: "ReadyForPR".to_string(),
                             ahead: 8,
                             behind: 0,
                             last_commit_date: "5 hours ago".to_string(),
+                            last_commit_at: None,
+                            has_open_pr: false,
+                            is_unmerged: true,
+                            is_abandoned: false,
                             commits: vec![],
+                            more_commits: 0,
                         },
                     ],
                 },
@@ -1663,29 +2561,48 @@ fn get_mock_groups() -> Vec<RepoGroup> {
                     id: "softwarewrighter/proact".to_string(),
                     owner: "softwarewrighter".to_string(),
                     name: "proact".to_string(),
-                    language: "Rust".to_string(),
+                    language: Some("Rust".to_string()),
                     last_push: "5 hours ago".to_string(),
+                    staleness: "Fresh".to_string(),
+                    status: "needsSync".to_string(),
                     unmerged_count: 0,
                     pr_count: 2,
+                    priority: 0.0,
+                    priority_locked: false,
+                    focus_branch: None,
+                    ci_status: "none".to_string(),
+                    snoozed_until: None,
                     pull_requests: vec![],
                     branches: vec![
                         BranchInfo {
                             name: "main".to_string(),
                             sha: "abc123def456".to_string(),
+                            short_sha: "abc123d".to_string(),
                             status: "InReview".to_string(),
                             ahead: 0,
                             behind: 0,
                             last_commit_date: "5 hours ago".to_string(),
+                            last_commit_at: None,
+                            has_open_pr: false,
+                            is_unmerged: false,
+                            is_abandoned: false,
                             commits: vec![],
+                            more_commits: 0,
                         },
                         BranchInfo {
                             name: "fix/docs-update".to_string(),
                             sha: "789fedcba012".to_string(),
+                            short_sha: "789fedc".to_string(),
                             status: "InReview".to_string(),
                             ahead: 2,
                             behind: 0,
                             last_commit_date: "6 hours ago".to_string(),
+                            last_commit_at: None,
+                            has_open_pr: false,
+                            is_unmerged: true,
+                            is_abandoned: false,
                             commits: vec![],
+                            more_commits: 0,
                         },
                     ],
                 },
@@ -1693,38 +2610,63 @@ fn get_mock_groups() -> Vec<RepoGroup> {
                     id: "softwarewrighter/ask".to_string(),
                     owner: "softwarewrighter".to_string(),
                     name: "ask".to_string(),
-                    language: "Rust".to_string(),
+                    language: Some("Rust".to_string()),
                     last_push: "1 day ago".to_string(),
+                    staleness: "Fresh".to_string(),
+                    status: "needsSync".to_string(),
                     unmerged_count: 2,
                     pr_count: 0,
+                    priority: 0.0,
+                    priority_locked: false,
+                    focus_branch: Some("refactor/error-handling".to_string()),
+                    ci_status: "failing".to_string(),
+                    snoozed_until: None,
                     pull_requests: vec![],
                     branches: vec![
                         BranchInfo {
                             name: "main".to_string(),
                             sha: "deadbeef1234".to_string(),
+                            short_sha: "deadbee".to_string(),
                             status: "InReview".to_string(),
                             ahead: 0,
                             behind: 0,
                             last_commit_date: "1 day ago".to_string(),
+                            last_commit_at: None,
+                            has_open_pr: false,
+                            is_unmerged: false,
+                            is_abandoned: false,
                             commits: vec![],
+                            more_commits: 0,
                         },
                         BranchInfo {
                             name: "feature/streaming".to_string(),
                             sha: "cafebabe5678".to_string(),
+                            short_sha: "cafebab".to_string(),
                             status: "NeedsUpdate".to_string(),
                             ahead: 5,
                             behind: 3,
                             last_commit_date: "2 days ago".to_string(),
+                            last_commit_at: None,
+                            has_open_pr: false,
+                            is_unmerged: true,
+                            is_abandoned: false,
                             commits: vec![],
+                            more_commits: 0,
                         },
                         BranchInfo {
                             name: "refactor/error-handling".to_string(),
                             sha: "1a2b3c4d5e6f".to_string(),
+                            short_sha: "1a2b3c4".to_string(),
                             status: "ReadyForPR".to_string(),
                             ahead: 12,
                             behind: 0,
                             last_commit_date: "1 day ago".to_string(),
+                            last_commit_at: None,
+                            has_open_pr: false,
+                            is_unmerged: true,
+                            is_abandoned: false,
                             commits: vec![],
+                            more_commits: 0,
                         },
                     ],
                 },
@@ -1738,38 +2680,64 @@ fn get_mock_groups() -> Vec<RepoGroup> {
                     id: "softwarewrighter/markdown-checker".to_string(),
                     owner: "softwarewrighter".to_string(),
                     name: "markdown-checker".to_string(),
-                    language: "Rust".to_string(),
+                    language: Some("Rust".to_string()),
                     last_push: "2 days ago".to_string(),
+                    staleness: "Fresh".to_string(),
+                    status: "complete".to_string(),
                     unmerged_count: 0,
                     pr_count: 0,
+                    priority: 0.0,
+                    priority_locked: false,
+                    focus_branch: None,
+                    ci_status: "none".to_string(),
+                    snoozed_until: None,
                     pull_requests: vec![],
                     branches: vec![BranchInfo {
                         name: "main".to_string(),
                         sha: "fedcba987654".to_string(),
+                        short_sha: "fedcba9".to_string(),
                         status: "InReview".to_string(),
                         ahead: 0,
                         behind: 0,
                         last_commit_date: "2 days ago".to_string(),
+                        last_commit_at: None,
+                        has_open_pr: false,
+                        is_unmerged: false,
+                        is_abandoned: false,
                         commits: vec![],
+                        more_commits: 0,
                     }],
                 },
                 Repository {
                     id: "softwarewrighter/dotfiles".to_string(),
                     owner: "softwarewrighter".to_string(),
                     name: "dotfiles".to_string(),
-                    language: "Shell".to_string(),
+                    language: Some("Shell".to_string()),
                     last_push: "1 week ago".to_string(),
+                    staleness: "Fresh".to_string(),
+                    status: "complete".to_string(),
                     unmerged_count: 0,
                     pr_count: 0,
+                    priority: 0.0,
+                    priority_locked: false,
+                    focus_branch: None,
+                    ci_status: "none".to_string(),
+                    snoozed_until: None,
                     pull_requests: vec![],
                     branches: vec![BranchInfo {
                         name: "main".to_string(),
                         sha: "0123456789ab".to_string(),
+                        short_sha: "0123456".to_string(),
                         status: "InReview".to_string(),
                         ahead: 0,
                         behind: 0,
                         last_commit_date: "1 week ago".to_string(),
+                        last_commit_at: None,
+                        has_open_pr: false,
+                        is_unmerged: false,
+                        is_abandoned: false,
                         commits: vec![],
+                        more_commits: 0,
                     }],
                 },
             ],
@@ -1781,29 +2749,48 @@ fn get_mock_groups() -> Vec<RepoGroup> {
                 id: "softwarewrighter/test-repo".to_string(),
                 owner: "softwarewrighter".to_string(),
                 name: "test-repo".to_string(),
-                language: "Python".to_string(),
+                language: Some("Python".to_string()),
                 last_push: "3 weeks ago".to_string(),
+                staleness: "Aging".to_string(),
+                status: "needsSync".to_string(),
                 unmerged_count: 1,
                 pr_count: 0,
+                priority: 0.0,
+                priority_locked: false,
+                focus_branch: Some("experimental".to_string()),
+                ci_status: "none".to_string(),
+                snoozed_until: None,
                 pull_requests: vec![],
                 branches: vec![
                     BranchInfo {
                         name: "main".to_string(),
                         sha: "abcdef123456".to_string(),
+                        short_sha: "abcdef1".to_string(),
                         status: "InReview".to_string(),
                         ahead: 0,
                         behind: 0,
                         last_commit_date: "3 weeks ago".to_string(),
+                        last_commit_at: None,
+                        has_open_pr: false,
+                        is_unmerged: false,
+                        is_abandoned: false,
                         commits: vec![],
+                        more_commits: 0,
                     },
                     BranchInfo {
                         name: "experimental".to_string(),
                         sha: "fedcba654321".to_string(),
+                        short_sha: "fedcba6".to_string(),
                         status: "ReadyForPR".to_string(),
                         ahead: 3,
                         behind: 0,
                         last_commit_date: "4 weeks ago".to_string(),
+                        last_commit_at: None,
+                        has_open_pr: false,
+                        is_unmerged: true,
+                        is_abandoned: false,
                         commits: vec![],
+                        more_commits: 0,
                     },
                 ],
             }],
@@ -1811,6 +2798,41 @@ fn get_mock_groups() -> Vec<RepoGroup> {
     ]
 }
 
+/// Link to a PR, preferring the URL GitHub reported for it
+#[cfg(target_arch = "wasm32")]
+fn pr_url(repo_id: &str, pr: &PullRequestInfo) -> String {
+    pr.url
+        .clone()
+        .unwrap_or_else(|| format!("https://github.com/{}/pull/{}", repo_id, pr.number))
+}
+
+/// Order branches by most recent commit (undated last), by name, or by
+/// commits ahead (most first, then most recent)
+#[cfg(target_arch = "wasm32")]
+fn sort_branches(branches: &[BranchInfo], sort: BranchSort) -> Vec<&BranchInfo> {
+    let mut sorted: Vec<&BranchInfo> = branches.iter().collect();
+    match sort {
+        // None sorts before Some, so reversing puts undated branches last
+        BranchSort::Recent => sorted.sort_by_key(|b| std::cmp::Reverse(b.last_commit_at)),
+        BranchSort::Name => sorted.sort_by_key(|b| b.name.to_lowercase()),
+        BranchSort::Ahead => sorted.sort_by(|a, b| {
+            b.ahead
+                .cmp(&a.ahead)
+                .then_with(|| b.last_commit_at.cmp(&a.last_commit_at))
+        }),
+    }
+    sorted
+}
+
+/// Render a language badge, or a muted dash when the language is unknown
+#[cfg(target_arch = "wasm32")]
+fn language_badge(language: Option<&str>, class: &'static str) -> Html {
+    match language {
+        Some(language) => html! { <span class={class}>{ language }</span> },
+        None => html! { <span class={classes!(class, "language-none")}>{ "—" }</span> },
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 fn format_refresh_time(timestamp_ms: f64) -> String {
     let now = js_sys::Date::now();
@@ -1844,62 +2866,63 @@ fn format_refresh_time(timestamp_ms: f64) -> String {
     }
 }
 
+/// Whether a branch matches one of the configured protected patterns: an
+/// exact name, or a prefix ending in `*` (e.g. `release/*`), as in the CLI
+#[cfg(target_arch = "wasm32")]
+fn is_protected_branch(name: &str, patterns: &[String]) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == pattern,
+        })
+}
+
+/// Whether a snooze is still running; the exported status ignores it, so an
+/// expired snooze shows the real status again without a re-export
+#[cfg(target_arch = "wasm32")]
+fn is_snoozed(repo: &Repository) -> bool {
+    repo.snoozed_until
+        .as_deref()
+        .is_some_and(|until| js_sys::Date::parse(until) > js_sys::Date::now())
+}
+
 #[cfg(target_arch = "wasm32")]
-fn calculate_repo_status_priority(repo: &Repository, local_status: Option<&LocalRepoStatus>) -> u8 {
-    // TRAFFIC LIGHT PRIORITY (lower number = more urgent):
-    // Priority 0 = RED (needs-sync)    - 🛑 STOP - Red stop sign / red ! - MOST URGENT
+fn calculate_repo_status_priority(repo: &Repository) -> u8 {
+    // TRAFFIC LIGHT PRIORITY (lower number = more urgent), from the exported status:
+    // Priority 0 = RED (diverged, needs-sync) - 🛑 STOP - Red stop sign / red ! - MOST URGENT
     // Priority 1 = YELLOW (local-changes) - ⚠️ YIELD - Yellow yield / yellow ? - 2nd urgent
     // Priority 2 = WHITE (stale)       - ℹ️ CLEAN UP - Innocuous cleanup - 3rd
     // Priority 3 = GREEN (complete)    - ✅ PROCEED - Green light, all clear - LEAST urgent
-
-    // PRIORITY 0 (RED): Check for sync issues FIRST - MOST URGENT
-    // Unpushed/behind locally OR branches ahead/behind on GitHub
-    if let Some(status) = local_status {
-        if status.unpushed_commits > 0 || status.behind_commits > 0 {
-            return 0; // needs-sync (RED - STOP!)
-        }
-    }
-
-    // CRITICAL: MUST also check GitHub branch status for ahead/behind
-    // A repo can have clean working directory but still have branches that need sync!
-    for branch in &repo.branches {
-        if branch.ahead > 0 || branch.behind > 0 {
-            return 0; // needs-sync (RED - STOP!)
-        }
-    }
-
-    // PRIORITY 1 (YELLOW): Check local uncommitted files - 2nd URGENT
-    // Rationale: Yield to commit/push this work before starting new work
-    if let Some(status) = local_status {
-        if status.uncommitted_files > 0 {
-            return 1; // local-changes (YELLOW - YIELD!)
-        }
+    // A snoozed repo was deliberately deferred and counts as complete
+    if is_snoozed(repo) {
+        return 3;
     }
-
-    // PRIORITY 2 (WHITE): Check for stale unmerged branches - cleanup
-    // Innocuous but should be cleaned up (merged branches should be deleted)
-    if repo.unmerged_count > 0 {
-        return 2; // stale (WHITE - clean up when convenient)
+    match repo.status.as_str() {
+        "diverged" | "needsSync" => 0,
+        "localChanges" => 1,
+        "stale" => 2,
+        _ => 3,
     }
-
-    // PRIORITY 3 (GREEN): All clear - ready to proceed
-    3 // complete (GREEN - GO! Ready for next feature)
 }
 
 #[cfg(target_arch = "wasm32")]
-fn sort_repositories(
-    repos: &mut [Repository],
-    sort_state: &SortState,
-    local_statuses: &std::collections::HashMap<String, LocalRepoStatus>,
-) {
+fn sort_repositories(repos: &mut [Repository], sort_state: &SortState) {
     repos.sort_by(|a, b| {
         let cmp = match sort_state.column {
             SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-            SortColumn::Language => a.language.to_lowercase().cmp(&b.language.to_lowercase()),
+            SortColumn::Language => match (&a.language, &b.language) {
+                (Some(a_lang), Some(b_lang)) => a_lang.to_lowercase().cmp(&b_lang.to_lowercase()),
+                // Repos without a language always sort last, whatever the direction
+                (Some(_), None) => return std::cmp::Ordering::Less,
+                (None, Some(_)) => return std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            },
             SortColumn::LastUpdated => a.last_push.cmp(&b.last_push),
+            SortColumn::Priority => a.priority.total_cmp(&b.priority),
             SortColumn::Status => {
-                let a_priority = calculate_repo_status_priority(a, local_statuses.get(&a.id));
-                let b_priority = calculate_repo_status_priority(b, local_statuses.get(&b.id));
+                let a_priority = calculate_repo_status_priority(a);
+                let b_priority = calculate_repo_status_priority(b);
                 a_priority.cmp(&b_priority)
             }
         };
@@ -1912,56 +2935,200 @@ fn sort_repositories(
     });
 }
 
+/// A repository entry as exported to repos.json and returned by the
+/// per-repo refresh endpoint
 #[cfg(target_arch = "wasm32")]
-async fn fetch_repos() -> Result<Vec<RepoGroup>, String> {
-    use gloo::net::http::Request;
-    use serde::Deserialize;
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepoJson {
+    id: String,
+    owner: String,
+    name: String,
+    language: Option<String>,
+    last_push: String,
+    #[serde(default)]
+    staleness: String,
+    #[serde(default)]
+    status: String,
+    branches: Vec<BranchJson>,
+    pull_requests: Vec<PullRequestJson>,
+    unmerged_count: u32,
+    pr_count: u32,
+    #[serde(default)]
+    priority: f32,
+    #[serde(default)]
+    priority_locked: bool,
+    #[serde(default)]
+    focus_branch: Option<String>,
+    #[serde(default)]
+    ci_status: String,
+    #[serde(default)]
+    snoozed_until: Option<String>,
+}
 
-    #[derive(Deserialize)]
-    #[serde(rename_all = "camelCase")]
-    struct RepoJson {
-        id: String,
-        owner: String,
-        name: String,
-        language: String,
-        last_push: String,
-        branches: Vec<BranchJson>,
-        pull_requests: Vec<PullRequestJson>,
-        unmerged_count: u32,
-        pr_count: u32,
+#[cfg(target_arch = "wasm32")]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BranchJson {
+    name: String,
+    sha: String,
+    #[serde(default)]
+    short_sha: String,
+    ahead_by: u32,
+    behind_by: u32,
+    status: String,
+    last_commit_date: String,
+    #[serde(default)]
+    has_open_pr: bool,
+    #[serde(default)]
+    is_unmerged: bool,
+    #[serde(default)]
+    is_abandoned: bool,
+    commits: Vec<CommitJson>,
+    #[serde(default)]
+    more_commits: usize,
+}
+
+#[cfg(target_arch = "wasm32")]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommitJson {
+    sha: String,
+    #[serde(default)]
+    short_sha: String,
+    message: String,
+    author_name: String,
+    author_email: String,
+    authored_date: String,
+    #[serde(default)]
+    is_verified: bool,
+}
+
+#[cfg(target_arch = "wasm32")]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PullRequestJson {
+    number: u32,
+    title: String,
+    state: String,
+    #[serde(default)]
+    head_ref_name: String,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    review_decision: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+/// Convert a repos.json (or commits endpoint) commit into the UI model
+#[cfg(target_arch = "wasm32")]
+fn commit_from_json(c: CommitJson) -> CommitInfo {
+    CommitInfo {
+        sha: c.sha,
+        short_sha: c.short_sha,
+        message: c.message,
+        author_name: c.author_name,
+        author_email: c.author_email,
+        authored_date: format_relative_time(&c.authored_date),
+        is_verified: c.is_verified,
     }
+}
 
-    #[derive(Deserialize)]
-    #[serde(rename_all = "camelCase")]
-    struct BranchJson {
-        name: String,
-        sha: String,
-        ahead_by: u32,
-        behind_by: u32,
-        status: String,
-        last_commit_date: String,
-        commits: Vec<CommitJson>,
+/// Convert a repos.json repository entry into the UI model
+#[cfg(target_arch = "wasm32")]
+fn repo_from_json(r: RepoJson) -> Repository {
+    Repository {
+        id: r.id,
+        owner: r.owner,
+        name: r.name,
+        language: r.language,
+        last_push: format_relative_time(&r.last_push),
+        staleness: r.staleness,
+        status: r.status,
+        branches: r
+            .branches
+            .into_iter()
+            .map(|b| BranchInfo {
+                name: b.name,
+                sha: b.sha.clone(),
+                short_sha: b.short_sha,
+                status: b.status,
+                ahead: b.ahead_by,
+                behind: b.behind_by,
+                last_commit_at: b.last_commit_date.parse().ok(),
+                last_commit_date: format_relative_time(&b.last_commit_date),
+                has_open_pr: b.has_open_pr,
+                is_unmerged: b.is_unmerged,
+                is_abandoned: b.is_abandoned,
+                commits: b.commits.into_iter().map(commit_from_json).collect(),
+                more_commits: b.more_commits,
+            })
+            .collect(),
+        pull_requests: r
+            .pull_requests
+            .into_iter()
+            .map(|pr| PullRequestInfo {
+                number: pr.number,
+                title: pr.title,
+                state: pr.state,
+                head_ref_name: pr.head_ref_name,
+                url: pr.url,
+                review_decision: pr.review_decision,
+                created_at: format_relative_time(&pr.created_at),
+                updated_at: format_relative_time(&pr.updated_at),
+            })
+            .collect(),
+        unmerged_count: r.unmerged_count,
+        pr_count: r.pr_count,
+        priority: r.priority,
+        priority_locked: r.priority_locked,
+        focus_branch: r.focus_branch,
+        ci_status: r.ci_status,
+        snoozed_until: r.snoozed_until,
     }
+}
+
+/// repos.json shape this UI understands; must match
+/// `overall_cli::export::EXPORT_SCHEMA_VERSION`
+#[cfg(target_arch = "wasm32")]
+const EXPORT_SCHEMA_VERSION: u32 = 2;
+
+/// Explain a repos.json written for a different UI, before its shape trips
+/// up the full parse
+#[cfg(target_arch = "wasm32")]
+fn schema_mismatch(json: &str) -> Option<String> {
+    use serde::Deserialize;
 
     #[derive(Deserialize)]
     #[serde(rename_all = "camelCase")]
-    struct CommitJson {
-        sha: String,
-        message: String,
-        author_name: String,
-        author_email: String,
-        authored_date: String,
+    struct VersionJson {
+        #[serde(default)]
+        schema_version: u32,
     }
 
-    #[derive(Deserialize)]
-    #[serde(rename_all = "camelCase")]
-    struct PullRequestJson {
-        number: u32,
-        title: String,
-        state: String,
-        created_at: String,
-        updated_at: String,
+    let version = serde_json::from_str::<VersionJson>(json)
+        .ok()?
+        .schema_version;
+    if version == EXPORT_SCHEMA_VERSION {
+        None
+    } else if version > EXPORT_SCHEMA_VERSION {
+        Some(format!(
+            "repos.json uses schema version {} but this UI only understands version {}. Please rebuild the UI.",
+            version, EXPORT_SCHEMA_VERSION
+        ))
+    } else {
+        Some(format!(
+            "repos.json uses schema version {} but this UI expects version {}. Please re-export with `overall export` or upgrade overall.",
+            version, EXPORT_SCHEMA_VERSION
+        ))
     }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn fetch_repos() -> Result<RepoData, String> {
+    use gloo::net::http::Request;
+    use serde::Deserialize;
 
     #[derive(Deserialize)]
     struct GroupJson {
@@ -1971,9 +3138,12 @@ async fn fetch_repos() -> Result<Vec<RepoGroup>, String> {
     }
 
     #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
     struct DataJson {
         groups: Vec<GroupJson>,
         ungrouped: Vec<RepoJson>,
+        #[serde(default)]
+        protected_branches: Vec<String>,
     }
 
     // Add timestamp to bypass browser caching
@@ -1984,62 +3154,21 @@ async fn fetch_repos() -> Result<Vec<RepoGroup>, String> {
         .await
         .map_err(|e| format!("Failed to fetch repos: {:?}", e))?;
 
-    let data: DataJson = response
-        .json()
+    let text = response
+        .text()
         .await
-        .map_err(|e| format!("Failed to parse repos: {:?}", e))?;
+        .map_err(|e| format!("Failed to read repos: {:?}", e))?;
+    if let Some(message) = schema_mismatch(&text) {
+        return Err(message);
+    }
+    let data: DataJson =
+        serde_json::from_str(&text).map_err(|e| format!("Failed to parse repos: {}", e))?;
 
     let mut result = Vec::new();
 
     // Convert grouped repositories
     for group in data.groups {
-        let repos: Vec<Repository> = group
-            .repos
-            .into_iter()
-            .map(|r| Repository {
-                id: r.id,
-                owner: r.owner,
-                name: r.name,
-                language: r.language,
-                last_push: format_relative_time(&r.last_push),
-                branches: r
-                    .branches
-                    .into_iter()
-                    .map(|b| BranchInfo {
-                        name: b.name,
-                        sha: b.sha.clone(),
-                        status: b.status,
-                        ahead: b.ahead_by,
-                        behind: b.behind_by,
-                        last_commit_date: format_relative_time(&b.last_commit_date),
-                        commits: b
-                            .commits
-                            .into_iter()
-                            .map(|c| CommitInfo {
-                                sha: c.sha,
-                                message: c.message,
-                                author_name: c.author_name,
-                                author_email: c.author_email,
-                                authored_date: format_relative_time(&c.authored_date),
-                            })
-                            .collect(),
-                    })
-                    .collect(),
-                pull_requests: r
-                    .pull_requests
-                    .into_iter()
-                    .map(|pr| PullRequestInfo {
-                        number: pr.number,
-                        title: pr.title,
-                        state: pr.state,
-                        created_at: format_relative_time(&pr.created_at),
-                        updated_at: format_relative_time(&pr.updated_at),
-                    })
-                    .collect(),
-                unmerged_count: r.unmerged_count,
-                pr_count: r.pr_count,
-            })
-            .collect();
+        let repos: Vec<Repository> = group.repos.into_iter().map(repo_from_json).collect();
 
         result.push(RepoGroup {
             id: Some(group.id),
@@ -2050,53 +3179,8 @@ async fn fetch_repos() -> Result<Vec<RepoGroup>, String> {
 
     // Add ungrouped repositories as a separate tab if any exist
     if !data.ungrouped.is_empty() {
-        let ungrouped_repos: Vec<Repository> = data
-            .ungrouped
-            .into_iter()
-            .map(|r| Repository {
-                id: r.id,
-                owner: r.owner,
-                name: r.name,
-                language: r.language,
-                last_push: format_relative_time(&r.last_push),
-                branches: r
-                    .branches
-                    .into_iter()
-                    .map(|b| BranchInfo {
-                        name: b.name,
-                        sha: b.sha.clone(),
-                        status: b.status,
-                        ahead: b.ahead_by,
-                        behind: b.behind_by,
-                        last_commit_date: format_relative_time(&b.last_commit_date),
-                        commits: b
-                            .commits
-                            .into_iter()
-                            .map(|c| CommitInfo {
-                                sha: c.sha,
-                                message: c.message,
-                                author_name: c.author_name,
-                                author_email: c.author_email,
-                                authored_date: format_relative_time(&c.authored_date),
-                            })
-                            .collect(),
-                    })
-                    .collect(),
-                pull_requests: r
-                    .pull_requests
-                    .into_iter()
-                    .map(|pr| PullRequestInfo {
-                        number: pr.number,
-                        title: pr.title,
-                        state: pr.state,
-                        created_at: format_relative_time(&pr.created_at),
-                        updated_at: format_relative_time(&pr.updated_at),
-                    })
-                    .collect(),
-                unmerged_count: r.unmerged_count,
-                pr_count: r.pr_count,
-            })
-            .collect();
+        let ungrouped_repos: Vec<Repository> =
+            data.ungrouped.into_iter().map(repo_from_json).collect();
 
         result.push(RepoGroup {
             id: None, // Ungrouped has no ID
@@ -2105,7 +3189,10 @@ async fn fetch_repos() -> Result<Vec<RepoGroup>, String> {
         });
     }
 
-    Ok(result)
+    Ok(RepoData {
+        groups: result,
+        protected_branches: data.protected_branches,
+    })
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -2136,8 +3223,116 @@ async fn move_repo_to_group(repo_id: &str, target_group_id: Option<i64>) -> Resu
     Ok(())
 }
 
+/// Move several repositories to a group at once, or to ungrouped with `None`
+#[cfg(target_arch = "wasm32")]
+async fn move_repos_to_group(
+    repo_ids: &[String],
+    target_group_id: Option<i64>,
+) -> Result<(), String> {
+    use gloo::net::http::Request;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct MoveReposRequest<'a> {
+        repo_ids: &'a [String],
+        target_group_id: Option<i64>,
+    }
+
+    let response = Request::post("/api/repos/move-batch")
+        .header("Content-Type", "application/json")
+        .json(&MoveReposRequest {
+            repo_ids,
+            target_group_id,
+        })
+        .map_err(|e| format!("Failed to serialize request: {:?}", e))?
+        .send()
+        .await
+        .map_err(|e| format!("Failed to move repositories: {:?}", e))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Moving repositories failed with status: {}",
+            response.status()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Snooze a repository for `duration` (e.g. `2d`), or wake it with `None`
+#[cfg(target_arch = "wasm32")]
+async fn snooze_repo(repo_id: &str, duration: Option<&str>) -> Result<(), String> {
+    use gloo::net::http::Request;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct SnoozeRequest<'a> {
+        duration: Option<&'a str>,
+    }
+
+    let response = Request::post(&format!("/api/repos/{}/snooze", repo_id))
+        .header("Content-Type", "application/json")
+        .json(&SnoozeRequest { duration })
+        .map_err(|e| format!("Failed to serialize request: {:?}", e))?
+        .send()
+        .await
+        .map_err(|e| format!("Failed to snooze: {:?}", e))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Snoozing failed with status: {}",
+            response.status()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Reload the groups once the server has had time to regenerate repos.json
 #[cfg(target_arch = "wasm32")]
-async fn create_pull_request(repo_id: &str, branch_name: &str) -> Result<String, String> {
+async fn reload_after_regeneration(groups: &UseStateHandle<Vec<RepoGroup>>) {
+    gloo::timers::future::sleep(std::time::Duration::from_millis(1000)).await;
+    if let Ok(data) = fetch_repos().await {
+        groups.set(data.groups);
+    }
+}
+
+/// Override a repository's priority, or unlock it with `None`
+#[cfg(target_arch = "wasm32")]
+async fn set_repo_priority(repo_id: &str, priority: Option<f32>) -> Result<(), String> {
+    use gloo::net::http::Request;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct SetPriorityRequest {
+        priority: Option<f32>,
+    }
+
+    let response = Request::post(&format!("/api/repos/{}/priority", repo_id))
+        .header("Content-Type", "application/json")
+        .json(&SetPriorityRequest { priority })
+        .map_err(|e| format!("Failed to serialize request: {:?}", e))?
+        .send()
+        .await
+        .map_err(|e| format!("Failed to set priority: {:?}", e))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Setting priority failed with status: {}",
+            response.status()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn create_pull_request(
+    repo_id: &str,
+    branch_name: &str,
+    draft: bool,
+) -> Result<String, String> {
     use gloo::net::http::Request;
     use serde::{Deserialize, Serialize};
 
@@ -2148,6 +3343,7 @@ async fn create_pull_request(repo_id: &str, branch_name: &str) -> Result<String,
         branch_name: String,
         title: Option<String>,
         body: Option<String>,
+        draft: bool,
     }
 
     #[derive(Deserialize)]
@@ -2163,6 +3359,7 @@ async fn create_pull_request(repo_id: &str, branch_name: &str) -> Result<String,
         branch_name: branch_name.to_string(),
         title: None, // Let the backend generate from branch name
         body: None,  // Use default
+        draft,
     };
 
     let response = Request::post("/api/pr/create")
@@ -2193,6 +3390,69 @@ async fn create_pull_request(repo_id: &str, branch_name: &str) -> Result<String,
     }
 }
 
+/// Squash-merge a pull request, asking for confirmation when the server
+/// reports that it targets a protected branch
+///
+/// Returns false when the user declined the confirmation.
+#[cfg(target_arch = "wasm32")]
+async fn merge_pull_request(repo_id: &str, number: u32) -> Result<bool, String> {
+    use gloo::net::http::Request;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct MergePRRequest {
+        repo_id: String,
+        method: &'static str,
+        confirm_protected: bool,
+    }
+
+    #[derive(Deserialize)]
+    struct MergePRResponse {
+        success: bool,
+        message: String,
+    }
+
+    let mut confirm_protected = false;
+    loop {
+        let request_body = MergePRRequest {
+            repo_id: repo_id.to_string(),
+            method: "squash",
+            confirm_protected,
+        };
+
+        let response = Request::post(&format!("/api/pr/{}/merge", number))
+            .header("Content-Type", "application/json")
+            .json(&request_body)
+            .map_err(|e| format!("Failed to serialize request: {:?}", e))?
+            .send()
+            .await
+            .map_err(|e| format!("Failed to merge PR: {:?}", e))?;
+
+        let status = response.status();
+        let result: MergePRResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {:?}", e))?;
+
+        if result.success {
+            return Ok(true);
+        }
+        // 409: the PR targets a protected branch and needs an explicit go-ahead
+        if status == 409 && !confirm_protected {
+            let confirmed = web_sys::window()
+                .and_then(|w| w.confirm_with_message(&result.message).ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return Ok(false);
+            }
+            confirm_protected = true;
+            continue;
+        }
+        return Err(result.message);
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 async fn create_all_pull_requests(repo_id: &str) -> Result<String, String> {
     use gloo::net::http::Request;
@@ -2304,6 +3564,129 @@ fn format_relative_time(iso_date: &str) -> String {
     iso_date.to_string()
 }
 
+#[cfg(target_arch = "wasm32")]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UiConfigJson {
+    default_sort: SortState,
+    /// Repositories currently red in the traffic light
+    #[serde(default)]
+    needs_attention: usize,
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn fetch_ui_config() -> Result<UiConfigJson, String> {
+    use gloo::net::http::Request;
+
+    let response = Request::get("/api/ui-config")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch UI config: {:?}", e))?;
+
+    let config: UiConfigJson = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse UI config: {:?}", e))?;
+
+    Ok(config)
+}
+
+/// Totals across all repositories from `/api/dashboard`
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DashboardJson {
+    total_repos: usize,
+    needs_sync: usize,
+    local_changes: usize,
+    stale: usize,
+    complete: usize,
+    open_prs: usize,
+    unmerged_branches: usize,
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn fetch_dashboard() -> Result<DashboardJson, String> {
+    use gloo::net::http::Request;
+
+    let response = Request::get("/api/dashboard")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch dashboard: {:?}", e))?;
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse dashboard: {:?}", e))
+}
+
+/// Summary chips for the header; status chips with nothing in them are left out
+#[cfg(target_arch = "wasm32")]
+fn dashboard_chips(counts: Option<&DashboardJson>) -> Html {
+    let Some(counts) = counts else {
+        return html! {};
+    };
+    let chip = |class: &'static str, count: usize, label: &'static str| {
+        html! {
+            <span class={classes!("dashboard-chip", class)} title={label}>
+                <span class="chip-count">{ count }</span>{ " " }{ label }
+            </span>
+        }
+    };
+    let status_chip = |class, count, label| {
+        if count > 0 {
+            chip(class, count, label)
+        } else {
+            html! {}
+        }
+    };
+
+    html! {
+        <div class="dashboard-chips">
+            { chip("chip-total", counts.total_repos, "repos") }
+            { status_chip("chip-needs-sync", counts.needs_sync, "need sync") }
+            { status_chip("chip-local-changes", counts.local_changes, "local changes") }
+            { status_chip("chip-stale", counts.stale, "stale") }
+            { status_chip("chip-complete", counts.complete, "complete") }
+            { chip("chip-prs", counts.open_prs, "open PRs") }
+            { chip("chip-unmerged", counts.unmerged_branches, "unmerged branches") }
+        </div>
+    }
+}
+
+/// Prefix the tab title with the needs-attention count, e.g. "(3) Overall",
+/// so it reads at a glance from a background tab
+#[cfg(target_arch = "wasm32")]
+fn set_document_title(needs_attention: usize) {
+    const TITLE: &str = "Overall";
+    if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+        if needs_attention > 0 {
+            document.set_title(&format!("({}) {}", needs_attention, TITLE));
+        } else {
+            document.set_title(TITLE);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn fetch_icon_set() -> Result<IconSet, String> {
+    use gloo::net::http::Request;
+
+    let response = Request::get("/icons/manifest.json")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch icon manifest: {:?}", e))?;
+
+    if !response.ok() {
+        return Err(format!("Icon manifest not found: {}", response.status()));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse icon manifest: {:?}", e))
+}
+
 #[cfg(target_arch = "wasm32")]
 async fn fetch_build_info() -> Result<BuildInfo, String> {
     use gloo::net::http::Request;
@@ -2338,51 +3721,156 @@ async fn fetch_build_info() -> Result<BuildInfo, String> {
 }
 
 #[cfg(target_arch = "wasm32")]
-async fn trigger_local_repo_scan() -> Result<(), String> {
+async fn trigger_refresh_all() -> Result<(), String> {
     use gloo::net::http::Request;
 
-    let response = Request::post("/api/local-repos/scan")
-        .header("Content-Type", "application/json")
-        .body("{}")
-        .map_err(|e| format!("Failed to create request: {}", e))?
+    let response = Request::post("/api/refresh-all")
         .send()
         .await
-        .map_err(|e| format!("Failed to trigger scan: {}", e))?;
+        .map_err(|e| format!("Failed to trigger refresh: {}", e))?;
 
     if !response.ok() {
-        return Err(format!("Scan failed with status: {}", response.status()));
+        return Err(format!("Refresh failed with status: {}", response.status()));
     }
 
     Ok(())
 }
 
 #[cfg(target_arch = "wasm32")]
-async fn sync_single_repo(repo_id: &str) -> Result<(), String> {
+async fn fetch_branch_diff(repo_id: &str, branch_name: &str) -> Result<String, String> {
     use gloo::net::http::Request;
-    use serde::Serialize;
 
-    #[derive(Serialize)]
-    struct SyncRepoRequest {
+    // Branch names may contain slashes, so encode them as a single path segment
+    let branch = String::from(js_sys::encode_uri_component(branch_name));
+    let url = format!("/api/repos/{}/branches/{}/diff", repo_id, branch);
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch diff: {}", e))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Diff request failed with status: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read diff: {}", e))
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn fetch_branch_commits(repo_id: &str, branch_name: &str) -> Result<Vec<CommitInfo>, String> {
+    use gloo::net::http::Request;
+
+    let branch = String::from(js_sys::encode_uri_component(branch_name));
+    let url = format!("/api/repos/{}/branches/{}/commits", repo_id, branch);
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch commits: {}", e))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Commits request failed with status: {}",
+            response.status()
+        ));
+    }
+
+    let commits: Vec<CommitJson> = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse commits: {}", e))?;
+    Ok(commits.into_iter().map(commit_from_json).collect())
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn fetch_commit_message(repo_id: &str, sha: &str) -> Result<String, String> {
+    use gloo::net::http::Request;
+
+    let url = format!("/api/repos/{}/commits/{}/message", repo_id, sha);
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch commit message: {}", e))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Commit message request failed with status: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read commit message: {}", e))
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn refresh_repo(repo_id: &str) -> Result<(Repository, Option<LocalRepoStatus>), String> {
+    use gloo::net::http::Request;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct LocalRepoStatusJson {
+        id: i64,
         repo_id: String,
+        local_path: String,
+        current_branch: Option<String>,
+        uncommitted_files: u32,
+        unpushed_commits: u32,
+        behind_commits: u32,
+        is_dirty: bool,
+        #[serde(default)]
+        has_conflicts: bool,
+        #[serde(default)]
+        is_diverged: bool,
+        #[serde(default)]
+        is_unpublished: bool,
+        last_checked: String,
     }
 
-    let request_body = SyncRepoRequest {
-        repo_id: repo_id.to_string(),
-    };
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct RefreshJson {
+        #[serde(flatten)]
+        repo: RepoJson,
+        local_status: Option<LocalRepoStatusJson>,
+    }
 
-    let response = Request::post("/api/repos/sync")
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .map_err(|e| format!("Failed to create sync request: {}", e))?
+    let response = Request::post(&format!("/api/repos/{}/refresh", repo_id))
         .send()
         .await
-        .map_err(|e| format!("Failed to sync repo: {}", e))?;
+        .map_err(|e| format!("Failed to refresh repo: {}", e))?;
 
     if !response.ok() {
-        return Err(format!("Sync failed with status: {}", response.status()));
+        return Err(format!("Refresh failed with status: {}", response.status()));
     }
 
-    Ok(())
+    let refreshed: RefreshJson = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse refreshed repo: {:?}", e))?;
+
+    let local_status = refreshed.local_status.map(|s| LocalRepoStatus {
+        id: s.id,
+        repo_id: s.repo_id,
+        local_path: s.local_path,
+        current_branch: s.current_branch,
+        uncommitted_files: s.uncommitted_files,
+        unpushed_commits: s.unpushed_commits,
+        behind_commits: s.behind_commits,
+        is_dirty: s.is_dirty,
+        has_conflicts: s.has_conflicts,
+        is_diverged: s.is_diverged,
+        is_unpublished: s.is_unpublished,
+        last_checked: s.last_checked,
+    });
+
+    Ok((repo_from_json(refreshed.repo), local_status))
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -2400,6 +3888,12 @@ async fn fetch_local_repo_statuses() -> Result<Vec<LocalRepoStatus>, String> {
         unpushed_commits: u32,
         behind_commits: u32,
         is_dirty: bool,
+        #[serde(default)]
+        has_conflicts: bool,
+        #[serde(default)]
+        is_diverged: bool,
+        #[serde(default)]
+        is_unpublished: bool,
         last_checked: String,
     }
 
@@ -2427,6 +3921,9 @@ async fn fetch_local_repo_statuses() -> Result<Vec<LocalRepoStatus>, String> {
             unpushed_commits: s.unpushed_commits,
             behind_commits: s.behind_commits,
             is_dirty: s.is_dirty,
+            has_conflicts: s.has_conflicts,
+            is_diverged: s.is_diverged,
+            is_unpublished: s.is_unpublished,
             last_checked: s.last_checked,
         })
         .collect())